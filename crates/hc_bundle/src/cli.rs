@@ -51,6 +51,12 @@ pub enum HcDnaBundle {
         /// provided working directory.
         #[structopt(short = "o", long)]
         output: Option<PathBuf>,
+
+        /// Sign the bundle with this ed25519 private key, a PKCS#8 document
+        /// in PEM or DER format, e.g. generated with
+        /// `openssl genpkey -algorithm ed25519`
+        #[structopt(long)]
+        signing_key: Option<PathBuf>,
     },
 
     /// Unpack parts of the `.dna` bundle file into a specific directory.
@@ -77,6 +83,25 @@ pub enum HcDnaBundle {
         #[structopt(short = "f", long)]
         force: bool,
     },
+
+    /// Compare two DNAs and explain why their DNA hashes differ.
+    /// Each DNA is either a `.dna` bundle or a working directory containing
+    /// a `dna.yaml` manifest.
+    ///
+    /// Reports the differences between the manifests, including properties,
+    /// the zomes whose wasms changed, and the DNA hash of each.
+    /// Exits with an error if the DNA hashes differ.
+    ///
+    /// e.g.:
+    ///
+    /// $ hc dna diff ./released/my-dna.dna ./some/directory/foo
+    Diff {
+        /// The first DNA bundle or working directory
+        a: PathBuf,
+
+        /// The second DNA bundle or working directory
+        b: PathBuf,
+    },
 }
 
 /// Work with Holochain hApp bundles
@@ -109,6 +134,12 @@ pub enum HcAppBundle {
         /// provided working directory.
         #[structopt(short = "o", long)]
         output: Option<PathBuf>,
+
+        /// Sign the bundle with this ed25519 private key, a PKCS#8 document
+        /// in PEM or DER format, e.g. generated with
+        /// `openssl genpkey -algorithm ed25519`
+        #[structopt(long)]
+        signing_key: Option<PathBuf>,
     },
 
     /// Unpack parts of the `.happ` bundle file into a specific directory.
@@ -135,6 +166,25 @@ pub enum HcAppBundle {
         #[structopt(short = "f", long)]
         force: bool,
     },
+
+    /// Compare two hApps and explain why the DNA hashes of their roles
+    /// differ. Each hApp is either a `.happ` bundle or a working directory
+    /// containing a `happ.yaml` manifest.
+    ///
+    /// Reports the differences between the hApp manifests and, for each role,
+    /// the differences between its DNAs, with the role's uid and properties
+    /// applied. Exits with an error if any DNA hashes differ.
+    ///
+    /// e.g.:
+    ///
+    /// $ hc app diff ./released/my-app.happ ./some/directory/foo
+    Diff {
+        /// The first hApp bundle or working directory
+        a: PathBuf,
+
+        /// The second hApp bundle or working directory
+        b: PathBuf,
+    },
 }
 
 /// Work with Holochain Web-hApp bundles
@@ -167,6 +217,12 @@ pub enum HcWebAppBundle {
         /// provided working directory.
         #[structopt(short = "o", long)]
         output: Option<PathBuf>,
+
+        /// Sign the bundle with this ed25519 private key, a PKCS#8 document
+        /// in PEM or DER format, e.g. generated with
+        /// `openssl genpkey -algorithm ed25519`
+        #[structopt(long)]
+        signing_key: Option<PathBuf>,
     },
 
     /// Unpack parts of the `.webhapp` bundle file into a specific directory.
@@ -202,11 +258,21 @@ impl HcDnaBundle {
             Self::Init { path } => {
                 crate::init::init_dna(path).await?;
             }
-            Self::Pack { path, output } => {
+            Self::Pack {
+                path,
+                output,
+                signing_key,
+            } => {
                 let name = get_dna_name(&path).await?;
-                let (bundle_path, _) =
-                    crate::packing::pack::<DnaManifest>(&path, output, name).await?;
+                let (bundle_path, bundle) = crate::packing::pack::<DnaManifest>(
+                    &path,
+                    output,
+                    name,
+                    signing_key.as_deref(),
+                )
+                .await?;
                 println!("Wrote bundle {}", bundle_path.to_string_lossy());
+                print_publisher(bundle.signature());
             }
             Self::Unpack {
                 path,
@@ -218,6 +284,10 @@ impl HcDnaBundle {
                         .await?;
                 println!("Unpacked to directory {}", dir_path.to_string_lossy());
             }
+            Self::Diff { a, b } => {
+                let (report, hash_differs) = crate::diff::diff_dnas(&a, &b).await?;
+                print_diff(report, hash_differs)?;
+            }
         }
         Ok(())
     }
@@ -230,11 +300,21 @@ impl HcAppBundle {
             Self::Init { path } => {
                 crate::init::init_app(path).await?;
             }
-            Self::Pack { path, output } => {
+            Self::Pack {
+                path,
+                output,
+                signing_key,
+            } => {
                 let name = get_app_name(&path).await?;
-                let (bundle_path, _) =
-                    crate::packing::pack::<AppManifest>(&path, output, name).await?;
+                let (bundle_path, bundle) = crate::packing::pack::<AppManifest>(
+                    &path,
+                    output,
+                    name,
+                    signing_key.as_deref(),
+                )
+                .await?;
                 println!("Wrote bundle {}", bundle_path.to_string_lossy());
+                print_publisher(bundle.signature());
             }
             Self::Unpack {
                 path,
@@ -246,6 +326,10 @@ impl HcAppBundle {
                         .await?;
                 println!("Unpacked to directory {}", dir_path.to_string_lossy());
             }
+            Self::Diff { a, b } => {
+                let (report, hash_differs) = crate::diff::diff_apps(&a, &b).await?;
+                print_diff(report, hash_differs)?;
+            }
         }
         Ok(())
     }
@@ -258,11 +342,21 @@ impl HcWebAppBundle {
             Self::Init { path } => {
                 crate::init::init_web_app(path).await?;
             }
-            Self::Pack { path, output } => {
+            Self::Pack {
+                path,
+                output,
+                signing_key,
+            } => {
                 let name = get_web_app_name(&path).await?;
-                let (bundle_path, _) =
-                    crate::packing::pack::<WebAppManifest>(&path, output, name).await?;
+                let (bundle_path, bundle) = crate::packing::pack::<WebAppManifest>(
+                    &path,
+                    output,
+                    name,
+                    signing_key.as_deref(),
+                )
+                .await?;
                 println!("Wrote bundle {}", bundle_path.to_string_lossy());
+                print_publisher(bundle.signature());
             }
             Self::Unpack {
                 path,
@@ -283,6 +377,22 @@ impl HcWebAppBundle {
     }
 }
 
+fn print_diff(report: Vec<String>, hash_differs: bool) -> anyhow::Result<()> {
+    for line in report {
+        println!("{}", line);
+    }
+    if hash_differs {
+        anyhow::bail!("The DNA hashes differ");
+    }
+    Ok(())
+}
+
+fn print_publisher(signature: Option<&mr_bundle::BundleSignature>) {
+    if let Some(signature) = signature {
+        println!("Signed by publisher {}", signature.publisher);
+    }
+}
+
 async fn get_dna_name(manifest_path: &Path) -> HcBundleResult<String> {
     let manifest_path = manifest_path.to_path_buf();
     let manifest_path = manifest_path.join(&DnaManifest::path());
@@ -0,0 +1,337 @@
+#![forbid(missing_docs)]
+
+//! Explains why two DNA or hApp bundles have different DNA hashes
+
+use crate::error::HcBundleResult;
+use holochain_types::prelude::*;
+use holochain_util::ffs;
+use mr_bundle::{Bundle, Manifest};
+use serde_yaml::Value;
+use std::path::Path;
+
+/// Compare two DNAs, each either a `.dna` bundle or a working directory
+/// containing a `dna.yaml` manifest.
+///
+/// Returns a report of the differences and whether the DNA hashes differ.
+pub async fn diff_dnas(a: &Path, b: &Path) -> HcBundleResult<(Vec<String>, bool)> {
+    let a = summarize_dna(load::<DnaManifest>(a).await?.into(), None, None).await?;
+    let b = summarize_dna(load::<DnaManifest>(b).await?.into(), None, None).await?;
+    let mut report = Vec::new();
+    let hash_differs = report_dna(&a, &b, "", &mut report);
+    Ok((report, hash_differs))
+}
+
+/// Compare two hApps, each either a `.happ` bundle or a working directory
+/// containing a `happ.yaml` manifest, and the DNAs of each of their roles.
+///
+/// Returns a report of the differences and whether any DNA hashes differ.
+pub async fn diff_apps(a: &Path, b: &Path) -> HcBundleResult<(Vec<String>, bool)> {
+    let a = load::<AppManifest>(a).await?;
+    let b = load::<AppManifest>(b).await?;
+    let mut report = Vec::new();
+    let mut manifest_diff = Vec::new();
+    diff_values(
+        "",
+        Some(&serde_yaml::to_value(a.manifest())?),
+        Some(&serde_yaml::to_value(b.manifest())?),
+        &mut manifest_diff,
+    );
+    section("Manifest", "", manifest_diff, &mut report);
+
+    let roles_a = roles(&a);
+    let roles_b = roles(&b);
+    let mut role_ids: Vec<_> = roles_a.iter().map(|role| role.id.clone()).collect();
+    role_ids.extend(
+        roles_b
+            .iter()
+            .map(|role| role.id.clone())
+            .filter(|id| !roles_a.iter().any(|role| &role.id == id)),
+    );
+
+    let mut hash_differs = false;
+    for id in role_ids {
+        let role_a = roles_a.iter().find(|role| role.id == id);
+        let role_b = roles_b.iter().find(|role| role.id == id);
+        let (role_a, role_b) = match (role_a, role_b) {
+            (Some(role_a), Some(role_b)) => (role_a, role_b),
+            (role_a, _) => {
+                report.push(format!(
+                    "Role {}: only in the {} hApp",
+                    id,
+                    if role_a.is_some() { "first" } else { "second" }
+                ));
+                hash_differs = true;
+                continue;
+            }
+        };
+        let (dna_a, dna_b) = match (
+            summarize_role_dna(&a, role_a).await?,
+            summarize_role_dna(&b, role_b).await?,
+        ) {
+            (Some(dna_a), Some(dna_b)) => (dna_a, dna_b),
+            (None, None) => continue,
+            _ => {
+                report.push(format!("Role {}: the DNA is only given in one hApp", id));
+                hash_differs = true;
+                continue;
+            }
+        };
+        let mut role_report = Vec::new();
+        if report_dna(&dna_a, &dna_b, "  ", &mut role_report) {
+            hash_differs = true;
+        }
+        report.push(format!("Role {}:", id));
+        report.extend(role_report);
+    }
+    Ok((report, hash_differs))
+}
+
+/// The parts of a DNA which determine its hash.
+struct DnaSummary {
+    manifest: Value,
+    wasms: Vec<(ZomeName, WasmHash)>,
+    dna_hash: DnaHash,
+}
+
+async fn summarize_dna(
+    bundle: DnaBundle,
+    uid: Option<Uid>,
+    properties: Option<YamlProperties>,
+) -> HcBundleResult<DnaSummary> {
+    let manifest = serde_yaml::to_value(bundle.manifest())?;
+    let (dna_file, _) = bundle.into_dna_file(uid, properties).await?;
+    let wasms = dna_file
+        .dna_def()
+        .zomes
+        .iter()
+        .filter_map(|(name, def)| def.wasm_hash(name).ok().map(|hash| (name.clone(), hash)))
+        .collect();
+    Ok(DnaSummary {
+        manifest,
+        wasms,
+        dna_hash: dna_file.dna_hash().clone(),
+    })
+}
+
+/// Summarize the DNA a role would install, with the role's overrides.
+async fn summarize_role_dna(
+    app: &Bundle<AppManifest>,
+    role: &AppRoleManifest,
+) -> HcBundleResult<Option<DnaSummary>> {
+    let location = match &role.dna.location {
+        Some(location) => location,
+        None => return Ok(None),
+    };
+    let bytes = app.resolve(location).await?;
+    let dna = DnaBundle::decode(&bytes)?;
+    Ok(Some(
+        summarize_dna(dna, role.dna.uid.clone(), role.dna.properties.clone()).await?,
+    ))
+}
+
+fn roles(app: &Bundle<AppManifest>) -> Vec<AppRoleManifest> {
+    match app.manifest() {
+        AppManifest::V1(manifest) => manifest.roles.clone(),
+    }
+}
+
+/// Read a bundle file, or pack a working directory in memory.
+async fn load<M: Manifest>(path: &Path) -> HcBundleResult<Bundle<M>> {
+    if path.is_dir() {
+        let dir = ffs::canonicalize(path).await?;
+        Ok(Bundle::pack_yaml(&dir.join(M::path())).await?)
+    } else {
+        Ok(Bundle::read_from_file(path).await?)
+    }
+}
+
+/// Add the differences between two DNAs to the report,
+/// returning whether their hashes differ.
+fn report_dna(a: &DnaSummary, b: &DnaSummary, indent: &str, report: &mut Vec<String>) -> bool {
+    let mut manifest_diff = Vec::new();
+    diff_values("", Some(&a.manifest), Some(&b.manifest), &mut manifest_diff);
+    section("Manifest", indent, manifest_diff, report);
+
+    let mut wasm_diff = Vec::new();
+    for (name, hash_a) in a.wasms.iter() {
+        match b.wasms.iter().find(|(n, _)| n == name) {
+            Some((_, hash_b)) if hash_a != hash_b => {
+                wasm_diff.push(format!("{}: {} => {}", name, hash_a, hash_b))
+            }
+            Some(_) => (),
+            None => wasm_diff.push(format!("{}: {} => (absent)", name, hash_a)),
+        }
+    }
+    for (name, hash_b) in b.wasms.iter() {
+        if !a.wasms.iter().any(|(n, _)| n == name) {
+            wasm_diff.push(format!("{}: (absent) => {}", name, hash_b));
+        }
+    }
+    section("Wasms", indent, wasm_diff, report);
+
+    let hash_differs = a.dna_hash != b.dna_hash;
+    if hash_differs {
+        report.push(format!(
+            "{}DNA hash: {} => {}",
+            indent, a.dna_hash, b.dna_hash
+        ));
+    } else {
+        report.push(format!("{}DNA hash: {} (unchanged)", indent, a.dna_hash));
+    }
+    hash_differs
+}
+
+fn section(title: &str, indent: &str, lines: Vec<String>, report: &mut Vec<String>) {
+    if !lines.is_empty() {
+        report.push(format!("{}{}:", indent, title));
+        report.extend(
+            lines
+                .into_iter()
+                .map(|line| format!("{}  {}", indent, line)),
+        );
+    }
+}
+
+/// List every leaf value which differs between `a` and `b`,
+/// as `path: old => new`.
+fn diff_values(path: &str, a: Option<&Value>, b: Option<&Value>, diff: &mut Vec<String>) {
+    match (a, b) {
+        (Some(Value::Mapping(_)), _) | (_, Some(Value::Mapping(_)))
+            if a.map_or(true, Value::is_mapping) && b.map_or(true, Value::is_mapping) =>
+        {
+            let empty = serde_yaml::Mapping::new();
+            let map_a = a.and_then(Value::as_mapping).unwrap_or(&empty);
+            let map_b = b.and_then(Value::as_mapping).unwrap_or(&empty);
+            let keys = map_a.iter().map(|(k, _)| k).chain(
+                map_b
+                    .iter()
+                    .map(|(k, _)| k)
+                    .filter(|k| !map_a.contains_key(k)),
+            );
+            for key in keys {
+                let key_path = match key {
+                    Value::String(key) => key.clone(),
+                    key => show(Some(key)),
+                };
+                let key_path = if path.is_empty() {
+                    key_path
+                } else {
+                    format!("{}.{}", path, key_path)
+                };
+                diff_values(&key_path, map_a.get(key), map_b.get(key), diff);
+            }
+        }
+        (Some(Value::Sequence(_)), _) | (_, Some(Value::Sequence(_)))
+            if a.map_or(true, Value::is_sequence) && b.map_or(true, Value::is_sequence) =>
+        {
+            let seq_a = a.and_then(Value::as_sequence).map_or(&[][..], |s| &s[..]);
+            let seq_b = b.and_then(Value::as_sequence).map_or(&[][..], |s| &s[..]);
+            for i in 0..seq_a.len().max(seq_b.len()) {
+                diff_values(
+                    &format!("{}[{}]", path, i),
+                    seq_a.get(i),
+                    seq_b.get(i),
+                    diff,
+                );
+            }
+        }
+        _ if a != b => diff.push(format!("{}: {} => {}", path, show(a), show(b))),
+        _ => (),
+    }
+}
+
+fn show(value: Option<&Value>) -> String {
+    match value {
+        None => "(absent)".to_string(),
+        Some(Value::String(s)) => format!("{:?}", s),
+        Some(value) => serde_yaml::to_string(value)
+            .map(|yaml| yaml.trim_start_matches("---").trim().replace('\n', " "))
+            .unwrap_or_else(|_| format!("{:?}", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_values() {
+        let a: Value = serde_yaml::from_str(
+            r#"
+name: dna
+uid: ~
+properties:
+  foo: 1
+  bar: [1, 2]
+"#,
+        )
+        .unwrap();
+        let b: Value = serde_yaml::from_str(
+            r#"
+name: dna
+uid: abc
+properties:
+  foo: 2
+  bar: [1]
+  baz: true
+"#,
+        )
+        .unwrap();
+        let mut diff = Vec::new();
+        diff_values("", Some(&a), Some(&b), &mut diff);
+        assert_eq!(
+            diff,
+            vec![
+                r#"uid: ~ => "abc""#,
+                "properties.foo: 1 => 2",
+                "properties.bar[1]: 2 => (absent)",
+                "properties.baz: (absent) => true",
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_diff_dnas() {
+        let tmpdir = tempdir::TempDir::new("hc-bundle-test").unwrap();
+        let write_dna = |name: &str, properties: &str, wasm: &[u8]| {
+            let dir = tmpdir.path().join(name);
+            std::fs::create_dir(&dir).unwrap();
+            std::fs::write(dir.join("zome-1.wasm"), wasm).unwrap();
+            let manifest = format!(
+                r#"
+---
+manifest_version: "1"
+name: test_dna
+uid: blablabla
+properties: {}
+zomes:
+  - name: zome1
+    bundled: zome-1.wasm
+"#,
+                properties
+            );
+            std::fs::write(dir.join("dna.yaml"), manifest).unwrap();
+            dir
+        };
+        let a = write_dna("a", "{ foo: 1 }", &[1, 2, 3]);
+        let b = write_dna("b", "{ foo: 2 }", &[4, 5, 6]);
+
+        let (report, hash_differs) = diff_dnas(&a, &b).await.unwrap();
+        assert!(hash_differs);
+        assert_eq!(report[0], "Manifest:");
+        assert_eq!(report[1], "  properties.foo: 1 => 2");
+        assert_eq!(report[2], "Wasms:");
+        assert!(report[3].starts_with("  zome1: ") && report[3].contains(" => "));
+        assert!(report[4].starts_with("DNA hash: "));
+
+        // A working directory and the bundle packed from it are the same
+        let (bundle_path, _) =
+            crate::packing::pack::<DnaManifest>(&a, None, "test_dna".to_string(), None)
+                .await
+                .unwrap();
+        let (report, hash_differs) = diff_dnas(&a, &bundle_path).await.unwrap();
+        assert!(!hash_differs);
+        assert_eq!(report.len(), 1);
+        assert!(report[0].ends_with("(unchanged)"));
+    }
+}
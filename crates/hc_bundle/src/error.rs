@@ -35,6 +35,10 @@ pub enum HcBundleError {
 
     #[error("This file should have a '.{0}' extension: {1}")]
     FileExtensionMissing(&'static str, PathBuf),
+
+    /// A PEM signing key file whose contents aren't base64
+    #[error("Could not decode the PEM signing key in: {0}")]
+    InvalidSigningKey(PathBuf),
 }
 
 /// HcBundle Result type.
@@ -1,4 +1,5 @@
 mod cli;
+mod diff;
 mod error;
 mod init;
 mod packing;
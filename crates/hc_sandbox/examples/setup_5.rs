@@ -55,6 +55,7 @@ async fn main() -> anyhow::Result<()> {
             source: AppBundleSource::Bundle(bundle),
             membrane_proofs: Default::default(),
             uid: None,
+            existing_cells: Default::default(),
         };
 
         let r = AdminRequest::InstallAppBundle(Box::new(payload));
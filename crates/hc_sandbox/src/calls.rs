@@ -14,8 +14,10 @@ use holochain_conductor_api::AdminRequest;
 use holochain_conductor_api::AdminResponse;
 use holochain_conductor_api::AppStatusFilter;
 use holochain_conductor_api::InterfaceDriver;
+use holochain_conductor_api::InterfaceTlsConfig;
 use holochain_conductor_api::{AdminInterfaceConfig, InstalledAppInfo};
 use holochain_p2p::kitsune_p2p::agent_store::AgentInfoSigned;
+use holochain_types::prelude::AppRoleId;
 use holochain_types::prelude::DnaHash;
 use holochain_types::prelude::InstallAppDnaPayload;
 use holochain_types::prelude::InstallAppPayload;
@@ -25,11 +27,14 @@ use holochain_types::prelude::{AgentPubKey, AppBundleSource};
 use holochain_types::prelude::{CellId, InstallAppBundlePayload};
 use holochain_types::prelude::{DnaSource, Uid};
 use std::convert::TryFrom;
+use tokio::process::Child;
 
 use crate::cmds::Existing;
 use crate::expect_match;
 use crate::ports::get_admin_ports;
+use crate::ports::get_admin_socket;
 use crate::run::run_async;
+use crate::run::run_async_admin;
 use crate::CmdRunner;
 use structopt::StructOpt;
 
@@ -41,6 +46,15 @@ pub struct Call {
     /// If this is empty existing sandboxes will be used.
     /// Cannot be combined with existing sandboxes.
     pub running: Vec<u16>,
+    #[structopt(long, conflicts_with_all = &["running", "existing_paths", "indices"], value_delimiter = ",")]
+    /// Paths to the unix domain sockets of running conductor admin interfaces.
+    /// Cannot be combined with ports or existing sandboxes.
+    pub socket: Vec<PathBuf>,
+    #[structopt(long, conflicts_with_all = &["existing_paths", "indices"])]
+    /// File with the admin token to authenticate with running conductors,
+    /// e.g. the `admin_token` file in the conductor's environment directory.
+    /// Existing sandboxes are authenticated with their own token.
+    pub admin_token: Option<PathBuf>,
     #[structopt(flatten)]
     pub existing: Existing,
     #[structopt(subcommand)]
@@ -87,6 +101,10 @@ pub struct AddAdminWs {
     /// Optional port number.
     /// Defaults to assigned by OS.
     pub port: Option<u16>,
+    #[structopt(long)]
+    /// Leave the interface open to anyone who can reach it
+    /// instead of requiring a session token.
+    pub no_auth: bool,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -96,6 +114,17 @@ pub struct AddAppWs {
     /// Optional port number.
     /// Defaults to assigned by OS.
     pub port: Option<u16>,
+    #[structopt(long)]
+    /// Require connections to authenticate with a session token.
+    pub require_auth: bool,
+    #[structopt(long, conflicts_with = "port")]
+    /// Serve the interface over a unix domain socket at this path
+    /// instead of a port.
+    pub socket: Option<PathBuf>,
+    #[structopt(long, conflicts_with = "socket")]
+    /// Serve the interface over TLS (wss) with a self-signed certificate.
+    /// Clients pin the certificate digest that is printed.
+    pub tls: bool,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -161,6 +190,13 @@ pub struct InstallAppBundle {
 
     /// Optional UID override for every DNA in this app
     pub uid: Option<Uid>,
+
+    #[structopt(long, parse(try_from_str = parse_existing_cell), requires = "agent_key")]
+    /// Binds a role of this app to the cell which another installed app
+    /// already runs for the agent, given as `ROLE_ID=DNA_HASH`.
+    /// The role must have `use_existing` or `create_if_not_exists` provisioning.
+    /// Can be given more than once.
+    pub existing_cell: Vec<(AppRoleId, DnaHash)>,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -229,26 +265,90 @@ pub async fn call(holochain_path: &Path, req: Call) -> anyhow::Result<()> {
     let Call {
         existing,
         running,
+        socket,
+        admin_token,
         call,
     } = req;
-    let cmds = if running.is_empty() {
+    let cmds = connect_admins(
+        holochain_path,
+        running,
+        socket,
+        admin_token.as_deref(),
+        existing,
+    )
+    .await?;
+    for mut cmd in cmds {
+        call_inner(&mut cmd.0, call.clone()).await?;
+    }
+    Ok(())
+}
+
+/// Connect to the admin interfaces of running conductors by port or socket,
+/// or to existing sandboxes, starting any that aren't running.
+/// Running conductors are authenticated with the `admin_token` file if one is given.
+/// Conductors that were started are returned with their process.
+pub(crate) async fn connect_admins(
+    holochain_path: &Path,
+    running: Vec<u16>,
+    socket: Vec<PathBuf>,
+    admin_token: Option<&Path>,
+    existing: Existing,
+) -> anyhow::Result<Vec<(CmdRunner, Option<Child>)>> {
+    let cmds = if !socket.is_empty() {
+        let mut cmds = Vec::with_capacity(socket.len());
+        for path in socket {
+            let mut cmd = connect_socket(&path).await?;
+            if let Some(admin_token) = admin_token {
+                cmd.authenticate_with_token_file(admin_token).await?;
+            }
+            cmds.push((cmd, None));
+        }
+        cmds
+    } else if running.is_empty() {
         let paths = if existing.is_empty() {
             crate::save::load(std::env::current_dir()?)?
         } else {
             existing.load()?
         };
-        let ports = get_admin_ports(paths.clone()).await?;
-        let mut cmds = Vec::with_capacity(ports.len());
-        for (port, path) in ports.into_iter().zip(paths.into_iter()) {
+        let mut cmds = Vec::with_capacity(paths.len());
+        // Sandboxes with an admin socket are reached through it,
+        // the rest through their admin port.
+        let mut port_paths = Vec::with_capacity(paths.len());
+        for path in paths {
+            match get_admin_socket(path.clone())? {
+                Some(socket) => {
+                    let (mut cmd, holochain) = match CmdRunner::try_from_socket(&socket).await {
+                        Ok(cmd) => (cmd, None),
+                        // Nothing is listening on the socket so start the conductor.
+                        Err(_) => {
+                            let (_, holochain) =
+                                run_async_admin(holochain_path, path.clone(), None).await?;
+                            (connect_socket(&socket).await?, Some(holochain))
+                        }
+                    };
+                    cmd.authenticate_sandbox(&path).await?;
+                    cmds.push((cmd, holochain));
+                }
+                None => port_paths.push(path),
+            }
+        }
+        let ports = get_admin_ports(port_paths.clone()).await?;
+        for (port, path) in ports.into_iter().zip(port_paths.into_iter()) {
             match CmdRunner::try_new(port).await {
-                Ok(cmd) => cmds.push((cmd, None)),
+                Ok(mut cmd) => {
+                    cmd.authenticate_sandbox(&path).await?;
+                    cmds.push((cmd, None));
+                }
                 Err(e) => {
                     if let holochain_websocket::WebsocketError::Io(e) = &e {
                         if let std::io::ErrorKind::ConnectionRefused
                         | std::io::ErrorKind::AddrNotAvailable = e.kind()
                         {
-                            let (port, holochain) = run_async(holochain_path, path, None).await?;
-                            cmds.push((CmdRunner::new(port).await, Some(holochain)));
+                            let (port, holochain) =
+                                run_async(holochain_path, path.clone(), None).await?;
+                            let mut cmd = CmdRunner::new(port).await;
+                            cmd.authenticate_sandbox(&path).await?;
+                            cmds.push((cmd, Some(holochain)));
                             continue;
                         }
                     }
@@ -263,14 +363,25 @@ pub async fn call(holochain_path: &Path, req: Call) -> anyhow::Result<()> {
     } else {
         let mut cmds = Vec::with_capacity(running.len());
         for port in running {
-            cmds.push((CmdRunner::new(port).await, None));
+            let mut cmd = CmdRunner::new(port).await;
+            if let Some(admin_token) = admin_token {
+                cmd.authenticate_with_token_file(admin_token).await?;
+            }
+            cmds.push((cmd, None));
         }
         cmds
     };
-    for mut cmd in cmds {
-        call_inner(&mut cmd.0, call.clone()).await?;
-    }
-    Ok(())
+    Ok(cmds)
+}
+
+async fn connect_socket(path: &Path) -> anyhow::Result<CmdRunner> {
+    CmdRunner::try_from_socket(path).await.map_err(|e| {
+        anyhow!(
+            "Failed to connect to the conductor admin socket at {}: {:?}",
+            path.display(),
+            e
+        )
+    })
 }
 
 async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Result<()> {
@@ -280,12 +391,19 @@ async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Resul
             msg!("Added Admin port {}", port);
         }
         AdminRequestCli::AddAppWs(args) => {
-            let port = attach_app_interface(cmd, args).await?;
-            msg!("Added App port {}", port);
+            let socket = args.socket.clone();
+            let (port, tls_cert_digest) = attach_app_interface(cmd, args).await?;
+            match socket {
+                Some(socket) => msg!("Added App socket {}", socket.display()),
+                None => msg!("Added App port {}", port),
+            }
+            if let Some(tls_cert_digest) = tls_cert_digest {
+                msg!("App interface TLS certificate digest {}", tls_cert_digest);
+            }
         }
         AdminRequestCli::ListAppWs => {
-            let ports = list_app_ws(cmd).await?;
-            msg!("Attached App Interfaces {:?}", ports);
+            let drivers = list_app_ws(cmd).await?;
+            msg!("Attached App Interfaces {:?}", drivers);
         }
         AdminRequestCli::RegisterDna(args) => {
             let dnas = register_dna(cmd, args).await?;
@@ -400,6 +518,9 @@ pub async fn add_admin_interface(cmd: &mut CmdRunner, args: AddAdminWs) -> anyho
         .command(AdminRequest::AddAdminInterfaces(vec![
             AdminInterfaceConfig {
                 driver: InterfaceDriver::Websocket { port },
+                require_auth: !args.no_auth,
+                session_duration_seconds: None,
+                tls: None,
             },
         ]))
         .await?;
@@ -497,6 +618,7 @@ pub async fn install_app_bundle(
         agent_key,
         path,
         uid,
+        existing_cell,
     } = args;
 
     let bundle = AppBundleSource::Path(path).resolve().await?;
@@ -505,6 +627,10 @@ pub async fn install_app_bundle(
         Some(agent) => agent,
         None => generate_agent_pub_key(cmd).await?,
     };
+    let existing_cells = existing_cell
+        .into_iter()
+        .map(|(role_id, dna)| (role_id, CellId::new(dna, agent_key.clone())))
+        .collect();
 
     let payload = InstallAppBundlePayload {
         installed_app_id: app_id,
@@ -512,6 +638,7 @@ pub async fn install_app_bundle(
         source: AppBundleSource::Bundle(bundle),
         membrane_proofs: Default::default(),
         uid,
+        existing_cells,
     };
 
     let r = AdminRequest::InstallAppBundle(Box::new(payload));
@@ -544,7 +671,8 @@ pub async fn uninstall_app(cmd: &mut CmdRunner, args: UninstallApp) -> anyhow::R
 }
 
 /// Calls [`AdminRequest::ListAppInterfaces`].
-pub async fn list_app_ws(cmd: &mut CmdRunner) -> anyhow::Result<Vec<u16>> {
+/// Returns the port or socket path of each app interface.
+pub async fn list_app_ws(cmd: &mut CmdRunner) -> anyhow::Result<Vec<InterfaceDriver>> {
     let resp = cmd.command(AdminRequest::ListAppInterfaces).await?;
     Ok(expect_match!(resp => AdminResponse::AppInterfacesListed, "Failed to list app interfaces"))
 }
@@ -615,13 +743,30 @@ pub async fn disable_app(cmd: &mut CmdRunner, args: DisableApp) -> anyhow::Resul
 }
 
 /// Calls [`AdminRequest::AttachAppInterface`] and adds another app interface.
-pub async fn attach_app_interface(cmd: &mut CmdRunner, args: AddAppWs) -> anyhow::Result<u16> {
+/// Returns the port and, if the interface serves TLS, its certificate digest.
+pub async fn attach_app_interface(
+    cmd: &mut CmdRunner,
+    args: AddAppWs,
+) -> anyhow::Result<(u16, Option<String>)> {
     let resp = cmd
-        .command(AdminRequest::AttachAppInterface { port: args.port })
+        .command(AdminRequest::AttachAppInterface {
+            port: args.port,
+            require_auth: args.require_auth,
+            path: args.socket.clone(),
+            tls: if args.tls {
+                Some(InterfaceTlsConfig::default())
+            } else {
+                None
+            },
+        })
         .await?;
     tracing::debug!(?resp);
     match resp {
-        AdminResponse::AppInterfaceAttached { port } => Ok(port),
+        AdminResponse::AppInterfaceAttached {
+            port,
+            tls_cert_digest,
+            ..
+        } => Ok((port, tls_cert_digest)),
         _ => Err(anyhow!(
             "Failed to attach app interface {:?}, got: {:?}",
             args.port,
@@ -668,14 +813,26 @@ pub async fn request_agent_info(
     Ok(expect_match!(resp => AdminResponse::AgentInfoRequested, "Failed to request agent info"))
 }
 
-fn parse_agent_key(arg: &str) -> anyhow::Result<AgentPubKey> {
+pub(crate) fn parse_agent_key(arg: &str) -> anyhow::Result<AgentPubKey> {
     AgentPubKey::try_from(arg).map_err(|e| anyhow::anyhow!("{:?}", e))
 }
 
-fn parse_dna_hash(arg: &str) -> anyhow::Result<DnaHash> {
+pub(crate) fn parse_dna_hash(arg: &str) -> anyhow::Result<DnaHash> {
     DnaHash::try_from(arg).map_err(|e| anyhow::anyhow!("{:?}", e))
 }
 
+fn parse_existing_cell(arg: &str) -> anyhow::Result<(AppRoleId, DnaHash)> {
+    match arg.split_once('=') {
+        Some((role_id, dna)) if !role_id.is_empty() => {
+            Ok((role_id.to_string(), parse_dna_hash(dna)?))
+        }
+        _ => Err(anyhow::anyhow!(
+            "Bad existing cell: {}, expected ROLE_ID=DNA_HASH",
+            arg
+        )),
+    }
+}
+
 fn parse_status_filter(arg: &str) -> anyhow::Result<AppStatusFilter> {
     match arg {
         "active" => Ok(AppStatusFilter::Enabled),
@@ -68,6 +68,21 @@ pub enum HcSandboxSubcommand {
     /// Make a call to a conductor's admin interface.
     Call(crate::calls::Call),
 
+    /// Call a zome function on a conductor and print the response.
+    ///
+    /// The cell is chosen by app id, and role id if the app has more than one cell,
+    /// or by dna hash and agent key.
+    /// For example `hc zome-call --app-id test-app posts create_post '{"title": "hi"}'`
+    ZomeCall(crate::zome_call::ZomeCall),
+
+    /// Inspect a sandbox's databases without running its conductor.
+    ///
+    /// Prints the source chains, the ops in each stage of validation and integration,
+    /// the links by base and the peer store of each dna.
+    /// The databases are only read so this works on crashed conductors.
+    /// For example `hc inspect --sandbox 1 --ops --op-type StoreEntry`
+    Inspect(crate::inspect::Inspect),
+
     /// List sandboxes found in `$(pwd)/.hc`.
     List {
         /// Show more verbose information.
@@ -149,6 +164,10 @@ impl HcSandbox {
             HcSandboxSubcommand::Call(call) => {
                 crate::calls::call(&self.holochain_path, call).await?
             }
+            HcSandboxSubcommand::ZomeCall(zome_call) => {
+                crate::zome_call::zome_call(&self.holochain_path, zome_call).await?
+            }
+            HcSandboxSubcommand::Inspect(inspect) => crate::inspect::inspect(inspect).await?,
             // HcSandboxSubcommand::Task => todo!("Running custom tasks is coming soon"),
             HcSandboxSubcommand::List { verbose } => {
                 crate::save::list(std::env::current_dir()?, verbose)?
@@ -0,0 +1,702 @@
+//! Inspect a sandbox's databases without running its conductor.
+//!
+//! The databases are opened read-only so this is safe to use on the
+//! databases of a conductor that crashed or is still running.
+//! Everything is printed per dna: the source chains from the authored
+//! database, the ops in each stage of the dht and cache databases,
+//! the links in the dht database by base and the peer store.
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use holochain_p2p::AgentPubKeyExt;
+use holochain_p2p::DnaHashExt;
+use holochain_sqlite::db::AsP2pStateTxExt;
+use holochain_sqlite::db::DbKindAuthored;
+use holochain_sqlite::db::DbKindCache;
+use holochain_sqlite::db::DbKindDht;
+use holochain_sqlite::db::DbKindP2pAgentStore;
+use holochain_sqlite::db::DbKindT;
+use holochain_sqlite::db::DbRead;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_state::query::from_blob;
+use holochain_state::query::StateQueryResult;
+use holochain_state::validation_db::ValidationLimboStatus;
+use holochain_types::prelude::AgentPubKey;
+use holochain_types::prelude::DhtOpHash;
+use holochain_types::prelude::DhtOpType;
+use holochain_types::prelude::DnaHash;
+use holochain_types::prelude::Entry;
+use holochain_types::prelude::EntryDefIndex;
+use holochain_types::prelude::EntryHash;
+use holochain_types::prelude::EntryType;
+use holochain_types::prelude::ExternIO;
+use holochain_types::prelude::Header;
+use holochain_types::prelude::HeaderHash;
+use holochain_types::prelude::HeaderType;
+use holochain_types::prelude::SignedHeader;
+use holochain_types::prelude::ValidationStatus;
+use holochain_types::prelude::ZomeId;
+use structopt::StructOpt;
+
+use crate::calls::parse_agent_key;
+use crate::calls::parse_dna_hash;
+
+#[doc(hidden)]
+#[derive(Debug, StructOpt)]
+pub struct Inspect {
+    #[structopt(short, long, default_value = "0", conflicts_with = "path")]
+    /// Index of the sandbox to inspect, as shown by `hc list`.
+    pub sandbox: usize,
+    #[structopt(long)]
+    /// Path to a sandbox directory to inspect instead.
+    pub path: Option<PathBuf>,
+    #[structopt(long, parse(try_from_str = parse_dna_hash))]
+    /// Only inspect this dna's databases.
+    pub dna: Option<DnaHash>,
+    #[structopt(long, parse(try_from_str = parse_agent_key))]
+    /// Only show this agent's source chain, headers, links and peer info.
+    pub agent: Option<AgentPubKey>,
+    #[structopt(long)]
+    /// Only show elements and ops for this entry type.
+    /// One of `AgentPubKey`, `CapClaim`, `CapGrant`
+    /// or `<zome id>:<entry def index>` for an app entry type, e.g. `0:2`.
+    pub entry_type: Option<EntryTypeFilter>,
+    #[structopt(long)]
+    /// Only show ops of this type, e.g. `StoreEntry` or `RegisterAddLink`.
+    pub op_type: Option<DhtOpType>,
+    #[structopt(long)]
+    /// Show the source chains.
+    pub chain: bool,
+    #[structopt(long)]
+    /// Show the ops in each stage of validation and integration.
+    pub ops: bool,
+    #[structopt(long)]
+    /// Show the links by base.
+    pub links: bool,
+    #[structopt(long)]
+    /// Show the peer store.
+    pub peers: bool,
+}
+
+impl Inspect {
+    /// If no sections are chosen they are all shown.
+    fn show_all(&self) -> bool {
+        !(self.chain || self.ops || self.links || self.peers)
+    }
+}
+
+/// Matches the entry types of headers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntryTypeFilter {
+    AgentPubKey,
+    CapClaim,
+    CapGrant,
+    App(ZomeId, EntryDefIndex),
+}
+
+impl EntryTypeFilter {
+    fn matches(&self, entry_type: Option<&EntryType>) -> bool {
+        match (self, entry_type) {
+            (Self::AgentPubKey, Some(EntryType::AgentPubKey))
+            | (Self::CapClaim, Some(EntryType::CapClaim))
+            | (Self::CapGrant, Some(EntryType::CapGrant)) => true,
+            (Self::App(zome_id, id), Some(EntryType::App(aet))) => {
+                aet.zome_id() == *zome_id && aet.id() == *id
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for EntryTypeFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "AgentPubKey" => Ok(Self::AgentPubKey),
+            "CapClaim" => Ok(Self::CapClaim),
+            "CapGrant" => Ok(Self::CapGrant),
+            _ => {
+                let (zome_id, id) = s.split_once(':').ok_or_else(|| {
+                    anyhow!(
+                        "Bad entry type: {}, expected AgentPubKey, CapClaim, CapGrant or <zome id>:<entry def index>",
+                        s
+                    )
+                })?;
+                Ok(Self::App(
+                    ZomeId::from(zome_id.parse::<u8>()?),
+                    EntryDefIndex(id.parse()?),
+                ))
+            }
+        }
+    }
+}
+
+/// Print what is in the sandbox's databases.
+pub async fn inspect(args: Inspect) -> anyhow::Result<()> {
+    let sandbox = match &args.path {
+        Some(path) => path.clone(),
+        None => crate::save::load(std::env::current_dir()?)?
+            .into_iter()
+            .nth(args.sandbox)
+            .ok_or_else(|| anyhow!("There is no sandbox with the index {}", args.sandbox))?,
+    };
+    print!("{}", report(&sandbox, &args).await?);
+    Ok(())
+}
+
+/// Render what is in the sandbox's databases.
+async fn report(sandbox: &Path, args: &Inspect) -> anyhow::Result<String> {
+    let mut out = String::new();
+    let config = crate::config::read_config(sandbox.to_path_buf())?
+        .ok_or_else(|| anyhow!("{} is not a sandbox", sandbox.display()))?;
+    let env_path: PathBuf = config.environment_path.into();
+    let env_path = sandbox.join(env_path);
+
+    let dnas = match &args.dna {
+        Some(dna) => vec![dna.clone()],
+        None => find_dnas(&env_path)?,
+    };
+    if dnas.is_empty() {
+        writeln!(out, "No databases found in {}", env_path.display())?;
+    }
+    for dna in dnas {
+        writeln!(out, "=== DNA {} ===", dna)?;
+        let dna = Arc::new(dna);
+        if args.chain || args.show_all() {
+            if let Some(db) = open(&env_path, DbKindAuthored(dna.clone()))? {
+                print_source_chains(&mut out, db, args).await?;
+            }
+        }
+        if args.ops || args.show_all() {
+            if let Some(db) = open(&env_path, DbKindDht(dna.clone()))? {
+                writeln!(out, "--- DHT ops ---")?;
+                print_ops(&mut out, db, args).await?;
+            }
+            if let Some(db) = open(&env_path, DbKindCache(dna.clone()))? {
+                writeln!(out, "--- Cached ops ---")?;
+                print_ops(&mut out, db, args).await?;
+            }
+        }
+        if args.links || args.show_all() {
+            if let Some(db) = open(&env_path, DbKindDht(dna.clone()))? {
+                print_links(&mut out, db, args).await?;
+            }
+        }
+        if args.peers || args.show_all() {
+            if let Some(db) = open(&env_path, DbKindP2pAgentStore(dna.to_kitsune()))? {
+                print_peers(&mut out, db, args).await?;
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// The dnas that have an authored or dht database.
+fn find_dnas(env_path: &Path) -> anyhow::Result<Vec<DnaHash>> {
+    let mut dnas = BTreeSet::new();
+    for (dir, prefix) in &[("authored", "authored-"), ("dht", "dht-")] {
+        let dir = env_path.join(dir);
+        if !dir.is_dir() {
+            continue;
+        }
+        for entry in std::fs::read_dir(dir)? {
+            let name = entry?.file_name();
+            let dna = name
+                .to_str()
+                .and_then(|name| name.strip_prefix(prefix))
+                .and_then(|name| name.strip_suffix(".sqlite3"));
+            if let Some(dna) = dna {
+                dnas.insert(parse_dna_hash(dna)?);
+            }
+        }
+    }
+    Ok(dnas.into_iter().collect())
+}
+
+/// Open a database read-only if it exists.
+fn open<Kind: DbKindT>(env_path: &Path, kind: Kind) -> anyhow::Result<Option<DbRead<Kind>>> {
+    if !env_path.join(kind.filename()).is_file() {
+        return Ok(None);
+    }
+    Ok(Some(DbRead::open_read_only(env_path, kind)?))
+}
+
+async fn print_source_chains(
+    out: &mut String,
+    db: DbRead<DbKindAuthored>,
+    args: &Inspect,
+) -> anyhow::Result<()> {
+    let author = args.agent.clone();
+    let elements = db
+        .async_reader(move |txn| {
+            txn.prepare(
+                "
+                SELECT Header.blob AS header_blob, Header.hash AS header_hash,
+                Entry.blob AS entry_blob
+                FROM Header
+                LEFT JOIN Entry ON Header.entry_hash = Entry.hash
+                WHERE :author IS NULL OR Header.author = :author
+                ORDER BY Header.author, Header.seq ASC
+                ",
+            )?
+            .query_and_then(named_params! { ":author": author }, |row| {
+                let SignedHeader(header, _) = from_blob(row.get("header_blob")?)?;
+                let hash: HeaderHash = row.get("header_hash")?;
+                let entry: Option<Vec<u8>> = row.get("entry_blob")?;
+                let entry: Option<Entry> = match entry {
+                    Some(entry) => Some(from_blob(entry)?),
+                    None => None,
+                };
+                StateQueryResult::Ok((header, hash, entry))
+            })?
+            .collect::<StateQueryResult<Vec<_>>>()
+        })
+        .await?;
+
+    let mut chains: BTreeMap<AgentPubKey, Vec<_>> = BTreeMap::new();
+    for (header, hash, entry) in elements {
+        chains
+            .entry(header.author().clone())
+            .or_default()
+            .push((header, hash, entry));
+    }
+    for (author, elements) in chains {
+        writeln!(
+            out,
+            "--- Source chain of {} ({} elements) ---",
+            author,
+            elements.len()
+        )?;
+        for (header, hash, entry) in elements {
+            if let Some(filter) = &args.entry_type {
+                if !filter.matches(header.entry_type()) {
+                    continue;
+                }
+            }
+            writeln!(
+                out,
+                "{:>4} {} {} at {}{}",
+                header.header_seq(),
+                header_type(&header),
+                hash,
+                header.timestamp(),
+                entry_type(&header)
+            )?;
+            if let Some(entry) = entry {
+                writeln!(out, "{}", indent(&render_entry(&entry)))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Where an op is on its way to being integrated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum OpStage {
+    PendingValidation,
+    AwaitingSysValidationDeps,
+    SysValidated,
+    AwaitingAppValidationDeps,
+    IntegrationLimbo,
+    Integrated,
+}
+
+impl OpStage {
+    fn new(validation_stage: Option<i64>, integrated: bool) -> Self {
+        if integrated {
+            return Self::Integrated;
+        }
+        match validation_stage {
+            None => Self::PendingValidation,
+            Some(ValidationLimboStatus::AWAITING_SYS_DEPS_STAGE) => Self::AwaitingSysValidationDeps,
+            Some(ValidationLimboStatus::SYS_VALIDATED_STAGE) => Self::SysValidated,
+            Some(ValidationLimboStatus::AWAITING_APP_DEPS_STAGE) => Self::AwaitingAppValidationDeps,
+            Some(_) => Self::IntegrationLimbo,
+        }
+    }
+}
+
+impl std::fmt::Display for OpStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::PendingValidation => "Validation limbo: pending validation",
+            Self::AwaitingSysValidationDeps => {
+                "Validation limbo: awaiting sys validation dependencies"
+            }
+            Self::SysValidated => "Validation limbo: sys validated, pending app validation",
+            Self::AwaitingAppValidationDeps => {
+                "Validation limbo: awaiting app validation dependencies"
+            }
+            Self::IntegrationLimbo => "Integration limbo",
+            Self::Integrated => "Integrated",
+        })
+    }
+}
+
+struct OpRow {
+    hash: DhtOpHash,
+    op_type: DhtOpType,
+    header: Header,
+    validation_status: Option<ValidationStatus>,
+    validation_attempts: Option<i64>,
+}
+
+async fn print_ops<Kind: DbKindT>(
+    out: &mut String,
+    db: DbRead<Kind>,
+    args: &Inspect,
+) -> anyhow::Result<()> {
+    let author = args.agent.clone();
+    let op_type = args.op_type.clone();
+    let ops = db
+        .async_reader(move |txn| {
+            txn.prepare(
+                "
+                SELECT DhtOp.hash, DhtOp.type, DhtOp.validation_stage,
+                DhtOp.validation_status, DhtOp.when_integrated,
+                DhtOp.num_validation_attempts, Header.blob AS header_blob
+                FROM DhtOp
+                JOIN Header ON DhtOp.header_hash = Header.hash
+                WHERE (:author IS NULL OR Header.author = :author)
+                AND (:op_type IS NULL OR DhtOp.type = :op_type)
+                ORDER BY DhtOp.authored_timestamp ASC
+                ",
+            )?
+            .query_and_then(
+                named_params! { ":author": author, ":op_type": op_type },
+                |row| {
+                    let SignedHeader(header, _) = from_blob(row.get("header_blob")?)?;
+                    let when_integrated: Option<i64> = row.get("when_integrated")?;
+                    let stage =
+                        OpStage::new(row.get("validation_stage")?, when_integrated.is_some());
+                    StateQueryResult::Ok((
+                        stage,
+                        OpRow {
+                            hash: row.get("hash")?,
+                            op_type: row.get("type")?,
+                            header,
+                            validation_status: row.get("validation_status")?,
+                            validation_attempts: row.get("num_validation_attempts")?,
+                        },
+                    ))
+                },
+            )?
+            .collect::<StateQueryResult<Vec<_>>>()
+        })
+        .await?;
+
+    let mut stages: BTreeMap<OpStage, Vec<OpRow>> = BTreeMap::new();
+    for (stage, op) in ops {
+        if let Some(filter) = &args.entry_type {
+            if !filter.matches(op.header.entry_type()) {
+                continue;
+            }
+        }
+        stages.entry(stage).or_default().push(op);
+    }
+    if stages.is_empty() {
+        writeln!(out, "No ops")?;
+    }
+    for (stage, ops) in stages {
+        writeln!(out, "{} ({} ops)", stage, ops.len())?;
+        for op in ops {
+            let status = match op.validation_status {
+                Some(status) => format!(" {:?}", status),
+                None => String::new(),
+            };
+            let attempts = match op.validation_attempts {
+                Some(n) if n > 0 => format!(" after {} validation attempts", n),
+                _ => String::new(),
+            };
+            writeln!(
+                out,
+                "  {} {}{}{}\n    {} {} by {}{}",
+                op.op_type,
+                op.hash,
+                status,
+                attempts,
+                header_type(&op.header),
+                op.header.header_seq(),
+                op.header.author(),
+                entry_type(&op.header),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+async fn print_links(
+    out: &mut String,
+    db: DbRead<DbKindDht>,
+    args: &Inspect,
+) -> anyhow::Result<()> {
+    let author = args.agent.clone();
+    let links = db
+        .async_reader(move |txn| {
+            txn.prepare(
+                "
+                SELECT Header.blob AS header_blob, Header.hash AS header_hash,
+                EXISTS(
+                    SELECT 1 FROM Header AS DeleteLink
+                    WHERE DeleteLink.type = :delete_link
+                    AND DeleteLink.create_link_hash = Header.hash
+                ) AS deleted
+                FROM Header
+                WHERE Header.type = :create_link
+                AND (:author IS NULL OR Header.author = :author)
+                ORDER BY Header.base_hash
+                ",
+            )?
+            .query_and_then(
+                named_params! {
+                    ":create_link": HeaderType::CreateLink,
+                    ":delete_link": HeaderType::DeleteLink,
+                    ":author": author,
+                },
+                |row| {
+                    let SignedHeader(header, _) = from_blob(row.get("header_blob")?)?;
+                    let hash: HeaderHash = row.get("header_hash")?;
+                    let deleted: bool = row.get("deleted")?;
+                    StateQueryResult::Ok((header, hash, deleted))
+                },
+            )?
+            .collect::<StateQueryResult<Vec<_>>>()
+        })
+        .await?;
+
+    let mut bases: BTreeMap<EntryHash, Vec<_>> = BTreeMap::new();
+    for (header, hash, deleted) in links {
+        if let Header::CreateLink(link) = header {
+            bases
+                .entry(link.base_address.clone())
+                .or_default()
+                .push((link, hash, deleted));
+        }
+    }
+    writeln!(out, "--- Links ({} bases) ---", bases.len())?;
+    for (base, links) in bases {
+        writeln!(out, "{}", base)?;
+        for (link, hash, deleted) in links {
+            writeln!(
+                out,
+                "  -> {} zome {} tag {}{}\n     by {} in {}",
+                link.target_address,
+                u8::from(link.zome_id),
+                render_tag(&link.tag.0),
+                if deleted { " (deleted)" } else { "" },
+                link.author,
+                hash
+            )?;
+        }
+    }
+    Ok(())
+}
+
+async fn print_peers(
+    out: &mut String,
+    db: DbRead<DbKindP2pAgentStore>,
+    args: &Inspect,
+) -> anyhow::Result<()> {
+    let agent = args.agent.clone().map(|agent| agent.to_kitsune());
+    let peers = db
+        .async_reader(|txn| txn.p2p_list_agents())
+        .await?
+        .into_iter()
+        .filter(|info| agent.as_ref().map_or(true, |agent| info.agent == *agent));
+    writeln!(out, "--- Peer store ---")?;
+    for info in peers {
+        writeln!(
+            out,
+            "{}\n  signed at {}, expires at {}\n  arc {:?}\n  urls {:?}",
+            AgentPubKey::from_kitsune(&info.agent),
+            millis_to_timestamp(info.signed_at_ms),
+            millis_to_timestamp(info.expires_at_ms),
+            info.storage_arc,
+            info.url_list,
+        )?;
+    }
+    Ok(())
+}
+
+/// The header type without the trailing new line its `Display` adds.
+fn header_type(header: &Header) -> String {
+    header.header_type().to_string().trim_end().to_string()
+}
+
+fn entry_type(header: &Header) -> String {
+    match header.entry_type() {
+        Some(EntryType::App(aet)) => format!(
+            " entry {}:{} {:?}",
+            u8::from(aet.zome_id()),
+            aet.id().0,
+            aet.visibility()
+        ),
+        Some(entry_type) => format!(" entry {}", entry_type.to_string().trim_end()),
+        None => String::new(),
+    }
+}
+
+/// App entries are decoded from msgpack, system entries are debug printed.
+fn render_entry(entry: &Entry) -> String {
+    let app_entry = match entry {
+        Entry::App(bytes) | Entry::CounterSign(_, bytes) => bytes,
+        _ => return format!("{:?}", entry),
+    };
+    let bytes = ExternIO(app_entry.bytes().clone());
+    crate::zome_call::decode_response(&bytes).unwrap_or_else(|e| format!("{:?}: {}", bytes, e))
+}
+
+/// Tags are usually text so show them as text if they are.
+fn render_tag(tag: &[u8]) -> String {
+    match std::str::from_utf8(tag) {
+        Ok(tag) => format!("{:?}", tag),
+        Err(_) => base64::encode(tag),
+    }
+}
+
+fn millis_to_timestamp(ms: u64) -> holochain_types::prelude::Timestamp {
+    holochain_types::prelude::Timestamp::from_micros(ms as i64 * 1000)
+}
+
+fn indent(s: &str) -> String {
+    s.trim_start_matches("---\n")
+        .trim_end()
+        .lines()
+        .map(|line| format!("       {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_sqlite::db::DbWrite;
+    use holochain_state::mutations::*;
+    use holochain_types::prelude::*;
+
+    fn inspect_args() -> Inspect {
+        Inspect {
+            sandbox: 0,
+            path: None,
+            dna: None,
+            agent: None,
+            entry_type: None,
+            op_type: None,
+            chain: false,
+            ops: false,
+            links: false,
+            peers: false,
+        }
+    }
+
+    async fn commit(db: &DbWrite<impl DbKindT>, ops: Vec<DhtOpHashed>) -> Vec<DhtOpHash> {
+        db.async_commit(move |txn| {
+            let mut hashes = Vec::new();
+            for op in ops {
+                hashes.push(op.as_hash().clone());
+                insert_op(txn, op)?;
+            }
+            StateMutationResult::Ok(hashes)
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn inspect_sandbox_with_a_committed_entry() {
+        let sandbox = tempdir::TempDir::new("inspect").unwrap();
+        let env_path = sandbox.path().join("env");
+        crate::config::write_config(
+            sandbox.path().to_path_buf(),
+            &crate::config::create_config(env_path.clone()),
+        );
+        let dna = Arc::new(DnaHash::from_raw_32(vec![1; 32]));
+        let agent = AgentPubKey::from_raw_32(vec![2; 32]);
+
+        let entry = Entry::app(SerializedBytes::from(UnsafeBytes::from(
+            ExternIO::encode("hello").unwrap().0,
+        )))
+        .unwrap();
+        let entry_hash = EntryHash::with_data_sync(&entry);
+        let create = Create {
+            author: agent.clone(),
+            timestamp: Timestamp::from_micros(1),
+            header_seq: 3,
+            prev_header: HeaderHash::from_raw_32(vec![3; 32]),
+            entry_type: EntryType::App(AppEntryType::new(
+                EntryDefIndex(2),
+                ZomeId::from(0),
+                EntryVisibility::Public,
+            )),
+            entry_hash: entry_hash.clone(),
+        };
+        let create_link = CreateLink {
+            author: agent.clone(),
+            timestamp: Timestamp::from_micros(2),
+            header_seq: 4,
+            prev_header: HeaderHash::with_data_sync(&Header::Create(create.clone())),
+            base_address: entry_hash.clone(),
+            target_address: entry_hash.clone(),
+            zome_id: ZomeId::from(0),
+            tag: LinkTag::new("a tag"),
+        };
+        let ops = || {
+            vec![
+                DhtOpHashed::from_content_sync(DhtOp::StoreEntry(
+                    Signature([0; 64]),
+                    NewEntryHeader::Create(create.clone()),
+                    Box::new(entry.clone()),
+                )),
+                DhtOpHashed::from_content_sync(DhtOp::RegisterAddLink(
+                    Signature([0; 64]),
+                    create_link.clone(),
+                )),
+            ]
+        };
+
+        let authored = DbWrite::open(&env_path, DbKindAuthored(dna.clone())).unwrap();
+        commit(&authored, ops()).await;
+        // The entry is sys validated and the link is integrated.
+        let dht = DbWrite::open(&env_path, DbKindDht(dna.clone())).unwrap();
+        let hashes = commit(&dht, ops()).await;
+        dht.async_commit(move |txn| {
+            set_validation_stage(txn, hashes[0].clone(), ValidationLimboStatus::SysValidated)?;
+            set_when_integrated(txn, hashes[1].clone(), Timestamp::from_micros(3))
+        })
+        .await
+        .unwrap();
+
+        let output = report(sandbox.path(), &inspect_args()).await.unwrap();
+        assert!(output.contains(&format!("=== DNA {} ===", dna)));
+
+        // - The source chain has both elements with the decoded entry.
+        assert!(output.contains(&format!("--- Source chain of {} (2 elements) ---", agent)));
+        assert!(output.contains(" entry 0:2 Public"));
+        assert!(output.contains("hello"));
+
+        // - The ops are grouped by stage.
+        assert!(output.contains("Validation limbo: sys validated, pending app validation (1 ops)"));
+        assert!(output.contains("Integrated (1 ops)"));
+
+        // - The link is listed under its base.
+        assert!(output.contains("--- Links (1 bases) ---"));
+        assert!(output.contains(&format!("  -> {} zome 0 tag \"a tag\"", entry_hash)));
+
+        // - Sections can be chosen on their own.
+        let args = Inspect {
+            links: true,
+            ..inspect_args()
+        };
+        let output = report(sandbox.path(), &args).await.unwrap();
+        assert!(output.contains("--- Links (1 bases) ---"));
+        assert!(!output.contains("Source chain"));
+        assert!(!output.contains("Integrated"));
+    }
+}
@@ -114,9 +114,11 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use holochain_conductor_api::{AdminRequest, AdminResponse};
+use holochain_conductor_api::{SessionToken, ADMIN_TOKEN_FILE};
 use holochain_websocket::WebsocketResult;
 use holochain_websocket::WebsocketSender;
 use ports::get_admin_api;
+use ports::get_admin_api_by_socket;
 
 pub use ports::force_admin_port;
 
@@ -137,9 +139,11 @@ pub mod cli;
 pub mod cmds;
 pub mod config;
 pub mod generate;
+pub mod inspect;
 pub mod run;
 pub mod sandbox;
 pub mod save;
+pub mod zome_call;
 pub use cli::HcSandbox;
 
 mod ports;
@@ -165,6 +169,13 @@ impl CmdRunner {
         Ok(Self { client })
     }
 
+    /// Create a new connection for calling admin interface commands
+    /// over the unix domain socket at this path.
+    pub async fn try_from_socket(path: &Path) -> WebsocketResult<Self> {
+        let client = get_admin_api_by_socket(path).await?;
+        Ok(Self { client })
+    }
+
     /// Create a command runner from a sandbox path.
     /// This expects holochain to be on the path.
     pub async fn from_sandbox(
@@ -179,11 +190,56 @@ impl CmdRunner {
         holochain_bin_path: &Path,
         sandbox_path: PathBuf,
     ) -> anyhow::Result<(Self, tokio::process::Child)> {
-        let conductor = run::run_async(holochain_bin_path, sandbox_path, None).await?;
-        let cmd = CmdRunner::try_new(conductor.0).await?;
+        let conductor = run::run_async(holochain_bin_path, sandbox_path.clone(), None).await?;
+        let mut cmd = CmdRunner::try_new(conductor.0).await?;
+        cmd.authenticate_sandbox(&sandbox_path).await?;
         Ok((cmd, conductor.1))
     }
 
+    /// Authenticate with the admin token that the sandbox's conductor writes
+    /// to its environment directory, if its admin interface requires it.
+    pub async fn authenticate_sandbox(&mut self, sandbox_path: &Path) -> anyhow::Result<()> {
+        let config = match config::read_config(sandbox_path.to_path_buf())? {
+            Some(config) => config,
+            None => return Ok(()),
+        };
+        let require_auth = config
+            .admin_interfaces
+            .as_ref()
+            .and_then(|ai| ai.first())
+            .map_or(false, |ai| ai.require_auth);
+        if !require_auth {
+            return Ok(());
+        }
+        let env_path: PathBuf = config.environment_path.into();
+        self.authenticate_with_token_file(&env_path.join(ADMIN_TOKEN_FILE))
+            .await
+    }
+
+    /// Authenticate with the admin token in a file,
+    /// e.g. the `admin_token` file a conductor writes to its environment directory.
+    pub async fn authenticate_with_token_file(&mut self, token_path: &Path) -> anyhow::Result<()> {
+        let token = std::fs::read_to_string(token_path).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to read the admin token at {}: {}",
+                token_path.display(),
+                e
+            )
+        })?;
+        let resp = self
+            .command(AdminRequest::Authenticate {
+                token: SessionToken::new(token),
+            })
+            .await?;
+        anyhow::ensure!(
+            matches!(resp, AdminResponse::Authenticated),
+            "Failed to authenticate with the admin token at {}, got: {:?}",
+            token_path.display(),
+            resp
+        );
+        Ok(())
+    }
+
     /// Make an Admin request to this conductor.
     pub async fn command(&mut self, cmd: AdminRequest) -> anyhow::Result<AdminResponse> {
         let response: Result<AdminResponse, _> = self.client.request(cmd).await;
@@ -1,4 +1,5 @@
 //! Helpers for working with websockets and ports.
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -33,6 +34,7 @@ pub async fn get_admin_ports(paths: Vec<PathBuf>) -> anyhow::Result<Vec<u16>> {
             if let Some(ai) = config.admin_interfaces {
                 if let Some(AdminInterfaceConfig {
                     driver: InterfaceDriver::Websocket { port },
+                    ..
                 }) = ai.get(0)
                 {
                     ports.push(*port)
@@ -43,11 +45,44 @@ pub async fn get_admin_ports(paths: Vec<PathBuf>) -> anyhow::Result<Vec<u16>> {
     Ok(ports)
 }
 
+/// The path of the unix domain socket the sandbox's first admin interface
+/// listens on, if it uses one.
+pub fn get_admin_socket(sandbox_path: PathBuf) -> anyhow::Result<Option<PathBuf>> {
+    Ok(read_config(sandbox_path)?
+        .and_then(|config| config.admin_interfaces)
+        .and_then(|ai| ai.into_iter().next())
+        .and_then(|ai| ai.driver.path().map(Path::to_path_buf)))
+}
+
 pub(crate) async fn get_admin_api(port: u16) -> WebsocketResult<WebsocketSender> {
     tracing::debug!(port);
     websocket_client_by_port(port).await.map(|p| p.0)
 }
 
+pub(crate) async fn get_admin_api_by_socket(path: &Path) -> WebsocketResult<WebsocketSender> {
+    tracing::debug!(socket = %path.display());
+    Ok(ws::connect(
+        ws::unix_socket_url(path)?,
+        Arc::new(WebsocketConfig::default()),
+    )
+    .await?
+    .0)
+}
+
+/// Connect to an app interface, pinning its certificate if it serves TLS.
+pub(crate) async fn get_app_api(
+    port: u16,
+    cert_digest: Option<ws::CertDigest>,
+) -> WebsocketResult<WebsocketSender> {
+    tracing::debug!(app_port = port);
+    let config = WebsocketConfig::default();
+    let (url, config) = match cert_digest {
+        Some(pin) => (url2!("wss://127.0.0.1:{}", port), config.tls_pin(pin)),
+        None => (url2!("ws://127.0.0.1:{}", port), config),
+    };
+    Ok(ws::connect(url, Arc::new(config)).await?.0)
+}
+
 async fn websocket_client_by_port(
     port: u16,
 ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
@@ -62,15 +97,24 @@ pub(crate) fn random_admin_port(config: &mut ConductorConfig) {
     match config.admin_interfaces.as_mut().and_then(|i| i.first_mut()) {
         Some(AdminInterfaceConfig {
             driver: InterfaceDriver::Websocket { port },
+            ..
         }) => {
             if *port != 0 {
                 *port = 0;
             }
         }
+        // Sockets don't collide like ports so there's nothing to change.
+        Some(AdminInterfaceConfig {
+            driver: InterfaceDriver::UnixSocket { .. },
+            ..
+        }) => (),
         None => {
             let port = 0;
             config.admin_interfaces = Some(vec![AdminInterfaceConfig {
                 driver: InterfaceDriver::Websocket { port },
+                require_auth: true,
+                session_duration_seconds: None,
+                tls: None,
             }]);
         }
     }
@@ -80,6 +124,9 @@ pub(crate) fn set_admin_port(config: &mut ConductorConfig, port: u16) {
     let p = port;
     let port = AdminInterfaceConfig {
         driver: InterfaceDriver::Websocket { port },
+        require_auth: true,
+        session_duration_seconds: None,
+        tls: None,
     };
     match config
         .admin_interfaces
@@ -87,7 +134,7 @@ pub(crate) fn set_admin_port(config: &mut ConductorConfig, port: u16) {
         .and_then(|ai| ai.get_mut(0))
     {
         Some(admin_interface) => {
-            *admin_interface = port;
+            admin_interface.driver = port.driver;
         }
         None => config.admin_interfaces = Some(vec![port]),
     }
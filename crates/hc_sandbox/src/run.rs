@@ -2,6 +2,7 @@
 use std::path::Path;
 use std::{path::PathBuf, process::Stdio};
 
+use holochain_conductor_api::InterfaceDriver;
 use tokio::io::AsyncBufReadExt;
 use tokio::io::BufReader;
 use tokio::process::{Child, Command};
@@ -10,6 +11,7 @@ use tokio::sync::oneshot;
 use crate::calls::attach_app_interface;
 use crate::calls::AddAppWs;
 use crate::config::*;
+use crate::ports::get_admin_socket;
 use crate::ports::random_admin_port;
 use crate::ports::set_admin_port;
 use crate::CmdRunner;
@@ -29,22 +31,38 @@ pub async fn run(
     app_ports: Vec<u16>,
     force_admin_port: Option<u16>,
 ) -> anyhow::Result<()> {
-    let (port, mut holochain) =
-        run_async(holochain_path, sandbox_path.clone(), force_admin_port).await?;
-    msg!("Running conductor on admin port {}", port);
+    let (driver, mut holochain) =
+        run_async_admin(holochain_path, sandbox_path.clone(), force_admin_port).await?;
+    let mut cmd = match &driver {
+        InterfaceDriver::Websocket { port } => {
+            msg!("Running conductor on admin port {}", port);
+            CmdRunner::try_new(*port).await?
+        }
+        InterfaceDriver::UnixSocket { path } => {
+            msg!("Running conductor on admin socket {}", path.display());
+            CmdRunner::try_from_socket(path).await?
+        }
+    };
+    cmd.authenticate_sandbox(&sandbox_path).await?;
     for app_port in app_ports {
         msg!("Attaching app port {}", app_port);
-        let mut cmd = CmdRunner::try_new(port).await?;
-        let port = attach_app_interface(
+        let (port, _) = attach_app_interface(
             &mut cmd,
             AddAppWs {
                 port: Some(app_port),
+                require_auth: false,
+                socket: None,
+                tls: false,
             },
         )
         .await?;
         msg!("App port attached at {}", port);
     }
-    crate::save::lock_live(std::env::current_dir()?, &sandbox_path, port).await?;
+    // Sandboxes with an admin socket are found through their config,
+    // so only admin ports need to be recorded.
+    if let Some(port) = driver.port() {
+        crate::save::lock_live(std::env::current_dir()?, &sandbox_path, port).await?;
+    }
     msg!("Connected successfully to a running holochain");
     let e = format!("Failed to run holochain at {}", sandbox_path.display());
 
@@ -59,11 +77,36 @@ pub async fn run(
 /// Can optionally force the admin port used. Otherwise
 /// the port in the config will be used if it's free or
 /// a random free port will be chosen.
+/// Sandboxes whose admin interface is a unix socket have no admin port,
+/// use [`run_async_admin`] for them.
 pub async fn run_async(
     holochain_path: &Path,
     sandbox_path: PathBuf,
     force_admin_port: Option<u16>,
 ) -> anyhow::Result<(u16, Child)> {
+    if force_admin_port.is_none() {
+        if let Some(path) = get_admin_socket(sandbox_path.clone())? {
+            anyhow::bail!(
+                "The sandbox's admin interface is the unix socket at {} so it has no admin port",
+                path.display()
+            );
+        }
+    }
+    match run_async_admin(holochain_path, sandbox_path, force_admin_port).await? {
+        (InterfaceDriver::Websocket { port }, child) => Ok((port, child)),
+        (InterfaceDriver::UnixSocket { .. }, _) => {
+            unreachable!("Only sandboxes without an admin socket are run")
+        }
+    }
+}
+
+/// Same as [`run_async`] but also runs sandboxes whose admin interface is
+/// a unix socket. Returns the driver of the admin interface.
+pub async fn run_async_admin(
+    holochain_path: &Path,
+    sandbox_path: PathBuf,
+    force_admin_port: Option<u16>,
+) -> anyhow::Result<(InterfaceDriver, Child)> {
     let mut config = match read_config(sandbox_path.clone())? {
         Some(c) => c,
         None => create_config(sandbox_path.clone()),
@@ -74,6 +117,12 @@ pub async fn run_async(
         }
         None => random_admin_port(&mut config),
     }
+    let socket = config
+        .admin_interfaces
+        .as_ref()
+        .and_then(|ai| ai.first())
+        .and_then(|ai| ai.driver.path())
+        .map(Path::to_path_buf);
     let config_path = write_config(sandbox_path.clone(), &config);
     let (tx_config, rx_config) = oneshot::channel();
     let mut child = start_holochain(holochain_path, config_path, tx_config).await;
@@ -81,13 +130,19 @@ pub async fn run_async(
     let port = rx_config
         .await
         .expect("Failed to get admin port from conductor");
-    Ok((port, child))
+    let driver = match socket {
+        Some(path) => InterfaceDriver::UnixSocket { path },
+        None => InterfaceDriver::Websocket {
+            port: port.ok_or_else(|| anyhow::anyhow!("The conductor has no admin port"))?,
+        },
+    };
+    Ok((driver, child))
 }
 
 async fn start_holochain(
     holochain_path: &Path,
     config_path: PathBuf,
-    tx_config: oneshot::Sender<u16>,
+    tx_config: oneshot::Sender<Option<u16>>,
 ) -> Child {
     tracing::info!("\n\n----\nstarting holochain\n----\n\n");
     let mut cmd = Command::new(holochain_path);
@@ -114,7 +169,9 @@ async fn check_started(holochain: &mut Child) {
     }
 }
 
-fn spawn_output(holochain: &mut Child, config: oneshot::Sender<u16>) {
+/// Sends the admin port once the conductor prints it,
+/// or `None` if it finishes printing its setup without one.
+fn spawn_output(holochain: &mut Child, config: oneshot::Sender<Option<u16>>) {
     let stdout = holochain.stdout.take();
     let stderr = holochain.stderr.take();
     tokio::task::spawn(async move {
@@ -128,12 +185,21 @@ fn spawn_output(holochain: &mut Child, config: oneshot::Sender<u16>) {
                         (true, Some(port)) => {
                             if let Some(config) = config.take() {
                                 config
-                                    .send(port)
+                                    .send(Some(port))
                                     .expect("Failed to send admin port from config");
                             }
                             continue;
                         }
-                        (true, None) => continue,
+                        (true, None) => {
+                            if line == SETUP_END {
+                                if let Some(config) = config.take() {
+                                    config
+                                        .send(None)
+                                        .expect("Failed to send admin port from config");
+                                }
+                            }
+                            continue;
+                        }
                         (false, _) => (),
                     }
                 }
@@ -151,6 +217,9 @@ fn spawn_output(holochain: &mut Child, config: oneshot::Sender<u16>) {
     });
 }
 
+/// The last line of the setup the conductor prints when it has started.
+const SETUP_END: &str = "###HOLOCHAIN_SETUP_END###";
+
 fn check_sandbox(line: &str, needs_setup: &mut bool) -> (bool, Option<u16>) {
     if let Some(line) = line.strip_prefix("###") {
         if let Some(line) = line.strip_suffix("###") {
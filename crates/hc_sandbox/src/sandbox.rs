@@ -28,6 +28,7 @@ pub async fn default_with_network(
         agent_key: None,
         path: happ,
         uid: None,
+        existing_cell: vec![],
     };
     crate::calls::install_app_bundle(&mut cmd, install_bundle).await?;
     Ok(path)
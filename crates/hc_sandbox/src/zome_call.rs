@@ -0,0 +1,475 @@
+//! Call a zome function on a conductor from the command line.
+//!
+//! The payload is written as JSON or YAML and sent as msgpack,
+//! the same as a client would send it. Hashes are written in their
+//! base64 form in the payload and shown that way in the response,
+//! which is decoded without knowing its type and printed as YAML.
+use std::convert::TryFrom;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::ensure;
+use holochain_conductor_api::AdminRequest;
+use holochain_conductor_api::AdminResponse;
+use holochain_conductor_api::AppRequest;
+use holochain_conductor_api::AppResponse;
+use holochain_conductor_api::ExternalApiWireError;
+use holochain_conductor_api::InterfaceDriver;
+use holochain_conductor_api::SessionScope;
+use holochain_conductor_api::ZomeCall as ZomeCallRequest;
+use holochain_types::prelude::AgentPubKey;
+use holochain_types::prelude::AppRoleId;
+use holochain_types::prelude::CapSecret;
+use holochain_types::prelude::CellId;
+use holochain_types::prelude::DnaHash;
+use holochain_types::prelude::ExternIO;
+use holochain_types::prelude::FunctionName;
+use holochain_types::prelude::InstalledAppId;
+use holochain_types::prelude::ZomeName;
+use holochain_websocket::CertDigest;
+use holochain_websocket::WebsocketSender;
+use structopt::StructOpt;
+
+use crate::calls::connect_admins;
+use crate::calls::parse_agent_key;
+use crate::calls::parse_dna_hash;
+use crate::cmds::Existing;
+use crate::expect_match;
+use crate::ports::get_app_api;
+use crate::CmdRunner;
+
+/// How long the session token issued for an app interface
+/// that requires authentication is valid for.
+const SESSION_DURATION_SECONDS: u32 = 60;
+
+#[doc(hidden)]
+#[derive(Debug, StructOpt)]
+pub struct ZomeCall {
+    #[structopt(short, long, conflicts_with_all = &["socket", "sandbox"])]
+    /// Port of a running conductor's admin interface.
+    /// If neither this nor `--socket` is set an existing sandbox is used.
+    pub running: Option<u16>,
+    #[structopt(long, conflicts_with = "sandbox")]
+    /// Path to the unix domain socket of a running conductor's admin interface.
+    pub socket: Option<PathBuf>,
+    #[structopt(long, conflicts_with = "sandbox")]
+    /// File with the admin token to authenticate with a running conductor.
+    /// An existing sandbox is authenticated with its own token.
+    pub admin_token: Option<PathBuf>,
+    #[structopt(short, long, default_value = "0")]
+    /// Index of the existing sandbox to call, as shown by `hc list`.
+    /// It is started if it isn't running.
+    pub sandbox: usize,
+    #[structopt(long)]
+    /// Port of the app interface to call through.
+    /// Defaults to the conductor's first app interface,
+    /// attaching one if it has none.
+    pub app_port: Option<u16>,
+    #[structopt(long, requires = "app-port")]
+    /// Certificate digest to pin if the app interface serves TLS.
+    pub cert_digest: Option<CertDigest>,
+    #[structopt(short, long, required_unless = "dna", conflicts_with = "dna")]
+    /// The installed app whose cell to call.
+    pub app_id: Option<InstalledAppId>,
+    #[structopt(long, requires = "app-id")]
+    /// Role of the cell to call when the app has more than one.
+    pub role_id: Option<AppRoleId>,
+    #[structopt(long, parse(try_from_str = parse_dna_hash), requires = "agent-key")]
+    /// The dna hash half of the id of the cell to call.
+    pub dna: Option<DnaHash>,
+    #[structopt(long, parse(try_from_str = parse_agent_key), requires = "dna")]
+    /// The agent half of the id of the cell to call.
+    pub agent_key: Option<AgentPubKey>,
+    #[structopt(long, parse(try_from_str = parse_cap_secret))]
+    /// Base64 encoded capability secret to call with.
+    /// Not needed when calling as the cell's own agent.
+    pub cap_secret: Option<CapSecret>,
+    #[structopt(long, parse(try_from_str = parse_agent_key))]
+    /// The agent making the call. Defaults to the cell's agent.
+    pub provenance: Option<AgentPubKey>,
+    #[structopt(parse(from_str))]
+    /// The zome to call.
+    pub zome: ZomeName,
+    #[structopt(parse(from_str))]
+    /// The function to call.
+    pub function: FunctionName,
+    #[structopt(conflicts_with = "payload-file")]
+    /// The function's input as JSON or YAML, e.g. `'{"title": "hello"}'`.
+    /// Defaults to `null` for functions that take no input.
+    pub payload: Option<String>,
+    #[structopt(long)]
+    /// Read the input from this JSON or YAML file instead.
+    pub payload_file: Option<PathBuf>,
+}
+
+/// Make the zome call and print the decoded response.
+pub async fn zome_call(holochain_path: &Path, args: ZomeCall) -> anyhow::Result<()> {
+    let payload = match (&args.payload, &args.payload_file) {
+        (Some(payload), _) => payload.clone(),
+        (None, Some(path)) => std::fs::read_to_string(path)?,
+        (None, None) => "null".to_string(),
+    };
+    let payload = encode_payload(&payload)?;
+    let existing = Existing {
+        existing_paths: Vec::new(),
+        all: false,
+        last: false,
+        indices: vec![args.sandbox],
+    };
+    // Keep a conductor that had to be started running until the call is done.
+    let (mut cmd, _conductor) = connect_admins(
+        holochain_path,
+        args.running.into_iter().collect(),
+        args.socket.clone().into_iter().collect(),
+        args.admin_token.as_deref(),
+        existing,
+    )
+    .await?
+    .into_iter()
+    .next()
+    .ok_or_else(|| anyhow!("There is no sandbox to call"))?;
+
+    let app_port = match args.app_port {
+        Some(port) => port,
+        None => app_port(&mut cmd).await?,
+    };
+    let mut app = get_app_api(app_port, args.cert_digest).await?;
+    let scope = match &args.app_id {
+        Some(app_id) => SessionScope::Apps(std::iter::once(app_id.clone()).collect()),
+        None => SessionScope::Admin,
+    };
+    let mut app = AppConnection {
+        cmd: &mut cmd,
+        app: &mut app,
+        scope,
+    };
+
+    let cell_id = match (args.app_id, args.dna, args.agent_key) {
+        (Some(app_id), _, _) => app.cell_id(app_id, args.role_id).await?,
+        (None, Some(dna), Some(agent_key)) => CellId::new(dna, agent_key),
+        _ => bail!("Either an app id or a cell id is needed to make a zome call"),
+    };
+    let provenance = args
+        .provenance
+        .unwrap_or_else(|| cell_id.agent_pubkey().clone());
+    let resp = app
+        .request(AppRequest::ZomeCall(Box::new(ZomeCallRequest {
+            cell_id,
+            zome_name: args.zome,
+            fn_name: args.function,
+            payload,
+            cap_secret: args.cap_secret,
+            provenance,
+        })))
+        .await?;
+    let output = expect_match!(resp => AppResponse::ZomeCall, "Failed to call zome function");
+    println!("{}", decode_response(&output)?);
+    Ok(())
+}
+
+/// The first app interface of the conductor with a port,
+/// or a new one if it has none.
+async fn app_port(cmd: &mut CmdRunner) -> anyhow::Result<u16> {
+    if let Some(port) = crate::calls::list_app_ws(cmd)
+        .await?
+        .iter()
+        .find_map(InterfaceDriver::port)
+    {
+        return Ok(port);
+    }
+    let (port, _) = crate::calls::attach_app_interface(
+        cmd,
+        crate::calls::AddAppWs {
+            port: None,
+            require_auth: false,
+            socket: None,
+            tls: false,
+        },
+    )
+    .await?;
+    msg!("Attached app interface on port {}", port);
+    Ok(port)
+}
+
+/// An app interface connection that authenticates with a session token
+/// issued over the admin interface when the app interface requires it.
+struct AppConnection<'a> {
+    cmd: &'a mut CmdRunner,
+    app: &'a mut WebsocketSender,
+    scope: SessionScope,
+}
+
+impl AppConnection<'_> {
+    async fn request(&mut self, request: AppRequest) -> anyhow::Result<AppResponse> {
+        let resp: AppResponse = self.app.request(request.clone()).await?;
+        match resp {
+            AppResponse::Error(ExternalApiWireError::Unauthenticated(_)) => {
+                self.authenticate().await?;
+                Ok(self.app.request(request).await?)
+            }
+            // A new token would have the same scope so there's no point retrying.
+            AppResponse::Error(ExternalApiWireError::OutOfScope(reason)) => {
+                bail!(
+                    "The request is not allowed by the session scope: {}",
+                    reason
+                )
+            }
+            resp => Ok(resp),
+        }
+    }
+
+    async fn authenticate(&mut self) -> anyhow::Result<()> {
+        let resp = self
+            .cmd
+            .command(AdminRequest::IssueSessionToken {
+                scope: self.scope.clone(),
+                duration_seconds: Some(SESSION_DURATION_SECONDS),
+            })
+            .await?;
+        let token = match resp {
+            AdminResponse::SessionTokenIssued { token, .. } => token,
+            _ => bail!("Failed to issue a session token, got: {:?}", resp),
+        };
+        let resp: AppResponse = self.app.request(AppRequest::Authenticate { token }).await?;
+        ensure!(
+            matches!(resp, AppResponse::Authenticated),
+            "Failed to authenticate with the app interface, got: {:?}",
+            resp
+        );
+        Ok(())
+    }
+
+    /// The id of the app's cell with this role,
+    /// which may be left out if the app only has one cell.
+    async fn cell_id(
+        &mut self,
+        app_id: InstalledAppId,
+        role_id: Option<AppRoleId>,
+    ) -> anyhow::Result<CellId> {
+        let resp = self
+            .request(AppRequest::AppInfo {
+                installed_app_id: app_id.clone(),
+            })
+            .await?;
+        let info = expect_match!(resp => AppResponse::AppInfo, "Failed to get app info")
+            .ok_or_else(|| anyhow!("No app is installed with the id {}", app_id))?;
+        let mut cells = info
+            .cell_data
+            .into_iter()
+            .filter(|cell| role_id.as_ref().map_or(true, |r| cell.as_role_id() == r));
+        match (cells.next(), cells.next()) {
+            (Some(cell), None) => Ok(cell.into_id()),
+            (None, _) => bail!("App {} has no cell with the role {:?}", app_id, role_id),
+            (Some(_), Some(_)) => bail!(
+                "App {} has more than one cell, choose one with --role-id",
+                app_id
+            ),
+        }
+    }
+}
+
+/// Encode a JSON or YAML payload as msgpack.
+/// YAML is a superset of JSON so both are parsed as YAML.
+fn encode_payload(payload: &str) -> anyhow::Result<ExternIO> {
+    let value: serde_yaml::Value = serde_yaml::from_str(payload)
+        .map_err(|e| anyhow!("The payload is not valid JSON or YAML: {}", e))?;
+    Ok(ExternIO::encode(Encoded(&value))?)
+}
+
+/// Decode a msgpack response into YAML.
+pub(crate) fn decode_response(output: &ExternIO) -> anyhow::Result<String> {
+    let value: Decoded = output.decode()?;
+    Ok(serde_yaml::to_string(&value.0)?)
+}
+
+fn parse_cap_secret(arg: &str) -> anyhow::Result<CapSecret> {
+    let bytes = base64::decode(arg.trim())?;
+    CapSecret::try_from(&bytes[..]).map_err(|e| anyhow!("{:?}", e))
+}
+
+/// A YAML value to send as msgpack.
+/// Strings that are base64 hashes, e.g. `uhCEk...`, are sent as the bytes
+/// of the hash so they can be used as hash arguments.
+/// Everything else is sent as it is.
+#[derive(Debug)]
+struct Encoded<'a>(&'a serde_yaml::Value);
+
+impl serde::Serialize for Encoded<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        use serde::ser::SerializeSeq;
+        match self.0 {
+            serde_yaml::Value::String(s) => match decode_hash(s) {
+                Some(hash) => serializer.serialize_bytes(&hash),
+                None => serializer.serialize_str(s),
+            },
+            serde_yaml::Value::Sequence(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(&Encoded(value))?;
+                }
+                seq.end()
+            }
+            serde_yaml::Value::Mapping(mapping) => {
+                let mut map = serializer.serialize_map(Some(mapping.len()))?;
+                for (k, v) in mapping {
+                    map.serialize_entry(&Encoded(k), &Encoded(v))?;
+                }
+                map.end()
+            }
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+/// The bytes of a base64 hash, if the string is one.
+/// The location bytes are checked so other strings that
+/// happen to start with `u` are not mistaken for hashes.
+fn decode_hash(s: &str) -> Option<Vec<u8>> {
+    if !s.starts_with('u') {
+        return None;
+    }
+    holo_hash::holo_hash_decode_unchecked(s).ok()
+}
+
+/// A msgpack value of any shape.
+/// Binary data can't be YAML so hashes are shown as base64
+/// like they are everywhere else and other bytes as plain base64.
+struct Decoded(serde_yaml::Value);
+
+impl std::fmt::Debug for Decoded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Decoded {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(DecodedVisitor).map(Decoded)
+    }
+}
+
+/// The length of a hash with its type prefix and location bytes.
+const HOLO_HASH_LEN: usize = 39;
+
+struct DecodedVisitor;
+
+impl<'de> serde::de::Visitor<'de> for DecodedVisitor {
+    type Value = serde_yaml::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("any msgpack value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+        if v.len() == HOLO_HASH_LEN {
+            Ok(format!("u{}", base64::encode_config(v, base64::URL_SAFE_NO_PAD)).into())
+        } else {
+            Ok(base64::encode(v).into())
+        }
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(serde_yaml::Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(serde_yaml::Value::Null)
+    }
+
+    fn visit_some<D: serde::Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+        d.deserialize_any(self)
+    }
+
+    fn visit_newtype_struct<D: serde::Deserializer<'de>>(
+        self,
+        d: D,
+    ) -> Result<Self::Value, D::Error> {
+        d.deserialize_any(self)
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::new();
+        while let Some(Decoded(value)) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(serde_yaml::Value::Sequence(values))
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut mapping = serde_yaml::Mapping::new();
+        while let Some((Decoded(k), Decoded(v))) = map.next_entry()? {
+            mapping.insert(k, v);
+        }
+        Ok(serde_yaml::Value::Mapping(mapping))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_types::prelude::EntryHash;
+
+    #[test]
+    fn payload_round_trips_through_msgpack() {
+        let json = encode_payload(r#"{"title": "hello", "tags": [1, 2]}"#).unwrap();
+        let yaml = encode_payload("title: hello\ntags: [1, 2]").unwrap();
+        assert_eq!(json, yaml);
+        assert_eq!(
+            decode_response(&json).unwrap(),
+            serde_yaml::to_string(
+                &serde_yaml::from_str::<serde_yaml::Value>("title: hello\ntags: [1, 2]").unwrap()
+            )
+            .unwrap()
+        );
+        assert!(encode_payload("{ not: valid").is_err());
+    }
+
+    #[test]
+    fn hash_arguments_round_trip() {
+        let hash = EntryHash::from_raw_36(vec![7; 36]);
+        let payload = format!("[{}, uhello]", hash);
+        let input = encode_payload(&payload).unwrap();
+
+        // - The hash is sent as a hash, other strings as strings.
+        let (sent, title): (EntryHash, String) = input.decode().unwrap();
+        assert_eq!(sent, hash);
+        assert_eq!(title, "uhello");
+
+        // - Decoding shows the hash the way it was written,
+        //   so the output can be sent again as it is.
+        let decoded = decode_response(&input).unwrap();
+        assert!(decoded.contains(&hash.to_string()), "{}", decoded);
+        assert_eq!(encode_payload(&decoded).unwrap(), input);
+    }
+
+    #[test]
+    fn hashes_are_decoded_as_base64() {
+        let hash = EntryHash::from_raw_36(vec![7; 36]);
+        let output = ExternIO::encode(Some(hash.clone())).unwrap();
+        let decoded = decode_response(&output).unwrap();
+        assert!(decoded.contains(&hash.to_string()), "{}", decoded);
+    }
+}
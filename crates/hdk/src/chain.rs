@@ -25,8 +25,45 @@ pub fn get_agent_activity(
 /// @todo implement cap grant/claim usage in terms of query
 /// @todo have ability to hash-bound query other agent's chains based on agent activity
 /// @todo tie query into validation so we track dependencies e.g. validation packages
-/// @todo decide which direction we want to iterate in (paramaterise query?)
 /// @todo more expresivity generally?
+///
+/// The whole result is returned at once, see [ `query_page` ] for long chains.
 pub fn query(filter: ChainQueryFilter) -> ExternResult<Vec<Element>> {
     HDK.with(|h| h.borrow().query(filter))
 }
+
+/// Walks the source chain a page at a time in either direction.
+///
+/// Every filter of the [ `ChainQueryFilter` ] is applied by the host before the page is
+/// returned, so a page never holds more than `limit` elements however long the chain is.
+/// The returned [ `ChainQueryPage` ] has a cursor for the next page until the chain is
+/// exhausted.
+///
+/// ```ignore
+/// let filter = ChainQueryFilter::new()
+///     .entry_types(vec![post_type, comment_type])
+///     .include_entries(true);
+/// let mut input = ChainQueryPageInput::new(filter, 100).descending();
+/// loop {
+///     let page = query_page(input.clone())?;
+///     // use page.elements
+///     match page.next {
+///         Some(cursor) => input = input.cursor(cursor),
+///         None => break,
+///     }
+/// }
+/// ```
+pub fn query_page(input: ChainQueryPageInput) -> ExternResult<ChainQueryPage> {
+    HDK.with(|h| h.borrow().query_page(input))
+}
+
+/// Check how far the ops of a header on this agent's chain have been published.
+///
+/// For each public op produced by the header the [ `PublishStatus` ] reports whether it
+/// has been published yet, how many validation receipts have come back for it and how
+/// many receipts it needs. Ops stop being republished once they have all their receipts.
+///
+/// Returns `None` if the header was not authored by this agent.
+pub fn publish_status(header_hash: HeaderHash) -> ExternResult<Option<PublishStatus>> {
+    HDK.with(|h| h.borrow().publish_status(header_hash))
+}
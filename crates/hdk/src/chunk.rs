@@ -0,0 +1,334 @@
+use crate::prelude::*;
+use validate::RequiredValidationType;
+
+pub use holochain_zome_types::chunk::Chunk;
+pub use holochain_zome_types::chunk::ChunkSet;
+
+/// Payloads are split into chunks of this many bytes unless told otherwise.
+///
+/// Small chunks publish and gossip like any other small entry and spread the
+/// payload over many DHT neighbourhoods instead of one.
+pub const DEFAULT_CHUNK_SIZE: usize = 256 * 1024;
+
+/// How many chunks a [ `ChunkReader` ] asks the host for at a time unless told otherwise.
+pub const DEFAULT_CHUNKS_PER_READ: u32 = 8;
+
+/// Registers the chunk entry types the same way [ `entry_def!` ] does.
+/// The types live in `holochain_zome_types` so the host can read them.
+macro_rules! chunk_entry_def {
+    ( $t:ident $id:expr ) => {
+        impl EntryDefRegistration for $t {
+            fn entry_def() -> EntryDef {
+                EntryDef {
+                    id: $id.into(),
+                    crdt_type: CrdtType::Unresolved,
+                    required_validations: RequiredValidations::default(),
+                    visibility: EntryVisibility::Public,
+                    required_validation_type: RequiredValidationType::default(),
+                }
+            }
+
+            fn entry_def_id() -> EntryDefId {
+                Self::entry_def().id
+            }
+
+            fn entry_visibility() -> EntryVisibility {
+                Self::entry_def().visibility
+            }
+
+            fn crdt_type() -> CrdtType {
+                Self::entry_def().crdt_type
+            }
+
+            fn required_validations() -> RequiredValidations {
+                Self::entry_def().required_validations
+            }
+        }
+    };
+}
+
+chunk_entry_def!(Chunk CHUNK_ENTRY_DEF_ID);
+chunk_entry_def!(ChunkSet CHUNK_SET_ENTRY_DEF_ID);
+
+/// Split a payload into [ `Chunk` ] entries of at most `chunk_size` bytes and
+/// commit them along with their [ `ChunkSet` ] manifest.
+///
+/// Everything is committed with a single [ `commit_batch` ] so either the whole
+/// payload is written or none of it is.
+/// Returns the hash of the [ `ChunkSet` ] entry.
+///
+/// The zome must register both entry types for this to work:
+///
+/// ```ignore
+/// entry_defs![Chunk::entry_def(), ChunkSet::entry_def()];
+///
+/// let chunk_set_hash = create_chunked(&file_bytes, DEFAULT_CHUNK_SIZE)?;
+/// ```
+///
+/// If the DNA lowers its entry size limit the chunk size must fit under it.
+pub fn create_chunked(payload: &[u8], chunk_size: usize) -> ExternResult<EntryHash> {
+    if chunk_size == 0 {
+        return Err(WasmError::Guest(
+            "Chunk size must be greater than zero".into(),
+        ));
+    }
+    let mut chunks = Vec::with_capacity(payload.len() / chunk_size + 1);
+    let mut batch = Vec::with_capacity(payload.len() / chunk_size + 2);
+    for bytes in payload.chunks(chunk_size) {
+        let chunk = Chunk::new(bytes.to_vec());
+        chunks.push(hash_entry(&chunk)?);
+        batch.push(CreateInput::try_from(chunk)?.into());
+    }
+    let chunk_set = ChunkSet::new(payload.len() as u64, chunks);
+    let chunk_set_hash = hash_entry(&chunk_set)?;
+    batch.push(CreateInput::try_from(chunk_set)?.into());
+    commit_batch(batch)?;
+    Ok(chunk_set_hash)
+}
+
+/// Get the [ `ChunkSet` ] manifest of a chunked payload.
+///
+/// Useful to find the size of the payload and how many chunks it has before
+/// reading part of it with [ `get_chunks` ] or a [ `ChunkReader` ].
+pub fn get_chunk_set(
+    chunk_set_hash: EntryHash,
+    options: GetOptions,
+) -> ExternResult<Option<ChunkSet>> {
+    match get(chunk_set_hash, options)? {
+        Some(element) => Ok(Some(ChunkSet::try_from(&element)?)),
+        None => Ok(None),
+    }
+}
+
+/// Get a range of the chunks of a chunked payload by their position in its
+/// [ `ChunkSet` ].
+///
+/// The host fetches the chunks in parallel and checks each one against its
+/// hash before handing it over.
+/// A range past the end of the set is cut short.
+///
+/// Returns `None` if the manifest or any chunk in the range can't be found (yet).
+pub fn get_chunks(
+    chunk_set_hash: EntryHash,
+    range: std::ops::Range<u32>,
+    options: GetOptions,
+) -> ExternResult<Option<Vec<Chunk>>> {
+    HDK.with(|h| {
+        h.borrow()
+            .get_chunks(GetChunksInput::new(chunk_set_hash, range, options))
+    })
+}
+
+/// Get a whole chunked payload back by the hash of its [ `ChunkSet` ].
+///
+/// All chunks are fetched by the host in parallel and checked against their
+/// hashes, then the payload size is checked against the manifest.
+/// This holds the whole payload in memory, use a [ `ChunkReader` ] to stream
+/// large payloads instead.
+///
+/// Returns `None` if the manifest or any of its chunks can't be found (yet).
+pub fn get_chunked(
+    chunk_set_hash: EntryHash,
+    options: GetOptions,
+) -> ExternResult<Option<Vec<u8>>> {
+    let chunk_set = match get_chunk_set(chunk_set_hash.clone(), options.clone())? {
+        Some(chunk_set) => chunk_set,
+        None => return Ok(None),
+    };
+    let chunks = match get_chunks(chunk_set_hash.clone(), 0..u32::MAX, options)? {
+        Some(chunks) => chunks,
+        None => return Ok(None),
+    };
+    let mut payload = Vec::with_capacity(chunk_set.size() as usize);
+    for chunk in chunks {
+        payload.extend(chunk.into_bytes());
+    }
+    if payload.len() as u64 != chunk_set.size() {
+        return Err(WasmError::Guest(format!(
+            "Chunked payload {} should be {} bytes but was {} bytes",
+            chunk_set_hash,
+            chunk_set.size(),
+            payload.len()
+        )));
+    }
+    Ok(Some(payload))
+}
+
+/// Reads a chunked payload one chunk at a time.
+///
+/// The reader asks the host for the next few chunks whenever it runs out, so
+/// only that many chunks are in memory at once however large the payload is.
+/// A missing manifest or chunk is an error because the payload can't be
+/// finished.
+///
+/// ```ignore
+/// for chunk in ChunkReader::new(chunk_set_hash, GetOptions::content()) {
+///     hasher.update(chunk?.bytes());
+/// }
+/// ```
+pub struct ChunkReader {
+    chunk_set_hash: EntryHash,
+    options: GetOptions,
+    chunks_per_read: u32,
+    next_index: u32,
+    buffer: std::vec::IntoIter<Chunk>,
+    done: bool,
+}
+
+impl ChunkReader {
+    /// Read the payload with this [ `ChunkSet` ] hash from the start,
+    /// [ `DEFAULT_CHUNKS_PER_READ` ] chunks at a time.
+    pub fn new(chunk_set_hash: EntryHash, options: GetOptions) -> Self {
+        Self {
+            chunk_set_hash,
+            options,
+            chunks_per_read: DEFAULT_CHUNKS_PER_READ,
+            next_index: 0,
+            buffer: Vec::new().into_iter(),
+            done: false,
+        }
+    }
+
+    /// Ask the host for this many chunks at a time.
+    pub fn chunks_per_read(mut self, chunks_per_read: u32) -> Self {
+        self.chunks_per_read = chunks_per_read.max(1);
+        self
+    }
+
+    /// Start reading from the chunk at this position in the [ `ChunkSet` ].
+    pub fn skip_chunks(mut self, index: u32) -> Self {
+        self.next_index = index;
+        self
+    }
+}
+
+impl Iterator for ChunkReader {
+    type Item = ExternResult<Chunk>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(chunk) = self.buffer.next() {
+            return Some(Ok(chunk));
+        }
+        if self.done {
+            return None;
+        }
+        let start = self.next_index;
+        let end = start.saturating_add(self.chunks_per_read);
+        match get_chunks(
+            self.chunk_set_hash.clone(),
+            start..end,
+            self.options.clone(),
+        ) {
+            Ok(Some(chunks)) => {
+                // A short read means the end of the set.
+                self.done = chunks.len() < (end - start) as usize;
+                self.next_index = end;
+                self.buffer = chunks.into_iter();
+                self.buffer.next().map(Ok)
+            }
+            Ok(None) => {
+                self.done = true;
+                Some(Err(WasmError::Guest(format!(
+                    "Chunked payload {} is missing chunks from position {}",
+                    self.chunk_set_hash, start
+                ))))
+            }
+            Err(error) => {
+                self.done = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "mem")]
+mod tests {
+    use super::*;
+    use crate::hdk::mem::MemHdk;
+
+    #[test]
+    fn chunked_roundtrip() {
+        let entry_defs: EntryDefs = vec![Chunk::entry_def(), ChunkSet::entry_def()].into();
+        let alice = MemHdk::new("files", entry_defs);
+        set_hdk(alice.clone());
+
+        let payload: Vec<u8> = (0..10_000_u32).map(|i| (i % 251) as u8).collect();
+        let chunk_set_hash = create_chunked(&payload, 1024).unwrap();
+
+        // Genesis, ten chunks and the manifest.
+        assert_eq!(3 + 10 + 1, alice.source_chain().len());
+        assert_eq!(
+            Some(payload),
+            get_chunked(chunk_set_hash, GetOptions::content()).unwrap()
+        );
+
+        // The empty payload is a manifest with no chunks.
+        let empty_hash = create_chunked(&[], 1024).unwrap();
+        assert_eq!(
+            Some(vec![]),
+            get_chunked(empty_hash, GetOptions::content()).unwrap()
+        );
+
+        let missing = hash_entry(&Chunk::new(vec![1, 2, 3])).unwrap();
+        assert_eq!(
+            None,
+            get_chunked(missing.clone(), GetOptions::content()).unwrap()
+        );
+        assert!(ChunkReader::new(missing, GetOptions::content())
+            .next()
+            .unwrap()
+            .is_err());
+        assert!(create_chunked(&[1, 2, 3], 0).is_err());
+    }
+
+    #[test]
+    fn chunked_ranges_and_reader() {
+        let entry_defs: EntryDefs = vec![Chunk::entry_def(), ChunkSet::entry_def()].into();
+        set_hdk(MemHdk::new("files", entry_defs));
+
+        let payload: Vec<u8> = (0..10_000_u32).map(|i| (i % 251) as u8).collect();
+        let chunk_set_hash = create_chunked(&payload, 1024).unwrap();
+
+        let chunk_set = get_chunk_set(chunk_set_hash.clone(), GetOptions::content())
+            .unwrap()
+            .unwrap();
+        assert_eq!(10_000, chunk_set.size());
+        assert_eq!(10, chunk_set.chunks().len());
+
+        // Only the chunks in the range come back, and a range past the end is cut short.
+        let chunks = get_chunks(chunk_set_hash.clone(), 2..4, GetOptions::content())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            chunks
+                .into_iter()
+                .flat_map(Chunk::into_bytes)
+                .collect::<Vec<_>>(),
+            payload[2048..4096].to_vec()
+        );
+        let chunks = get_chunks(chunk_set_hash.clone(), 9..20, GetOptions::content())
+            .unwrap()
+            .unwrap();
+        assert_eq!(1, chunks.len());
+        assert_eq!(&payload[9216..], chunks[0].bytes());
+
+        // The reader walks every chunk in order a few at a time.
+        let read: Vec<Chunk> = ChunkReader::new(chunk_set_hash.clone(), GetOptions::content())
+            .chunks_per_read(3)
+            .collect::<ExternResult<_>>()
+            .unwrap();
+        assert_eq!(10, read.len());
+        assert_eq!(
+            read.into_iter()
+                .flat_map(Chunk::into_bytes)
+                .collect::<Vec<_>>(),
+            payload
+        );
+        let read: Vec<Chunk> = ChunkReader::new(chunk_set_hash, GetOptions::content())
+            .skip_chunks(8)
+            .collect::<ExternResult<_>>()
+            .unwrap();
+        assert_eq!(2, read.len());
+    }
+}
@@ -45,6 +45,28 @@ pub fn delete(deletes_header_address: HeaderHash) -> ExternResult<HeaderHash> {
     })
 }
 
+/// Apply several creates, updates, deletes and links in a single host call.
+///
+/// Every op is written in order, so later ops can reference the headers and entries of earlier
+/// ops, e.g. by hashing an entry before it is created and linking to it.
+///
+/// The host validates the whole batch as soon as it is written, rather than at the end of the
+/// zome call. If any op fails or is invalid then none of the batch is kept and the error is
+/// returned, leaving the zome free to carry on with other writes.
+///
+/// Returns the [ `HeaderHash` ] of every op in the same order as the batch.
+///
+/// ```ignore
+/// let batch = posts
+///     .iter()
+///     .map(|post| Ok(CreateInput::try_from(post)?.into()))
+///     .collect::<ExternResult<Vec<BatchOp>>>()?;
+/// commit_batch(batch)?;
+/// ```
+pub fn commit_batch(batch: Vec<BatchOp>) -> ExternResult<Vec<HeaderHash>> {
+    HDK.with(|h| h.borrow().commit_batch(batch))
+}
+
 /// Create an app entry.
 ///
 /// Apps define app entries by registering entry def ids with the `entry_defs` callback and serialize the
@@ -179,9 +201,13 @@ where
 ///       @todo implement a 'get optimistic' that returns based on the current opinion of the world
 ///       and performs network calls in the background so they are available 'next time'.
 ///
-/// Note: Deletes are considered in the liveness but Updates are not currently followed
-///       automatically due to the need for the happ to disambiguate update logic.
-///       @todo implement 'redirect' logic so that updates are followed by [ `get` ].
+/// Note: Deletes are considered in the liveness but Updates are only followed automatically
+///       for entry hashes with [ `GetStrategy::Latest` ] when the entry def declares a
+///       [ `CrdtType` ] other than `Unresolved`. The strategy picks the winning update among
+///       concurrent updates and [ `get` ] returns the end of that chain of winners.
+///       With `CrdtType::ResolveCallback` the zome must define a `resolve_conflict` extern that
+///       takes a [ `ResolveConflictInput` ] and returns the `Option<HeaderHash>` of the winner.
+///       Only the winner is returned; use [ `get_details` ] to see the updates it was picked from.
 ///
 /// Note: Updates typically point to a different entry hash than what they are updating but not
 ///       always, e.g. consider changing `foo` to `bar` back to `foo`. The entry hashes in a crud
@@ -52,4 +52,10 @@ pub mod path;
 /// The widths of strings are normalised as UTF32 as path components so multibyte characters count as 1.
 ///
 /// The tests in the shard module include several examples of the DSL including multibyte characters.
+///
+/// Sharding on the data itself puts short or similar strings into the same few shards.
+/// Add an `h` to the depth to shard on the hash of the data instead, e.g. `foo.1:2h#al`.
+///
+/// Time based data can be sharded into year, month, day and hour components with
+/// `Path::time_shard` and listed lazily with `Path::time_range`.
 pub mod shard;
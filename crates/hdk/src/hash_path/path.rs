@@ -1,4 +1,5 @@
 use crate::hash_path::shard::ShardStrategy;
+use crate::hash_path::shard::TimeGranularity;
 use crate::hash_path::shard::SHARDEND;
 use crate::prelude::*;
 use holochain_wasmer_guest::*;
@@ -23,8 +24,20 @@ pub const DHT_PREFIX: u8 = 0;
 
 /// Each path component is arbitrary bytes to be hashed together in a predictable way when the path
 /// is hashed to create something that can be linked and discovered by all DHT participants.
+///
+/// Components are ordered by their bytes. For strings of ascii characters of the same length,
+/// e.g. zero padded numbers, this is the same as the order of the strings.
 #[derive(
-    Clone, PartialEq, Debug, Default, serde::Deserialize, serde::Serialize, SerializedBytes,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Debug,
+    Default,
+    serde::Deserialize,
+    serde::Serialize,
+    SerializedBytes,
 )]
 #[repr(transparent)]
 pub struct Component(#[serde(with = "serde_bytes")] Vec<u8>);
@@ -157,7 +170,7 @@ pub struct Path(Vec<Component>);
 
 entry_def!(Path EntryDef {
     id: "hdk.path".into(),
-    crdt_type: CrdtType,
+    crdt_type: CrdtType::Unresolved,
     required_validations: RequiredValidations::default(),
     visibility: EntryVisibility::Public,
     required_validation_type: RequiredValidationType::default(),
@@ -195,7 +208,7 @@ impl PathEntry {
 
 entry_def!(PathEntry EntryDef {
     id: "hdk.path_entry".into(),
-    crdt_type: CrdtType,
+    crdt_type: CrdtType::Unresolved,
     required_validations: RequiredValidations::default(),
     visibility: EntryVisibility::Public,
     required_validation_type: RequiredValidationType::default(),
@@ -363,21 +376,8 @@ impl Path {
     /// to produce each child, by using `&self` as that parent.
     pub fn children_paths(&self) -> ExternResult<Vec<Self>> {
         let children = self.children()?;
-        let components: ExternResult<Vec<Option<Component>>> = children
-            .into_iter()
-            .map(|link| {
-                let component_bytes = &link.tag.0[1..];
-                if component_bytes.is_empty() {
-                    Ok(None)
-                } else {
-                    Ok(Some(
-                        SerializedBytes::from(UnsafeBytes::from(component_bytes.to_vec()))
-                            .try_into()
-                            .map_err(WasmError::Serialize)?,
-                    ))
-                }
-            })
-            .collect();
+        let components: ExternResult<Vec<Option<Component>>> =
+            children.iter().map(Self::link_component).collect();
         Ok(components?
             .into_iter()
             .map(|maybe_component| {
@@ -409,6 +409,138 @@ impl Path {
     pub fn leaf(&self) -> Option<&Component> {
         self.0.last()
     }
+
+    /// This path with the time shard components for a timestamp appended.
+    /// e.g. `foo` with `TimeGranularity::Day` becomes `foo.2021.07.14`.
+    pub fn time_shard(&self, granularity: TimeGranularity, timestamp: Timestamp) -> Self {
+        let mut path = self.clone();
+        for component in Vec::<Component>::from(Path::from((&granularity, timestamp))) {
+            path.append_component(component);
+        }
+        path
+    }
+
+    /// Lazily walk the existing paths `depth` components below this path, in component order.
+    /// Children are only fetched from the DHT as the walk reaches them, so a caller
+    /// that stops early only pays for the branches it visited.
+    /// Unlike `children` this does not `ensure` any paths.
+    pub fn walk(&self, depth: usize) -> PathWalk {
+        PathWalk::new(self.clone(), depth, None, None)
+    }
+
+    /// Same as `Path::walk` but only visits paths between `start` and `end` inclusive.
+    /// The bounds are the components below this path, and may be shorter than `depth`,
+    /// in which case everything below a bound is included.
+    pub fn walk_range(&self, depth: usize, start: Option<&Path>, end: Option<&Path>) -> PathWalk {
+        PathWalk::new(
+            self.clone(),
+            depth,
+            start.map(|path| path.as_ref().clone()),
+            end.map(|path| path.as_ref().clone()),
+        )
+    }
+
+    /// Lazily walk the time shards below this path from `start` to `end` inclusive,
+    /// e.g. every hour that has data between two timestamps.
+    pub fn time_range(
+        &self,
+        granularity: TimeGranularity,
+        start: Timestamp,
+        end: Timestamp,
+    ) -> PathWalk {
+        self.walk_range(
+            granularity.depth(),
+            Some(&Path::from((&granularity, start))),
+            Some(&Path::from((&granularity, end))),
+        )
+    }
+
+    /// The existing children of this path as sorted, deduplicated components.
+    fn child_components(&self) -> ExternResult<Vec<Component>> {
+        let links = get_links(
+            self.path_entry_hash()?,
+            Some(holochain_zome_types::link::LinkTag::new([DHT_PREFIX])),
+        )?;
+        let components: ExternResult<Vec<Option<Component>>> =
+            links.iter().map(Self::link_component).collect();
+        let mut components: Vec<Component> = components?.into_iter().flatten().collect();
+        components.sort();
+        components.dedup();
+        Ok(components)
+    }
+
+    /// The component encoded in the tag of a link to a child path.
+    fn link_component(link: &holochain_zome_types::link::Link) -> ExternResult<Option<Component>> {
+        let component_bytes = &link.tag.0[1..];
+        if component_bytes.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(
+                SerializedBytes::from(UnsafeBytes::from(component_bytes.to_vec()))
+                    .try_into()
+                    .map_err(WasmError::Serialize)?,
+            ))
+        }
+    }
+}
+
+/// A lazy depth first walk over the paths below a [ `Path` ].
+/// Built by `Path::walk`, `Path::walk_range` and `Path::time_range`.
+pub struct PathWalk {
+    depth: usize,
+    start: Option<Vec<Component>>,
+    end: Option<Vec<Component>>,
+    /// Paths still to visit with their depth below the root and whether
+    /// they are on the start and end bounds.
+    stack: Vec<(Path, usize, bool, bool)>,
+}
+
+impl PathWalk {
+    fn new(
+        root: Path,
+        depth: usize,
+        start: Option<Vec<Component>>,
+        end: Option<Vec<Component>>,
+    ) -> Self {
+        Self {
+            depth,
+            start,
+            end,
+            stack: vec![(root, 0, true, true)],
+        }
+    }
+}
+
+impl Iterator for PathWalk {
+    type Item = ExternResult<Path>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((path, level, on_start, on_end)) = self.stack.pop() {
+            if level == self.depth {
+                return Some(Ok(path));
+            }
+            let children = match path.child_components() {
+                Ok(children) => children,
+                Err(e) => return Some(Err(e)),
+            };
+            let start = self.start.as_ref().and_then(|start| start.get(level));
+            let end = self.end.as_ref().and_then(|end| end.get(level));
+            // Push in reverse so the smallest child is visited first.
+            for component in children.into_iter().rev() {
+                let after_start = !on_start || start.map_or(true, |start| &component >= start);
+                let before_end = !on_end || end.map_or(true, |end| &component <= end);
+                if after_start && before_end {
+                    let child_on_start = on_start && start == Some(&component);
+                    let child_on_end = on_end && end == Some(&component);
+                    let mut child = path.clone();
+                    child.append_component(component);
+                    self.stack
+                        .push((child, level + 1, child_on_start, child_on_end));
+                }
+            }
+        }
+        None
+    }
 }
 
 #[test]
@@ -533,3 +665,81 @@ fn hash_path_path() {
         assert_eq!(Path::from(input), Path::from(output),);
     }
 }
+
+#[test]
+#[cfg(all(test, feature = "mem"))]
+fn hash_path_walk() {
+    use crate::hdk::MemHdk;
+
+    set_hdk(MemHdk::new("paths", vec![PathEntry::entry_def()].into()));
+
+    let root = Path::from("events");
+    for hour in &[
+        "2021.07.13.23",
+        "2021.07.14.01",
+        "2021.07.14.09",
+        "2021.08.01.00",
+    ] {
+        let mut path = root.clone();
+        for component in Vec::<Component>::from(Path::from(*hour)) {
+            path.append_component(component);
+        }
+        path.ensure().unwrap();
+    }
+
+    let walked = |walk: PathWalk| -> Vec<Path> { walk.map(Result::unwrap).collect() };
+    let below_root = |s: &str| {
+        let mut path = root.clone();
+        for component in Vec::<Component>::from(Path::from(s)) {
+            path.append_component(component);
+        }
+        path
+    };
+
+    // Everything at a depth, in order.
+    assert_eq!(
+        vec![
+            below_root("2021.07.13"),
+            below_root("2021.07.14"),
+            below_root("2021.08.01")
+        ],
+        walked(root.walk(3)),
+    );
+
+    // A range of hours.
+    let timestamp = |s: u64| Timestamp::from_micros(s as i64 * 1_000_000);
+    assert_eq!(
+        vec![below_root("2021.07.14.01"), below_root("2021.07.14.09")],
+        walked(root.time_range(
+            TimeGranularity::Hour,
+            // 2021-07-14T00:00:00Z
+            timestamp(1_626_220_800),
+            // 2021-07-15T00:00:00Z
+            timestamp(1_626_307_200),
+        )),
+    );
+
+    // Bounds shorter than the depth include everything below them.
+    assert_eq!(
+        vec![
+            below_root("2021.07.13.23"),
+            below_root("2021.07.14.01"),
+            below_root("2021.07.14.09")
+        ],
+        walked(root.walk_range(
+            4,
+            Some(&Path::from("2021.07")),
+            Some(&Path::from("2021.07"))
+        )),
+    );
+
+    // The walk is lazy.
+    assert_eq!(
+        Some(below_root("2021.07.13.23")),
+        root.walk(4).next().transpose().unwrap()
+    );
+    assert_eq!(
+        root.time_shard(TimeGranularity::Hour, timestamp(1_626_220_800)),
+        below_root("2021.07.14.00")
+    );
+}
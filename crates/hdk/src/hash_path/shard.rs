@@ -1,11 +1,15 @@
 use crate::hash_path::path::Component;
 use crate::hash_path::path::Path;
+use crate::prelude::Timestamp;
 use std::str::FromStr;
 
 /// Separates the shard width and depth.
 pub const SHARDSPLIT: &str = ":";
 /// Terminates the end of a shard shorthand.
 pub const SHARDEND: &str = "#";
+/// Suffix of the shard depth that selects shards from the hash of the data.
+/// e.g. 1:2h#foo shards on the hash of foo rather than on foo itself.
+pub const SHARDHASH: &str = "h";
 
 /// The width of a shard is how many bytes/characters to use for each path component in sharding.
 /// e.g. abcdef with width 1 shards to a.b.c.d.e.f.abcdef and 2 shards to ab.cd.ef.abcdef.
@@ -14,16 +18,39 @@ pub type ShardWidth = u32;
 /// e.g. abcdef with a depth of 1 and width 1 shards to a.abcdef and depth 2 shards to a.b.abcdef.
 pub type ShardDepth = u32;
 
-#[derive(Debug)]
+/// Where the components of a shard are taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShardSource {
+    /// The leading bytes/characters of the data itself.
+    /// Only works well for data that is reliably longer than width/depth.
+    Data,
+    /// The leading bytes of the blake2b hash of the data.
+    /// Strings are sharded on the lowercase hex characters of the hash so the
+    /// shards are still strings.
+    /// Short or similar data is spread evenly across all the shards.
+    Hash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// A valid strategy for sharding requires both a width and a depth.
-/// At the moment sharding only works well for data that is reliably longer than width/depth.
-/// For example, sharding the username foo with width 4 doesn't make sense.
-/// There is no magic padding or extending of the provided data to make up undersized shards.
-/// @todo stretch short shards out in a nice balanced way (append some bytes from the hash?)
-pub struct ShardStrategy(ShardWidth, ShardDepth);
+/// Sharding on the data itself only works well for data that is reliably longer than width/depth.
+/// For example, sharding the username foo with width 4 doesn't make sense, and usernames that
+/// share a prefix all end up in the same shard.
+/// Use a hashed strategy for data like this.
+pub struct ShardStrategy(ShardWidth, ShardDepth, ShardSource);
 
 /// impl [ `ShardStrategy` ] as an immutable/read-only thingy.
 impl ShardStrategy {
+    /// Shard on the data itself.
+    pub fn new(width: ShardWidth, depth: ShardDepth) -> Self {
+        Self(width, depth, ShardSource::Data)
+    }
+
+    /// Shard on the hash of the data.
+    pub fn hashed(width: ShardWidth, depth: ShardDepth) -> Self {
+        Self(width, depth, ShardSource::Hash)
+    }
+
     fn width(&self) -> ShardWidth {
         self.0
     }
@@ -31,6 +58,19 @@ impl ShardStrategy {
     fn depth(&self) -> ShardDepth {
         self.1
     }
+
+    fn source(&self) -> ShardSource {
+        self.2
+    }
+}
+
+/// The 32 byte blake2b hash that hashed shards are taken from.
+fn shard_hash(bytes: &[u8]) -> Vec<u8> {
+    blake2b_simd::Params::new()
+        .hash_length(32)
+        .hash(bytes)
+        .as_bytes()
+        .to_vec()
 }
 
 #[derive(Debug)]
@@ -49,7 +89,7 @@ pub enum ParseShardStrategyError {
     EmptyString,
 }
 
-/// Attempt to parse a "width:depth#" shard out of a string.
+/// Attempt to parse a "width:depth#" or hashed "width:depthh#" shard out of a string.
 /// This function looks way scarier than it is.
 /// Each level of nesting is just handling a potential parse failure.
 impl FromStr for ShardStrategy {
@@ -70,17 +110,20 @@ impl FromStr for ShardStrategy {
                                     Some(split_index) => {
                                         let (maybe_width, maybe_depth) =
                                             maybe_strategy.split_at(split_index);
+                                        let maybe_depth =
+                                            maybe_depth.trim_start_matches(SHARDSPLIT);
+                                        let (maybe_depth, source) =
+                                            match maybe_depth.strip_suffix(SHARDHASH) {
+                                                Some(depth) => (depth, ShardSource::Hash),
+                                                None => (maybe_depth, ShardSource::Data),
+                                            };
                                         match u32::from_str(maybe_width) {
-                                            Ok(width) => {
-                                                match u32::from_str(
-                                                    maybe_depth.trim_start_matches(SHARDSPLIT),
-                                                ) {
-                                                    Ok(depth) => Ok(ShardStrategy(width, depth)),
-                                                    Err(_) => {
-                                                        Err(ParseShardStrategyError::BadDepth)
-                                                    }
+                                            Ok(width) => match u32::from_str(maybe_depth) {
+                                                Ok(depth) => {
+                                                    Ok(ShardStrategy(width, depth, source))
                                                 }
-                                            }
+                                                Err(_) => Err(ParseShardStrategyError::BadDepth),
+                                            },
                                             Err(_) => Err(ParseShardStrategyError::BadWidth),
                                         }
                                     }
@@ -99,9 +142,16 @@ impl FromStr for ShardStrategy {
 }
 
 /// Builds a path for a shard strategy and some binary bytes.
-/// This is the trivial case, we just split the bytes out one by one and make a path from it.
+/// This is the trivial case, we just split the bytes (or the bytes of their hash) out one by one
+/// and make a path from it.
 impl From<(&ShardStrategy, &[u8])> for Path {
     fn from((strategy, bytes): (&ShardStrategy, &[u8])) -> Path {
+        if strategy.source() == ShardSource::Hash {
+            return Path::from((
+                &ShardStrategy::new(strategy.width(), strategy.depth()),
+                shard_hash(bytes),
+            ));
+        }
         let full_length = strategy.width() * strategy.depth();
         // Fold a flat slice of bytes into `strategy.depth` number of `strategy.width` length byte
         // [ `Component` ]s.
@@ -145,14 +195,25 @@ impl From<(&ShardStrategy, Vec<u8>)> for Path {
 /// To ensure that this works for all utf8, which can have anywhere from 1-4 bytes for a single
 /// character, we first represent each character as a utf32 so it gets padded out with 0 bytes.
 /// This means the width is 4x what it would be for raw bytes with the same strategy.
+/// Hashed strategies shard on the hex characters of the hash of the utf8 string.
 impl From<(&ShardStrategy, &str)> for Path {
     fn from((strategy, s): (&ShardStrategy, &str)) -> Path {
+        if strategy.source() == ShardSource::Hash {
+            let hex: String = shard_hash(s.as_bytes())
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            return Path::from((
+                &ShardStrategy::new(strategy.width(), strategy.depth()),
+                hex.as_str(),
+            ));
+        }
         // Truncate the string to only relevant chars.
         let full_length = strategy.width() * strategy.depth();
         let shard_string: String = s.chars().take(full_length as _).collect();
 
         Path::from((
-            &ShardStrategy(
+            &ShardStrategy::new(
                 // Relies on the fact that we're encoding string characters as fixed width u32
                 // bytes rather than variable width utf8 bytes.
                 strategy.width() * std::mem::size_of::<u32>() as u32,
@@ -177,6 +238,75 @@ impl From<(&ShardStrategy, String)> for Path {
     }
 }
 
+/// The finest unit of time that a time shard is split into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TimeGranularity {
+    /// A single year component, e.g. `["2021"]`.
+    Year,
+    /// Year and month components, e.g. `["2021", "07"]`.
+    Month,
+    /// Year, month and day components, e.g. `["2021", "07", "14"]`.
+    Day,
+    /// Year, month, day and hour components, e.g. `["2021", "07", "14", "09"]`.
+    Hour,
+}
+
+impl TimeGranularity {
+    /// The number of path components in a time shard of this granularity.
+    pub fn depth(&self) -> usize {
+        match self {
+            TimeGranularity::Year => 1,
+            TimeGranularity::Month => 2,
+            TimeGranularity::Day => 3,
+            TimeGranularity::Hour => 4,
+        }
+    }
+}
+
+/// Convert days since the UNIX epoch to a UTC (year, month, day).
+/// This is the `civil_from_days` algorithm from <http://howardhinnant.github.io/date_algorithms.html>
+/// as there is no calendar support in wasm.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Builds the time shard components for a timestamp, in UTC.
+/// The components are zero padded strings so that sibling shards sort in time order,
+/// which is what `Path::time_range` relies on.
+impl From<(&TimeGranularity, Timestamp)> for Path {
+    fn from((granularity, timestamp): (&TimeGranularity, Timestamp)) -> Path {
+        let seconds = timestamp.as_micros().div_euclid(1_000_000);
+        let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+        let hour = seconds.rem_euclid(86_400) / 3600;
+        Path::from(
+            vec![
+                format!("{:04}", year),
+                format!("{:02}", month),
+                format!("{:02}", day),
+                format!("{:02}", hour),
+            ]
+            .into_iter()
+            .take(granularity.depth())
+            .map(Component::from)
+            .collect::<Vec<Component>>(),
+        )
+    }
+}
+
 #[test]
 #[cfg(test)]
 fn hash_path_shard_bytes() {
@@ -219,10 +349,13 @@ fn hash_path_shard_bytes() {
             ]),
         ),
     ] {
-        assert_eq!(output, Path::from((&ShardStrategy(width, depth), &b)));
+        assert_eq!(output, Path::from((&ShardStrategy::new(width, depth), &b)));
         let bytes: &[u8] = b.as_ref();
-        assert_eq!(output, Path::from((&ShardStrategy(width, depth), bytes)));
-        assert_eq!(output, Path::from((&ShardStrategy(width, depth), b)));
+        assert_eq!(
+            output,
+            Path::from((&ShardStrategy::new(width, depth), bytes))
+        );
+        assert_eq!(output, Path::from((&ShardStrategy::new(width, depth), b)));
     }
 }
 
@@ -251,14 +384,97 @@ fn hash_path_shard_string() {
         (4, 4, "foobarbaz", Path::from("foob.arba")),
         (4, 4, "€€€€€€€€€", Path::from("€€€€.€€€€")),
     ] {
-        assert_eq!(output, Path::from((&ShardStrategy(width, depth), s)));
+        assert_eq!(output, Path::from((&ShardStrategy::new(width, depth), s)));
         assert_eq!(
             output,
-            Path::from((&ShardStrategy(width, depth), s.to_string()))
+            Path::from((&ShardStrategy::new(width, depth), s.to_string()))
         );
         assert_eq!(
             output,
-            Path::from((&ShardStrategy(width, depth), &s.to_string()))
+            Path::from((&ShardStrategy::new(width, depth), &s.to_string()))
         );
     }
 }
+
+#[test]
+#[cfg(test)]
+fn hash_path_shard_hashed() {
+    use std::convert::TryFrom;
+
+    // The DSL selects a hashed strategy with the `h` suffix.
+    assert_eq!(
+        ShardStrategy::hashed(2, 3),
+        ShardStrategy::from_str("2:3h#foo").unwrap()
+    );
+    assert_eq!(
+        ShardStrategy::new(2, 3),
+        ShardStrategy::from_str("2:3#foo").unwrap()
+    );
+
+    // Short strings are sharded as hex characters of their hash at full width and depth.
+    let path = Path::from((&ShardStrategy::hashed(2, 2), "al"));
+    let components: Vec<Component> = path.into();
+    assert_eq!(2, components.len());
+    for component in components.iter() {
+        let s = String::try_from(component).unwrap();
+        assert_eq!(2, s.len());
+        assert!(s.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    // Similar inputs no longer share a shard.
+    assert_ne!(
+        Path::from((&ShardStrategy::hashed(1, 2), "alice")),
+        Path::from((&ShardStrategy::hashed(1, 2), "alicia")),
+    );
+    assert_eq!(
+        Path::from((&ShardStrategy::new(1, 2), "alice")),
+        Path::from((&ShardStrategy::new(1, 2), "alicia")),
+    );
+
+    // Bytes are sharded on the raw bytes of their hash.
+    let bytes: Vec<Component> = Path::from((&ShardStrategy::hashed(3, 2), vec![1_u8])).into();
+    assert_eq!(
+        vec![3, 3],
+        bytes.iter().map(|c| c.as_ref().len()).collect::<Vec<_>>()
+    );
+
+    // Paths built from the DSL keep the original component as the leaf.
+    assert_eq!(
+        Path::from(format!(
+            "foo.{}.al",
+            String::try_from(
+                &Vec::<Component>::from(Path::from((&ShardStrategy::hashed(1, 1), "al")))[0]
+            )
+            .unwrap()
+        )),
+        Path::from("foo.1:1h#al"),
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn hash_path_shard_time() {
+    // 2021-07-14T09:30:00Z
+    let timestamp = Timestamp::from_micros(1_626_255_000 * 1_000_000);
+    for (granularity, output) in vec![
+        (TimeGranularity::Year, "2021"),
+        (TimeGranularity::Month, "2021.07"),
+        (TimeGranularity::Day, "2021.07.14"),
+        (TimeGranularity::Hour, "2021.07.14.09"),
+    ] {
+        assert_eq!(Path::from(output), Path::from((&granularity, timestamp)));
+    }
+
+    // Leap days and times before the epoch.
+    assert_eq!(
+        Path::from("2020.02.29.23"),
+        Path::from((
+            &TimeGranularity::Hour,
+            Timestamp::from_micros(1_583_020_799 * 1_000_000)
+        ))
+    );
+    assert_eq!(
+        Path::from("1969.12.31.23"),
+        Path::from((&TimeGranularity::Hour, Timestamp::from_micros(-1)))
+    );
+}
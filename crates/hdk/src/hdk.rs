@@ -3,6 +3,11 @@ use crate::prelude::*;
 #[cfg(feature = "mock")]
 use mockall::*;
 
+#[cfg(feature = "mem")]
+pub mod mem;
+#[cfg(feature = "mem")]
+pub use mem::MemHdk;
+
 pub const HDK_NOT_REGISTERED: &str = "HDK not registered";
 
 /// This is a cell so it can be set many times.
@@ -29,6 +34,8 @@ pub trait HdkT: Send + Sync {
         get_agent_activity_input: GetAgentActivityInput,
     ) -> ExternResult<AgentActivity>;
     fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Element>>;
+    fn query_page(&self, input: ChainQueryPageInput) -> ExternResult<ChainQueryPage>;
+    fn publish_status(&self, header_hash: HeaderHash) -> ExternResult<Option<PublishStatus>>;
     // Ed25519
     fn sign(&self, sign: Sign) -> ExternResult<Signature>;
     fn sign_ephemeral(&self, sign_ephemeral: SignEphemeral) -> ExternResult<EphemeralSignatures>;
@@ -37,8 +44,10 @@ pub trait HdkT: Send + Sync {
     fn create(&self, create_input: CreateInput) -> ExternResult<HeaderHash>;
     fn update(&self, update_input: UpdateInput) -> ExternResult<HeaderHash>;
     fn delete(&self, delete_input: DeleteInput) -> ExternResult<HeaderHash>;
+    fn commit_batch(&self, batch: Vec<BatchOp>) -> ExternResult<Vec<HeaderHash>>;
     fn hash_entry(&self, entry: Entry) -> ExternResult<EntryHash>;
     fn get(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Element>>>;
+    fn get_chunks(&self, input: GetChunksInput) -> ExternResult<Option<Vec<Chunk>>>;
     fn get_details(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>>;
     fn must_get_entry(&self, must_get_entry_input: MustGetEntryInput) -> ExternResult<EntryHashed>;
     fn must_get_header(
@@ -71,6 +80,10 @@ pub trait HdkT: Send + Sync {
     fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>>;
     fn emit_signal(&self, app_signal: AppSignal) -> ExternResult<()>;
     fn remote_signal(&self, remote_signal: RemoteSignal) -> ExternResult<()>;
+    fn remote_signal_acked(
+        &self,
+        remote_signal: RemoteSignal,
+    ) -> ExternResult<Vec<RemoteSignalAck>>;
     // Random
     fn random_bytes(&self, number_of_bytes: u32) -> ExternResult<Bytes>;
     // Time
@@ -117,6 +130,12 @@ impl HdkT for ErrHdk {
     fn query(&self, _: ChainQueryFilter) -> ExternResult<Vec<Element>> {
         Self::err()
     }
+    fn query_page(&self, _: ChainQueryPageInput) -> ExternResult<ChainQueryPage> {
+        Self::err()
+    }
+    fn publish_status(&self, _: HeaderHash) -> ExternResult<Option<PublishStatus>> {
+        Self::err()
+    }
     fn sign(&self, _: Sign) -> ExternResult<Signature> {
         Self::err()
     }
@@ -135,12 +154,18 @@ impl HdkT for ErrHdk {
     fn delete(&self, _: DeleteInput) -> ExternResult<HeaderHash> {
         Self::err()
     }
+    fn commit_batch(&self, _: Vec<BatchOp>) -> ExternResult<Vec<HeaderHash>> {
+        Self::err()
+    }
     fn hash_entry(&self, _: Entry) -> ExternResult<EntryHash> {
         Self::err()
     }
     fn get(&self, _: Vec<GetInput>) -> ExternResult<Vec<Option<Element>>> {
         Self::err()
     }
+    fn get_chunks(&self, _: GetChunksInput) -> ExternResult<Option<Vec<Chunk>>> {
+        Self::err()
+    }
     fn get_details(&self, _: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>> {
         Self::err()
     }
@@ -195,6 +220,9 @@ impl HdkT for ErrHdk {
     fn remote_signal(&self, _: RemoteSignal) -> ExternResult<()> {
         Self::err()
     }
+    fn remote_signal_acked(&self, _: RemoteSignal) -> ExternResult<Vec<RemoteSignalAck>> {
+        Self::err()
+    }
     // Random
     fn random_bytes(&self, _: u32) -> ExternResult<Bytes> {
         Self::err()
@@ -264,6 +292,12 @@ impl HdkT for HostHdk {
     fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Element>> {
         host_call::<ChainQueryFilter, Vec<Element>>(__query, filter)
     }
+    fn query_page(&self, input: ChainQueryPageInput) -> ExternResult<ChainQueryPage> {
+        host_call::<ChainQueryPageInput, ChainQueryPage>(__query_page, input)
+    }
+    fn publish_status(&self, header_hash: HeaderHash) -> ExternResult<Option<PublishStatus>> {
+        host_call::<HeaderHash, Option<PublishStatus>>(__publish_status, header_hash)
+    }
     fn sign(&self, sign: Sign) -> ExternResult<Signature> {
         host_call::<Sign, Signature>(__sign, sign)
     }
@@ -282,12 +316,18 @@ impl HdkT for HostHdk {
     fn delete(&self, hash: DeleteInput) -> ExternResult<HeaderHash> {
         host_call::<DeleteInput, HeaderHash>(__delete, hash)
     }
+    fn commit_batch(&self, batch: Vec<BatchOp>) -> ExternResult<Vec<HeaderHash>> {
+        host_call::<Vec<BatchOp>, Vec<HeaderHash>>(__commit_batch, batch)
+    }
     fn hash_entry(&self, entry: Entry) -> ExternResult<EntryHash> {
         host_call::<Entry, EntryHash>(__hash_entry, entry)
     }
     fn get(&self, get_inputs: Vec<GetInput>) -> ExternResult<Vec<Option<Element>>> {
         host_call::<Vec<GetInput>, Vec<Option<Element>>>(__get, get_inputs)
     }
+    fn get_chunks(&self, input: GetChunksInput) -> ExternResult<Option<Vec<Chunk>>> {
+        host_call::<GetChunksInput, Option<Vec<Chunk>>>(__get_chunks, input)
+    }
     fn get_details(&self, get_inputs: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>> {
         host_call::<Vec<GetInput>, Vec<Option<Details>>>(__get_details, get_inputs)
     }
@@ -358,6 +398,12 @@ impl HdkT for HostHdk {
     fn remote_signal(&self, remote_signal: RemoteSignal) -> ExternResult<()> {
         host_call::<RemoteSignal, ()>(__remote_signal, remote_signal)
     }
+    fn remote_signal_acked(
+        &self,
+        remote_signal: RemoteSignal,
+    ) -> ExternResult<Vec<RemoteSignalAck>> {
+        host_call::<RemoteSignal, Vec<RemoteSignalAck>>(__remote_signal_acked, remote_signal)
+    }
     fn random_bytes(&self, number_of_bytes: u32) -> ExternResult<Bytes> {
         host_call::<u32, Bytes>(__random_bytes, number_of_bytes)
     }
@@ -0,0 +1,1279 @@
+//! An in-memory [`HdkT`] for unit testing zome code natively.
+//!
+//! The [`MemHdk`] keeps a simulated DHT in process: every agent has a source
+//! chain that starts with the usual genesis elements, and every commit is
+//! published to the shared DHT immediately. Entries, updates, deletes, links
+//! and capability grants behave like they do on a conductor, so zome code can
+//! be exercised with a plain `cargo test` and no wasm build.
+//!
+//! ```ignore
+//! let alice = MemHdk::new("my_zome", entry_defs);
+//! let bob = alice.new_agent();
+//! set_hdk(alice.clone());
+//! let header_hash = create_entry(&post)?;
+//! ```
+//!
+//! Not everything can be simulated:
+//! - Signatures are deterministic hashes rather than ed25519, so they only
+//!   verify against other `MemHdk` signatures.
+//! - `random_bytes` is deterministic per network.
+//! - Every element is valid and every op is integrated as soon as it is
+//!   committed. There is no validation and no publish status.
+//! - Countersigning and the `x_salsa20_poly1305` functions return an error.
+//! - `call` and `remote_signal` can only reach zome functions registered with
+//!   [`MemHdk::register_zome_fn`].
+
+use crate::prelude::*;
+use holo_hash::encode::blake2b_256;
+use holo_hash::hash_type::AnyDht;
+use holo_hash::DnaHash;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+/// A native zome function that `call` and `remote_signal` can dispatch to.
+pub type MemZomeFn = Arc<dyn Fn(ExternIO) -> ExternResult<ExternIO> + Send + Sync>;
+
+/// The network shared by every [`MemHdk`] created from the same root.
+struct MemState {
+    dna_hash: DnaHash,
+    zomes: Vec<(ZomeName, EntryDefs)>,
+    /// Every agent's full source chain, including private entries.
+    chains: HashMap<AgentPubKey, Vec<Element>>,
+    /// Every element in the order it was published, with private entries hidden.
+    dht: Vec<Element>,
+    zome_fns: HashMap<(ZomeName, FunctionName), MemZomeFn>,
+    signals: HashMap<AgentPubKey, Vec<AppSignal>>,
+    schedules: HashMap<AgentPubKey, Vec<String>>,
+    nonce: u64,
+}
+
+/// An [`HdkT`] backed by an in-memory source chain and DHT.
+///
+/// Cloning a `MemHdk` gives another handle to the same agent.
+/// Use [`MemHdk::new_agent`] to add more agents to the same network.
+#[derive(Clone)]
+pub struct MemHdk {
+    state: Arc<Mutex<MemState>>,
+    agent: AgentPubKey,
+    zome_id: ZomeId,
+    /// The caller and grant of the zome call this handle is running, if any.
+    call: Option<(AgentPubKey, FunctionName, CapGrant)>,
+}
+
+impl MemHdk {
+    /// A new network with a single zome and one agent that has run genesis.
+    ///
+    /// The entry defs are usually the ones returned by the zome's
+    /// `entry_defs` callback.
+    pub fn new<Z: Into<ZomeName>>(zome_name: Z, entry_defs: EntryDefs) -> Self {
+        let state = MemState {
+            dna_hash: DnaHash::from_raw_32(blake2b_256(b"mem_hdk_dna")),
+            zomes: vec![(zome_name.into(), entry_defs)],
+            chains: HashMap::new(),
+            dht: Vec::new(),
+            zome_fns: HashMap::new(),
+            signals: HashMap::new(),
+            schedules: HashMap::new(),
+            nonce: 0,
+        };
+        Self::genesis(Arc::new(Mutex::new(state)), ZomeId::new(0))
+    }
+
+    /// Another agent on the same network, in the same zome.
+    pub fn new_agent(&self) -> Self {
+        Self::genesis(self.state.clone(), self.zome_id)
+    }
+
+    /// A handle for the same agent in another zome of the network's DNA.
+    /// The zome is added to the DNA if it isn't part of it yet.
+    pub fn with_zome<Z: Into<ZomeName>>(&self, zome_name: Z, entry_defs: EntryDefs) -> Self {
+        let zome_name = zome_name.into();
+        let mut state = self.state();
+        let index = match state.zomes.iter().position(|(name, _)| name == &zome_name) {
+            Some(index) => {
+                state.zomes[index].1 = entry_defs;
+                index
+            }
+            None => {
+                state.zomes.push((zome_name, entry_defs));
+                state.zomes.len() - 1
+            }
+        };
+        Self {
+            state: self.state.clone(),
+            agent: self.agent.clone(),
+            zome_id: ZomeId::new(index as u8),
+            call: None,
+        }
+    }
+
+    /// The agent this handle commits as.
+    pub fn agent(&self) -> AgentPubKey {
+        self.agent.clone()
+    }
+
+    /// Make a native function callable as `zome_name::fn_name` by `call` and,
+    /// for `recv_remote_signal`, by `remote_signal`.
+    ///
+    /// The function runs on its own thread with the callee's `MemHdk` set as
+    /// the HDK, so it sees the callee's chain and the caller's provenance.
+    pub fn register_zome_fn<Z, F, C>(&self, zome_name: Z, fn_name: F, zome_fn: C)
+    where
+        Z: Into<ZomeName>,
+        F: Into<FunctionName>,
+        C: Fn(ExternIO) -> ExternResult<ExternIO> + Send + Sync + 'static,
+    {
+        self.state()
+            .zome_fns
+            .insert((zome_name.into(), fn_name.into()), Arc::new(zome_fn));
+    }
+
+    /// This agent's full source chain.
+    pub fn source_chain(&self) -> Vec<Element> {
+        self.state()
+            .chains
+            .get(&self.agent)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The signals this agent has emitted with `emit_signal`.
+    pub fn emitted_signals(&self) -> Vec<AppSignal> {
+        self.state()
+            .signals
+            .get(&self.agent)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The functions this agent has scheduled with `schedule`.
+    pub fn scheduled_fns(&self) -> Vec<String> {
+        self.state()
+            .schedules
+            .get(&self.agent)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn genesis(state: Arc<Mutex<MemState>>, zome_id: ZomeId) -> Self {
+        let agent = {
+            let mut state = state.lock().expect("MemHdk state poisoned");
+            let agent = AgentPubKey::from_raw_32(state.seed("agent"));
+            let dna_hash = state.dna_hash.clone();
+            state.chains.insert(agent.clone(), Vec::new());
+            state.push(
+                Header::Dna(Dna {
+                    author: agent.clone(),
+                    timestamp: now(),
+                    hash: dna_hash,
+                }),
+                None,
+            );
+            state.commit(&agent, None, |common| {
+                AgentValidationPkg::from_builder(common, None).into()
+            });
+            let entry = Entry::Agent(agent.clone());
+            let entry_hash = EntryHash::with_data_sync(&entry);
+            state.commit(&agent, Some(entry), |common| {
+                Create::from_builder(common, EntryType::AgentPubKey, entry_hash).into()
+            });
+            agent
+        };
+        Self {
+            state,
+            agent,
+            zome_id,
+            call: None,
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, MemState> {
+        self.state.lock().expect("MemHdk state poisoned")
+    }
+
+    fn entry_type(&self, state: &MemState, entry_def_id: &EntryDefId) -> ExternResult<EntryType> {
+        Ok(match entry_def_id {
+            EntryDefId::App(id) => {
+                let (zome_name, entry_defs) = &state.zomes[self.zome_id.index()];
+                let entry_def_index = entry_defs
+                    .entry_def_index_from_id(entry_def_id.clone())
+                    .ok_or_else(|| {
+                        WasmError::Host(format!(
+                            "Entry def {} is not defined in zome {}",
+                            id, zome_name
+                        ))
+                    })?;
+                let visibility = entry_defs
+                    .get(entry_def_index.index())
+                    .map(|entry_def| entry_def.visibility)
+                    .unwrap_or_default();
+                EntryType::App(AppEntryType::new(entry_def_index, self.zome_id, visibility))
+            }
+            EntryDefId::CapClaim => EntryType::CapClaim,
+            EntryDefId::CapGrant => EntryType::CapGrant,
+        })
+    }
+
+    fn chain_head(&self, state: &MemState) -> (HeaderHash, u32, Timestamp) {
+        let head = state
+            .chains
+            .get(&self.agent)
+            .and_then(|chain| chain.last())
+            .expect("MemHdk agents always have genesis elements");
+        (
+            head.header_address().clone(),
+            head.header().header_seq(),
+            head.header().timestamp(),
+        )
+    }
+
+    fn get_entry(
+        &self,
+        state: &MemState,
+        entry_hash: &EntryHash,
+        strategy: GetStrategy,
+    ) -> Option<Element> {
+        let entry = state.entry(&self.agent, entry_hash)?;
+        let header = state
+            .entry_headers(entry_hash)
+            .into_iter()
+            .find(|header| state.deletes(header.as_hash()).is_empty())?;
+        let mut element = Element::new(header, Some(entry));
+        if strategy == GetStrategy::Latest {
+            // Follow the winning updates the same way the host does.
+            let mut seen = HashSet::new();
+            while seen.insert(element.header_address().clone()) {
+                let updates: Vec<_> = match element.header().entry_hash() {
+                    Some(entry_hash) => state
+                        .updates(|update| &update.original_entry_address == entry_hash)
+                        .into_iter()
+                        .filter(|update| state.deletes(update.as_hash()).is_empty())
+                        .collect(),
+                    None => break,
+                };
+                let winner = match state
+                    .crdt_type(element.header())
+                    .pick_winner(element.header().author(), &updates)
+                {
+                    Some(winner) => winner.clone(),
+                    None => break,
+                };
+                let entry = winner
+                    .header()
+                    .entry_hash()
+                    .and_then(|entry_hash| state.entry(&self.agent, entry_hash));
+                element = Element::new(winner, entry);
+            }
+        }
+        Some(element)
+    }
+
+    fn call_one(&self, call: Call) -> ExternResult<ZomeCallResponse> {
+        let Call {
+            target,
+            zome_name,
+            fn_name,
+            cap_secret,
+            payload,
+        } = call;
+        let (callee, remote) = match target {
+            CallTarget::NetworkAgent(agent) => (agent, true),
+            CallTarget::ConductorCell(CallTargetCell::Local) => (self.agent.clone(), false),
+            CallTarget::ConductorCell(CallTargetCell::Other(cell_id)) => {
+                (cell_id.agent_pubkey().clone(), false)
+            }
+        };
+        let (dna_hash, zome_id, zome_fn, cap_grant) = {
+            let state = self.state();
+            if !state.chains.contains_key(&callee) {
+                return Ok(ZomeCallResponse::NetworkError(format!(
+                    "Agent {} is not part of this MemHdk network",
+                    callee
+                )));
+            }
+            let granted_function = (zome_name.clone(), fn_name.clone());
+            (
+                state.dna_hash.clone(),
+                state.zomes.iter().position(|(name, _)| name == &zome_name),
+                state.zome_fns.get(&granted_function).cloned(),
+                state.cap_grants(&callee).into_iter().find(|cap_grant| {
+                    cap_grant.is_valid(&granted_function, &self.agent, cap_secret.as_ref())
+                }),
+            )
+        };
+        let cap_grant = match cap_grant {
+            Some(cap_grant) => cap_grant,
+            None => {
+                return Ok(ZomeCallResponse::Unauthorized(
+                    CellId::new(dna_hash, callee),
+                    zome_name,
+                    fn_name,
+                    self.agent.clone(),
+                ))
+            }
+        };
+        let (zome_id, zome_fn) = match (zome_id, zome_fn) {
+            (Some(zome_id), Some(zome_fn)) => (zome_id, zome_fn),
+            _ => {
+                return Err(WasmError::Host(format!(
+                    "Zome function {}::{} is not registered with the MemHdk",
+                    zome_name, fn_name
+                )))
+            }
+        };
+        let callee_hdk = Self {
+            state: self.state.clone(),
+            agent: callee,
+            zome_id: ZomeId::new(zome_id as u8),
+            call: Some((self.agent.clone(), fn_name, cap_grant)),
+        };
+        // The HDK is thread local, so the callee gets its own thread.
+        let result = std::thread::spawn(move || {
+            set_hdk(callee_hdk);
+            zome_fn(payload)
+        })
+        .join()
+        .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+        match result {
+            Ok(extern_io) => Ok(ZomeCallResponse::Ok(extern_io)),
+            Err(wasm_error) if remote => Ok(ZomeCallResponse::NetworkError(wasm_error.to_string())),
+            Err(wasm_error) => Err(WasmError::Host(wasm_error.to_string())),
+        }
+    }
+
+    fn link_details(&self, state: &MemState, get_links_input: GetLinksInput) -> LinkDetails {
+        let GetLinksInput {
+            base_address,
+            tag_prefix,
+        } = get_links_input;
+        state
+            .dht
+            .iter()
+            .map(|element| element.signed_header())
+            .filter(|header| match header.header() {
+                Header::CreateLink(create_link) => {
+                    create_link.base_address == base_address
+                        && create_link.zome_id == self.zome_id
+                        && tag_prefix
+                            .as_ref()
+                            .map(|prefix| create_link.tag.as_ref().starts_with(prefix.as_ref()))
+                            .unwrap_or(true)
+                }
+                _ => false,
+            })
+            .map(|create_link| (create_link.clone(), state.deletes(create_link.as_hash())))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    fn unsupported<T>(name: &str) -> ExternResult<T> {
+        Err(WasmError::Host(format!(
+            "{} is not supported by the MemHdk",
+            name
+        )))
+    }
+}
+
+impl MemState {
+    fn seed(&mut self, kind: &str) -> Vec<u8> {
+        self.nonce += 1;
+        blake2b_256(format!("mem_hdk_{}_{}", kind, self.nonce).as_bytes())
+    }
+
+    /// Sign, store and publish a header at the top of its author's chain.
+    fn push(&mut self, header: Header, entry: Option<Entry>) -> HeaderHash {
+        let author = header.author().clone();
+        let signature = sign_header(&author, &header);
+        let header = HeaderHashed::from_content_sync(header);
+        let header_hash = header.as_hash().clone();
+        let element = Element::new(SignedHeaderHashed::with_presigned(header, signature), entry);
+        self.dht.push(element.clone().privatized());
+        self.chains.entry(author).or_default().push(element);
+        header_hash
+    }
+
+    fn commit<F>(&mut self, author: &AgentPubKey, entry: Option<Entry>, header: F) -> HeaderHash
+    where
+        F: FnOnce(HeaderBuilderCommon) -> Header,
+    {
+        let head = self
+            .chains
+            .get(author)
+            .and_then(|chain| chain.last())
+            .expect("MemHdk agents always have genesis elements");
+        // Headers must not go back in time, even when they are committed
+        // within the same microsecond.
+        let timestamp = std::cmp::max(
+            now(),
+            Timestamp::from_micros(head.header().timestamp().as_micros() + 1),
+        );
+        let common = HeaderBuilderCommon::new(
+            author.clone(),
+            timestamp,
+            head.header().header_seq() + 1,
+            head.header_address().clone(),
+        );
+        self.push(header(common), entry)
+    }
+
+    /// The author's own view of a header, falling back to the DHT.
+    fn element(&self, agent: &AgentPubKey, header_hash: &HeaderHash) -> Option<Element> {
+        self.chains
+            .get(agent)
+            .into_iter()
+            .flatten()
+            .chain(self.dht.iter())
+            .find(|element| element.header_address() == header_hash)
+            .cloned()
+    }
+
+    /// An entry the agent can see: its own private entries or any public one.
+    fn entry(&self, agent: &AgentPubKey, entry_hash: &EntryHash) -> Option<Entry> {
+        self.chains
+            .get(agent)
+            .into_iter()
+            .flatten()
+            .chain(self.dht.iter())
+            .filter(|element| element.header().entry_hash() == Some(entry_hash))
+            .find_map(|element| element.entry().as_option().cloned())
+    }
+
+    fn entry_headers(&self, entry_hash: &EntryHash) -> Vec<SignedHeaderHashed> {
+        self.dht
+            .iter()
+            .map(|element| element.signed_header())
+            .filter(|header| header.header().entry_hash() == Some(entry_hash))
+            .cloned()
+            .collect()
+    }
+
+    fn deletes(&self, header_hash: &HeaderHash) -> Vec<SignedHeaderHashed> {
+        self.dht
+            .iter()
+            .map(|element| element.signed_header())
+            .filter(|header| match header.header() {
+                Header::Delete(delete) => &delete.deletes_address == header_hash,
+                Header::DeleteLink(delete_link) => &delete_link.link_add_address == header_hash,
+                _ => false,
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn updates<F: Fn(&Update) -> bool>(&self, f: F) -> Vec<SignedHeaderHashed> {
+        self.dht
+            .iter()
+            .map(|element| element.signed_header())
+            .filter(|header| match header.header() {
+                Header::Update(update) => f(update),
+                _ => false,
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn crdt_type(&self, header: &Header) -> CrdtType {
+        match header.entry_type() {
+            Some(EntryType::App(app_entry_type)) => self
+                .zomes
+                .get(app_entry_type.zome_id().index())
+                .and_then(|(_, entry_defs)| entry_defs.get(app_entry_type.id().index()))
+                .map(|entry_def| entry_def.crdt_type)
+                .unwrap_or_default(),
+            _ => CrdtType::default(),
+        }
+    }
+
+    /// The grants on the agent's chain that have not been updated or deleted.
+    fn cap_grants(&self, agent: &AgentPubKey) -> Vec<CapGrant> {
+        self.chains
+            .get(agent)
+            .into_iter()
+            .flatten()
+            .filter(|element| {
+                self.deletes(element.header_address()).is_empty()
+                    && self
+                        .updates(|update| {
+                            &update.original_header_address == element.header_address()
+                        })
+                        .is_empty()
+            })
+            .filter_map(|element| element.entry().as_option()?.as_cap_grant())
+            .collect()
+    }
+}
+
+fn now() -> Timestamp {
+    let micros = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_micros() as i64)
+        .unwrap_or_default();
+    Timestamp::from_micros(micros)
+}
+
+/// A deterministic stand in for an ed25519 signature.
+fn fake_signature(key: &AgentPubKey, data: &[u8]) -> Signature {
+    let mut input = key.get_raw_39().to_vec();
+    input.extend_from_slice(data);
+    let mut signature = [0; SIGNATURE_BYTES];
+    signature[..32].copy_from_slice(&blake2b_256(&input));
+    input.reverse();
+    signature[32..].copy_from_slice(&blake2b_256(&input));
+    Signature(signature)
+}
+
+fn sign_header(author: &AgentPubKey, header: &Header) -> Signature {
+    let bytes =
+        SerializedBytes::try_from(header.clone()).expect("Headers can always be serialized");
+    fake_signature(author, bytes.bytes())
+}
+
+impl HdkT for MemHdk {
+    fn get_agent_activity(
+        &self,
+        get_agent_activity_input: GetAgentActivityInput,
+    ) -> ExternResult<AgentActivity> {
+        let GetAgentActivityInput {
+            agent_pubkey,
+            chain_query_filter,
+            activity_request,
+        } = get_agent_activity_input;
+        let state = self.state();
+        let chain = match state.chains.get(&agent_pubkey) {
+            Some(chain) => chain,
+            None => {
+                return Ok(AgentActivity {
+                    valid_activity: Vec::new(),
+                    rejected_activity: Vec::new(),
+                    status: ChainStatus::Empty,
+                    highest_observed: None,
+                    warrants: Vec::new(),
+                })
+            }
+        };
+        let head = chain.last().map(|element| ChainHead {
+            header_seq: element.header().header_seq(),
+            hash: element.header_address().clone(),
+        });
+        let valid_activity = match activity_request {
+            ActivityRequest::Status => Vec::new(),
+            ActivityRequest::Full => chain_query_filter
+                .filter_elements(chain.clone())
+                .into_iter()
+                .map(|element| {
+                    (
+                        element.header().header_seq(),
+                        element.header_address().clone(),
+                    )
+                })
+                .collect(),
+        };
+        Ok(AgentActivity {
+            valid_activity,
+            rejected_activity: Vec::new(),
+            status: head
+                .clone()
+                .map(ChainStatus::Valid)
+                .unwrap_or(ChainStatus::Empty),
+            highest_observed: head.map(|head| HighestObserved {
+                header_seq: head.header_seq,
+                hash: vec![head.hash],
+            }),
+            warrants: Vec::new(),
+        })
+    }
+    fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Element>> {
+        let elements = self
+            .source_chain()
+            .into_iter()
+            .map(|element| {
+                if filter.include_entries {
+                    element
+                } else {
+                    Element::new(element.signed_header().clone(), None)
+                }
+            })
+            .collect();
+        Ok(filter.filter_elements(elements))
+    }
+    fn query_page(&self, input: ChainQueryPageInput) -> ExternResult<ChainQueryPage> {
+        let ChainQueryPageInput {
+            filter,
+            cursor,
+            limit,
+            direction,
+        } = input;
+        let mut elements = self.query(filter)?;
+        if direction == ChainQueryDirection::Descending {
+            elements.reverse();
+        }
+        let mut elements: Vec<Element> = elements
+            .into_iter()
+            .filter(|element| {
+                let seq = element.header().header_seq();
+                match (cursor, direction) {
+                    (None, _) => true,
+                    (Some(cursor), ChainQueryDirection::Ascending) => seq > cursor.header_seq(),
+                    (Some(cursor), ChainQueryDirection::Descending) => seq < cursor.header_seq(),
+                }
+            })
+            .take(limit as usize + 1)
+            .collect();
+        let next = if elements.len() > limit as usize {
+            elements.truncate(limit as usize);
+            elements
+                .last()
+                .map(|element| ChainQueryCursor::new(element.header().header_seq()))
+        } else {
+            None
+        };
+        Ok(ChainQueryPage { elements, next })
+    }
+    fn publish_status(&self, _: HeaderHash) -> ExternResult<Option<PublishStatus>> {
+        Self::unsupported("publish_status")
+    }
+    fn sign(&self, sign: Sign) -> ExternResult<Signature> {
+        Ok(fake_signature(&sign.key, &sign.data))
+    }
+    fn sign_ephemeral(&self, sign_ephemeral: SignEphemeral) -> ExternResult<EphemeralSignatures> {
+        let key = AgentPubKey::from_raw_32(self.state().seed("ephemeral"));
+        let signatures = sign_ephemeral
+            .0
+            .iter()
+            .map(|data| fake_signature(&key, data))
+            .collect();
+        Ok(EphemeralSignatures { key, signatures })
+    }
+    fn verify_signature(&self, verify_signature: VerifySignature) -> ExternResult<bool> {
+        Ok(
+            fake_signature(&verify_signature.key, &verify_signature.data)
+                == verify_signature.signature,
+        )
+    }
+    fn create(&self, create_input: CreateInput) -> ExternResult<HeaderHash> {
+        let CreateInput {
+            entry_def_id,
+            entry,
+            ..
+        } = create_input;
+        let mut state = self.state();
+        let entry_type = self.entry_type(&state, &entry_def_id)?;
+        let entry_hash = EntryHash::with_data_sync(&entry);
+        Ok(state.commit(&self.agent, Some(entry), |common| {
+            Create::from_builder(common, entry_type, entry_hash).into()
+        }))
+    }
+    fn update(&self, update_input: UpdateInput) -> ExternResult<HeaderHash> {
+        let UpdateInput {
+            original_header_address,
+            create_input:
+                CreateInput {
+                    entry_def_id,
+                    entry,
+                    ..
+                },
+        } = update_input;
+        let mut state = self.state();
+        let original_entry_address = state
+            .element(&self.agent, &original_header_address)
+            .and_then(|element| element.header().entry_hash().cloned())
+            .ok_or_else(|| {
+                WasmError::Host(format!(
+                    "Header {} to update was not found or has no entry",
+                    original_header_address
+                ))
+            })?;
+        let entry_type = self.entry_type(&state, &entry_def_id)?;
+        let entry_hash = EntryHash::with_data_sync(&entry);
+        Ok(state.commit(&self.agent, Some(entry), |common| {
+            Update::from_builder(
+                common,
+                original_entry_address,
+                original_header_address,
+                entry_type,
+                entry_hash,
+            )
+            .into()
+        }))
+    }
+    fn delete(&self, delete_input: DeleteInput) -> ExternResult<HeaderHash> {
+        let deletes_address = delete_input.deletes_header_address;
+        let mut state = self.state();
+        let deletes_entry_address = state
+            .element(&self.agent, &deletes_address)
+            .and_then(|element| element.header().entry_hash().cloned())
+            .ok_or_else(|| {
+                WasmError::Host(format!(
+                    "Header {} to delete was not found or has no entry",
+                    deletes_address
+                ))
+            })?;
+        Ok(state.commit(&self.agent, None, |common| {
+            Delete::from_builder(common, deletes_address, deletes_entry_address).into()
+        }))
+    }
+    fn commit_batch(&self, batch: Vec<BatchOp>) -> ExternResult<Vec<HeaderHash>> {
+        let (chain_len, dht_len) = {
+            let state = self.state();
+            let chain_len = state.chains.get(&self.agent).map_or(0, Vec::len);
+            (chain_len, state.dht.len())
+        };
+        let result = batch
+            .into_iter()
+            .map(|op| match op {
+                BatchOp::Create(input) => self.create(input),
+                BatchOp::Update(input) => self.update(input),
+                BatchOp::Delete(input) => self.delete(input),
+                BatchOp::CreateLink(input) => self.create_link(input),
+                BatchOp::DeleteLink(input) => self.delete_link(input),
+            })
+            .collect::<ExternResult<Vec<_>>>();
+        // Nothing is kept if any op fails.
+        if result.is_err() {
+            let mut state = self.state();
+            if let Some(chain) = state.chains.get_mut(&self.agent) {
+                chain.truncate(chain_len);
+            }
+            let published = state.dht.split_off(dht_len);
+            state.dht.extend(
+                published
+                    .into_iter()
+                    .filter(|element| element.header().author() != &self.agent),
+            );
+        }
+        result
+    }
+    fn hash_entry(&self, entry: Entry) -> ExternResult<EntryHash> {
+        Ok(EntryHash::with_data_sync(&entry))
+    }
+    fn get(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Element>>> {
+        let state = self.state();
+        Ok(get_input
+            .into_iter()
+            .map(
+                |GetInput {
+                     any_dht_hash,
+                     get_options,
+                 }| match *any_dht_hash.hash_type() {
+                    AnyDht::Entry => {
+                        self.get_entry(&state, &any_dht_hash.into(), get_options.strategy)
+                    }
+                    AnyDht::Header => {
+                        let header_hash = any_dht_hash.into();
+                        if state.deletes(&header_hash).is_empty() {
+                            state.element(&self.agent, &header_hash)
+                        } else {
+                            None
+                        }
+                    }
+                },
+            )
+            .collect())
+    }
+    fn get_chunks(&self, input: GetChunksInput) -> ExternResult<Option<Vec<Chunk>>> {
+        let GetChunksInput {
+            chunk_set_hash,
+            range,
+            get_options,
+        } = input;
+        let state = self.state();
+        let chunk_set = match self.get_entry(&state, &chunk_set_hash, get_options.strategy) {
+            Some(element) => ChunkSet::try_from(&element)?,
+            None => return Ok(None),
+        };
+        let end = (range.end as usize).min(chunk_set.chunks().len());
+        let start = (range.start as usize).min(end);
+        chunk_set.chunks()[start..end]
+            .iter()
+            .map(
+                |chunk_hash| match self.get_entry(&state, chunk_hash, get_options.strategy) {
+                    Some(element) => Chunk::try_from(&element).map(Some),
+                    None => Ok(None),
+                },
+            )
+            .collect()
+    }
+    fn get_details(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>> {
+        let state = self.state();
+        Ok(get_input
+            .into_iter()
+            .map(
+                |GetInput { any_dht_hash, .. }| match *any_dht_hash.hash_type() {
+                    AnyDht::Entry => {
+                        let entry_hash: EntryHash = any_dht_hash.into();
+                        let entry = state.entry(&self.agent, &entry_hash)?;
+                        let headers = state.entry_headers(&entry_hash);
+                        let deletes: Vec<_> = headers
+                            .iter()
+                            .flat_map(|header| state.deletes(header.as_hash()))
+                            .collect();
+                        let entry_dht_status = if headers
+                            .iter()
+                            .any(|header| state.deletes(header.as_hash()).is_empty())
+                        {
+                            EntryDhtStatus::Live
+                        } else {
+                            EntryDhtStatus::Dead
+                        };
+                        Some(Details::Entry(EntryDetails {
+                            entry,
+                            headers,
+                            rejected_headers: Vec::new(),
+                            deletes,
+                            updates: state
+                                .updates(|update| update.original_entry_address == entry_hash),
+                            entry_dht_status,
+                        }))
+                    }
+                    AnyDht::Header => {
+                        let header_hash: HeaderHash = any_dht_hash.into();
+                        Some(Details::Element(ElementDetails {
+                            element: state.element(&self.agent, &header_hash)?,
+                            validation_status: ValidationStatus::Valid,
+                            deletes: state.deletes(&header_hash),
+                            updates: state
+                                .updates(|update| update.original_header_address == header_hash),
+                        }))
+                    }
+                },
+            )
+            .collect())
+    }
+    fn must_get_entry(&self, must_get_entry_input: MustGetEntryInput) -> ExternResult<EntryHashed> {
+        let entry_hash = must_get_entry_input.into_inner();
+        self.state()
+            .entry(&self.agent, &entry_hash)
+            .map(EntryHashed::from_content_sync)
+            .ok_or_else(|| WasmError::Host(format!("Failed to get EntryHashed {}", entry_hash)))
+    }
+    fn must_get_header(
+        &self,
+        must_get_header_input: MustGetHeaderInput,
+    ) -> ExternResult<SignedHeaderHashed> {
+        let header_hash = must_get_header_input.into_inner();
+        self.state()
+            .element(&self.agent, &header_hash)
+            .map(|element| element.signed_header().clone())
+            .ok_or_else(|| {
+                WasmError::Host(format!("Failed to get SignedHeaderHashed {}", header_hash))
+            })
+    }
+    fn must_get_valid_element(
+        &self,
+        must_get_valid_element_input: MustGetValidElementInput,
+    ) -> ExternResult<Element> {
+        let header_hash = must_get_valid_element_input.into_inner();
+        self.state()
+            .element(&self.agent, &header_hash)
+            .ok_or_else(|| WasmError::Host(format!("Failed to get Element {}", header_hash)))
+    }
+    // CounterSigning
+    fn accept_countersigning_preflight_request(
+        &self,
+        _: PreflightRequest,
+    ) -> ExternResult<PreflightRequestAcceptance> {
+        Self::unsupported("accept_countersigning_preflight_request")
+    }
+    fn agent_info(&self, _: ()) -> ExternResult<AgentInfo> {
+        let state = self.state();
+        Ok(AgentInfo::new(
+            self.agent.clone(),
+            self.agent.clone(),
+            self.chain_head(&state),
+        ))
+    }
+    fn dna_info(&self, _: ()) -> ExternResult<DnaInfo> {
+        let state = self.state();
+        Ok(DnaInfo {
+            name: "mem_hdk".to_string(),
+            hash: state.dna_hash.clone(),
+            properties: SerializedBytes::try_from(()).map_err(WasmError::Serialize)?,
+            zome_names: state.zomes.iter().map(|(name, _)| name.clone()).collect(),
+        })
+    }
+    fn zome_info(&self, _: ()) -> ExternResult<ZomeInfo> {
+        let state = self.state();
+        let (zome_name, entry_defs) = state.zomes[self.zome_id.index()].clone();
+        let mut extern_fns: Vec<_> = state
+            .zome_fns
+            .keys()
+            .filter(|(name, _)| name == &zome_name)
+            .map(|(_, fn_name)| fn_name.clone())
+            .collect();
+        extern_fns.sort();
+        Ok(ZomeInfo::new(
+            zome_name,
+            self.zome_id,
+            SerializedBytes::try_from(()).map_err(WasmError::Serialize)?,
+            entry_defs,
+            extern_fns,
+        ))
+    }
+    fn call_info(&self, _: ()) -> ExternResult<CallInfo> {
+        let state = self.state();
+        let (provenance, function_name, cap_grant) = self.call.clone().unwrap_or_else(|| {
+            (
+                self.agent.clone(),
+                FunctionName::from("mem_hdk"),
+                CapGrant::ChainAuthor(self.agent.clone()),
+            )
+        });
+        Ok(CallInfo {
+            provenance,
+            function_name,
+            as_at: self.chain_head(&state),
+            cap_grant,
+        })
+    }
+    // Link
+    fn create_link(&self, create_link_input: CreateLinkInput) -> ExternResult<HeaderHash> {
+        let CreateLinkInput {
+            base_address,
+            target_address,
+            tag,
+            ..
+        } = create_link_input;
+        let zome_id = self.zome_id;
+        Ok(self.state().commit(&self.agent, None, |common| {
+            CreateLink::from_builder(common, base_address, target_address, zome_id, tag).into()
+        }))
+    }
+    fn delete_link(&self, delete_link_input: DeleteLinkInput) -> ExternResult<HeaderHash> {
+        let link_add_address = delete_link_input.address;
+        let mut state = self.state();
+        let base_address = match state
+            .element(&self.agent, &link_add_address)
+            .map(|element| element.header().clone())
+        {
+            Some(Header::CreateLink(create_link)) => create_link.base_address,
+            _ => {
+                return Err(WasmError::Host(format!(
+                    "CreateLink {} to delete was not found",
+                    link_add_address
+                )))
+            }
+        };
+        Ok(state.commit(&self.agent, None, |common| {
+            DeleteLink::from_builder(common, link_add_address, base_address).into()
+        }))
+    }
+    fn get_links(&self, get_links_input: Vec<GetLinksInput>) -> ExternResult<Vec<Vec<Link>>> {
+        let state = self.state();
+        Ok(get_links_input
+            .into_iter()
+            .map(|get_links_input| {
+                self.link_details(&state, get_links_input)
+                    .into_inner()
+                    .into_iter()
+                    .filter(|(_, deletes)| deletes.is_empty())
+                    .filter_map(|(create_link, _)| match create_link.header() {
+                        Header::CreateLink(header) => Some(Link {
+                            target: header.target_address.clone(),
+                            timestamp: header.timestamp,
+                            tag: header.tag.clone(),
+                            create_link_hash: create_link.as_hash().clone(),
+                        }),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+    fn get_link_details(
+        &self,
+        get_links_input: Vec<GetLinksInput>,
+    ) -> ExternResult<Vec<LinkDetails>> {
+        let state = self.state();
+        Ok(get_links_input
+            .into_iter()
+            .map(|get_links_input| self.link_details(&state, get_links_input))
+            .collect())
+    }
+    // P2P
+    fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>> {
+        call.into_iter().map(|call| self.call_one(call)).collect()
+    }
+    fn emit_signal(&self, app_signal: AppSignal) -> ExternResult<()> {
+        self.state()
+            .signals
+            .entry(self.agent.clone())
+            .or_default()
+            .push(app_signal);
+        Ok(())
+    }
+    fn remote_signal(&self, remote_signal: RemoteSignal) -> ExternResult<()> {
+        let zome_name = self.state().zomes[self.zome_id.index()].0.clone();
+        // Remote signals are fire and forget so failures are not reported.
+        for agent in remote_signal.agents {
+            self.call_one(Call::new(
+                CallTarget::NetworkAgent(agent),
+                zome_name.clone(),
+                "recv_remote_signal".into(),
+                None,
+                remote_signal.signal.clone(),
+            ))
+            .ok();
+        }
+        Ok(())
+    }
+    fn remote_signal_acked(
+        &self,
+        remote_signal: RemoteSignal,
+    ) -> ExternResult<Vec<RemoteSignalAck>> {
+        let zome_name = self.state().zomes[self.zome_id.index()].0.clone();
+        let mut acks = Vec::with_capacity(remote_signal.agents.len());
+        for agent in remote_signal.agents {
+            let delivery = if !self.state().chains.contains_key(&agent) {
+                RemoteSignalDelivery::Unreachable(format!(
+                    "Agent {} is not part of this MemHdk network",
+                    agent
+                ))
+            } else {
+                match self.call_one(Call::new(
+                    CallTarget::NetworkAgent(agent.clone()),
+                    zome_name.clone(),
+                    "recv_remote_signal".into(),
+                    None,
+                    remote_signal.signal.clone(),
+                )) {
+                    Ok(ZomeCallResponse::Ok(_)) => RemoteSignalDelivery::Delivered,
+                    Ok(ZomeCallResponse::Unauthorized(..)) => RemoteSignalDelivery::Rejected(
+                        "recv_remote_signal is not granted to this agent".into(),
+                    ),
+                    Ok(ZomeCallResponse::NetworkError(reason))
+                    | Ok(ZomeCallResponse::CountersigningSession(reason)) => {
+                        RemoteSignalDelivery::Rejected(reason)
+                    }
+                    Err(wasm_error) => RemoteSignalDelivery::Rejected(wasm_error.to_string()),
+                }
+            };
+            acks.push(RemoteSignalAck { agent, delivery });
+        }
+        Ok(acks)
+    }
+    // Random
+    fn random_bytes(&self, number_of_bytes: u32) -> ExternResult<Bytes> {
+        let mut state = self.state();
+        let mut bytes = Vec::with_capacity(number_of_bytes as usize);
+        while bytes.len() < number_of_bytes as usize {
+            bytes.extend(state.seed("random"));
+        }
+        bytes.truncate(number_of_bytes as usize);
+        Ok(Bytes::from(bytes))
+    }
+    // Time
+    fn sys_time(&self, _: ()) -> ExternResult<Timestamp> {
+        Ok(now())
+    }
+    fn schedule(&self, scheduled_fn: String) -> ExternResult<()> {
+        self.state()
+            .schedules
+            .entry(self.agent.clone())
+            .or_default()
+            .push(scheduled_fn);
+        Ok(())
+    }
+    fn sleep(&self, wake_after: std::time::Duration) -> ExternResult<()> {
+        std::thread::sleep(wake_after);
+        Ok(())
+    }
+    // Trace
+    fn trace(&self, trace_msg: TraceMsg) -> ExternResult<()> {
+        let TraceMsg { msg, level } = trace_msg;
+        match level {
+            holochain_zome_types::trace::Level::ERROR => tracing::error!("{}", msg),
+            holochain_zome_types::trace::Level::WARN => tracing::warn!("{}", msg),
+            holochain_zome_types::trace::Level::INFO => tracing::info!("{}", msg),
+            holochain_zome_types::trace::Level::DEBUG => tracing::debug!("{}", msg),
+            holochain_zome_types::trace::Level::TRACE => tracing::trace!("{}", msg),
+        }
+        Ok(())
+    }
+    // XSalsa20Poly1305
+    fn create_x25519_keypair(&self, _: ()) -> ExternResult<X25519PubKey> {
+        Self::unsupported("create_x25519_keypair")
+    }
+    fn x_salsa20_poly1305_decrypt(
+        &self,
+        _: XSalsa20Poly1305Decrypt,
+    ) -> ExternResult<Option<XSalsa20Poly1305Data>> {
+        Self::unsupported("x_salsa20_poly1305_decrypt")
+    }
+    fn x_salsa20_poly1305_encrypt(
+        &self,
+        _: XSalsa20Poly1305Encrypt,
+    ) -> ExternResult<XSalsa20Poly1305EncryptedData> {
+        Self::unsupported("x_salsa20_poly1305_encrypt")
+    }
+    fn x_25519_x_salsa20_poly1305_encrypt(
+        &self,
+        _: X25519XSalsa20Poly1305Encrypt,
+    ) -> ExternResult<XSalsa20Poly1305EncryptedData> {
+        Self::unsupported("x_25519_x_salsa20_poly1305_encrypt")
+    }
+    fn x_25519_x_salsa20_poly1305_decrypt(
+        &self,
+        _: X25519XSalsa20Poly1305Decrypt,
+    ) -> ExternResult<Option<XSalsa20Poly1305Data>> {
+        Self::unsupported("x_25519_x_salsa20_poly1305_decrypt")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry_defs() -> EntryDefs {
+        vec![EntryDef::new(
+            "post".into(),
+            EntryVisibility::Public,
+            CrdtType::LastWriterWins,
+            Default::default(),
+            Default::default(),
+        )]
+        .into()
+    }
+
+    fn post(content: &str) -> Entry {
+        let bytes = ExternIO::encode(content).unwrap().0;
+        Entry::app(SerializedBytes::from(UnsafeBytes::from(bytes))).unwrap()
+    }
+
+    fn post_input(content: &str) -> CreateInput {
+        CreateInput::new("post".into(), post(content), ChainTopOrdering::default())
+    }
+
+    fn create_post(content: &str) -> ExternResult<HeaderHash> {
+        create(post_input(content))
+    }
+
+    #[test]
+    fn mem_hdk_crud() {
+        let alice = MemHdk::new("posts", entry_defs());
+        set_hdk(alice.clone());
+
+        // Genesis plus the new post.
+        let header_hash = create_post("hello").unwrap();
+        assert_eq!(4, alice.source_chain().len());
+
+        let entry_hash = hash_entry(post("hello")).unwrap();
+        let element = get(entry_hash.clone(), GetOptions::content())
+            .unwrap()
+            .unwrap();
+        assert_eq!(&header_hash, element.header_address());
+
+        // The latest update wins when getting the original entry.
+        let update_hash = update(header_hash.clone(), post_input("hello again")).unwrap();
+        let latest = get(entry_hash.clone(), GetOptions::latest())
+            .unwrap()
+            .unwrap();
+        assert_eq!(&update_hash, latest.header_address());
+        assert_eq!(Some(&post("hello again")), latest.entry().as_option());
+
+        delete(header_hash.clone()).unwrap();
+        assert_eq!(
+            None,
+            get(header_hash.clone(), GetOptions::content()).unwrap()
+        );
+        match get_details(entry_hash, GetOptions::content()).unwrap() {
+            Some(Details::Entry(details)) => {
+                assert_eq!(EntryDhtStatus::Dead, details.entry_dht_status);
+                assert_eq!(1, details.deletes.len());
+                assert_eq!(1, details.updates.len());
+            }
+            other => panic!("unexpected details {:?}", other),
+        }
+
+        let creates = query(ChainQueryFilter::new().header_type(HeaderType::Create)).unwrap();
+        // The agent key and the post.
+        assert_eq!(2, creates.len());
+    }
+
+    #[test]
+    fn mem_hdk_links() {
+        let alice = MemHdk::new("posts", entry_defs());
+        set_hdk(alice);
+
+        create_post("base").unwrap();
+        create_post("target").unwrap();
+        let base = hash_entry(post("base")).unwrap();
+        let target = hash_entry(post("target")).unwrap();
+
+        let link_hash = create_link(base.clone(), target.clone(), LinkTag::new("a")).unwrap();
+        create_link(base.clone(), target.clone(), LinkTag::new("b")).unwrap();
+
+        assert_eq!(2, get_links(base.clone(), None).unwrap().len());
+        let links = get_links(base.clone(), Some(LinkTag::new("a"))).unwrap();
+        assert_eq!(1, links.len());
+        assert_eq!(target, links[0].target);
+
+        delete_link(link_hash).unwrap();
+        assert_eq!(1, get_links(base.clone(), None).unwrap().len());
+        let details = get_link_details(base, None).unwrap().into_inner();
+        assert_eq!(2, details.len());
+        assert_eq!(1, details.iter().filter(|(_, d)| !d.is_empty()).count());
+    }
+
+    #[test]
+    fn mem_hdk_call_checks_cap_grants() {
+        let alice = MemHdk::new("posts", entry_defs());
+        let bob = alice.new_agent();
+        alice.register_zome_fn("posts", "whoami", |_| {
+            ExternIO::encode((agent_info()?.agent_latest_pubkey, call_info()?.provenance))
+                .map_err(WasmError::Serialize)
+        });
+
+        set_hdk(alice.clone());
+        let call_bob =
+            || call_remote(bob.agent(), "posts".into(), "whoami".into(), None, ()).unwrap();
+        assert!(matches!(call_bob(), ZomeCallResponse::Unauthorized(..)));
+
+        // Bob grants everyone access to the function.
+        set_hdk(bob.clone());
+        let mut functions = GrantedFunctions::new();
+        functions.insert(("posts".into(), "whoami".into()));
+        create_cap_grant(CapGrantEntry::new(
+            "whoami".into(),
+            CapAccess::Unrestricted,
+            functions,
+        ))
+        .unwrap();
+
+        set_hdk(alice.clone());
+        match call_bob() {
+            ZomeCallResponse::Ok(extern_io) => {
+                let (callee, provenance): (AgentPubKey, AgentPubKey) = extern_io.decode().unwrap();
+                assert_eq!(bob.agent(), callee);
+                assert_eq!(alice.agent(), provenance);
+            }
+            other => panic!("unexpected response {:?}", other),
+        }
+    }
+
+    #[test]
+    fn mem_hdk_commit_batch() {
+        let alice = MemHdk::new("posts", entry_defs());
+        set_hdk(alice.clone());
+
+        let header_hashes =
+            commit_batch(vec![post_input("one").into(), post_input("two").into()]).unwrap();
+        assert_eq!(2, header_hashes.len());
+        assert_eq!(5, alice.source_chain().len());
+
+        // The second op deletes a header that doesn't exist so the first
+        // create is rolled back too.
+        let missing = HeaderHash::from_raw_32(blake2b_256(b"missing"));
+        assert!(commit_batch(vec![
+            post_input("three").into(),
+            DeleteInput::new(missing, ChainTopOrdering::default()).into(),
+        ])
+        .is_err());
+        assert_eq!(5, alice.source_chain().len());
+        assert_eq!(
+            None,
+            get(hash_entry(post("three")).unwrap(), GetOptions::content()).unwrap()
+        );
+    }
+}
@@ -266,6 +266,13 @@
 /// @todo in the future grant secrets may be moved to lair somehow.
 pub mod capability;
 
+/// Store payloads larger than an entry as a set of content addressed chunks.
+///
+/// Entries travel as a single op so very large entries are slow to publish and gossip, and are capped by the DNA's entry size limit.
+/// [ `chunk::create_chunked` ] splits a payload into [ `chunk::Chunk` ] entries that are published and gossiped independently, plus a [ `chunk::ChunkSet` ] manifest listing them.
+/// The host fetches chunks in parallel and checks each one against its hash, either a range at a time with [ `chunk::get_chunks` ] and [ `chunk::ChunkReader` ] or the whole payload with [ `chunk::get_chunked` ].
+pub mod chunk;
+
 pub mod countersigning;
 
 /// Working with app and system entries.
@@ -502,4 +509,6 @@ pub mod random;
 /// When the `mock` feature is set then this will default to an HDK that always errors, else a WASM host is assumed to exist.
 /// The `mockall` crate (in prelude with `mock` feature) can be used to generate compatible mocks for unit testing.
 /// See mocking examples in the test WASMs crate, such as `agent_info`.
+/// The `mem` feature adds `MemHdk`, a working in-memory HDK with a simulated source chain and DHT,
+/// so zome code can be unit tested natively without mocking every call.
 pub mod hdk;
@@ -130,6 +130,10 @@ where
 ///
 /// This requirements will likely be removed in the future as
 /// we design a better way to grant the capability to remote signal.
+///
+/// Use [ `hdk_remote_signal` ] to generate a `recv_remote_signal` that decodes
+/// the signal as a typed payload, and [ `remote_signal_acked` ] to find out
+/// which agents got the signal.
 pub fn remote_signal<I>(input: I, agents: Vec<AgentPubKey>) -> ExternResult<()>
 where
     I: serde::Serialize + std::fmt::Debug,
@@ -141,3 +145,42 @@ where
         })
     })
 }
+
+/// ## Acknowledged Remote Signal
+/// Send a signal to a list of other agents like [ `remote_signal` ] but wait
+/// for each agent to handle it.
+///
+/// ### Blocking
+/// The signals are sent concurrently and this returns once every agent has
+/// answered or timed out, with a [ `RemoteSignalAck` ] per agent in the same
+/// order as `agents`:
+///
+/// - [ `RemoteSignalDelivery::Delivered` ] if `recv_remote_signal` ran and returned `Ok`
+/// - [ `RemoteSignalDelivery::Rejected` ] if the agent didn't grant `recv_remote_signal`
+///   or the handler returned an error
+/// - [ `RemoteSignalDelivery::Unreachable` ] if the agent couldn't be reached
+///
+/// Signals are not queued for agents that are offline.
+///
+/// ```ignore
+/// let acks = remote_signal_acked(&message, recipients)?;
+/// let missed: Vec<AgentPubKey> = acks
+///     .into_iter()
+///     .filter(|ack| !ack.is_delivered())
+///     .map(|ack| ack.agent)
+///     .collect();
+/// ```
+pub fn remote_signal_acked<I>(
+    input: I,
+    agents: Vec<AgentPubKey>,
+) -> ExternResult<Vec<RemoteSignalAck>>
+where
+    I: serde::Serialize + std::fmt::Debug,
+{
+    HDK.with(|h| {
+        h.borrow().remote_signal_acked(RemoteSignal {
+            signal: ExternIO::encode(input)?,
+            agents,
+        })
+    })
+}
@@ -5,7 +5,16 @@ pub use crate::capability::delete_cap_grant;
 pub use crate::capability::generate_cap_secret;
 pub use crate::capability::update_cap_grant;
 pub use crate::chain::get_agent_activity;
+pub use crate::chain::publish_status;
 pub use crate::chain::query;
+pub use crate::chain::query_page;
+pub use crate::chunk::create_chunked;
+pub use crate::chunk::get_chunk_set;
+pub use crate::chunk::get_chunked;
+pub use crate::chunk::get_chunks;
+pub use crate::chunk::ChunkReader;
+pub use crate::chunk::DEFAULT_CHUNKS_PER_READ;
+pub use crate::chunk::DEFAULT_CHUNK_SIZE;
 pub use crate::countersigning::accept_countersigning_preflight_request;
 pub use crate::countersigning::session_times_from_millis;
 pub use crate::ed25519::sign;
@@ -14,6 +23,7 @@ pub use crate::ed25519::sign_ephemeral_raw;
 pub use crate::ed25519::sign_raw;
 pub use crate::ed25519::verify_signature;
 pub use crate::ed25519::verify_signature_raw;
+pub use crate::entry::commit_batch;
 pub use crate::entry::create;
 pub use crate::entry::create_entry;
 pub use crate::entry::delete;
@@ -38,6 +48,8 @@ pub use crate::hash_path::anchor::list_anchor_type_addresses;
 pub use crate::hash_path::anchor::Anchor;
 pub use crate::hash_path::path::Path;
 pub use crate::hash_path::path::PathEntry;
+pub use crate::hash_path::path::PathWalk;
+pub use crate::hash_path::shard::TimeGranularity;
 pub use crate::hdk::*;
 pub use crate::info::agent_info;
 pub use crate::info::call_info;
@@ -54,6 +66,7 @@ pub use crate::p2p::call;
 pub use crate::p2p::call_remote;
 pub use crate::p2p::emit_signal;
 pub use crate::p2p::remote_signal;
+pub use crate::p2p::remote_signal_acked;
 pub use crate::random::*;
 pub use crate::register_entry;
 pub use crate::time::schedule;
@@ -67,6 +80,7 @@ pub use crate::x_salsa20_poly1305::x_salsa20_poly1305_decrypt;
 pub use crate::x_salsa20_poly1305::x_salsa20_poly1305_encrypt;
 pub use hdk_derive::hdk_entry;
 pub use hdk_derive::hdk_extern;
+pub use hdk_derive::hdk_remote_signal;
 pub use holo_hash;
 pub use holo_hash::AgentPubKey;
 pub use holo_hash::AnyDhtHash;
@@ -110,6 +124,7 @@ macro_rules! holochain_externs {
             __capability_grants,
             __capability_info,
             __get,
+            __get_chunks,
             __get_details,
             __get_links,
             __get_link_details,
@@ -118,16 +133,20 @@ macro_rules! holochain_externs {
             __must_get_valid_element,
             __must_get_header,
             __accept_countersigning_preflight_request,
+            __publish_status,
             __query,
+            __query_page,
             __call_remote,
             __call,
             __create,
             __emit_signal,
             __remote_signal,
+            __remote_signal_acked,
             __create_link,
             __delete_link,
             __update,
             __delete,
+            __commit_batch,
             __schedule,
             __sleep,
             __x_salsa20_poly1305_encrypt,
@@ -20,7 +20,7 @@ impl Parse for EntryDef {
         let mut required_validations =
             holochain_zome_types::entry_def::RequiredValidations::default();
         let mut visibility = holochain_zome_types::entry_def::EntryVisibility::default();
-        let crdt_type = holochain_zome_types::crdt::CrdtType::default();
+        let mut crdt_type = holochain_zome_types::crdt::CrdtType::default();
         let mut required_validation_type =
             holochain_zome_types::validate::RequiredValidationType::default();
 
@@ -86,7 +86,29 @@ impl Parse for EntryDef {
                         };
                     }
                     "crdt_type" => {
-                        unimplemented!();
+                        match var.lit {
+                            syn::Lit::Str(s) => {
+                                crdt_type = match s.value().as_str() {
+                                    "unresolved" => {
+                                        holochain_zome_types::crdt::CrdtType::Unresolved
+                                    }
+                                    "last_writer_wins" => {
+                                        holochain_zome_types::crdt::CrdtType::LastWriterWins
+                                    }
+                                    "single_author" => {
+                                        holochain_zome_types::crdt::CrdtType::SingleAuthor
+                                    }
+                                    "resolve_callback" => {
+                                        holochain_zome_types::crdt::CrdtType::ResolveCallback
+                                    }
+                                    _ => unreachable!(
+                                        "Invalid crdt_type
+                                        Options are: unresolved, last_writer_wins, single_author and resolve_callback"
+                                    ),
+                                }
+                            }
+                            _ => unreachable!(),
+                        };
                     }
                     _ => {}
                 }
@@ -104,8 +126,17 @@ impl Parse for EntryDef {
 
 impl quote::ToTokens for CrdtType {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let variant = syn::Ident::new(
+            match self.0 {
+                holochain_zome_types::crdt::CrdtType::Unresolved => "Unresolved",
+                holochain_zome_types::crdt::CrdtType::LastWriterWins => "LastWriterWins",
+                holochain_zome_types::crdt::CrdtType::SingleAuthor => "SingleAuthor",
+                holochain_zome_types::crdt::CrdtType::ResolveCallback => "ResolveCallback",
+            },
+            proc_macro2::Span::call_site(),
+        );
         tokens.append_all(quote::quote! {
-            hdk::prelude::CrdtType
+            hdk::prelude::CrdtType::#variant
         });
     }
 }
@@ -238,3 +269,43 @@ pub fn hdk_extern(attrs: TokenStream, item: TokenStream) -> TokenStream {
         .into()
     }
 }
+
+/// Makes a fn the typed handler for remote signals sent to this zome.
+///
+/// Generates the `recv_remote_signal` extern that the host calls for every
+/// remote signal and decodes the payload as the fn's input type, e.g.
+///
+/// ```ignore
+/// #[hdk_remote_signal]
+/// fn receive_message(message: ChatMessage) -> ExternResult<()> {
+///     emit_signal(&message)
+/// }
+/// ```
+///
+/// A payload that doesn't decode as the input type is rejected.
+/// The zome still needs to grant `recv_remote_signal` to the senders.
+#[proc_macro_attribute]
+pub fn hdk_remote_signal(_attrs: TokenStream, item: TokenStream) -> TokenStream {
+    let item_fn = syn::parse_macro_input!(item as syn::ItemFn);
+
+    let handler_fn_ident = item_fn.sig.ident.clone();
+    let input_type = if let Some(syn::FnArg::Typed(pat_type)) = item_fn.sig.inputs.first() {
+        pat_type.ty.clone()
+    } else {
+        unreachable!();
+    };
+    let output_type = if let syn::ReturnType::Type(_, ref ty) = item_fn.sig.output {
+        ty.clone()
+    } else {
+        Box::new(syn::Type::Verbatim(quote::quote! { () }))
+    };
+
+    (quote::quote! {
+        #[hdk_extern]
+        fn recv_remote_signal(signal: #input_type) -> #output_type {
+            #handler_fn_ident(signal)
+        }
+        #item_fn
+    })
+    .into()
+}
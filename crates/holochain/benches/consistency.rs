@@ -183,6 +183,9 @@ async fn setup() -> (Producer, Consumer, Others) {
             network: Some(network),
             admin_interfaces: Some(vec![AdminInterfaceConfig {
                 driver: InterfaceDriver::Websocket { port: 0 },
+                require_auth: false,
+                session_duration_seconds: None,
+                tls: None,
             }]),
             ..Default::default()
         }
@@ -7,11 +7,14 @@ use super::error::ConductorApiResult;
 use crate::conductor::interface::SignalBroadcaster;
 use crate::conductor::ConductorHandle;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitArgs;
+use crate::core::ribosome::real_ribosome::RealRibosome;
+use crate::core::workflow::call_zome_workflow::validate_elements;
 use crate::core::workflow::ZomeCallResult;
 use async_trait::async_trait;
 use holo_hash::DnaHash;
 use holochain_conductor_api::ZomeCall;
 use holochain_keystore::MetaLairClient;
+use holochain_p2p::HolochainP2pDna;
 use holochain_state::host_fn_workspace::SourceChainWorkspace;
 use holochain_types::prelude::*;
 use tokio::sync::mpsc::error::SendError;
@@ -169,6 +172,19 @@ pub trait CellConductorReadHandleT: Send + Sync {
 
     /// Get a zome from this cell's Dna
     fn get_zome(&self, dna_hash: &DnaHash, zome_name: &ZomeName) -> ConductorApiResult<Zome>;
+
+    /// Get an [`EntryDef`] from the conductor's entry def store
+    fn get_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef>;
+
+    /// Run sys and app validation on elements that a zome has written
+    /// to the scratch of an in progress zome call.
+    async fn validate_elements(
+        &self,
+        zome: Zome,
+        elements: Vec<Element>,
+        workspace_lock: SourceChainWorkspace,
+        network: HolochainP2pDna,
+    ) -> ConductorApiResult<()>;
 }
 
 #[async_trait]
@@ -194,4 +210,29 @@ impl CellConductorReadHandleT for CellConductorApi {
     fn get_zome(&self, dna_hash: &DnaHash, zome_name: &ZomeName) -> ConductorApiResult<Zome> {
         CellConductorApiT::get_zome(self, dna_hash, zome_name)
     }
+
+    fn get_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef> {
+        CellConductorApiT::get_entry_def(self, key)
+    }
+
+    async fn validate_elements(
+        &self,
+        zome: Zome,
+        elements: Vec<Element>,
+        workspace_lock: SourceChainWorkspace,
+        network: HolochainP2pDna,
+    ) -> ConductorApiResult<()> {
+        let ribosome = RealRibosome::new(CellConductorApiT::get_this_dna(self)?);
+        validate_elements(
+            elements,
+            workspace_lock,
+            network,
+            self.conductor_handle.clone(),
+            Some(zome),
+            ribosome,
+        )
+        .await
+        .map_err(Box::new)?;
+        Ok(())
+    }
 }
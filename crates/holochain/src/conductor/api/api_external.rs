@@ -1,5 +1,6 @@
 use crate::conductor::interface::error::InterfaceResult;
 use holochain_serialized_bytes::prelude::*;
+use holochain_types::signal::Signal;
 
 mod admin_interface;
 mod app_interface;
@@ -13,9 +14,17 @@ pub trait InterfaceApi: 'static + Send + Sync + Clone {
     type ApiRequest: TryFrom<SerializedBytes, Error = SerializedBytesError> + Send + Sync;
     /// Which response is sent to the above request
     type ApiResponse: TryInto<SerializedBytes, Error = SerializedBytesError> + Send + Sync;
+    /// A copy of this api for a new connection.
+    /// Each connection has to authenticate on its own.
+    fn for_connection(&self) -> Self;
     /// Handle a request on this API
     async fn handle_request(
         &self,
         request: Result<Self::ApiRequest, SerializedBytesError>,
     ) -> InterfaceResult<Self::ApiResponse>;
+
+    /// Should this connection be sent the signal?
+    async fn allows_signal(&self, _signal: &Signal) -> bool {
+        true
+    }
 }
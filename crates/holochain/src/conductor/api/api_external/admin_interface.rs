@@ -8,6 +8,9 @@ use crate::conductor::conductor::CellStatus;
 use crate::conductor::error::ConductorError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
+use crate::conductor::interface::session::out_of_scope;
+use crate::conductor::interface::session::ConnectionSession;
+use crate::conductor::interface::InterfaceDriver;
 use crate::conductor::ConductorHandle;
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::dna::DnaBundle;
@@ -52,11 +55,37 @@ pub trait AdminInterfaceApi: 'static + Send + Sync + Clone {
 pub struct RealAdminInterfaceApi {
     /// Mutable access to the Conductor
     conductor_handle: ConductorHandle,
+    /// The authentication state of this connection
+    session: ConnectionSession,
+    /// How long tokens issued through this interface are valid for by default
+    session_duration: std::time::Duration,
 }
 
 impl RealAdminInterfaceApi {
     pub(crate) fn new(conductor_handle: ConductorHandle) -> Self {
-        RealAdminInterfaceApi { conductor_handle }
+        RealAdminInterfaceApi {
+            conductor_handle,
+            session: ConnectionSession::default(),
+            session_duration: std::time::Duration::from_secs(
+                DEFAULT_SESSION_DURATION_SECONDS as u64,
+            ),
+        }
+    }
+
+    /// An api for the admin interface with this config.
+    pub(crate) fn for_interface(
+        conductor_handle: ConductorHandle,
+        config: &AdminInterfaceConfig,
+    ) -> Self {
+        RealAdminInterfaceApi {
+            conductor_handle,
+            session: ConnectionSession::new(config.require_auth),
+            session_duration: std::time::Duration::from_secs(
+                config
+                    .session_duration_seconds
+                    .unwrap_or(DEFAULT_SESSION_DURATION_SECONDS) as u64,
+            ),
+        }
     }
 }
 
@@ -280,14 +309,48 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AppStarted(app.status().is_running()))
             }
-            AttachAppInterface { port } => {
-                let port = port.unwrap_or(0);
-                let port = self
+            Authenticate { token } => {
+                match self.session.authenticate(&self.conductor_handle, token) {
+                    Ok(_) => Ok(AdminResponse::Authenticated),
+                    Err(e) => Ok(AdminResponse::Error(e)),
+                }
+            }
+            IssueSessionToken {
+                scope,
+                duration_seconds,
+            } => {
+                let duration = duration_seconds
+                    .map(|seconds| std::time::Duration::from_secs(seconds as u64))
+                    .unwrap_or(self.session_duration);
+                let (token, expires_at) =
+                    self.conductor_handle.issue_session_token(scope, duration)?;
+                Ok(AdminResponse::SessionTokenIssued { token, expires_at })
+            }
+            RevokeSessionToken { token } => Ok(AdminResponse::SessionTokenRevoked(
+                self.conductor_handle.revoke_session_token(&token)?,
+            )),
+            AttachAppInterface {
+                port,
+                require_auth,
+                path,
+                tls,
+            } => {
+                let driver = match path {
+                    Some(path) => InterfaceDriver::UnixSocket { path },
+                    None => InterfaceDriver::Websocket {
+                        port: port.unwrap_or(0),
+                    },
+                };
+                let (driver, cert_digest) = self
                     .conductor_handle
                     .clone()
-                    .add_app_interface(port)
+                    .add_app_interface(driver, require_auth, tls)
                     .await?;
-                Ok(AdminResponse::AppInterfaceAttached { port })
+                Ok(AdminResponse::AppInterfaceAttached {
+                    port: driver.port().unwrap_or(0),
+                    path: driver.path().map(std::path::Path::to_path_buf),
+                    tls_cert_digest: cert_digest.map(|digest| digest.to_string()),
+                })
             }
             ListAppInterfaces => {
                 let interfaces = self.conductor_handle.list_app_interfaces().await?;
@@ -378,6 +441,13 @@ impl InterfaceApi for RealAdminInterfaceApi {
     type ApiRequest = AdminRequest;
     type ApiResponse = AdminResponse;
 
+    fn for_connection(&self) -> Self {
+        Self {
+            session: self.session.fresh(),
+            ..self.clone()
+        }
+    }
+
     async fn handle_request(
         &self,
         request: Result<Self::ApiRequest, SerializedBytesError>,
@@ -390,7 +460,18 @@ impl InterfaceApi for RealAdminInterfaceApi {
                 .map_err(InterfaceError::RequestHandler)?;
         }
         match request {
-            Ok(request) => Ok(AdminInterfaceApi::handle_admin_request(self, request).await),
+            Ok(request) => {
+                if !matches!(request, AdminRequest::Authenticate { .. }) {
+                    match self.session.scope(&self.conductor_handle) {
+                        Ok(Some(scope)) if !scope.allows_admin_request(&request) => {
+                            return Ok(AdminResponse::Error(out_of_scope()))
+                        }
+                        Ok(_) => (),
+                        Err(e) => return Ok(AdminResponse::Error(e)),
+                    }
+                }
+                Ok(AdminInterfaceApi::handle_admin_request(self, request).await)
+            }
             Err(e) => Ok(AdminResponse::Error(SerializationError::from(e).into())),
         }
     }
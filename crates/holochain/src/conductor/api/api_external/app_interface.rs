@@ -2,14 +2,20 @@ use super::InterfaceApi;
 use crate::conductor::api::error::ConductorApiResult;
 use crate::conductor::api::error::ExternalApiWireError;
 use crate::conductor::api::error::SerializationError;
+use crate::conductor::error::ConductorError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
+use crate::conductor::interface::session::out_of_scope;
+use crate::conductor::interface::session::ConnectionSession;
 use crate::conductor::state::AppInterfaceId;
 use crate::conductor::ConductorHandle;
 
 use holochain_serialized_bytes::prelude::*;
 
 use holochain_types::prelude::*;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
 
 pub use holochain_conductor_api::*;
 
@@ -43,6 +49,14 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
 pub struct RealAppInterfaceApi {
     conductor_handle: ConductorHandle,
     interface_id: AppInterfaceId,
+    session: ConnectionSession,
+    /// The Cells of the running apps in this connection's scope, resolved
+    /// when it authenticates so requests and signals can be checked without
+    /// reading the conductor's state.
+    /// `None` if the scope allows every app.
+    allowed_cells: Arc<parking_lot::Mutex<Option<HashSet<CellId>>>>,
+    /// The Cells of each app this connection has unsubscribed from.
+    unsubscribed_cells: Arc<parking_lot::Mutex<HashMap<InstalledAppId, HashSet<CellId>>>>,
 }
 
 impl RealAppInterfaceApi {
@@ -51,8 +65,102 @@ impl RealAppInterfaceApi {
         Self {
             conductor_handle,
             interface_id,
+            session: ConnectionSession::default(),
+            allowed_cells: Default::default(),
+            unsubscribed_cells: Default::default(),
         }
     }
+
+    /// Require connections to authenticate with a session token.
+    pub fn with_auth(mut self, require_auth: bool) -> Self {
+        self.session = ConnectionSession::new(require_auth);
+        self
+    }
+
+    /// Check the request against the scope of this connection's session.
+    fn check_scope(&self, request: &AppRequest) -> Result<(), ExternalApiWireError> {
+        let scope = match self.session.scope(&self.conductor_handle)? {
+            Some(scope) => scope,
+            None => return Ok(()),
+        };
+        let allowed = match request {
+            AppRequest::Authenticate { .. } => true,
+            AppRequest::AppInfo { installed_app_id } => scope.allows_app(installed_app_id),
+            AppRequest::ZomeCall(call) | AppRequest::ZomeCallInvocation(call) => {
+                self.allows_cell(&call.cell_id)
+            }
+            AppRequest::PublishStatus { cell_id, .. } => self.allows_cell(cell_id),
+            AppRequest::SignalSubscription(subscription) => {
+                scope.allows_app(subscription.installed_app_id())
+            }
+            AppRequest::Crypto(_) => true,
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(out_of_scope())
+        }
+    }
+
+    /// A cell can be used if any running app that uses it was in scope when
+    /// this connection authenticated.
+    fn allows_cell(&self, cell_id: &CellId) -> bool {
+        self.allowed_cells
+            .lock()
+            .as_ref()
+            .map_or(true, |cells| cells.contains(cell_id))
+    }
+
+    /// A system signal is sent if it is about an app in scope or the DNA of
+    /// a cell in scope.
+    /// Signals that don't say which app or cell they came from are only sent
+    /// to connections that can see every app.
+    fn allows_system_signal(&self, scope: &SessionScope, signal: &SystemSignal) -> bool {
+        if let SessionScope::Admin = scope {
+            return true;
+        }
+        match signal {
+            SystemSignal::AppStatusChanged {
+                installed_app_id, ..
+            } => scope.allows_app(installed_app_id),
+            SystemSignal::DataIntegrated { dna_hash, .. }
+            | SystemSignal::PeerJoined { dna_hash, .. }
+            | SystemSignal::PeerLeft { dna_hash, .. } => {
+                self.allowed_cells.lock().as_ref().map_or(true, |cells| {
+                    cells.iter().any(|cell_id| cell_id.dna_hash() == dna_hash)
+                })
+            }
+            SystemSignal::Test(_)
+            | SystemSignal::SuccessfulCountersigning(_)
+            | SystemSignal::FailedCountersigning(_)
+            | SystemSignal::CountersigningTimedOut(_)
+            | SystemSignal::HeaderSaturated(_) => false,
+        }
+    }
+
+    /// The Cells of the running apps this scope allows.
+    async fn resolve_allowed_cells(
+        &self,
+        scope: &SessionScope,
+    ) -> ConductorApiResult<Option<HashSet<CellId>>> {
+        if let SessionScope::Admin = scope {
+            return Ok(None);
+        }
+        let mut cells = HashSet::new();
+        for installed_app_id in self.conductor_handle.list_running_apps().await? {
+            if !scope.allows_app(&installed_app_id) {
+                continue;
+            }
+            if let Some(app_info) = self
+                .conductor_handle
+                .get_app_info(&installed_app_id)
+                .await?
+            {
+                cells.extend(app_info.cell_data.into_iter().map(|cell| cell.into_id()));
+            }
+        }
+        Ok(Some(cells))
+    }
 }
 
 #[async_trait::async_trait]
@@ -105,7 +213,42 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     Err(e) => Ok(AppResponse::Error(e.into())),
                 }
             }
-            AppRequest::SignalSubscription(_) => Ok(AppResponse::Unimplemented(request)),
+            AppRequest::PublishStatus {
+                cell_id,
+                header_hash,
+            } => Ok(AppResponse::PublishStatus(
+                self.conductor_handle
+                    .get_publish_status(&cell_id, header_hash)
+                    .await?,
+            )),
+            AppRequest::Authenticate { token } => {
+                match self.session.authenticate(&self.conductor_handle, token) {
+                    Ok(scope) => {
+                        *self.allowed_cells.lock() = self.resolve_allowed_cells(&scope).await?;
+                        Ok(AppResponse::Authenticated)
+                    }
+                    Err(e) => Ok(AppResponse::Error(e)),
+                }
+            }
+            AppRequest::SignalSubscription(subscription) => {
+                let installed_app_id = subscription.installed_app_id().clone();
+                let app_info = self
+                    .conductor_handle
+                    .get_app_info(&installed_app_id)
+                    .await?
+                    .ok_or_else(|| ConductorError::AppNotInstalled(installed_app_id.clone()))?;
+                let unsubscribed = app_info
+                    .cell_data
+                    .iter()
+                    .map(|cell| cell.as_id())
+                    .filter(|cell_id| !subscription.filters().allows(cell_id))
+                    .cloned()
+                    .collect();
+                self.unsubscribed_cells
+                    .lock()
+                    .insert(installed_app_id, unsubscribed);
+                Ok(AppResponse::SignalSubscriptionUpdated)
+            }
             AppRequest::Crypto(_) => Ok(AppResponse::Unimplemented(request)),
         }
     }
@@ -115,6 +258,36 @@ impl AppInterfaceApi for RealAppInterfaceApi {
 impl InterfaceApi for RealAppInterfaceApi {
     type ApiRequest = AppRequest;
     type ApiResponse = AppResponse;
+
+    fn for_connection(&self) -> Self {
+        Self {
+            session: self.session.fresh(),
+            allowed_cells: Default::default(),
+            unsubscribed_cells: Default::default(),
+            ..self.clone()
+        }
+    }
+
+    async fn allows_signal(&self, signal: &Signal) -> bool {
+        if let Signal::App(cell_id, _) = signal {
+            if self
+                .unsubscribed_cells
+                .lock()
+                .values()
+                .any(|cells| cells.contains(cell_id))
+            {
+                return false;
+            }
+        }
+        match self.session.scope(&self.conductor_handle) {
+            Ok(None) => true,
+            Ok(Some(scope)) => match signal {
+                Signal::App(cell_id, _) => self.allows_cell(cell_id),
+                Signal::System(signal) => self.allows_system_signal(&scope, signal),
+            },
+            Err(_) => false,
+        }
+    }
     async fn handle_request(
         &self,
         request: Result<Self::ApiRequest, SerializedBytesError>,
@@ -126,7 +299,12 @@ impl InterfaceApi for RealAppInterfaceApi {
                 .map_err(InterfaceError::RequestHandler)?;
         }
         match request {
-            Ok(request) => Ok(AppInterfaceApi::handle_app_request(self, request).await),
+            Ok(request) => {
+                if let Err(e) = self.check_scope(&request) {
+                    return Ok(AppResponse::Error(e));
+                }
+                Ok(AppInterfaceApi::handle_app_request(self, request).await)
+            }
             Err(e) => Ok(AppResponse::Error(SerializationError::from(e).into())),
         }
     }
@@ -17,6 +17,7 @@ use crate::core::queue_consumer::spawn_queue_consumer_tasks;
 use crate::core::queue_consumer::InitialQueueTriggers;
 use crate::core::queue_consumer::QueueTriggers;
 use crate::core::ribosome::guest_callback::init::InitResult;
+use crate::core::ribosome::host_fn::remote_signal_acked::RECV_REMOTE_SIGNAL;
 use crate::core::ribosome::real_ribosome::RealRibosome;
 use crate::core::ribosome::ZomeCallInvocation;
 use crate::core::workflow::call_zome_workflow;
@@ -702,54 +703,70 @@ impl Cell {
             })
             .await?;
 
-        // If the header has an app entry type get the entry def
-        // from the conductor.
-        let required_receipt_count = match header.as_ref().and_then(|h| h.0.entry_type()) {
-            Some(EntryType::App(entry_type)) => {
-                let zome_index = u8::from(entry_type.zome_id()) as usize;
-                let dna_file = self.conductor_api.get_this_dna().map_err(Box::new)?;
-                let zome = dna_file.dna().zomes.get(zome_index).map(|(_, z)| z.clone());
-                match zome {
-                    Some(zome) => self
-                        .conductor_api
-                        .get_entry_def(&EntryDefBufferKey::new(zome, entry_type.id()))
-                        .map(|e| u8::from(e.required_validations)),
-                    None => None,
-                }
-            }
-            _ => None,
-        };
-
-        // If no required receipt count was found then fallback to the default.
-        let required_validation_count = required_receipt_count.unwrap_or(
-            crate::core::workflow::publish_dht_ops_workflow::DEFAULT_RECEIPT_BUNDLE_SIZE,
-        );
+        let dna_file = self.conductor_api.get_this_dna().map_err(Box::new)?;
+        let required_validation_count =
+            self.required_receipt_count(&dna_file, header.as_ref().map(|h| &h.0));
 
-        self.space
+        let op_hash = receipt.receipt.dht_op_hash.clone();
+        let newly_complete = self
+            .space
             .dht_env
             .async_commit(move |txn| {
+                let required = required_validation_count as usize;
+                let previous_count = validation_receipts::count_valid(txn, &op_hash)?;
+
+                // Add to receipts db
+                validation_receipts::add_if_unique(txn, receipt)?;
+
                 // Get the current count for this dhtop.
-                let receipt_count: usize = txn.query_row(
-                    "SELECT COUNT(rowid) FROM ValidationReceipt WHERE op_hash = :op_hash",
-                    named_params! {
-                        ":op_hash": receipt.receipt.dht_op_hash,
-                    },
-                    |row| row.get(0),
-                )?;
+                let receipt_count = validation_receipts::count_valid(txn, &op_hash)?;
 
                 // If we have enough receipts then set receipts to complete.
-                if receipt_count >= required_validation_count as usize {
-                    set_receipts_complete(txn, &receipt.receipt.dht_op_hash, true)?;
+                if receipt_count >= required {
+                    set_receipts_complete(txn, &op_hash, true)?;
                 }
-
-                // Add to receipts db
-                validation_receipts::add_if_unique(txn, receipt)
+                CellResult::Ok(previous_count < required && receipt_count >= required)
             })
             .await?;
 
+        // Let clients know once every op for the header has enough receipts.
+        if let (true, Some(header)) = (newly_complete, header) {
+            let header_hash = HeaderHash::with_data_sync(&header.0);
+            if let Some(status) = self.publish_status(header_hash.clone()).await? {
+                if status.is_saturated() {
+                    self.signal_broadcaster()
+                        .await
+                        .send(Signal::System(SystemSignal::HeaderSaturated(header_hash)))
+                        .ok();
+                }
+            }
+        }
+
         Ok(())
     }
 
+    /// The number of validation receipts an op from this header needs.
+    fn required_receipt_count(&self, dna_file: &DnaFile, header: Option<&Header>) -> u8 {
+        required_receipt_count(dna_file.dna(), header, |key| {
+            self.conductor_api.get_entry_def(key)
+        })
+    }
+
+    /// Get the publish and validation receipt status of a header authored by this cell.
+    pub(super) async fn publish_status(
+        &self,
+        header_hash: HeaderHash,
+    ) -> CellResult<Option<PublishStatus>> {
+        let dna_file = self.conductor_api.get_this_dna().map_err(Box::new)?;
+        Ok(validation_receipts::get_publish_status(
+            &self.space.authored_env.clone().into(),
+            &self.space.dht_env.clone().into(),
+            header_hash,
+            |header| self.required_receipt_count(&dna_file, Some(header)) as usize,
+        )
+        .await?)
+    }
+
     /// the network module would like this cell/agent to sign some data
     #[tracing::instrument(skip(self))]
     async fn handle_sign_network_data(&self) -> CellResult<Signature> {
@@ -766,6 +783,7 @@ impl Cell {
         cap_secret: Option<CapSecret>,
         payload: ExternIO,
     ) -> CellResult<SerializedBytes> {
+        let is_remote_signal = fn_name.0 == RECV_REMOTE_SIGNAL;
         let invocation = ZomeCall {
             cell_id: self.id.clone(),
             zome_name,
@@ -774,10 +792,16 @@ impl Cell {
             provenance: from_agent,
             fn_name,
         };
-        // double ? because
-        // - ConductorApiResult
-        // - ZomeCallResult
-        Ok(self.call_zome(invocation, None).await??.try_into()?)
+        match self.call_zome(invocation, None).await? {
+            Ok(response) => Ok(response.try_into()?),
+            // A remote signal handler that fails rejects the signal. This is
+            // sent back as a response so the sender can tell a rejected signal
+            // from an agent that couldn't be reached.
+            Err(ribosome_error) if is_remote_signal => {
+                Ok(ZomeCallResponse::NetworkError(ribosome_error.to_string()).try_into()?)
+            }
+            Err(ribosome_error) => Err(ribosome_error.into()),
+        }
     }
 
     /// Function called by the Conductor
@@ -943,6 +967,34 @@ impl Cell {
     }
 }
 
+/// The number of validation receipts an op from this header needs.
+/// Headers with an app entry type use the entry def's required validations,
+/// everything else falls back to the default.
+pub(crate) fn required_receipt_count(
+    dna_def: &DnaDef,
+    header: Option<&Header>,
+    get_entry_def: impl Fn(&EntryDefBufferKey) -> Option<EntryDef>,
+) -> u8 {
+    // If the header has an app entry type get the entry def
+    // from the conductor.
+    let required_receipt_count = match header.and_then(|h| h.entry_type()) {
+        Some(EntryType::App(entry_type)) => {
+            let zome_index = u8::from(entry_type.zome_id()) as usize;
+            let zome = dna_def.zomes.get(zome_index).map(|(_, z)| z.clone());
+            match zome {
+                Some(zome) => get_entry_def(&EntryDefBufferKey::new(zome, entry_type.id()))
+                    .map(|e| u8::from(e.required_validations)),
+                None => None,
+            }
+        }
+        _ => None,
+    };
+
+    // If no required receipt count was found then fallback to the default.
+    required_receipt_count
+        .unwrap_or(crate::core::workflow::publish_dht_ops_workflow::DEFAULT_RECEIPT_BUNDLE_SIZE)
+}
+
 impl std::fmt::Debug for Cell {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Cell").field("id", &self.id()).finish()
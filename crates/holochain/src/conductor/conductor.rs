@@ -14,14 +14,18 @@ pub use self::share::RwShare;
 use super::api::RealAppInterfaceApi;
 use super::config::AdminInterfaceConfig;
 use super::config::InterfaceDriver;
+use super::config::InterfaceTlsConfig;
 use super::dna_store::RealDnaStore;
 use super::entry_def_store::get_entry_defs;
 use super::error::ConductorError;
 use super::handle::ConductorHandleImpl;
 use super::interface::error::InterfaceResult;
+use super::interface::session::SessionStore;
+use super::interface::websocket::bound_driver;
 use super::interface::websocket::spawn_admin_interface_task;
 use super::interface::websocket::spawn_app_interface_task;
 use super::interface::websocket::spawn_websocket_listener;
+use super::interface::websocket::tls_cert_digest;
 use super::interface::websocket::SIGNAL_BUFFER_SIZE;
 use super::interface::SignalBroadcaster;
 use super::manager::keep_alive_task;
@@ -60,6 +64,9 @@ use holochain_conductor_api::AppStatusFilter;
 use holochain_conductor_api::FullIntegrationStateDump;
 use holochain_conductor_api::InstalledAppInfo;
 use holochain_conductor_api::IntegrationStateDump;
+use holochain_conductor_api::SessionScope;
+use holochain_conductor_api::SessionToken;
+use holochain_conductor_api::ADMIN_TOKEN_FILE;
 use holochain_keystore::lair_keystore::spawn_lair_keystore;
 use holochain_keystore::lair_keystore::spawn_new_lair_keystore;
 use holochain_keystore::test_keystore::spawn_legacy_test_keystore;
@@ -73,6 +80,7 @@ use holochain_state::prelude::from_blob;
 use holochain_state::prelude::StateMutationResult;
 use holochain_state::prelude::StateQueryResult;
 use holochain_types::prelude::*;
+use holochain_websocket::CertDigest;
 use rusqlite::{OptionalExtension, Transaction};
 use std::collections::{HashMap, HashSet};
 use std::sync::atomic::AtomicBool;
@@ -160,6 +168,9 @@ where
     /// Collection app interface data, keyed by id
     app_interfaces: RwShare<HashMap<AppInterfaceId, AppInterfaceRuntime>>,
 
+    /// The session tokens that interface connections can authenticate with.
+    sessions: RwShare<SessionStore>,
+
     /// The channels and handles needed to interact with the task_manager task.
     /// If this is None, then the task manager has not yet been initialized.
     pub(super) task_manager: RwShare<Option<TaskManagerClient>>,
@@ -314,7 +325,10 @@ where
     where
         DS: DnaStore + 'static,
     {
-        let admin_api = RealAdminInterfaceApi::new(handle);
+        if configs.iter().any(|config| config.require_auth) {
+            self.write_admin_token()?;
+        }
+
         let stop_tx = self.task_manager.share_ref(|tm| {
             tm.as_ref()
                 .expect("Task manager not started yet")
@@ -323,23 +337,27 @@ where
         });
 
         // Closure to process each admin config item
-        let spawn_from_config = |AdminInterfaceConfig { driver, .. }| {
-            let admin_api = admin_api.clone();
+        let spawn_from_config = |config: AdminInterfaceConfig| {
+            let admin_api = RealAdminInterfaceApi::for_interface(handle.clone(), &config);
             let stop_tx = stop_tx.clone();
             async move {
-                match driver {
-                    InterfaceDriver::Websocket { port } => {
-                        let (listener_handle, listener) = spawn_websocket_listener(port).await?;
-                        let port = listener_handle.local_addr().port().unwrap_or(port);
-                        let handle: ManagedTaskHandle = spawn_admin_interface_task(
-                            listener_handle,
-                            listener,
-                            admin_api.clone(),
-                            stop_tx.subscribe(),
-                        )?;
-                        InterfaceResult::Ok((port, handle))
-                    }
+                let (listener_handle, listener) =
+                    spawn_websocket_listener(&config.driver, config.tls.as_ref()).await?;
+                let driver = bound_driver(&config.driver, &listener_handle)?;
+                if let Some(cert_digest) = tls_cert_digest(&listener_handle) {
+                    tracing::info!(
+                        "Admin interface {:?} serves TLS with certificate digest {}",
+                        driver,
+                        cert_digest
+                    );
                 }
+                let handle: ManagedTaskHandle = spawn_admin_interface_task(
+                    listener_handle,
+                    listener,
+                    admin_api.clone(),
+                    stop_tx.subscribe(),
+                )?;
+                InterfaceResult::Ok((driver, handle))
             }
         };
 
@@ -365,13 +383,18 @@ where
             .await?;
 
             // Now that tasks are spawned, register them with the TaskManager
-            for (port, handle) in handles {
-                ports.push(port);
-                self.manage_task(ManagedTaskAdd::ignore(
-                    handle,
-                    &format!("admin interface, port {}", port),
-                ))
-                .await?
+            for (driver, handle) in handles {
+                let name = match &driver {
+                    InterfaceDriver::Websocket { port } => {
+                        ports.push(*port);
+                        format!("admin interface, port {}", port)
+                    }
+                    InterfaceDriver::UnixSocket { path } => {
+                        format!("admin interface, socket {}", path.display())
+                    }
+                };
+                self.manage_task(ManagedTaskAdd::ignore(handle, &name))
+                    .await?
             }
             for p in ports {
                 self.add_admin_port(p);
@@ -380,18 +403,79 @@ where
         Ok(())
     }
 
+    /// Write the token with the admin scope to the environment directory
+    /// so local tools run by the same user can authenticate.
+    fn write_admin_token(&self) -> ConductorResult<()> {
+        let token = self.sessions.share_mut(|sessions| sessions.admin_token());
+        let path = self.root_env_dir.as_ref().join(ADMIN_TOKEN_FILE);
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&path)?;
+        // The mode is only applied when the file is created,
+        // so tighten the permissions of an existing file too.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        std::io::Write::write_all(&mut file, token.as_str().as_bytes())?;
+        tracing::info!("Wrote the admin session token to {}", path.display());
+        Ok(())
+    }
+
+    /// Issue a session token for an interface connection.
+    pub(super) fn issue_session_token(
+        &self,
+        scope: SessionScope,
+        duration: std::time::Duration,
+    ) -> ConductorResult<(SessionToken, Timestamp)> {
+        self.sessions
+            .share_mut(|sessions| sessions.issue(scope, duration))
+            .ok_or_else(|| ConductorError::other("Session duration is too long"))
+    }
+
+    /// Revoke a session token.
+    ///
+    /// The admin token only exists while an admin interface requires
+    /// authentication, so if it is revoked a new one is written in its place.
+    pub(super) fn revoke_session_token(&self, token: &SessionToken) -> ConductorResult<bool> {
+        let (revoked, was_admin_token) = self.sessions.share_mut(|sessions| {
+            let was_admin_token = sessions.is_admin_token(token);
+            (sessions.revoke(token), was_admin_token)
+        });
+        if revoked && was_admin_token {
+            self.write_admin_token()?;
+        }
+        Ok(revoked)
+    }
+
+    /// The scope of a session token if it's still valid.
+    pub(super) fn session_scope(&self, token: &SessionToken) -> Option<SessionScope> {
+        self.sessions.share_mut(|sessions| sessions.scope(token))
+    }
+
     pub(super) async fn add_app_interface_via_handle(
         &self,
-        port: either::Either<u16, AppInterfaceId>,
+        interface: either::Either<InterfaceDriver, (AppInterfaceId, InterfaceDriver)>,
+        require_auth: bool,
+        tls: Option<InterfaceTlsConfig>,
         handle: ConductorHandle,
-    ) -> ConductorResult<u16> {
-        let interface_id = match port {
-            either::Either::Left(port) => AppInterfaceId::new(port),
-            either::Either::Right(id) => id,
+    ) -> ConductorResult<(InterfaceDriver, Option<CertDigest>)> {
+        let (interface_id, driver) = match interface {
+            // Interfaces without a port get a unique id like those on port 0.
+            either::Either::Left(driver) => {
+                (AppInterfaceId::new(driver.port().unwrap_or(0)), driver)
+            }
+            either::Either::Right(id_and_driver) => id_and_driver,
         };
-        let port = interface_id.port();
-        tracing::debug!("Attaching interface {}", port);
-        let app_api = RealAppInterfaceApi::new(handle, interface_id.clone());
+        tracing::debug!("Attaching interface {:?}", driver);
+        let app_api =
+            RealAppInterfaceApi::new(handle, interface_id.clone()).with_auth(require_auth);
         // This receiver is thrown away because we can produce infinite new
         // receivers from the Sender
         let (signal_tx, _r) = tokio::sync::broadcast::channel(SIGNAL_BUFFER_SIZE);
@@ -401,13 +485,14 @@ where
                 .task_stop_broadcaster()
                 .subscribe()
         });
-        let (port, task) = spawn_app_interface_task(port, app_api, signal_tx.clone(), stop_rx)
-            .await
-            .map_err(Box::new)?;
+        let (driver, cert_digest, task) =
+            spawn_app_interface_task(&driver, tls.as_ref(), app_api, signal_tx.clone(), stop_rx)
+                .await
+                .map_err(Box::new)?;
         // TODO: RELIABILITY: Handle this task by restarting it if it fails and log the error
         self.manage_task(ManagedTaskAdd::ignore(
             task,
-            &format!("app interface, port {}", port),
+            &format!("app interface, {:?}", driver),
         ))
         .await?;
         let interface = AppInterfaceRuntime::Websocket { signal_tx };
@@ -422,23 +507,35 @@ where
             app_interfaces.insert(interface_id.clone(), interface);
             Ok(())
         })?;
-        let config = AppInterfaceConfig::websocket(port);
+        let config = AppInterfaceConfig {
+            signal_subscriptions: Default::default(),
+            driver: driver.clone(),
+            require_auth,
+            tls,
+        };
         self.update_state(|mut state| {
             state.app_interfaces.insert(interface_id, config);
             Ok(state)
         })
         .await?;
-        tracing::debug!("App interface added at port: {}", port);
-        Ok(port)
+        tracing::debug!("App interface added at: {:?}", driver);
+        if let Some(cert_digest) = &cert_digest {
+            tracing::info!(
+                "App interface {:?} serves TLS with certificate digest {}",
+                driver,
+                cert_digest
+            );
+        }
+        Ok((driver, cert_digest))
     }
 
-    pub(super) async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>> {
+    pub(super) async fn list_app_interfaces(&self) -> ConductorResult<Vec<InterfaceDriver>> {
         Ok(self
             .get_state()
             .await?
             .app_interfaces
             .values()
-            .map(|config| config.driver.port())
+            .map(|config| config.driver.clone())
             .collect())
     }
 
@@ -479,10 +576,21 @@ where
         &self,
         handle: ConductorHandle,
     ) -> ConductorResult<()> {
-        for id in self.get_state().await?.app_interfaces.keys().cloned() {
+        for (id, config) in self.get_state().await?.app_interfaces {
             tracing::debug!("Starting up app interface: {:?}", id);
+            // Websocket interfaces bind to the port they were asked for,
+            // which may be 0, rather than the one they were given last time.
+            let driver = match config.driver {
+                InterfaceDriver::Websocket { .. } => InterfaceDriver::Websocket { port: id.port() },
+                driver => driver,
+            };
             let _ = self
-                .add_app_interface_via_handle(either::Right(id), handle.clone())
+                .add_app_interface_via_handle(
+                    either::Right((id, driver)),
+                    config.require_auth,
+                    config.tls,
+                    handle.clone(),
+                )
                 .await?;
         }
         Ok(())
@@ -541,8 +649,9 @@ where
 
         let app_ids: Option<HashSet<InstalledAppId>> = app_ids.map(S::into);
         let running_cells: HashSet<CellId> = self.running_cell_ids();
-        let (_, delta) = self
+        let (_, (delta, changed)) = self
             .update_state_prime(move |mut state| {
+                let mut changed = Vec::new();
                 let apps = state.installed_apps_mut().iter_mut().filter(|(id, _)| {
                     app_ids
                         .as_ref()
@@ -551,8 +660,8 @@ where
                 });
                 let delta = apps
                     .into_iter()
-                    .map(|(_app_id, app)| {
-                        match app.status().clone() {
+                    .map(|(app_id, app)| {
+                        let delta = match app.status().clone() {
                             Running => {
                                 // If not all required cells are running, pause the app
                                 let missing: Vec<_> = app
@@ -581,12 +690,19 @@ where
                                 // Disabled status should never automatically change.
                                 AppStatusFx::NoChange
                             }
+                        };
+                        if delta != AppStatusFx::NoChange {
+                            changed.push((app_id.clone(), app.status().clone()));
                         }
+                        delta
                     })
                     .fold(AppStatusFx::default(), AppStatusFx::combine);
-                Ok((state, delta))
+                Ok((state, (delta, changed)))
             })
             .await?;
+        for (installed_app_id, status) in changed {
+            self.signal_app_status_changed(installed_app_id, status);
+        }
         Ok(delta)
     }
 
@@ -709,14 +825,29 @@ where
         app_id: InstalledAppId,
         transition: AppStatusTransition,
     ) -> ConductorResult<(InstalledApp, AppStatusFx)> {
-        Ok(self
+        let (app, delta) = self
             .update_state_prime(move |mut state| {
                 let (app, delta) = state.transition_app_status(&app_id, transition)?.clone();
                 let app = app.clone();
                 Ok((state, (app, delta)))
             })
             .await?
-            .1)
+            .1;
+        if delta != AppStatusFx::NoChange {
+            self.signal_app_status_changed(app.id().clone(), app.status().clone());
+        }
+        Ok((app, delta))
+    }
+
+    /// Let clients know that an app's status has changed.
+    fn signal_app_status_changed(&self, installed_app_id: InstalledAppId, status: AppStatus) {
+        // There may be no interfaces to listen for this.
+        self.signal_broadcaster()
+            .send(Signal::System(SystemSignal::AppStatusChanged {
+                installed_app_id,
+                status,
+            }))
+            .ok();
     }
 
     /// Entirely remove an app from the database, returning the removed app.
@@ -974,6 +1105,25 @@ where
         Ok(zome_defs)
     }
 
+    /// All registered DNAs, with the versions they declare, and the cells of
+    /// all installed apps
+    pub(super) async fn dna_gamut(&self) -> ConductorResult<DnaGamut> {
+        let dnas = self.dna_store().share_ref(|ds| {
+            ds.list()
+                .into_iter()
+                .filter_map(|hash| ds.get(&hash))
+                .map(|dna| (dna.dna_hash().clone(), dna.dna_def().lineage.clone()))
+                .collect::<Vec<_>>()
+        });
+        let state = self.get_state().await?;
+        let cells = state
+            .installed_apps()
+            .values()
+            .flat_map(|app| app.all_cells().cloned().collect::<Vec<_>>())
+            .collect::<HashSet<_>>();
+        Ok(DnaGamut::new(dnas, cells))
+    }
+
     pub(super) fn list_cell_ids(&self, filter: Option<CellStatusFilter>) -> Vec<CellId> {
         self.cells.share_ref(|cells| {
             cells
@@ -1161,6 +1311,29 @@ pub(super) async fn genesis_cells<DS: DnaStore + 'static>(
     }
 }
 
+/// Check that a hApp bundle is signed by one of the trusted publishers.
+///
+/// The signature only covers DNAs which are bundled or fetched from a url
+/// pinned to their hash, so a bundle which refers to DNAs by path or by an
+/// unpinned url is rejected too.
+pub(super) fn verify_bundle_publisher(
+    bundle: &AppBundle,
+    trusted: &[mr_bundle::PublisherKey],
+) -> ConductorResult<()> {
+    bundle.verify_publisher(trusted)?;
+    match mr_bundle::Manifest::locations(bundle.manifest())
+        .into_iter()
+        .find(|location| match location {
+            mr_bundle::Location::Bundled(_) => false,
+            mr_bundle::Location::Url(url) => url.sha256.is_none(),
+            mr_bundle::Location::Path(_) => true,
+        })
+    {
+        Some(location) => Err(ConductorError::UnbundledDnaFromTrustedPublisher(location)),
+        None => Ok(()),
+    }
+}
+
 /// Dump the integration json state.
 pub async fn integration_dump(
     vault: &DbRead<DbKindDht>,
@@ -1294,6 +1467,7 @@ where
             cells: RwShare::new(HashMap::new()),
             shutting_down: Arc::new(AtomicBool::new(false)),
             app_interfaces: RwShare::new(HashMap::new()),
+            sessions: RwShare::new(SessionStore::default()),
             task_manager: RwShare::new(None),
             admin_websocket_ports: RwShare::new(Vec::new()),
             dna_store: RwShare::new(dna_store),
@@ -1547,6 +1721,7 @@ mod builder {
                 keystore,
                 holochain_p2p,
                 db_sync_strategy: config.db_sync_strategy,
+                trusted_bundle_publishers: config.trusted_bundle_publishers.clone(),
 
                 p2p_env: Arc::new(parking_lot::Mutex::new(HashMap::new())),
                 p2p_batch_senders: Arc::new(parking_lot::Mutex::new(HashMap::new())),
@@ -1706,6 +1881,7 @@ mod builder {
                 p2p_batch_senders: Arc::new(parking_lot::Mutex::new(HashMap::new())),
                 p2p_metrics_env: envs.p2p_metrics(),
                 db_sync_strategy: self.config.db_sync_strategy,
+                trusted_bundle_publishers: self.config.trusted_bundle_publishers.clone(),
                 #[cfg(any(test, feature = "test_utils"))]
                 dev_settings: parking_lot::RwLock::new(DevSettings::default()),
             });
@@ -561,7 +561,14 @@ async fn test_signing_error_during_genesis_doesnt_bork_interfaces() {
     let (cell1,) = app1.into_tuple();
     let (cell2,) = app2.into_tuple();
 
-    let app_port = conductor.inner_handle().add_app_interface(0).await.unwrap();
+    let app_port = conductor
+        .inner_handle()
+        .add_app_interface(InterfaceDriver::Websocket { port: 0 }, false, None)
+        .await
+        .unwrap()
+        .0
+        .port()
+        .unwrap();
     let (mut app_client, _) = websocket_client_by_port(app_port).await.unwrap();
     let (mut admin_client, _) = conductor.admin_ws_client().await;
 
@@ -747,6 +754,185 @@ async fn test_installation_fails_if_genesis_self_check_is_invalid() {
     }
 }
 
+#[test]
+fn test_bundle_publisher_verification() {
+    let manifest = |location: &str| -> AppManifest {
+        serde_yaml::from_str(&format!(
+            r#"---
+manifest_version: "1"
+name: signed-app
+roles:
+  - id: role-1
+    dna:
+      {}
+"#,
+            location
+        ))
+        .unwrap()
+    };
+    let key = mr_bundle::generate_signing_key().unwrap();
+    let sign = |manifest: AppManifest, resources: Vec<(std::path::PathBuf, Vec<u8>)>| {
+        AppBundle::from(
+            mr_bundle::Bundle::new_unchecked(manifest, resources)
+                .unwrap()
+                .sign(&key)
+                .unwrap(),
+        )
+    };
+
+    let bundled = sign(
+        manifest("bundled: dna.dna"),
+        vec![("dna.dna".into(), vec![1, 2, 3])],
+    );
+    let publisher = bundled.verify_signature().unwrap();
+    assert_matches!(verify_bundle_publisher(&bundled, &[publisher]), Ok(()));
+
+    let other = mr_bundle::generate_signing_key().unwrap();
+    let other = mr_bundle::Bundle::new_unchecked(manifest("bundled: dna.dna"), vec![])
+        .unwrap()
+        .sign(&other)
+        .unwrap()
+        .verify_signature()
+        .unwrap();
+    assert_matches!(
+        verify_bundle_publisher(&bundled, &[other]),
+        Err(ConductorError::MrBundleError(
+            mr_bundle::error::MrBundleError::BundleError(
+                mr_bundle::error::BundleError::UntrustedPublisher(_)
+            )
+        ))
+    );
+
+    // A DNA outside the bundle isn't covered by the signature,
+    // unless its url is pinned to a hash
+    let unbundled = sign(manifest("path: /dna.dna"), vec![]);
+    assert_matches!(
+        verify_bundle_publisher(&unbundled, &[publisher]),
+        Err(ConductorError::UnbundledDnaFromTrustedPublisher(_))
+    );
+    let unpinned = sign(manifest("url: http://example.com/dna.dna"), vec![]);
+    assert_matches!(
+        verify_bundle_publisher(&unpinned, &[publisher]),
+        Err(ConductorError::UnbundledDnaFromTrustedPublisher(_))
+    );
+    let pinned = sign(
+        manifest(&format!(
+            "url: {{ url: \"http://example.com/dna.dna\", sha256: {} }}",
+            mr_bundle::ResourceHash::of(&[1, 2, 3])
+        )),
+        vec![],
+    );
+    assert_matches!(verify_bundle_publisher(&pinned, &[publisher]), Ok(()));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_use_existing_cell_between_apps() {
+    observability::test_run().ok();
+    let conductor = SweetConductor::from_standard_config().await;
+    let agent = SweetAgents::one(conductor.keystore()).await;
+    let (dna, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .unwrap();
+    let dna_hash = dna.dna_hash().clone();
+    let cell_id = CellId::new(dna_hash.clone(), agent.clone());
+    let dna_bundle = DnaBundle::from_dna_file(dna).await.unwrap();
+
+    let bundle = |name: &str, strategy: &str| {
+        let manifest: AppManifest = serde_yaml::from_str(&format!(
+            r#"---
+manifest_version: "1"
+name: {}
+roles:
+  - id: profile
+    provisioning:
+      strategy: {}
+      deferred: false
+    dna:
+      bundled: profile.dna
+      version: {}
+"#,
+            name, strategy, dna_hash
+        ))
+        .unwrap();
+        let dna_bundle = dna_bundle.clone();
+        async move {
+            AppBundle::new(
+                manifest,
+                vec![("profile.dna".into(), dna_bundle)],
+                std::path::PathBuf::from("."),
+            )
+            .await
+            .unwrap()
+        }
+    };
+    let install = |bundle: AppBundle,
+                   existing_cells: std::collections::HashMap<AppRoleId, CellId>| {
+        conductor
+            .inner_handle()
+            .install_app_bundle(InstallAppBundlePayload {
+                source: AppBundleSource::Bundle(bundle),
+                agent_key: agent.clone(),
+                installed_app_id: None,
+                membrane_proofs: Default::default(),
+                uid: None,
+                existing_cells,
+            })
+    };
+    let consent = maplit::hashmap! { "profile".to_string() => cell_id.clone() };
+
+    install(bundle("chat", "create").await, Default::default())
+        .await
+        .unwrap();
+    conductor
+        .inner_handle()
+        .enable_app("chat".to_string())
+        .await
+        .unwrap();
+
+    // - Without consent, an app can't use the existing cell, but is told about it
+    assert_matches!(
+        install(bundle("mail", "use_existing").await, Default::default()).await,
+        Err(ConductorError::AppBundleError(
+            AppBundleError::ExistingCellNotShared(_, c)
+        )) if c == cell_id
+    );
+
+    // - With consent, the second app shares the cell instead of creating one
+    install(bundle("mail", "use_existing").await, consent.clone())
+        .await
+        .unwrap();
+    conductor
+        .inner_handle()
+        .enable_app("mail".to_string())
+        .await
+        .unwrap();
+    let state = conductor.get_state_from_handle().await.unwrap();
+    let role = state
+        .get_app(&"mail".to_string())
+        .unwrap()
+        .role(&"profile".to_string())
+        .unwrap()
+        .clone();
+    assert_eq!(role.cell_id(), &cell_id);
+    assert!(role.is_shared());
+    assert_eq!(
+        conductor.inner_handle().list_cell_ids(None),
+        vec![cell_id.clone()]
+    );
+
+    // - Uninstalling the app which created the cell leaves it intact for the other
+    conductor
+        .inner_handle()
+        .uninstall_app(&"chat".to_string())
+        .await
+        .unwrap();
+    assert_eq!(
+        conductor.list_running_apps().await.unwrap(),
+        vec!["mail".to_string()]
+    );
+    assert_eq!(conductor.inner_handle().list_cell_ids(None), vec![cell_id]);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_bad_entry_validation_after_genesis_returns_zome_call_error() {
     observability::test_run().ok();
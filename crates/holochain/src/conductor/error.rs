@@ -103,6 +103,9 @@ pub enum ConductorError {
     #[error(transparent)]
     MrBundleError(#[from] mr_bundle::error::MrBundleError),
 
+    #[error("The hApp bundle refers to a DNA at {0:?}, which isn't bundled or pinned to a hash, so isn't covered by the publisher's signature")]
+    UnbundledDnaFromTrustedPublisher(mr_bundle::Location),
+
     #[error(transparent)]
     StateQueryError(#[from] holochain_state::query::StateQueryError),
 
@@ -39,6 +39,8 @@ use super::api::error::ConductorApiResult;
 use super::api::ZomeCall;
 use super::conductor::CellStatus;
 use super::config::AdminInterfaceConfig;
+use super::config::InterfaceDriver;
+use super::config::InterfaceTlsConfig;
 use super::error::ConductorResult;
 use super::interface::SignalBroadcaster;
 use super::manager::spawn_task_manager;
@@ -69,6 +71,8 @@ use holochain_conductor_api::AppStatusFilter;
 use holochain_conductor_api::FullStateDump;
 use holochain_conductor_api::InstalledAppInfo;
 use holochain_conductor_api::JsonDump;
+use holochain_conductor_api::SessionScope;
+use holochain_conductor_api::SessionToken;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::actor::HolochainP2pRefToDna;
 use holochain_p2p::event::HolochainP2pEvent;
@@ -83,6 +87,7 @@ use holochain_state::prelude::StateMutationError;
 use holochain_state::prelude::StateMutationResult;
 use holochain_state::source_chain;
 use holochain_types::prelude::*;
+use holochain_websocket::CertDigest;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 use kitsune_p2p::event::{KGenReq, KGenRes};
 use kitsune_p2p::KitsuneSpace;
@@ -130,11 +135,32 @@ pub trait ConductorHandleT: Send + Sync {
         configs: Vec<AdminInterfaceConfig>,
     ) -> ConductorResult<()>;
 
-    /// Add an app interface
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16>;
+    /// Add an app interface.
+    /// Returns the driver it listens with, which has the port the OS chose
+    /// if it was asked to listen on port 0, and the digest of its TLS
+    /// certificate if it serves TLS.
+    async fn add_app_interface(
+        self: Arc<Self>,
+        driver: InterfaceDriver,
+        require_auth: bool,
+        tls: Option<InterfaceTlsConfig>,
+    ) -> ConductorResult<(InterfaceDriver, Option<CertDigest>)>;
 
     /// List the app interfaces currently install.
-    async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>>;
+    async fn list_app_interfaces(&self) -> ConductorResult<Vec<InterfaceDriver>>;
+
+    /// Issue a session token that interface connections can authenticate with.
+    fn issue_session_token(
+        &self,
+        scope: SessionScope,
+        duration: std::time::Duration,
+    ) -> ConductorResult<(SessionToken, Timestamp)>;
+
+    /// Revoke a session token. Returns false if the token wasn't valid.
+    fn revoke_session_token(&self, token: &SessionToken) -> ConductorResult<bool>;
+
+    /// Get the scope of a session token if it is still valid.
+    fn session_scope(&self, token: &SessionToken) -> Option<SessionScope>;
 
     /// Install a [DnaFile] in this Conductor
     async fn register_dna(&self, dna: DnaFile) -> ConductorResult<()>;
@@ -171,6 +197,14 @@ pub trait ConductorHandleT: Send + Sync {
         workspace_lock: SourceChainWorkspace,
     ) -> ConductorApiResult<ZomeCallResult>;
 
+    /// Get the publish and validation receipt status of a header
+    /// authored by a Cell. Returns `None` if the Cell did not author it.
+    async fn get_publish_status(
+        &self,
+        cell_id: &CellId,
+        header_hash: HeaderHash,
+    ) -> ConductorApiResult<Option<PublishStatus>>;
+
     /// Get a Websocket port which will
     fn get_arbitrary_admin_websocket_port(&self) -> Option<u16>;
 
@@ -481,6 +515,9 @@ pub struct ConductorHandleImpl<DS: DnaStore + 'static> {
     /// Database sync level
     pub(super) db_sync_strategy: DbSyncStrategy,
 
+    /// The publishers whose signed hApp bundles may be installed, if restricted
+    pub(super) trusted_bundle_publishers: Option<Vec<mr_bundle::PublisherKey>>,
+
     /// The batch sender for writes to the p2p database.
     pub(super) p2p_batch_senders:
         Arc<parking_lot::Mutex<HashMap<Arc<KitsuneSpace>, tokio::sync::mpsc::Sender<P2pBatch>>>>,
@@ -543,16 +580,37 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         self.process_app_status_fx(AppStatusFx::SpinUp, None).await
     }
 
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16> {
+    async fn add_app_interface(
+        self: Arc<Self>,
+        driver: InterfaceDriver,
+        require_auth: bool,
+        tls: Option<InterfaceTlsConfig>,
+    ) -> ConductorResult<(InterfaceDriver, Option<CertDigest>)> {
         self.conductor
-            .add_app_interface_via_handle(either::Left(port), self.clone())
+            .add_app_interface_via_handle(either::Left(driver), require_auth, tls, self.clone())
             .await
     }
 
-    async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>> {
+    async fn list_app_interfaces(&self) -> ConductorResult<Vec<InterfaceDriver>> {
         self.conductor.list_app_interfaces().await
     }
 
+    fn issue_session_token(
+        &self,
+        scope: SessionScope,
+        duration: std::time::Duration,
+    ) -> ConductorResult<(SessionToken, Timestamp)> {
+        self.conductor.issue_session_token(scope, duration)
+    }
+
+    fn revoke_session_token(&self, token: &SessionToken) -> ConductorResult<bool> {
+        self.conductor.revoke_session_token(token)
+    }
+
+    fn session_scope(&self, token: &SessionToken) -> Option<SessionScope> {
+        self.conductor.session_scope(token)
+    }
+
     async fn register_dna(&self, dna: DnaFile) -> ConductorResult<()> {
         self.register_genotype(dna.clone()).await?;
         self.conductor.register_phenotype(dna);
@@ -635,6 +693,7 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             PutAgentInfoSigned {
                 peer_data, respond, ..
             } => {
+                let dna_hash = DnaHash::from_kitsune(&space);
                 let sender = self.p2p_batch_sender(space);
                 let (result_sender, response) = tokio::sync::oneshot::channel();
                 let _ = sender
@@ -646,7 +705,8 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
                 let res = match response.await {
                     Ok(r) => r.map_err(holochain_p2p::HolochainP2pError::other),
                     Err(e) => Err(holochain_p2p::HolochainP2pError::other(e)),
-                };
+                }
+                .map(|changes| self.signal_peer_changes(dna_hash, changes));
                 respond.respond(Ok(async move { res }.boxed().into()));
             }
             GetAgentInfoSigned {
@@ -832,6 +892,15 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         Ok(cell.call_zome(call, Some(workspace_lock)).await?)
     }
 
+    async fn get_publish_status(
+        &self,
+        cell_id: &CellId,
+        header_hash: HeaderHash,
+    ) -> ConductorApiResult<Option<PublishStatus>> {
+        let cell = self.cell_by_id(cell_id)?;
+        Ok(cell.publish_status(header_hash).await?)
+    }
+
     fn take_shutdown_handle(&self) -> Option<TaskManagerRunHandle> {
         self.conductor.take_shutdown_handle()
     }
@@ -920,23 +989,35 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             installed_app_id,
             membrane_proofs,
             uid,
+            existing_cells,
         } = payload;
 
         let bundle: AppBundle = {
             let original_bundle = source.resolve().await?;
+            if let Some(trusted) = &self.trusted_bundle_publishers {
+                crate::conductor::conductor::verify_bundle_publisher(&original_bundle, trusted)?;
+            }
+            let bundle = original_bundle
+                .into_inner()
+                .with_resource_cache(Some(self.root_env_dir.resource_cache()));
             if let Some(uid) = uid {
-                let mut manifest = original_bundle.manifest().to_owned();
+                let mut manifest = bundle.manifest().to_owned();
                 manifest.set_uid(uid);
-                AppBundle::from(original_bundle.into_inner().update_manifest(manifest)?)
+                AppBundle::from(bundle.update_manifest(manifest)?)
             } else {
-                original_bundle
+                AppBundle::from(bundle)
             }
         };
 
         let installed_app_id =
             installed_app_id.unwrap_or_else(|| bundle.manifest().app_name().to_owned());
         let ops = bundle
-            .resolve_cells(agent_key.clone(), DnaGamut::placeholder(), membrane_proofs)
+            .resolve_cells(
+                agent_key.clone(),
+                self.conductor.dna_gamut().await?,
+                membrane_proofs,
+                existing_cells,
+            )
             .await?;
 
         let cells_to_create = ops.cells_to_create();
@@ -1499,6 +1580,25 @@ impl<DS: DnaStore + 'static> ConductorHandleImpl<DS> {
         Ok(self.conductor.cell_by_id(cell_id)?)
     }
 
+    /// Let clients know about peers joining or leaving a space.
+    fn signal_peer_changes(&self, dna_hash: DnaHash, changes: Vec<p2p_agent_store::PeerChange>) {
+        let mut signal_tx = self.conductor.signal_broadcaster();
+        for change in changes {
+            let signal = match change {
+                p2p_agent_store::PeerChange::Joined(agent) => SystemSignal::PeerJoined {
+                    dna_hash: dna_hash.clone(),
+                    agent,
+                },
+                p2p_agent_store::PeerChange::Left(agent) => SystemSignal::PeerLeft {
+                    dna_hash: dna_hash.clone(),
+                    agent,
+                },
+            };
+            // There may be no interfaces to listen for this.
+            signal_tx.send(Signal::System(signal)).ok();
+        }
+    }
+
     /// Install just the "code parts" (the wasm and entry defs) of a dna
     async fn register_genotype(&self, dna: DnaFile) -> ConductorResult<()> {
         let entry_defs = self.conductor.register_dna_wasm(dna).await?;
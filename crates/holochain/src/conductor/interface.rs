@@ -14,6 +14,7 @@ use tokio::sync::broadcast;
 
 #[allow(missing_docs)]
 pub mod error;
+pub mod session;
 pub mod websocket;
 
 /// Represents runtime data about an existing App interface.
@@ -68,7 +69,7 @@ impl SignalBroadcaster {
         Self { senders }
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "test_utils"))]
     /// A sender with nothing to send to. A placeholder for tests
     pub fn noop() -> Self {
         Self {
@@ -99,3 +100,4 @@ impl SignalBroadcaster {
 }
 
 pub use holochain_conductor_api::config::InterfaceDriver;
+pub use holochain_conductor_api::config::InterfaceTlsConfig;
@@ -0,0 +1,218 @@
+//! Session tokens issued by this conductor and the authentication state of
+//! each interface connection.
+
+use crate::conductor::api::error::ExternalApiWireError;
+use crate::conductor::ConductorHandle;
+use holochain_conductor_api::SessionScope;
+use holochain_conductor_api::SessionToken;
+use holochain_types::prelude::*;
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Number of random bytes in a session token.
+const TOKEN_BYTES: usize = 32;
+
+/// All the session tokens this conductor has issued that haven't been revoked.
+/// Tokens only live in memory so they are all gone when the conductor stops.
+#[derive(Default)]
+pub struct SessionStore {
+    sessions: HashMap<SessionToken, Session>,
+    admin_token: Option<SessionToken>,
+}
+
+struct Session {
+    scope: SessionScope,
+    expires_at: Option<Timestamp>,
+}
+
+impl SessionStore {
+    /// Issue a new token with this scope that is valid for `duration`.
+    pub fn issue(
+        &mut self,
+        scope: SessionScope,
+        duration: std::time::Duration,
+    ) -> Option<(SessionToken, Timestamp)> {
+        self.prune_expired();
+        let expires_at = Timestamp::now().checked_add(&duration)?;
+        let token = new_token();
+        self.sessions.insert(
+            token.clone(),
+            Session {
+                scope,
+                expires_at: Some(expires_at),
+            },
+        );
+        Some((token, expires_at))
+    }
+
+    /// The token with the [`SessionScope::Admin`] scope that is valid for as
+    /// long as the conductor runs. It is created the first time it's needed.
+    pub fn admin_token(&mut self) -> SessionToken {
+        if let Some(token) = &self.admin_token {
+            return token.clone();
+        }
+        let token = new_token();
+        self.sessions.insert(
+            token.clone(),
+            Session {
+                scope: SessionScope::Admin,
+                expires_at: None,
+            },
+        );
+        self.admin_token = Some(token.clone());
+        token
+    }
+
+    /// Is this the token with the [`SessionScope::Admin`] scope that is valid
+    /// for as long as the conductor runs?
+    pub fn is_admin_token(&self, token: &SessionToken) -> bool {
+        self.admin_token.as_ref() == Some(token)
+    }
+
+    /// Stop a token from being used.
+    /// Returns false if the token was unknown or had already expired.
+    pub fn revoke(&mut self, token: &SessionToken) -> bool {
+        if self.is_admin_token(token) {
+            self.admin_token = None;
+        }
+        self.scope(token).is_some() && self.sessions.remove(token).is_some()
+    }
+
+    /// The scope of a token if it is still valid.
+    pub fn scope(&mut self, token: &SessionToken) -> Option<SessionScope> {
+        self.prune_expired();
+        self.sessions
+            .get(token)
+            .map(|session| session.scope.clone())
+    }
+
+    /// Drop every token that has expired so tokens which are never used
+    /// again don't pile up.
+    fn prune_expired(&mut self) {
+        let now = Timestamp::now();
+        self.sessions.retain(|_, session| {
+            session
+                .expires_at
+                .map_or(true, |expires_at| expires_at > now)
+        });
+    }
+}
+
+fn new_token() -> SessionToken {
+    let mut bytes = [0; TOKEN_BYTES];
+    rand::thread_rng().fill(&mut bytes[..]);
+    SessionToken::new(base64::encode_config(bytes, base64::URL_SAFE_NO_PAD))
+}
+
+/// The authentication state of one connection to an interface.
+///
+/// Interface apis are cloned for every connection, so [`ConnectionSession::fresh`]
+/// is used to give each connection its own state.
+#[derive(Clone, Default)]
+pub struct ConnectionSession {
+    required: bool,
+    token: Arc<parking_lot::Mutex<Option<SessionToken>>>,
+}
+
+impl ConnectionSession {
+    /// State for a connection to an interface that may require authentication.
+    pub fn new(required: bool) -> Self {
+        Self {
+            required,
+            token: Default::default(),
+        }
+    }
+
+    /// Unauthenticated state for a new connection to the same interface.
+    pub fn fresh(&self) -> Self {
+        Self::new(self.required)
+    }
+
+    /// Authenticate this connection if the token is valid.
+    /// Returns the scope of the token.
+    pub fn authenticate(
+        &self,
+        conductor_handle: &ConductorHandle,
+        token: SessionToken,
+    ) -> Result<SessionScope, ExternalApiWireError> {
+        match conductor_handle.session_scope(&token) {
+            Some(scope) => {
+                *self.token.lock() = Some(token);
+                Ok(scope)
+            }
+            None => Err(ExternalApiWireError::Unauthenticated(
+                "The session token is not valid or has expired".into(),
+            )),
+        }
+    }
+
+    /// The scope this connection may act with.
+    ///
+    /// `None` means the interface doesn't require authentication so anything
+    /// is allowed. The token is checked every time so a connection loses
+    /// access as soon as its token expires or is revoked.
+    pub fn scope(
+        &self,
+        conductor_handle: &ConductorHandle,
+    ) -> Result<Option<SessionScope>, ExternalApiWireError> {
+        if !self.required {
+            return Ok(None);
+        }
+        let token = self.token.lock().clone().ok_or_else(|| {
+            ExternalApiWireError::Unauthenticated(
+                "This interface requires authenticating with a session token first".into(),
+            )
+        })?;
+        conductor_handle
+            .session_scope(&token)
+            .map(Some)
+            .ok_or_else(|| {
+                ExternalApiWireError::Unauthenticated(
+                    "The session token has expired or was revoked".into(),
+                )
+            })
+    }
+}
+
+/// The error for a request outside of the connection's scope.
+pub fn out_of_scope() -> ExternalApiWireError {
+    ExternalApiWireError::OutOfScope("The session token's scope does not allow this request".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_expire_and_can_be_revoked() {
+        let mut store = SessionStore::default();
+        let (token, _) = store
+            .issue(SessionScope::Admin, std::time::Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(Some(SessionScope::Admin), store.scope(&token));
+        assert!(store.revoke(&token));
+        assert_eq!(None, store.scope(&token));
+        assert!(!store.revoke(&token));
+
+        let (expired, _) = store
+            .issue(SessionScope::Admin, std::time::Duration::from_secs(0))
+            .unwrap();
+        assert_eq!(None, store.scope(&expired));
+
+        // Expired tokens are dropped even if they are never used again.
+        store
+            .issue(SessionScope::Admin, std::time::Duration::from_secs(0))
+            .unwrap();
+        store
+            .issue(SessionScope::Admin, std::time::Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(1, store.sessions.len());
+
+        let admin_token = store.admin_token();
+        assert_eq!(admin_token, store.admin_token());
+        assert_eq!(Some(SessionScope::Admin), store.scope(&admin_token));
+        assert!(store.revoke(&admin_token));
+        assert_ne!(admin_token, store.admin_token());
+    }
+}
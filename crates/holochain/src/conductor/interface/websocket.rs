@@ -1,5 +1,6 @@
 //! Module for establishing Websocket-based Interfaces,
 //! i.e. those configured with `InterfaceDriver::Websocket`
+//! or `InterfaceDriver::UnixSocket`
 
 use super::error::InterfaceError;
 use super::error::InterfaceResult;
@@ -9,9 +10,12 @@ use crate::conductor::manager::ManagedTaskHandle;
 use crate::conductor::manager::ManagedTaskResult;
 use holochain_serialized_bytes::SerializedBytes;
 use holochain_types::signal::Signal;
+use holochain_websocket::CertDigest;
 use holochain_websocket::ListenerHandle;
 use holochain_websocket::ListenerItem;
+use holochain_websocket::TlsIdentity;
 use holochain_websocket::WebsocketConfig;
+use holochain_websocket::WebsocketError;
 use holochain_websocket::WebsocketListener;
 use holochain_websocket::WebsocketMessage;
 use holochain_websocket::WebsocketReceiver;
@@ -25,6 +29,7 @@ use tokio::sync::broadcast;
 use tokio_stream::StreamExt;
 use tracing::*;
 use url2::url2;
+use url2::Url2;
 
 // TODO: This is arbitrary, choose reasonable size.
 /// Number of signals in buffer before applying
@@ -34,21 +39,93 @@ const MAX_CONNECTIONS: isize = 400;
 
 /// Create a WebsocketListener to be used in interfaces
 pub async fn spawn_websocket_listener(
-    port: u16,
+    driver: &InterfaceDriver,
+    tls: Option<&InterfaceTlsConfig>,
 ) -> InterfaceResult<(
     ListenerHandle,
     impl futures::stream::Stream<Item = ListenerItem>,
 )> {
     trace!("Initializing Admin interface");
-    let listener = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await?;
+    let (url, config) = listener_config(driver, tls)?;
+    let listener = WebsocketListener::bind_with_handle(url, Arc::new(config)).await?;
     trace!("LISTENING AT: {}", listener.0.local_addr());
     Ok(listener)
 }
 
+/// The url to bind an interface with this driver to
+/// and the config to bind it with.
+fn listener_config(
+    driver: &InterfaceDriver,
+    tls: Option<&InterfaceTlsConfig>,
+) -> InterfaceResult<(Url2, WebsocketConfig)> {
+    let config = WebsocketConfig::default();
+    match (driver, tls) {
+        (InterfaceDriver::Websocket { port }, None) => {
+            Ok((url2!("ws://127.0.0.1:{}", port), config))
+        }
+        (InterfaceDriver::Websocket { port }, Some(tls)) => Ok((
+            url2!("wss://127.0.0.1:{}", port),
+            config.tls(tls_identity(tls)?),
+        )),
+        (InterfaceDriver::UnixSocket { path }, None) => Ok((
+            holochain_websocket::unix_socket_url(path).map_err(WebsocketError::from)?,
+            config,
+        )),
+        (InterfaceDriver::UnixSocket { .. }, Some(_)) => Err(InterfaceError::Other(
+            "TLS is only served on websocket interfaces".into(),
+        )),
+    }
+}
+
+/// Load the certificate from the config or make a self-signed one.
+fn tls_identity(tls: &InterfaceTlsConfig) -> InterfaceResult<TlsIdentity> {
+    let identity = match (&tls.cert_path, &tls.key_path) {
+        (Some(cert_path), Some(key_path)) => TlsIdentity::from_pem_files(cert_path, key_path),
+        (None, None) => {
+            let subject_alt_names = if tls.subject_alt_names.is_empty() {
+                vec!["localhost".to_string()]
+            } else {
+                tls.subject_alt_names.clone()
+            };
+            TlsIdentity::self_signed(subject_alt_names)
+        }
+        _ => {
+            return Err(InterfaceError::Other(
+                "An interface TLS config needs both a cert_path and a key_path, \
+                 or neither to use a self-signed certificate"
+                    .into(),
+            ))
+        }
+    };
+    Ok(identity.map_err(WebsocketError::from)?)
+}
+
+/// The digest of the certificate a listener serves TLS with, if it does.
+pub fn tls_cert_digest(handle: &ListenerHandle) -> Option<CertDigest> {
+    handle
+        .get_config()
+        .tls
+        .as_ref()
+        .map(TlsIdentity::cert_digest)
+}
+
+/// The driver an interface ended up listening with,
+/// i.e. with the port the OS chose if it was 0.
+pub fn bound_driver(
+    driver: &InterfaceDriver,
+    handle: &ListenerHandle,
+) -> InterfaceResult<InterfaceDriver> {
+    match driver {
+        InterfaceDriver::Websocket { .. } => Ok(InterfaceDriver::Websocket {
+            port: handle
+                .local_addr()
+                .port()
+                .ok_or(InterfaceError::PortError)?,
+        }),
+        InterfaceDriver::UnixSocket { .. } => Ok(driver.clone()),
+    }
+}
+
 /// Create an Admin Interface, which only receives AdminRequest messages
 /// from the external client
 pub fn spawn_admin_interface_task<A: InterfaceApi>(
@@ -75,7 +152,7 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
                         continue;
                     };
                     tokio::task::spawn(recv_incoming_admin_msgs(
-                        api.clone(),
+                        api.for_connection(),
                         rx_from_iface,
                         num_connections.clone(),
                     ));
@@ -92,22 +169,18 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
 /// Create an App Interface, which includes the ability to receive signals
 /// from Cells via a broadcast channel
 pub async fn spawn_app_interface_task<A: InterfaceApi>(
-    port: u16,
+    driver: &InterfaceDriver,
+    tls: Option<&InterfaceTlsConfig>,
     api: A,
     signal_broadcaster: broadcast::Sender<Signal>,
     mut stop_rx: StopReceiver,
-) -> InterfaceResult<(u16, ManagedTaskHandle)> {
+) -> InterfaceResult<(InterfaceDriver, Option<CertDigest>, ManagedTaskHandle)> {
     trace!("Initializing App interface");
-    let (handle, mut listener) = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await?;
+    let (url, config) = listener_config(driver, tls)?;
+    let (handle, mut listener) = WebsocketListener::bind_with_handle(url, Arc::new(config)).await?;
     trace!("LISTENING AT: {}", handle.local_addr());
-    let port = handle
-        .local_addr()
-        .port()
-        .ok_or(InterfaceError::PortError)?;
+    let driver = bound_driver(driver, &handle)?;
+    let cert_digest = tls_cert_digest(&handle);
     // Task that will kill the listener and all child connections.
     tokio::task::spawn(
         handle.close_on(async move { stop_rx.recv().await.map(|_| true).unwrap_or(true) }),
@@ -119,7 +192,7 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
                 Ok((tx_to_iface, rx_from_iface)) => {
                     let rx_from_cell = signal_broadcaster.subscribe();
                     spawn_recv_incoming_msgs_and_outgoing_signals(
-                        api.clone(),
+                        api.for_connection(),
                         rx_from_iface,
                         rx_from_cell,
                         tx_to_iface,
@@ -133,7 +206,7 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
 
         ManagedTaskResult::Ok(())
     });
-    Ok((port, task))
+    Ok((driver, cert_digest, task))
 }
 
 /// Polls for messages coming in from the external client.
@@ -180,9 +253,15 @@ fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
         }
     });
 
+    let signal_api = api.clone();
     tokio::task::spawn(rx_from_cell.for_each_concurrent(4096, move |signal| {
         let mut tx_to_iface = tx_to_iface.clone();
+        let api = signal_api.clone();
         async move {
+            // Connections only get signals their session allows.
+            if !api.allows_signal(&signal).await {
+                return;
+            }
             trace!(msg = "Sending signal!", ?signal);
             if let Err(err) = async move {
                 let bytes = SerializedBytes::try_from(signal)?;
@@ -276,10 +355,18 @@ pub mod test {
     use super::test_utils::setup_app;
     use super::*;
     use crate::conductor::api::error::ExternalApiWireError;
+    use crate::conductor::api::signal_subscription::SignalFilter;
+    use crate::conductor::api::signal_subscription::SignalFilterSet;
+    use crate::conductor::api::signal_subscription::SignalSubscription;
     use crate::conductor::api::AdminRequest;
     use crate::conductor::api::AdminResponse;
+    use crate::conductor::api::AppInterfaceApi;
+    use crate::conductor::api::InterfaceApi;
     use crate::conductor::api::RealAdminInterfaceApi;
+    use crate::conductor::api::SessionScope;
+    use crate::conductor::api::SessionToken;
     use crate::conductor::conductor::ConductorBuilder;
+    use crate::conductor::config::AdminInterfaceConfig;
     use crate::conductor::state::ConductorState;
     use crate::conductor::Conductor;
     use crate::conductor::ConductorHandle;
@@ -485,6 +572,195 @@ pub mod test {
         shutdown.await.unwrap().unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn signal_subscription_filters_app_signals() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let cell_1 = CellId::from((dna_hash.clone(), fake_agent_pubkey_1()));
+        let cell_2 = CellId::from((dna_hash, fake_agent_pubkey_2()));
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store.expect_get().returning(move |_| Some(dna.clone()));
+        dna_store.expect_add_dnas::<Vec<_>>().return_const(());
+        dna_store.expect_add_entry_defs::<Vec<_>>().return_const(());
+
+        let (_tmpdir, app_api, handle) = setup_app(
+            vec![
+                (InstalledCell::new(cell_1.clone(), "one".into()), None),
+                (InstalledCell::new(cell_2.clone(), "two".into()), None),
+            ],
+            dna_store,
+        )
+        .await;
+        let subscribed = app_api.for_connection();
+        let other = app_api.for_connection();
+        let app_signal = |cell_id: &CellId| {
+            Signal::App(
+                cell_id.clone(),
+                AppSignal::new(ExternIO::encode(()).unwrap()),
+            )
+        };
+
+        let filters = SignalFilterSet::Include(
+            vec![(cell_1.clone(), SignalFilter::empty())]
+                .into_iter()
+                .collect(),
+        );
+        let response = subscribed
+            .handle_app_request(AppRequest::SignalSubscription(SignalSubscription::new(
+                "test app".into(),
+                filters,
+            )))
+            .await;
+        assert_matches!(response, AppResponse::SignalSubscriptionUpdated);
+
+        // - Only the included cell's signals reach the subscribed connection.
+        assert!(subscribed.allows_signal(&app_signal(&cell_1)).await);
+        assert!(!subscribed.allows_signal(&app_signal(&cell_2)).await);
+        assert!(
+            subscribed
+                .allows_signal(&Signal::System(SystemSignal::Test("test".into())))
+                .await
+        );
+
+        // - Other connections are not affected.
+        assert!(other.allows_signal(&app_signal(&cell_2)).await);
+
+        // - A new subscription replaces the old one.
+        let response = subscribed
+            .handle_app_request(AppRequest::SignalSubscription(SignalSubscription::new(
+                "test app".into(),
+                SignalFilterSet::allow_all(),
+            )))
+            .await;
+        assert_matches!(response, AppResponse::SignalSubscriptionUpdated);
+        assert!(subscribed.allows_signal(&app_signal(&cell_2)).await);
+
+        // - Subscribing to an app that isn't installed is an error.
+        let response = subscribed
+            .handle_app_request(AppRequest::SignalSubscription(SignalSubscription::new(
+                "no app".into(),
+                SignalFilterSet::block_all(),
+            )))
+            .await;
+        assert_matches!(response, AppResponse::Error(_));
+
+        let shutdown = handle.take_shutdown_handle().unwrap();
+        handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn session_scope_filters_cells_and_signals() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let cell_1 = CellId::from((dna_hash.clone(), fake_agent_pubkey_1()));
+        // Not part of any app.
+        let cell_2 = CellId::from((dna_hash.clone(), fake_agent_pubkey_2()));
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store.expect_get().returning(move |_| Some(dna.clone()));
+        dna_store.expect_add_dnas::<Vec<_>>().return_const(());
+        dna_store.expect_add_entry_defs::<Vec<_>>().return_const(());
+
+        let (_tmpdir, app_api, handle) = setup_app(
+            vec![(InstalledCell::new(cell_1.clone(), "one".into()), None)],
+            dna_store,
+        )
+        .await;
+        let app_api = app_api.with_auth(true);
+        let app_signal = |cell_id: &CellId| {
+            Signal::App(
+                cell_id.clone(),
+                AppSignal::new(ExternIO::encode(()).unwrap()),
+            )
+        };
+        let system_signal = Signal::System(SystemSignal::Test("test".into()));
+        let status_signal = Signal::System(SystemSignal::AppStatusChanged {
+            installed_app_id: "test app".into(),
+            status: AppStatus::Running,
+        });
+        let peer_signal = |dna_hash: DnaHash| {
+            Signal::System(SystemSignal::PeerJoined {
+                dna_hash,
+                agent: fake_agent_pubkey_2(),
+            })
+        };
+        let connect = |scope: SessionScope| {
+            let connection = app_api.for_connection();
+            let (token, _) = handle
+                .issue_session_token(scope, std::time::Duration::from_secs(60))
+                .unwrap();
+            async move {
+                assert_matches!(
+                    connection
+                        .handle_request(Ok(AppRequest::Authenticate { token }))
+                        .await
+                        .unwrap(),
+                    AppResponse::Authenticated
+                );
+                connection
+            }
+        };
+
+        // - Unauthenticated connections get no signals at all.
+        let unauthenticated = app_api.for_connection();
+        assert!(!unauthenticated.allows_signal(&app_signal(&cell_1)).await);
+        assert!(!unauthenticated.allows_signal(&system_signal).await);
+
+        // - Only the cells of apps in scope are allowed.
+        let in_scope = connect(SessionScope::Apps(
+            vec!["test app".to_string()].into_iter().collect(),
+        ))
+        .await;
+        assert!(in_scope.allows_signal(&app_signal(&cell_1)).await);
+        assert!(!in_scope.allows_signal(&app_signal(&cell_2)).await);
+
+        // - Only system signals about apps and DNAs in scope are allowed.
+        assert!(in_scope.allows_signal(&status_signal).await);
+        assert!(in_scope.allows_signal(&peer_signal(dna_hash.clone())).await);
+        assert!(!in_scope.allows_signal(&peer_signal(fake_dna_hash(1))).await);
+        assert!(!in_scope.allows_signal(&system_signal).await);
+        assert_matches!(
+            in_scope
+                .handle_request(Ok(AppRequest::PublishStatus {
+                    cell_id: cell_2.clone(),
+                    header_hash: fixt!(HeaderHash),
+                }))
+                .await
+                .unwrap(),
+            AppResponse::Error(ExternalApiWireError::OutOfScope(_))
+        );
+
+        let other_app = connect(SessionScope::Apps(
+            vec!["other app".to_string()].into_iter().collect(),
+        ))
+        .await;
+        assert!(!other_app.allows_signal(&app_signal(&cell_1)).await);
+        assert!(!other_app.allows_signal(&status_signal).await);
+        assert!(!other_app.allows_signal(&peer_signal(dna_hash)).await);
+
+        // - The admin scope allows every cell.
+        let admin = connect(SessionScope::Admin).await;
+        assert!(admin.allows_signal(&app_signal(&cell_1)).await);
+        assert!(admin.allows_signal(&app_signal(&cell_2)).await);
+        assert!(admin.allows_signal(&system_signal).await);
+
+        let shutdown = handle.take_shutdown_handle().unwrap();
+        handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn enable_disable_app() {
         observability::test_run().ok();
@@ -625,7 +901,12 @@ pub mod test {
         let (_tmpdir, conductor_handle) = setup_admin().await;
         let shutdown = conductor_handle.take_shutdown_handle().unwrap();
         let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
-        let msg = AdminRequest::AttachAppInterface { port: None };
+        let msg = AdminRequest::AttachAppInterface {
+            port: None,
+            require_auth: false,
+            path: None,
+            tls: None,
+        };
         let msg = msg.try_into().unwrap();
         let respond = |bytes: SerializedBytes| {
             let response: AdminResponse = bytes.try_into().unwrap();
@@ -639,6 +920,153 @@ pub mod test {
         shutdown.await.unwrap().unwrap();
     }
 
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn attach_app_interface_on_unix_socket() {
+        observability::test_run().ok();
+        let (tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().unwrap();
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let path = tmpdir.path().join("app.sock");
+        let msg = AdminRequest::AttachAppInterface {
+            port: None,
+            require_auth: false,
+            path: Some(path.clone()),
+            tls: None,
+        };
+        assert_matches!(
+            admin_api.handle_request(Ok(msg)).await.unwrap(),
+            AdminResponse::AppInterfaceAttached { port: 0, path: Some(p), .. } if p == path
+        );
+        assert_matches!(
+            admin_api
+                .handle_request(Ok(AdminRequest::ListAppInterfaces))
+                .await
+                .unwrap(),
+            AdminResponse::AppInterfacesListed(drivers)
+                if drivers == vec![InterfaceDriver::UnixSocket { path: path.clone() }]
+        );
+
+        let url = holochain_websocket::unix_socket_url(&path).unwrap();
+        let (mut client, _) =
+            holochain_websocket::connect(url, Arc::new(WebsocketConfig::default()))
+                .await
+                .unwrap();
+        let response: AppResponse = client
+            .request(AppRequest::AppInfo {
+                installed_app_id: "no app".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_matches!(response, AppResponse::AppInfo(None));
+
+        conductor_handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn attach_app_interface_with_tls() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().unwrap();
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = AdminRequest::AttachAppInterface {
+            port: None,
+            require_auth: false,
+            path: None,
+            tls: Some(InterfaceTlsConfig::default()),
+        };
+        let (port, cert_digest) = match admin_api.handle_request(Ok(msg)).await.unwrap() {
+            AdminResponse::AppInterfaceAttached {
+                port,
+                tls_cert_digest: Some(cert_digest),
+            } => (port, cert_digest),
+            response => panic!("unexpected response {:?}", response),
+        };
+
+        let config = WebsocketConfig::default().tls_pin(cert_digest.parse().unwrap());
+        let (mut client, _) =
+            holochain_websocket::connect(url2!("wss://127.0.0.1:{}", port), Arc::new(config))
+                .await
+                .unwrap();
+        let response: AppResponse = client
+            .request(AppRequest::AppInfo {
+                installed_app_id: "no app".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_matches!(response, AppResponse::AppInfo(None));
+
+        conductor_handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn admin_interface_requires_session_token() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let config = AdminInterfaceConfig {
+            driver: InterfaceDriver::Websocket { port: 0 },
+            require_auth: true,
+            session_duration_seconds: None,
+            tls: None,
+        };
+        let admin_api = RealAdminInterfaceApi::for_interface(conductor_handle.clone(), &config);
+        let request = |api: RealAdminInterfaceApi, request: AdminRequest| async move {
+            api.handle_request(Ok(request)).await.unwrap()
+        };
+        let connection = admin_api.for_connection();
+
+        assert_matches!(
+            request(connection.clone(), AdminRequest::ListDnas).await,
+            AdminResponse::Error(ExternalApiWireError::Unauthenticated(_))
+        );
+        let token = SessionToken::new("not a token".into());
+        assert_matches!(
+            request(connection.clone(), AdminRequest::Authenticate { token }).await,
+            AdminResponse::Error(ExternalApiWireError::Unauthenticated(_))
+        );
+
+        // This token can only list dnas.
+        let scope =
+            SessionScope::AdminRequests(vec!["list_dnas".to_string()].into_iter().collect());
+        let (token, _) = conductor_handle
+            .issue_session_token(scope, std::time::Duration::from_secs(60))
+            .unwrap();
+        assert_matches!(
+            request(
+                connection.clone(),
+                AdminRequest::Authenticate {
+                    token: token.clone()
+                }
+            )
+            .await,
+            AdminResponse::Authenticated
+        );
+        assert_matches!(
+            request(connection.clone(), AdminRequest::ListDnas).await,
+            AdminResponse::DnasListed(_)
+        );
+        assert_matches!(
+            request(connection.clone(), AdminRequest::GenerateAgentPubKey).await,
+            AdminResponse::Error(ExternalApiWireError::OutOfScope(_))
+        );
+
+        // Other connections to the interface authenticate on their own.
+        assert_matches!(
+            request(admin_api.for_connection(), AdminRequest::ListDnas).await,
+            AdminResponse::Error(ExternalApiWireError::Unauthenticated(_))
+        );
+
+        // Revoking the token cuts off connections using it.
+        assert!(conductor_handle.revoke_session_token(&token).unwrap());
+        assert_matches!(
+            request(connection, AdminRequest::ListDnas).await,
+            AdminResponse::Error(ExternalApiWireError::Unauthenticated(_))
+        );
+        conductor_handle.shutdown();
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn dump_state() {
         observability::test_run().ok();
@@ -694,6 +1122,8 @@ pub mod test {
                 uid: uid.to_string(),
                 properties: SerializedBytes::try_from(()).unwrap(),
                 zomes: zomes.clone().into_iter().map(Into::into).collect(),
+                integrity: Default::default(),
+                lineage: None,
             },
             zomes.into_iter().map(Into::into),
         )
@@ -14,6 +14,7 @@ use holochain_state::prelude::StateMutationResult;
 use holochain_state::prelude::StateQueryResult;
 use holochain_zome_types::CellId;
 use kitsune_p2p::KitsuneBinType;
+use rusqlite::Transaction;
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -24,8 +25,8 @@ use super::error::ConductorResult;
 pub struct P2pBatch {
     /// Agent information to be committed.
     pub peer_data: Vec<AgentInfoSigned>,
-    /// The result of this commit.
-    pub result_sender: tokio::sync::oneshot::Sender<Result<(), P2pBatchError>>,
+    /// The result of this commit, with the peers that joined or left.
+    pub result_sender: tokio::sync::oneshot::Sender<Result<Vec<PeerChange>, P2pBatchError>>,
 }
 
 #[derive(Debug, Error)]
@@ -62,16 +63,19 @@ pub async fn p2p_put_all_batch(
                     result_sender: response,
                 } in batch
                 {
+                    let mut changes = Vec::new();
                     for info in batch {
-                        match p2p_put_single(&mut txn, &info) {
-                            Ok(_) => (),
+                        let result = peer_change(&txn, &info)
+                            .and_then(|change| p2p_put_single(&mut txn, &info).map(|_| change));
+                        match result {
+                            Ok(change) => changes.extend(change),
                             Err(e) => {
                                 responses.push((Err(e), response));
                                 continue 'batch;
                             }
                         }
                     }
-                    responses.push((Ok(()), response));
+                    responses.push((Ok(changes), response));
                 }
                 tx.send(responses).map_err(|_| {
                     DatabaseError::Other(anyhow::anyhow!(
@@ -157,6 +161,30 @@ where
     Ok(r)
 }
 
+/// A peer joining or leaving a space.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeerChange {
+    /// The peer was not in the peer store, or had left, and is now reachable.
+    Joined(AgentPubKey),
+    /// The peer was reachable and has published an agent info with no urls.
+    Left(AgentPubKey),
+}
+
+/// Compare incoming agent info with the peer store to find
+/// whether the peer is joining or leaving the space.
+fn peer_change(txn: &Transaction, info: &AgentInfoSigned) -> DatabaseResult<Option<PeerChange>> {
+    let was_active = txn
+        .p2p_get_agent(&info.agent)?
+        .map(|existing| !existing.url_list.is_empty())
+        .unwrap_or(false);
+    let agent = AgentPubKey::from_kitsune(&info.agent);
+    Ok(match (was_active, !info.url_list.is_empty()) {
+        (false, true) => Some(PeerChange::Joined(agent)),
+        (true, false) => Some(PeerChange::Left(agent)),
+        _ => None,
+    })
+}
+
 /// Get agent info for a single agent
 pub async fn get_agent_info_signed(
     environ: DbRead<DbKindP2pAgentStore>,
@@ -308,6 +336,9 @@ mod tests {
     use ::fixt::prelude::*;
     use holochain_state::test_utils::test_p2p_agent_store_env;
     use kitsune_p2p::fixt::AgentInfoSignedFixturator;
+    use kitsune_p2p::fixt::KitsuneAgentFixturator;
+    use kitsune_p2p::fixt::KitsuneSignatureFixturator;
+    use kitsune_p2p::fixt::KitsuneSpaceFixturator;
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_store_agent_info_signed() {
@@ -365,4 +396,55 @@ mod tests {
 
         assert_eq!(expect, agents);
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn batch_put_detects_join_and_leave() {
+        observability::test_run().ok();
+        let t_env = test_p2p_agent_store_env();
+        let env = t_env.env();
+
+        let agent = Arc::new(fixt!(KitsuneAgent));
+        let sign = |urls: Vec<&str>, signed_at_ms| {
+            AgentInfoSigned::sign(
+                Arc::new(fixt!(KitsuneSpace)),
+                agent.clone(),
+                u32::MAX / 4,
+                urls.into_iter().map(Into::into).collect(),
+                signed_at_ms,
+                signed_at_ms + 1000,
+                |_| async move { Ok(Arc::new(fixt!(KitsuneSignature))) },
+            )
+        };
+        let joined = sign(vec!["https://example.com/a"], 1).await.unwrap();
+        let left = sign(vec![], 2).await.unwrap();
+        let agent = AgentPubKey::from_kitsune(&agent);
+
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        tokio::spawn(p2p_put_all_batch(env.clone(), rx));
+        let put = |peer_data| {
+            let tx = tx.clone();
+            async move {
+                let (result_sender, response) = tokio::sync::oneshot::channel();
+                tx.send(P2pBatch {
+                    peer_data,
+                    result_sender,
+                })
+                .await
+                .unwrap();
+                response.await.unwrap().unwrap()
+            }
+        };
+
+        // - An unknown agent with urls has joined.
+        let changes = put(vec![joined.clone()]).await;
+        assert_eq!(changes, vec![PeerChange::Joined(agent.clone())]);
+
+        // - Seeing the same info again is not a change.
+        let changes = put(vec![joined]).await;
+        assert!(changes.is_empty());
+
+        // - A known agent with no urls has left.
+        let changes = put(vec![left]).await;
+        assert_eq!(changes, vec![PeerChange::Left(agent)]);
+    }
 }
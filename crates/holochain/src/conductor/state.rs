@@ -1,6 +1,7 @@
 //! Structs which allow the Conductor's state to be persisted across
 //! startups and shutdowns
 
+use holochain_conductor_api::config::InterfaceTlsConfig;
 use holochain_conductor_api::signal_subscription::SignalSubscription;
 use holochain_conductor_api::{config::InterfaceDriver, InstalledAppInfo};
 use holochain_types::prelude::*;
@@ -187,6 +188,14 @@ pub struct AppInterfaceConfig {
 
     /// The driver for the interface, e.g. Websocket
     pub driver: InterfaceDriver,
+
+    /// Whether connections must authenticate with a session token
+    #[serde(default)]
+    pub require_auth: bool,
+
+    /// The certificate to serve TLS with, if the interface uses TLS
+    #[serde(default)]
+    pub tls: Option<InterfaceTlsConfig>,
 }
 
 impl AppInterfaceConfig {
@@ -195,6 +204,8 @@ impl AppInterfaceConfig {
         Self {
             signal_subscriptions: HashMap::new(),
             driver: InterfaceDriver::Websocket { port },
+            require_auth: false,
+            tls: None,
         }
     }
 }
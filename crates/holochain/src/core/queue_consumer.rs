@@ -140,7 +140,9 @@ pub async fn spawn_queue_consumer_tasks(
             spawn_integrate_dht_ops_consumer(
                 dna_hash.clone(),
                 dht_env.clone(),
+                authored_env.clone().into(),
                 cell_id.clone(),
+                conductor_handle.clone(),
                 stop.subscribe(),
                 tx_receipt.clone(),
                 network.clone(),
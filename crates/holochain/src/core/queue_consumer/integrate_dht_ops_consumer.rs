@@ -7,11 +7,13 @@ use tokio::task::JoinHandle;
 use tracing::*;
 
 /// Spawn the QueueConsumer for DhtOpIntegration workflow
-#[instrument(skip(env, stop, trigger_receipt, network))]
+#[instrument(skip(env, authored_env, conductor_handle, stop, trigger_receipt, network))]
 pub fn spawn_integrate_dht_ops_consumer(
     dna_hash: Arc<DnaHash>,
     env: DbWrite<DbKindDht>,
+    authored_env: DbRead<DbKindAuthored>,
     cell_id: CellId,
+    conductor_handle: ConductorHandle,
     mut stop: sync::broadcast::Receiver<()>,
     trigger_receipt: TriggerSender,
     network: HolochainP2pDna,
@@ -29,8 +31,14 @@ pub fn spawn_integrate_dht_ops_consumer(
             }
 
             // Run the workflow
-            match integrate_dht_ops_workflow(env.clone(), trigger_receipt.clone(), network.clone())
-                .await
+            match integrate_dht_ops_workflow(
+                env.clone(),
+                authored_env.clone(),
+                trigger_receipt.clone(),
+                network.clone(),
+                conductor_handle.signal_broadcaster().await,
+            )
+            .await
             {
                 Ok(WorkComplete::Incomplete) => trigger_self.trigger(),
                 Err(err) => handle_workflow_error(err)?,
@@ -89,6 +89,10 @@ host_fn_api_impls! {
     // Recipient, Sender, Encrypted data.
     fn x_25519_x_salsa20_poly1305_decrypt (holochain_zome_types::x_salsa20_poly1305::X25519XSalsa20Poly1305Decrypt) -> Option<holochain_zome_types::x_salsa20_poly1305::data::XSalsa20Poly1305Data>;
 
+    // Apply several writes to the scratch and validate them together.
+    // Returns the HeaderHash of every write in order.
+    fn commit_batch (Vec<zt::batch::BatchOp>) -> Vec<holo_hash::HeaderHash>;
+
     // Create a link between two entries.
     fn create_link (zt::link::CreateLinkInput) -> holo_hash::HeaderHash;
 
@@ -109,6 +113,9 @@ host_fn_api_impls! {
     // Attempt to get a live entry from the cascade.
     fn get (Vec<zt::entry::GetInput>) -> Vec<Option<zt::element::Element>>;
 
+    // Get a range of the chunks of a chunked payload from the cascade.
+    fn get_chunks (zt::chunk::GetChunksInput) -> Option<Vec<zt::chunk::Chunk>>;
+
     fn get_agent_activity (zt::agent_activity::GetAgentActivityInput) -> zt::query::AgentActivity;
 
     fn get_details (Vec<zt::entry::GetInput>) -> Vec<Option<zt::metadata::Details>>;
@@ -133,15 +140,24 @@ host_fn_api_impls! {
     // Attempt to accept a preflight request.
     fn accept_countersigning_preflight_request(zt::countersigning::PreflightRequest) -> zt::countersigning::PreflightRequestAcceptance;
 
+    // Get the publish and validation receipt status of an authored header.
+    fn publish_status (holo_hash::HeaderHash) -> Option<zt::publish::PublishStatus>;
+
     // Query the source chain for data.
     fn query (zt::query::ChainQueryFilter) -> Vec<Element>;
 
+    // Query a page of the source chain, continuing from a cursor.
+    fn query_page (zt::query::ChainQueryPageInput) -> zt::query::ChainQueryPage;
+
     // the length of random bytes to create
     fn random_bytes (u32) -> zt::bytes::Bytes;
 
     // Remotely signal many agents without waiting for responses
     fn remote_signal (zt::signal::RemoteSignal) -> ();
 
+    // Send a remote signal to many agents and wait for each to acknowledge it.
+    fn remote_signal_acked (zt::signal::RemoteSignal) -> Vec<zt::signal::RemoteSignalAck>;
+
     // // @todo
     // fn send (()) -> ();
 
@@ -0,0 +1,181 @@
+use super::create::create;
+use super::create_link::create_link;
+use super::delete::delete;
+use super::delete_link::delete_link;
+use super::update::update;
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_state::host_fn_workspace::SourceChainWorkspace;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::WasmError;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+/// Apply every op in the batch to the scratch then validate the new elements
+/// together. If any op fails or any element is invalid the scratch is put back
+/// the way it was before the batch so none of it can be flushed.
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn commit_batch<'a>(
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    inputs: Vec<BatchOp>,
+) -> Result<Vec<HeaderHash>, WasmError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            write_workspace: Permission::Allow,
+            ..
+        } => {
+            let source_chain = call_context
+                .host_context
+                .workspace_write()
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain if write_workspace access is given")
+                .clone();
+            let snapshot = source_chain
+                .snapshot()
+                .map_err(|source_chain_error| WasmError::Host(source_chain_error.to_string()))?;
+            let batch_start = snapshot.len();
+
+            let result = apply_batch(ribosome, call_context, inputs, batch_start);
+            if result.is_err() {
+                source_chain
+                    .scratch()
+                    .apply(|scratch| *scratch = snapshot)
+                    .map_err(|scratch_error| WasmError::Host(scratch_error.to_string()))?;
+            }
+            result
+        }
+        _ => Err(WasmError::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "commit_batch".into(),
+            )
+            .to_string(),
+        )),
+    }
+}
+
+fn apply_batch(
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    inputs: Vec<BatchOp>,
+    batch_start: usize,
+) -> Result<Vec<HeaderHash>, WasmError> {
+    let mut header_hashes = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        let header_hash = match input {
+            BatchOp::Create(input) => create(ribosome.clone(), call_context.clone(), input),
+            BatchOp::Update(input) => update(ribosome.clone(), call_context.clone(), input),
+            BatchOp::Delete(input) => delete(ribosome.clone(), call_context.clone(), input),
+            BatchOp::CreateLink(input) => {
+                create_link(ribosome.clone(), call_context.clone(), input)
+            }
+            BatchOp::DeleteLink(input) => {
+                delete_link(ribosome.clone(), call_context.clone(), input)
+            }
+        }?;
+        header_hashes.push(header_hash);
+    }
+
+    // Only zome calls can reach the conductor to validate early.
+    // Writes in any other context are validated with the rest of the scratch
+    // by their workflow before the flush.
+    if let HostContext::ZomeCall(_) = call_context.host_context {
+        let workspace = call_context.host_context.workspace_write().clone();
+        let elements = workspace
+            .source_chain()
+            .as_ref()
+            .expect("Must have source chain if write_workspace access is given")
+            .scratch_elements()
+            .map_err(|source_chain_error| WasmError::Host(source_chain_error.to_string()))?
+            .split_off(batch_start);
+        let workspace_lock = SourceChainWorkspace::try_from(workspace)
+            .map_err(|source_chain_error| WasmError::Host(source_chain_error.to_string()))?;
+        let network = call_context.host_context.network().clone();
+        let zome = call_context.zome.clone();
+        tokio_helper::block_forever_on(async move {
+            call_context
+                .host_context
+                .call_zome_handle()
+                .validate_elements(zome, elements, workspace_lock, network)
+                .await
+        })
+        .map_err(|conductor_api_error| WasmError::Host(conductor_api_error.to_string()))?;
+    }
+
+    Ok(header_hashes)
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod slow_tests {
+    use crate::sweettest::*;
+    use hdk::prelude::*;
+    use holochain_keystore::MetaLairClient;
+    use holochain_state::prelude::SourceChain;
+    use holochain_wasm_test_utils::TestWasm;
+
+    async fn chain_len(cell: &SweetCell, keystore: MetaLairClient) -> u32 {
+        SourceChain::new(
+            cell.authored_env().clone(),
+            cell.dht_env().clone(),
+            keystore,
+            cell.agent_pubkey().clone(),
+        )
+        .await
+        .unwrap()
+        .len()
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn commit_batch_is_atomic() {
+        observability::test_run().ok();
+        let (dna, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create])
+            .await
+            .unwrap();
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let keystore = conductor.keystore();
+        let app = conductor.setup_app("app", &[dna]).await.unwrap();
+        let (cell,) = app.into_tuple();
+        let zome = cell.zome(TestWasm::Create);
+
+        let header_hashes: Vec<HeaderHash> = conductor
+            .call(
+                &zome,
+                "create_batch",
+                vec!["apple".to_string(), "pear".to_string()],
+            )
+            .await;
+        assert_eq!(header_hashes.len(), 2);
+        for header_hash in header_hashes {
+            let element: Option<Element> = conductor.call(&zome, "get_post", header_hash).await;
+            assert!(element.is_some());
+        }
+
+        let len_before = chain_len(&cell, keystore.clone()).await;
+
+        // The banana is invalid so the whole batch is dropped but the zome
+        // call can still carry on and commit other entries.
+        let rejected: bool = conductor
+            .call(
+                &zome,
+                "create_batch_or_post",
+                vec!["plum".to_string(), "Banana".to_string()],
+            )
+            .await;
+        assert!(rejected);
+        assert_eq!(chain_len(&cell, keystore.clone()).await, len_before + 1);
+
+        let r: Result<Vec<HeaderHash>, _> = conductor
+            .call_fallible(&zome, "create_batch", vec!["Banana".to_string()])
+            .await;
+        assert!(r.is_err());
+        assert_eq!(chain_len(&cell, keystore.clone()).await, len_before + 1);
+    }
+}
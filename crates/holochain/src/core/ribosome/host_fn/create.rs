@@ -252,6 +252,8 @@ pub mod wasm_test {
                 uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
                 properties: SerializedBytes::try_from(()).unwrap(),
                 zomes: vec![TestWasm::MultipleCalls.into()].into(),
+                integrity: Default::default(),
+                lineage: None,
             },
             vec![TestWasm::MultipleCalls.into()],
         )
@@ -1,40 +1,61 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostContext;
 use crate::core::ribosome::RibosomeT;
+use crate::core::ribosome::ZomeCall;
 use holochain_cascade::Cascade;
 use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::WasmError;
+use std::collections::HashSet;
 use std::sync::Arc;
 use crate::core::ribosome::HostFnAccess;
 use futures::future::join_all;
 use crate::core::ribosome::RibosomeError;
 
+/// The zome function asked to resolve conflicts for [`CrdtType::ResolveCallback`].
+const RESOLVE_CONFLICT_FN: &str = "resolve_conflict";
+
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn get<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     inputs: Vec<GetInput>,
 ) -> Result<Vec<Option<Element>>, WasmError> {
     match HostFnAccess::from(&call_context.host_context()) {
         HostFnAccess{ read_workspace: Permission::Allow, .. } => {
-            let results: Vec<Result<Option<Element>, _>> = tokio_helper::block_forever_on(async move {
+            let results: Vec<Result<Option<Element>, WasmError>> = tokio_helper::block_forever_on(async move {
                 join_all(inputs.into_iter().map(|input| {
                     async {
                         let GetInput {
                             any_dht_hash,
                             get_options,
                         } = input;
-                        Cascade::from_workspace_network(
+                        // Updates are only resolved in zome calls, which have
+                        // access to the conductor's entry defs.
+                        let resolve = matches!(*any_dht_hash.hash_type(), holo_hash::hash_type::AnyDht::Entry)
+                            && get_options.strategy == GetStrategy::Latest
+                            && matches!(call_context.host_context, HostContext::ZomeCall(_));
+                        let mut cascade = Cascade::from_workspace_network(
                             &call_context.host_context.workspace(),
                             call_context.host_context.network().clone()
-                        )
-                        .dht_get(any_dht_hash, get_options).await
+                        );
+                        let maybe_element = cascade
+                            .dht_get(any_dht_hash, get_options.clone())
+                            .await
+                            .map_err(|cascade_error| WasmError::Host(cascade_error.to_string()))?;
+                        match maybe_element {
+                            Some(element) if resolve => resolve_updates(
+                                ribosome.as_ref(),
+                                &call_context,
+                                &mut cascade,
+                                element,
+                                get_options,
+                            ).await.map(Some),
+                            maybe_element => Ok(maybe_element),
+                        }
                     }
                 })).await
             });
-            let results: Result<Vec<_>, _> = results.into_iter().map(|result| match result {
-                Ok(v) => Ok(v),
-                Err(cascade_error) => Err(WasmError::Host(cascade_error.to_string())),
-            }).collect();
+            let results: Result<Vec<_>, _> = results.into_iter().collect();
             Ok(results?)
         },
         _ => Err(WasmError::Host(RibosomeError::HostFnPermissions(
@@ -45,5 +66,230 @@ pub fn get<'a>(
     }
 }
 
+/// Follow the winning update of a concurrently updated entry, as chosen by
+/// the [`CrdtType`] of its entry def, until we reach an entry with no updates.
+async fn resolve_updates(
+    ribosome: &impl RibosomeT,
+    call_context: &CallContext,
+    cascade: &mut Cascade,
+    mut element: Element,
+    get_options: GetOptions,
+) -> Result<Element, WasmError> {
+    let mut seen = HashSet::new();
+    loop {
+        let (entry_hash, app_entry_type) = match element.header().entry_data() {
+            Some((entry_hash, EntryType::App(app_entry_type))) => (entry_hash.clone(), app_entry_type.clone()),
+            _ => return Ok(element),
+        };
+
+        // Guard against update cycles.
+        if !seen.insert(entry_hash.clone()) {
+            return Ok(element);
+        }
+
+        let (zome_name, zome) = match ribosome.dna_def().zomes.get(app_entry_type.zome_id().index()) {
+            Some((zome_name, zome)) => (zome_name.clone(), zome.clone()),
+            None => return Ok(element),
+        };
+
+        // Entry defs are cached by the conductor so this doesn't call into the zome.
+        let crdt_type = call_context
+            .host_context
+            .call_zome_handle()
+            .get_entry_def(&EntryDefBufferKey::new(zome, app_entry_type.id()))
+            .map(|entry_def| entry_def.crdt_type)
+            .unwrap_or_default();
+
+        if crdt_type == CrdtType::Unresolved {
+            return Ok(element);
+        }
+
+        let conflicts = cascade
+            .get_conflicts(entry_hash, get_options.clone())
+            .await
+            .map_err(|cascade_error| WasmError::Host(cascade_error.to_string()))?;
+
+        if conflicts.is_empty() {
+            return Ok(element);
+        }
+
+        let winner = match crdt_type {
+            CrdtType::ResolveCallback => {
+                resolve_with_callback(call_context, zome_name, element.clone(), conflicts).await?
+            }
+            crdt_type => crdt_type
+                .pick_winner(element.header().author(), &conflicts)
+                .map(|winner| winner.as_hash().clone()),
+        };
+
+        let winner = match winner {
+            Some(winner) => winner,
+            None => return Ok(element),
+        };
+
+        match cascade
+            .dht_get(winner.into(), get_options.clone())
+            .await
+            .map_err(|cascade_error| WasmError::Host(cascade_error.to_string()))?
+        {
+            Some(next) => element = next,
+            None => return Ok(element),
+        }
+    }
+}
+
+/// Ask the zome that defines the entry to pick the winning update.
+///
+/// This is a re-entrant zome call on the same cell, made while the calling
+/// zome function is still running, and it shares the caller's workspace.
+/// To bound the nesting, conflicts found by a `get` inside `resolve_conflict`
+/// are left unresolved rather than calling back into the zome again.
+async fn resolve_with_callback(
+    call_context: &CallContext,
+    zome_name: ZomeName,
+    original: Element,
+    conflicts: Vec<SignedHeaderHashed>,
+) -> Result<Option<HeaderHash>, WasmError> {
+    if call_context.function_name().0 == RESOLVE_CONFLICT_FN {
+        return Ok(None);
+    }
+
+    let candidates: HashSet<HeaderHash> = conflicts.iter().map(|c| c.as_hash().clone()).collect();
+    let provenance = call_context.host_context.workspace().source_chain()
+        .as_ref().expect("Must have source chain to know provenance")
+        .agent_pubkey()
+        .clone();
+    let invocation = ZomeCall {
+        cell_id: call_context.host_context().call_zome_handle().cell_id().clone(),
+        zome_name,
+        fn_name: RESOLVE_CONFLICT_FN.into(),
+        payload: ExternIO::encode(ResolveConflictInput { original, conflicts })?,
+        cap_secret: None,
+        provenance,
+    };
+    let response = call_context
+        .host_context()
+        .call_zome_handle()
+        .call_zome(
+            invocation,
+            call_context
+                .host_context()
+                .workspace_write()
+                .clone()
+                .try_into()
+                .expect("Must have source chain to make zome call"),
+        )
+        .await;
+    let winner: Option<HeaderHash> = match response {
+        Ok(Ok(ZomeCallResponse::Ok(extern_io))) => extern_io.decode()?,
+        Ok(Ok(other)) => return Err(WasmError::Host(format!("resolve_conflict failed: {:?}", other))),
+        Ok(Err(ribosome_error)) => return Err(WasmError::Host(ribosome_error.to_string())),
+        Err(conductor_api_error) => return Err(WasmError::Host(conductor_api_error.to_string())),
+    };
+    match winner {
+        Some(winner) if !candidates.contains(&winner) => Err(WasmError::Host(format!(
+            "resolve_conflict picked {} which is not in the conflict set",
+            winner
+        ))),
+        winner => Ok(winner),
+    }
+}
+
 // we are relying on the create tests to show the commit/get round trip
 // See create.rs
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+mod slow_tests {
+    use crate::sweettest::*;
+    use hdk::prelude::*;
+    use holochain_types::prelude::*;
+
+    fn entry(n: u8) -> Entry {
+        Entry::app(SerializedBytes::from(UnsafeBytes::from(vec![n]))).unwrap()
+    }
+
+    fn zome() -> InlineZome {
+        let entry_def = EntryDef {
+            crdt_type: CrdtType::LastWriterWins,
+            ..EntryDef::default_with_id("entrydef")
+        };
+        let entry_def_id = entry_def.id.clone();
+        InlineZome::new_unique(vec![entry_def.clone()])
+            .callback("create", move |api, n: u8| {
+                Ok(api.create(CreateInput::new(
+                    entry_def.id.clone(),
+                    entry(n),
+                    ChainTopOrdering::default(),
+                ))?)
+            })
+            .callback("update", move |api, (original, n): (HeaderHash, u8)| {
+                Ok(api.update(UpdateInput::new(
+                    original,
+                    CreateInput::new(entry_def_id.clone(), entry(n), ChainTopOrdering::default()),
+                ))?)
+            })
+            .callback("get", |api, (hash, latest): (AnyDhtHash, bool)| {
+                let get_options = if latest {
+                    GetOptions::latest()
+                } else {
+                    GetOptions::content()
+                };
+                Ok(api
+                    .get(vec![GetInput::new(hash, get_options)])?
+                    .into_iter()
+                    .next()
+                    .flatten())
+            })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn get_latest_follows_the_winning_update() {
+        observability::test_run().ok();
+        let (dna_file, _) = SweetDnaFile::unique_from_inline_zome("zome1", zome())
+            .await
+            .unwrap();
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let app = conductor.setup_app("app", &[dna_file]).await.unwrap();
+        let (cell,) = app.into_tuple();
+        let zome = cell.zome("zome1");
+
+        let original: HeaderHash = conductor.call(&zome, "create", 0u8).await;
+        let update_1: HeaderHash = conductor
+            .call(&zome, "update", (original.clone(), 1u8))
+            .await;
+        let update_2: HeaderHash = conductor
+            .call(&zome, "update", (original.clone(), 2u8))
+            .await;
+
+        let mut conflicts = Vec::new();
+        for header_hash in [update_1, update_2] {
+            let element: Option<Element> = conductor
+                .call(&zome, "get", (AnyDhtHash::from(header_hash), false))
+                .await;
+            conflicts.push(element.unwrap().signed_header().clone());
+        }
+        let winner = CrdtType::LastWriterWins
+            .pick_winner(cell.agent_pubkey(), &conflicts)
+            .unwrap()
+            .as_hash()
+            .clone();
+
+        // - Latest follows the winning update from the original entry.
+        let original_entry = EntryHash::with_data_sync(&entry(0));
+        let element: Option<Element> = conductor
+            .call(
+                &zome,
+                "get",
+                (AnyDhtHash::from(original_entry.clone()), true),
+            )
+            .await;
+        assert_eq!(element.unwrap().header_address(), &winner);
+
+        // - Content returns the requested entry.
+        let element: Option<Element> = conductor
+            .call(&zome, "get", (AnyDhtHash::from(original_entry), false))
+            .await;
+        assert_eq!(element.unwrap().header_address(), &original);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use futures::future::join_all;
+use holochain_cascade::Cascade;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::WasmError;
+use std::sync::Arc;
+
+/// Get a range of the chunks of a chunked payload.
+///
+/// The manifest is fetched first and then every chunk in the range is fetched
+/// in parallel. Each chunk is hashed here on the host and checked against the
+/// manifest so the guest never has to hash the payload itself.
+///
+/// Returns `None` if the manifest or any chunk in the range can't be found.
+pub fn get_chunks(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: GetChunksInput,
+) -> Result<Option<Vec<Chunk>>, WasmError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let GetChunksInput {
+                chunk_set_hash,
+                range,
+                get_options,
+            } = input;
+            tokio_helper::block_forever_on(async move {
+                let chunk_set: ChunkSet =
+                    match get_entry(&call_context, chunk_set_hash, get_options.clone()).await? {
+                        Some(entry) => ChunkSet::try_from(&entry)?,
+                        None => return Ok(None),
+                    };
+                let end = (range.end as usize).min(chunk_set.chunks().len());
+                let start = (range.start as usize).min(end);
+                let entries = join_all(chunk_set.chunks()[start..end].iter().map(|chunk_hash| {
+                    get_entry(&call_context, chunk_hash.clone(), get_options.clone())
+                }))
+                .await;
+                let mut chunks = Vec::with_capacity(end - start);
+                for entry in entries {
+                    match entry? {
+                        Some(entry) => chunks.push(Chunk::try_from(&entry)?),
+                        None => return Ok(None),
+                    }
+                }
+                Ok(Some(chunks))
+            })
+        }
+        _ => Err(WasmError::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "get_chunks".into(),
+            )
+            .to_string(),
+        )),
+    }
+}
+
+/// Get an entry from the cascade and check it has the hash it was asked for.
+async fn get_entry(
+    call_context: &CallContext,
+    entry_hash: EntryHash,
+    get_options: GetOptions,
+) -> Result<Option<Entry>, WasmError> {
+    let mut cascade = Cascade::from_workspace_network(
+        &call_context.host_context.workspace(),
+        call_context.host_context.network().clone(),
+    );
+    let entry = match cascade
+        .dht_get(entry_hash.clone().into(), get_options)
+        .await
+        .map_err(|cascade_error| WasmError::Host(cascade_error.to_string()))?
+        .and_then(|element| element.into_inner().1.into_option())
+    {
+        Some(entry) => entry,
+        None => return Ok(None),
+    };
+    let actual = EntryHash::with_data_sync(&entry);
+    if actual == entry_hash {
+        Ok(Some(entry))
+    } else {
+        Err(WasmError::Host(format!(
+            "Chunked data failed its integrity check, expected {} but got {}",
+            entry_hash, actual
+        )))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+mod slow_tests {
+    use crate::sweettest::*;
+    use hdk::prelude::*;
+    use holochain_types::prelude::*;
+
+    fn zome() -> InlineZome {
+        InlineZome::new_unique(vec![
+            EntryDef::default_with_id(CHUNK_ENTRY_DEF_ID),
+            EntryDef::default_with_id(CHUNK_SET_ENTRY_DEF_ID),
+        ])
+        .callback("create_chunked", |api, payload: Vec<u8>| {
+            let mut chunks = Vec::new();
+            for bytes in payload.chunks(4) {
+                let chunk = Chunk::new(bytes.to_vec());
+                chunks.push(api.hash_entry(Entry::try_from(&chunk).unwrap())?);
+                api.create(CreateInput::try_from(chunk).unwrap())?;
+            }
+            let chunk_set = ChunkSet::new(payload.len() as u64, chunks);
+            let chunk_set_hash = api.hash_entry(Entry::try_from(&chunk_set).unwrap())?;
+            api.create(CreateInput::try_from(chunk_set).unwrap())?;
+            Ok(chunk_set_hash)
+        })
+        .callback("get_chunks", |api, input: GetChunksInput| {
+            Ok(api.get_chunks(input)?)
+        })
+    }
+
+    fn bytes(chunks: Option<Vec<Chunk>>) -> Option<Vec<u8>> {
+        chunks.map(|chunks| chunks.into_iter().flat_map(Chunk::into_bytes).collect())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn get_chunks_by_range() {
+        observability::test_run().ok();
+        let (dna_file, _) = SweetDnaFile::unique_from_inline_zome("zome1", zome())
+            .await
+            .unwrap();
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let app = conductor.setup_app("app", &[dna_file]).await.unwrap();
+        let (cell,) = app.into_tuple();
+        let zome = cell.zome("zome1");
+
+        let payload: Vec<u8> = (0..10).collect();
+        let chunk_set_hash: EntryHash = conductor
+            .call(&zome, "create_chunked", payload.clone())
+            .await;
+
+        let get = |range| GetChunksInput::new(chunk_set_hash.clone(), range, GetOptions::content());
+
+        // The whole payload.
+        let chunks: Option<Vec<Chunk>> =
+            conductor.call(&zome, "get_chunks", get(0..u32::MAX)).await;
+        assert_eq!(chunks.as_ref().map(Vec::len), Some(3));
+        assert_eq!(bytes(chunks), Some(payload));
+
+        // Just the middle chunk.
+        let chunks: Option<Vec<Chunk>> = conductor.call(&zome, "get_chunks", get(1..2)).await;
+        assert_eq!(bytes(chunks), Some(vec![4, 5, 6, 7]));
+
+        // A range past the end is empty.
+        let chunks: Option<Vec<Chunk>> = conductor.call(&zome, "get_chunks", get(5..9)).await;
+        assert_eq!(chunks, Some(vec![]));
+
+        // An unknown manifest is missing.
+        let chunks: Option<Vec<Chunk>> = conductor
+            .call(
+                &zome,
+                "get_chunks",
+                GetChunksInput::new(
+                    EntryHash::with_data_sync(&Entry::try_from(Chunk::new(vec![1])).unwrap()),
+                    0..1,
+                    GetOptions::content(),
+                ),
+            )
+            .await;
+        assert_eq!(chunks, None);
+    }
+}
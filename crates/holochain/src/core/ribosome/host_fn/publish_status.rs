@@ -0,0 +1,117 @@
+use crate::conductor::cell::required_receipt_count;
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_state::validation_receipts::get_publish_status;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::WasmError;
+use std::sync::Arc;
+
+pub fn publish_status(
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    header_hash: HeaderHash,
+) -> Result<Option<PublishStatus>, WasmError> {
+    match (HostFnAccess::from(&call_context.host_context()), call_context.host_context()) {
+        // The required receipts come from the conductor's entry def store,
+        // which only zome calls have a handle to.
+        (HostFnAccess{ read_workspace: Permission::Allow, .. }, HostContext::ZomeCall(_)) => {
+            let call_zome_handle = call_context.host_context.call_zome_handle().clone();
+            let stores = call_context.host_context.workspace().stores();
+            tokio_helper::block_forever_on(async move {
+                get_publish_status(&stores.authored, &stores.dht, header_hash, |header| {
+                    required_receipt_count(ribosome.dna_def(), Some(header), |key| {
+                        call_zome_handle.get_entry_def(key)
+                    }) as usize
+                })
+                .await
+                .map_err(|state_query_error| WasmError::Host(state_query_error.to_string()))
+            })
+        },
+        _ => Err(WasmError::Host(RibosomeError::HostFnPermissions(
+            call_context.zome.zome_name().clone(),
+            call_context.function_name().clone(),
+            "publish_status".into()
+        ).to_string()))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+mod slow_tests {
+    use crate::core::workflow::publish_dht_ops_workflow::DEFAULT_RECEIPT_BUNDLE_SIZE;
+    use crate::sweettest::*;
+    use ::fixt::prelude::*;
+    use hdk::prelude::*;
+    use holochain_types::prelude::*;
+
+    fn zome() -> InlineZome {
+        let entry_def = EntryDef {
+            required_validations: 3.into(),
+            ..EntryDef::default_with_id("entrydef")
+        };
+        InlineZome::new_unique(vec![entry_def.clone()])
+            .callback("create", move |api, ()| {
+                Ok(api.create(CreateInput::new(
+                    entry_def.id.clone(),
+                    Entry::app(().try_into().unwrap()).unwrap(),
+                    ChainTopOrdering::default(),
+                ))?)
+            })
+            .callback("create_link", |api, ()| {
+                let base = api.hash_entry(Entry::app(().try_into().unwrap()).unwrap())?;
+                Ok(api.create_link(CreateLinkInput::new(
+                    base.clone(),
+                    base,
+                    LinkTag::new(vec![]),
+                    ChainTopOrdering::default(),
+                ))?)
+            })
+            .callback("publish_status", |api, header_hash: HeaderHash| {
+                Ok(api.publish_status(header_hash)?)
+            })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn publish_status_uses_entry_def_required_validations() {
+        observability::test_run().ok();
+        let (dna_file, _) = SweetDnaFile::unique_from_inline_zome("zome1", zome())
+            .await
+            .unwrap();
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let app = conductor.setup_app("app", &[dna_file]).await.unwrap();
+        let (cell,) = app.into_tuple();
+        let zome = cell.zome("zome1");
+
+        let entry_header: HeaderHash = conductor.call(&zome, "create", ()).await;
+        let link_header: HeaderHash = conductor.call(&zome, "create_link", ()).await;
+
+        // App entries need their entry def's required validations.
+        let status: Option<PublishStatus> = conductor
+            .call(&zome, "publish_status", entry_header.clone())
+            .await;
+        let status = status.unwrap();
+        assert_eq!(status.header_hash, entry_header);
+        assert!(!status.ops.is_empty());
+        assert!(status.ops.iter().all(|op| op.required_receipts == 3));
+        assert!(!status.is_saturated());
+
+        // Everything else needs the default.
+        let status: Option<PublishStatus> = conductor
+            .call(&zome, "publish_status", link_header)
+            .await;
+        assert!(status
+            .unwrap()
+            .ops
+            .iter()
+            .all(|op| op.required_receipts == DEFAULT_RECEIPT_BUNDLE_SIZE as usize));
+
+        // Headers this agent didn't author have no status.
+        let status: Option<PublishStatus> = conductor
+            .call(&zome, "publish_status", fixt!(HeaderHash))
+            .await;
+        assert!(status.is_none());
+    }
+}
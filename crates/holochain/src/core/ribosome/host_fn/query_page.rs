@@ -0,0 +1,82 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::WasmError;
+use std::sync::Arc;
+
+pub fn query_page(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: ChainQueryPageInput,
+) -> Result<ChainQueryPage, WasmError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => tokio_helper::block_forever_on(async move {
+            call_context
+                .host_context
+                .workspace()
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain to query the source chain")
+                .query_page(input)
+                .await
+                .map_err(|source_chain_error| WasmError::Host(source_chain_error.to_string()))
+        }),
+        _ => Err(WasmError::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "query_page".into(),
+            )
+            .to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod slow_tests {
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use hdk::prelude::*;
+
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn query_page_smoke_test() {
+        let host_access = fixt!(ZomeCallHostAccess, Predictable);
+
+        for path in ["a", "b", "c"] {
+            let _: EntryHash = crate::call_test_ribosome!(
+                host_access,
+                TestWasm::Query,
+                "add_path",
+                path.to_string()
+            )
+            .unwrap();
+        }
+
+        let input = ChainQueryPageInput::new(ChainQueryFilter::new(), 4).descending();
+        let page: ChainQueryPage =
+            crate::call_test_ribosome!(host_access, TestWasm::Query, "query_page", input.clone())
+                .unwrap();
+        assert_eq!(page.elements.len(), 4);
+        assert!(page.elements[0].header().header_seq() > page.elements[3].header().header_seq());
+
+        let next: ChainQueryPage = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Query,
+            "query_page",
+            input.cursor(page.next.unwrap())
+        )
+        .unwrap();
+        assert_eq!(
+            next.elements[0].header().header_seq() + 1,
+            page.elements[3].header().header_seq()
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use futures::future::join_all;
+use holochain_p2p::actor::HolochainP2pResult;
+use holochain_p2p::HolochainP2pDnaT;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::WasmError;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+/// The zome fn that handles remote signals.
+pub(crate) const RECV_REMOTE_SIGNAL: &str = "recv_remote_signal";
+
+/// Send the signal to every agent as a remote call to `recv_remote_signal`
+/// and report what happened for each of them.
+/// The calls run concurrently so one slow agent doesn't hold up the others
+/// past the network timeout.
+#[tracing::instrument(skip(ribosome, call_context, input))]
+pub fn remote_signal_acked(
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: RemoteSignal,
+) -> Result<Vec<RemoteSignalAck>, WasmError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            write_network: Permission::Allow,
+            agent_info: Permission::Allow,
+            ..
+        } => {
+            let from_agent = super::agent_info::agent_info(ribosome, call_context.clone(), ())?
+                .agent_latest_pubkey;
+            let network = call_context.host_context().network().clone();
+            let RemoteSignal { agents, signal } = input;
+            let zome_name = call_context.zome().zome_name().clone();
+            let fn_name: FunctionName = RECV_REMOTE_SIGNAL.into();
+            Ok(tokio_helper::block_forever_on(async move {
+                let network = &network;
+                let from_agent = &from_agent;
+                let zome_name = &zome_name;
+                let fn_name = &fn_name;
+                let signal = &signal;
+                join_all(agents.into_iter().map(|agent| async move {
+                    let result = network
+                        .call_remote(
+                            from_agent.clone(),
+                            agent.clone(),
+                            zome_name.clone(),
+                            fn_name.clone(),
+                            None,
+                            signal.clone(),
+                        )
+                        .await;
+                    RemoteSignalAck {
+                        agent,
+                        delivery: delivery(result),
+                    }
+                }))
+                .await
+            }))
+        }
+        _ => Err(WasmError::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "remote_signal_acked".into(),
+            )
+            .to_string(),
+        )),
+    }
+}
+
+/// An agent that answered at all was reached, so any response other than `Ok`
+/// means the signal was rejected.
+/// Errors returned by the handler come back as a `NetworkError` response,
+/// see `Cell::handle_call_remote`.
+fn delivery(result: HolochainP2pResult<SerializedBytes>) -> RemoteSignalDelivery {
+    match result.map(ZomeCallResponse::try_from) {
+        Ok(Ok(ZomeCallResponse::Ok(_))) => RemoteSignalDelivery::Delivered,
+        Ok(Ok(ZomeCallResponse::Unauthorized(..))) => {
+            RemoteSignalDelivery::Rejected("recv_remote_signal is not granted to this agent".into())
+        }
+        Ok(Ok(ZomeCallResponse::NetworkError(reason)))
+        | Ok(Ok(ZomeCallResponse::CountersigningSession(reason))) => {
+            RemoteSignalDelivery::Rejected(reason)
+        }
+        Ok(Err(serialized_bytes_error)) => {
+            RemoteSignalDelivery::Rejected(serialized_bytes_error.to_string())
+        }
+        Err(p2p_error) => RemoteSignalDelivery::Unreachable(p2p_error.to_string()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+mod slow_tests {
+    use crate::sweettest::*;
+    use futures::future;
+    use hdk::prelude::*;
+    use holochain_types::signal::Signal;
+    use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::zome::inline_zome::error::InlineZomeError;
+
+    fn zome() -> InlineZome {
+        InlineZome::new_unique(vec![])
+            .callback(
+                "signal_others",
+                |api, (agents, message): (Vec<AgentPubKey>, String)| {
+                    Ok(api.remote_signal_acked(RemoteSignal {
+                        agents,
+                        signal: ExternIO::encode(message).unwrap(),
+                    })?)
+                },
+            )
+            .callback("recv_remote_signal", |_api, signal: ExternIO| {
+                let message: String = signal.decode()?;
+                if message == "spam" {
+                    Err(InlineZomeError::TestError("No spam please".into()))
+                } else {
+                    Ok(())
+                }
+            })
+            .callback("init", |api, ()| {
+                let mut functions: GrantedFunctions = BTreeSet::new();
+                functions.insert((api.zome_info(()).unwrap().name, "recv_remote_signal".into()));
+                api.create(CreateInput::new(
+                    EntryDefId::CapGrant,
+                    Entry::CapGrant(CapGrantEntry {
+                        tag: "".into(),
+                        // empty access converts to unrestricted
+                        access: ().into(),
+                        functions,
+                    }),
+                    ChainTopOrdering::default(),
+                ))
+                .unwrap();
+                Ok(InitCallbackResult::Pass)
+            })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn remote_signal_acked_reports_each_agent() {
+        observability::test_run().ok();
+        let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+        let agents =
+            future::join_all(conductors.iter().map(|c| SweetAgents::one(c.keystore()))).await;
+        // A key that no conductor is running a cell for.
+        let offline = SweetAgents::one(conductors[0].keystore()).await;
+
+        let (dna_file, _) = SweetDnaFile::unique_from_inline_zome("zome1", zome())
+            .await
+            .unwrap();
+        let apps = conductors
+            .setup_app_for_zipped_agents("app", &agents, &[dna_file])
+            .await
+            .unwrap();
+        conductors.exchange_peer_info().await;
+        let cells = apps.cells_flattened();
+        let zome = cells[0].zome("zome1");
+
+        let acks: Vec<RemoteSignalAck> = conductors[0]
+            .call(
+                &zome,
+                "signal_others",
+                (vec![agents[1].clone(), offline.clone()], "Hey".to_string()),
+            )
+            .await;
+        assert_eq!(acks.len(), 2);
+        assert_eq!(acks[0].agent, agents[1]);
+        assert_eq!(acks[0].delivery, RemoteSignalDelivery::Delivered);
+        assert_eq!(acks[1].agent, offline);
+        assert!(matches!(
+            acks[1].delivery,
+            RemoteSignalDelivery::Unreachable(_)
+        ));
+
+        let acks: Vec<RemoteSignalAck> = conductors[0]
+            .call(
+                &zome,
+                "signal_others",
+                (vec![agents[1].clone()], "spam".to_string()),
+            )
+            .await;
+        assert!(matches!(
+            acks[0].delivery,
+            RemoteSignalDelivery::Rejected(_)
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn typed_remote_signal_handler() {
+        observability::test_run().ok();
+        let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+        let agents =
+            future::join_all(conductors.iter().map(|c| SweetAgents::one(c.keystore()))).await;
+
+        let (dna_file, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::RemoteSignal])
+            .await
+            .unwrap();
+        let apps = conductors
+            .setup_app_for_zipped_agents("app", &agents, &[dna_file])
+            .await
+            .unwrap();
+        conductors.exchange_peer_info().await;
+        let cells = apps.cells_flattened();
+        let zome = cells[0].zome(TestWasm::RemoteSignal);
+        let mut rxs = conductors[1]
+            .signal_broadcaster()
+            .await
+            .subscribe_separately();
+
+        // The handler decodes the message and emits it on the receiving conductor.
+        let acks: Vec<RemoteSignalAck> = conductors[0]
+            .call(
+                &zome,
+                "signal_others",
+                (vec![agents[1].clone()], "Hey".to_string()),
+            )
+            .await;
+        assert_eq!(acks[0].delivery, RemoteSignalDelivery::Delivered);
+        let signal = AppSignal::new(ExternIO::encode("Hey".to_string()).unwrap());
+        assert!(matches!(
+            rxs[0].recv().await,
+            Ok(Signal::App(_, app_signal)) if app_signal == signal
+        ));
+
+        // An error from the handler is the reason the signal was rejected.
+        let acks: Vec<RemoteSignalAck> = conductors[0]
+            .call(
+                &zome,
+                "signal_others",
+                (vec![agents[1].clone()], "spam".to_string()),
+            )
+            .await;
+        assert!(matches!(
+            &acks[0].delivery,
+            RemoteSignalDelivery::Rejected(reason) if reason.contains("No spam please")
+        ));
+    }
+}
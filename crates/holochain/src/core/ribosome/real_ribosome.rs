@@ -35,6 +35,7 @@ use crate::core::ribosome::host_fn::call_info::call_info;
 use crate::core::ribosome::host_fn::capability_claims::capability_claims;
 use crate::core::ribosome::host_fn::capability_grants::capability_grants;
 use crate::core::ribosome::host_fn::capability_info::capability_info;
+use crate::core::ribosome::host_fn::commit_batch::commit_batch;
 use crate::core::ribosome::host_fn::create::create;
 use crate::core::ribosome::host_fn::create_link::create_link;
 use crate::core::ribosome::host_fn::create_x25519_keypair::create_x25519_keypair;
@@ -43,6 +44,7 @@ use crate::core::ribosome::host_fn::delete_link::delete_link;
 use crate::core::ribosome::host_fn::dna_info::dna_info;
 use crate::core::ribosome::host_fn::emit_signal::emit_signal;
 use crate::core::ribosome::host_fn::get::get;
+use crate::core::ribosome::host_fn::get_chunks::get_chunks;
 use crate::core::ribosome::host_fn::get_details::get_details;
 use crate::core::ribosome::host_fn::get_link_details::get_link_details;
 use crate::core::ribosome::host_fn::get_links::get_links;
@@ -50,9 +52,12 @@ use crate::core::ribosome::host_fn::hash_entry::hash_entry;
 use crate::core::ribosome::host_fn::must_get_entry::must_get_entry;
 use crate::core::ribosome::host_fn::must_get_header::must_get_header;
 use crate::core::ribosome::host_fn::must_get_valid_element::must_get_valid_element;
+use crate::core::ribosome::host_fn::publish_status::publish_status;
 use crate::core::ribosome::host_fn::query::query;
+use crate::core::ribosome::host_fn::query_page::query_page;
 use crate::core::ribosome::host_fn::random_bytes::random_bytes;
 use crate::core::ribosome::host_fn::remote_signal::remote_signal;
+use crate::core::ribosome::host_fn::remote_signal_acked::remote_signal_acked;
 use crate::core::ribosome::host_fn::schedule::schedule;
 use crate::core::ribosome::host_fn::sign::sign;
 use crate::core::ribosome::host_fn::sign_ephemeral::sign_ephemeral;
@@ -409,6 +414,7 @@ impl RealRibosome {
             .with_host_function(&mut ns, "__capability_grants", capability_grants)
             .with_host_function(&mut ns, "__capability_info", capability_info)
             .with_host_function(&mut ns, "__get", get)
+            .with_host_function(&mut ns, "__get_chunks", get_chunks)
             .with_host_function(&mut ns, "__get_details", get_details)
             .with_host_function(&mut ns, "__get_links", get_links)
             .with_host_function(&mut ns, "__get_link_details", get_link_details)
@@ -421,8 +427,11 @@ impl RealRibosome {
                 "__accept_countersigning_preflight_request",
                 accept_countersigning_preflight_request,
             )
+            .with_host_function(&mut ns, "__publish_status", publish_status)
             .with_host_function(&mut ns, "__query", query)
+            .with_host_function(&mut ns, "__query_page", query_page)
             .with_host_function(&mut ns, "__remote_signal", remote_signal)
+            .with_host_function(&mut ns, "__remote_signal_acked", remote_signal_acked)
             .with_host_function(&mut ns, "__call", call)
             .with_host_function(&mut ns, "__create", create)
             .with_host_function(&mut ns, "__emit_signal", emit_signal)
@@ -430,6 +439,7 @@ impl RealRibosome {
             .with_host_function(&mut ns, "__delete_link", delete_link)
             .with_host_function(&mut ns, "__update", update)
             .with_host_function(&mut ns, "__delete", delete)
+            .with_host_function(&mut ns, "__commit_batch", commit_batch)
             .with_host_function(&mut ns, "__schedule", schedule);
 
         imports.register("env", ns);
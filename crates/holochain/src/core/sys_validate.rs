@@ -28,12 +28,14 @@ mod tests;
 
 /// 16mb limit on Entries due to websocket limits.
 /// Consider splitting large entries up.
-pub const MAX_ENTRY_SIZE: usize = 16_000_000;
+/// DNAs can lower this with their [`IntegritySettings`].
+pub const MAX_ENTRY_SIZE: usize = ENTRY_SIZE_LIMIT;
 
-/// 1kb limit on LinkTags.
+/// 1kb default limit on LinkTags.
 /// Tags are used as keys to the database to allow
 /// fast lookup so they should be small.
-pub const MAX_TAG_SIZE: usize = 1000;
+/// DNAs can change this within bounds with their [`IntegritySettings`].
+pub const MAX_TAG_SIZE: usize = DEFAULT_TAG_SIZE_LIMIT;
 
 /// Verify the signature for this header
 pub async fn verify_header_signature(sig: &Signature, header: &Header) -> SysValidationResult<()> {
@@ -315,15 +317,28 @@ pub fn check_new_entry_header(header: &Header) -> SysValidationResult<()> {
     }
 }
 
-/// Check the entry size is under the MAX_ENTRY_SIZE
-pub fn check_entry_size(entry: &Entry) -> SysValidationResult<()> {
+/// Get the integrity settings of the DNA being validated
+pub fn get_integrity_settings(
+    dna_hash: &DnaHash,
+    conductor: &dyn ConductorHandleT,
+) -> SysValidationResult<IntegritySettings> {
+    Ok(conductor
+        .get_dna(dna_hash)
+        .ok_or_else(|| SysValidationError::DnaMissing(dna_hash.clone()))?
+        .dna_def()
+        .integrity)
+}
+
+/// Check the entry size is under the DNA's entry size limit
+pub fn check_entry_size(entry: &Entry, integrity: &IntegritySettings) -> SysValidationResult<()> {
     match entry {
         Entry::App(bytes) => {
             let size = std::mem::size_of_val(&bytes.bytes()[..]);
-            if size < MAX_ENTRY_SIZE {
+            let limit = integrity.entry_size_limit();
+            if size < limit {
                 Ok(())
             } else {
-                Err(ValidationOutcome::EntryTooLarge(size, MAX_ENTRY_SIZE).into())
+                Err(ValidationOutcome::EntryTooLarge(size, limit).into())
             }
         }
         // Other entry types are small
@@ -331,13 +346,14 @@ pub fn check_entry_size(entry: &Entry) -> SysValidationResult<()> {
     }
 }
 
-/// Check the link tag size is under the MAX_TAG_SIZE
-pub fn check_tag_size(tag: &LinkTag) -> SysValidationResult<()> {
+/// Check the link tag size is under the DNA's tag size limit
+pub fn check_tag_size(tag: &LinkTag, integrity: &IntegritySettings) -> SysValidationResult<()> {
     let size = std::mem::size_of_val(&tag.0[..]);
-    if size < MAX_TAG_SIZE {
+    let limit = integrity.tag_size_limit();
+    if size < limit {
         Ok(())
     } else {
-        Err(ValidationOutcome::TagTooLarge(size, MAX_TAG_SIZE).into())
+        Err(ValidationOutcome::TagTooLarge(size, limit).into())
     }
 }
 
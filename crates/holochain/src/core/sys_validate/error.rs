@@ -107,13 +107,13 @@ pub enum ValidationOutcome {
     EntryDefId(AppEntryType),
     #[error("The entry has a different hash to the header's entry hash")]
     EntryHash,
-    #[error("The entry size {0} was bigger then the MAX_ENTRY_SIZE {1}")]
+    #[error("The entry size {0} was bigger then the entry size limit {1}")]
     EntryTooLarge(usize, usize),
     #[error("The entry has a different type to the header's entry type")]
     EntryType,
     #[error("The app entry type {0:?} visibility didn't match the zome")]
     EntryVisibility(AppEntryType),
-    #[error("The link tag size {0} was bigger then the MAX_TAG_SIZE {1}")]
+    #[error("The link tag size {0} was bigger then the link tag size limit {1}")]
     TagTooLarge(usize, usize),
     #[error("The header {0:?} was expected to be a link add header")]
     NotCreateLink(HeaderHash),
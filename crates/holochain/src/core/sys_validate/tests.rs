@@ -223,12 +223,35 @@ async fn check_entry_size_test() {
     // let tiny = Entry::App(SerializedBytes::from(UnsafeBytes::from(vec![0; 1])));
     // let bytes = (0..16_000_000).map(|_| 0u8).into_iter().collect::<Vec<_>>();
     // let huge = Entry::App(SerializedBytes::from(UnsafeBytes::from(bytes)));
-    // assert_matches!(check_entry_size(&tiny), Ok(()));
+    // assert_matches!(check_entry_size(&tiny, &IntegritySettings::default()), Ok(()));
 
     // assert_matches!(
-    //     check_entry_size(&huge),
+    //     check_entry_size(&huge, &IntegritySettings::default()),
     //     Err(SysValidationError::ValidationOutcome(ValidationOutcome::EntryTooLarge(_, _)))
     // );
+
+    // A DNA can set a much smaller limit.
+    let integrity = IntegritySettings {
+        max_entry_size: Some(4096),
+        max_tag_size: None,
+    };
+    let entry = |size: usize| {
+        Entry::App(
+            AppEntryBytes::try_from(SerializedBytes::from(UnsafeBytes::from(vec![0; size])))
+                .unwrap(),
+        )
+    };
+    assert_matches!(check_entry_size(&entry(4095), &integrity), Ok(()));
+    assert_matches!(
+        check_entry_size(&entry(4096), &integrity),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::EntryTooLarge(4096, 4096)
+        ))
+    );
+    assert_matches!(
+        check_entry_size(&entry(4096), &IntegritySettings::default()),
+        Ok(())
+    );
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -278,14 +301,24 @@ async fn check_link_tag_size_test() {
         .into_iter()
         .collect::<Vec<_>>();
     let huge = LinkTag(bytes);
-    assert_matches!(check_tag_size(&tiny), Ok(()));
+    let integrity = IntegritySettings::default();
+    assert_matches!(check_tag_size(&tiny, &integrity), Ok(()));
 
     assert_matches!(
-        check_tag_size(&huge),
+        check_tag_size(&huge, &integrity),
         Err(SysValidationError::ValidationOutcome(
             ValidationOutcome::TagTooLarge(_, _)
         ))
     );
+
+    // Raising the limit lets the bigger tag through
+    // but it can't go past the hard bound.
+    let integrity = IntegritySettings {
+        max_entry_size: None,
+        max_tag_size: Some(10_000),
+    };
+    assert_eq!(integrity.tag_size_limit(), MAX_TAG_SIZE_LIMIT);
+    assert_matches!(check_tag_size(&huge, &integrity), Ok(()));
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -298,6 +331,8 @@ async fn check_app_entry_type_test() {
             uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::EntryDefs.into()].into(),
+            integrity: Default::default(),
+            lineage: None,
         },
         vec![TestWasm::EntryDefs.into()],
     )
@@ -84,9 +84,23 @@ where
         if !is_empty {
             match countersigning_op {
                 Some(op) => {
+                    super::countersigning_workflow::countersigning_timeout_watch(
+                        workspace.source_chain().author_db().clone(),
+                        workspace.source_chain().agent_pubkey().clone(),
+                        &op,
+                        conductor_handle.clone(),
+                    )?;
+                    let entry_hash = op.header().entry_data().map(|(hash, _)| hash.clone());
                     if let Err(error_response) =
                         super::countersigning_workflow::countersigning_publish(&network, op).await
                     {
+                        if let Some(entry_hash) = entry_hash {
+                            super::countersigning_workflow::signal_failed_countersigning(
+                                &conductor_handle,
+                                entry_hash,
+                            )
+                            .await;
+                        }
                         return Ok(Ok(error_response));
                     }
                 }
@@ -148,7 +162,7 @@ where
     let validation_result = inline_validation(
         workspace.clone(),
         network,
-        conductor_handle,
+        conductor_handle.clone(),
         Some(zome),
         ribosome,
     )
@@ -174,6 +188,13 @@ where
                 if let Err(error) = workspace.source_chain().unlock_chain().await {
                     tracing::error!(?error);
                 }
+                if let Some(entry_hash) = scratch_elements[0].header().entry_hash() {
+                    super::countersigning_workflow::signal_failed_countersigning(
+                        &conductor_handle,
+                        entry_hash.clone(),
+                    )
+                    .await;
+                }
             }
         }
     }
@@ -219,15 +240,39 @@ pub async fn inline_validation<Ribosome>(
     zome: Option<Zome>,
     ribosome: Ribosome,
 ) -> WorkflowResult<()>
+where
+    Ribosome: RibosomeT + Send + 'static,
+{
+    let scratch_elements = workspace.source_chain().scratch_elements()?;
+    validate_elements(
+        scratch_elements,
+        workspace,
+        network,
+        conductor_handle,
+        zome,
+        ribosome,
+    )
+    .await
+}
+
+/// Run sys and app validation inline on some of the elements in the scratch
+/// and wait for the result.
+pub async fn validate_elements<Ribosome>(
+    elements: Vec<Element>,
+    workspace: SourceChainWorkspace,
+    network: HolochainP2pDna,
+    conductor_handle: ConductorHandle,
+    zome: Option<Zome>,
+    ribosome: Ribosome,
+) -> WorkflowResult<()>
 where
     Ribosome: RibosomeT + Send + 'static,
 {
     let to_app_validate = {
         // collect all the elements we need to validate in wasm
-        let scratch_elements = workspace.source_chain().scratch_elements()?;
-        let mut to_app_validate: Vec<Element> = Vec::with_capacity(scratch_elements.len());
+        let mut to_app_validate: Vec<Element> = Vec::with_capacity(elements.len());
         // Loop forwards through all the new elements
-        for element in scratch_elements {
+        for element in elements {
             sys_validate_element(&element, &workspace, network.clone(), &(*conductor_handle))
                 .await
                 // If the was en error exit
@@ -21,6 +21,8 @@ async fn direct_validation_test() {
             uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Update.into()].into(),
+            integrity: Default::default(),
+            lineage: None,
         },
         vec![TestWasm::Update.into()],
     )
@@ -20,6 +20,7 @@ use rusqlite::{named_params, Transaction};
 
 use crate::conductor::interface::SignalBroadcaster;
 use crate::conductor::space::Space;
+use crate::conductor::ConductorHandle;
 use crate::core::queue_consumer::{QueueTriggers, TriggerSender, WorkComplete};
 
 use super::{error::WorkflowResult, incoming_dht_ops_workflow::incoming_dht_ops_workflow};
@@ -283,6 +284,71 @@ pub(crate) async fn countersigning_success(
     Ok(())
 }
 
+/// Watch a countersigning session that this agent has committed to
+/// and signal the UI if it reaches its end time without completing.
+/// A completed session unlocks the chain, so a lock that is still held
+/// after the session end means the session timed out.
+pub(crate) fn countersigning_timeout_watch(
+    authored_env: DbWrite<DbKindAuthored>,
+    author: AgentPubKey,
+    op: &DhtOp,
+    conductor_handle: ConductorHandle,
+) -> SourceChainResult<()> {
+    let (entry_hash, entry) = match op {
+        DhtOp::StoreEntry(_, header, entry) => (header.entry().clone(), entry.as_ref()),
+        _ => return Ok(()),
+    };
+    let end = match entry {
+        Entry::CounterSign(session_data, _) => {
+            *session_data.preflight_request().session_times().end()
+        }
+        _ => return Ok(()),
+    };
+    let lock = holochain_state::source_chain::lock_for_entry(Some(entry))?;
+    tokio::spawn(async move {
+        let wait = end.as_micros() - Timestamp::now().as_micros();
+        if wait > 0 {
+            tokio::time::sleep(std::time::Duration::from_micros(wait as u64)).await;
+        }
+        let timed_out = authored_env
+            .async_reader(move |txn| {
+                holochain_state::chain_lock::is_lock_held(&txn, &lock, &author)
+            })
+            .await;
+        match timed_out {
+            Ok(true) => {
+                // There may be no interfaces to listen for this.
+                conductor_handle
+                    .signal_broadcaster()
+                    .await
+                    .send(Signal::System(SystemSignal::CountersigningTimedOut(
+                        entry_hash,
+                    )))
+                    .ok();
+            }
+            Ok(false) => (),
+            Err(error) => tracing::error!(?error),
+        }
+    });
+    Ok(())
+}
+
+/// Let clients know that a countersigning session this agent
+/// committed to has failed.
+pub(crate) async fn signal_failed_countersigning(
+    conductor_handle: &ConductorHandle,
+    entry_hash: EntryHash,
+) {
+    // There may be no interfaces to listen for this.
+    conductor_handle
+        .signal_broadcaster()
+        .await
+        .send(Signal::System(SystemSignal::FailedCountersigning(
+            entry_hash,
+        )))
+        .ok();
+}
+
 /// Publish to entry authorities so they can gather all the signed
 /// headers for this session and respond with a session complete.
 pub async fn countersigning_publish(
@@ -3,9 +3,11 @@
 use std::collections::HashMap;
 
 use super::*;
+use crate::conductor::interface::SignalBroadcaster;
 use crate::core::queue_consumer::TriggerSender;
 use crate::core::queue_consumer::WorkComplete;
 use error::WorkflowResult;
+use holo_hash::AnyDhtHash;
 use holochain_p2p::HolochainP2pDna;
 use holochain_p2p::HolochainP2pDnaT;
 use holochain_state::prelude::*;
@@ -18,15 +20,17 @@ mod query_tests;
 #[cfg(feature = "test_utils")]
 mod tests;
 
-#[instrument(skip(vault, trigger_receipt, network))]
+#[instrument(skip(vault, authored_env, trigger_receipt, network, signal_tx))]
 pub async fn integrate_dht_ops_workflow(
     vault: DbWrite<DbKindDht>,
+    authored_env: DbRead<DbKindAuthored>,
     trigger_receipt: TriggerSender,
     network: HolochainP2pDna,
+    mut signal_tx: SignalBroadcaster,
 ) -> WorkflowResult<WorkComplete> {
     let start = std::time::Instant::now();
     let time = holochain_zome_types::Timestamp::now();
-    let changed = vault
+    let (changed, bases) = vault
         .async_commit(move |txn| {
             let span = tracing::debug_span!("integrate_dht_ops_workflow");
             let _g = span.enter();
@@ -125,12 +129,33 @@ pub async fn integrate_dht_ops_workflow(
 
                 })?;
             total += changed;
-            WorkflowResult::Ok(total)
+            // Every op integrated by this run has this run's integration time.
+            let bases: Vec<AnyDhtHash> = if total > 0 {
+                txn.prepare_cached(holochain_sqlite::sql::sql_cell::INTEGRATED_BASES)?
+                    .query_map(named_params! { ":when_integrated": time }, |row| {
+                        row.get("basis_hash")
+                    })?
+                    .collect::<rusqlite::Result<_>>()?
+            } else {
+                Vec::new()
+            };
+            WorkflowResult::Ok((total, bases))
         })
         .await?;
     let ops_ps = changed as f64 / start.elapsed().as_micros() as f64 * 1_000_000.0;
     tracing::debug!(?changed, %ops_ps);
     if changed > 0 {
+        let bases = authored_bases(authored_env, bases).await?;
+        if !bases.is_empty() {
+            let dna_hash = network.dna_hash();
+            // There may be no interfaces to listen for this.
+            signal_tx
+                .send(Signal::System(SystemSignal::DataIntegrated {
+                    dna_hash,
+                    bases,
+                }))
+                .ok();
+        }
         trigger_receipt.trigger();
         network.new_integrated_data().await?;
         Ok(WorkComplete::Incomplete)
@@ -138,3 +163,27 @@ pub async fn integrate_dht_ops_workflow(
         Ok(WorkComplete::Complete)
     }
 }
+
+/// Most integrated data is held on behalf of other peers, so only the bases
+/// that data authored on this conductor lives at are of interest to clients.
+async fn authored_bases(
+    authored_env: DbRead<DbKindAuthored>,
+    bases: Vec<AnyDhtHash>,
+) -> WorkflowResult<Vec<AnyDhtHash>> {
+    if bases.is_empty() {
+        return Ok(bases);
+    }
+    authored_env
+        .async_reader(move |txn| {
+            let mut stmt =
+                txn.prepare_cached("SELECT 1 FROM DhtOp WHERE basis_hash = :basis_hash LIMIT 1")?;
+            let mut authored = Vec::new();
+            for basis in bases {
+                if stmt.exists(named_params! { ":basis_hash": basis })? {
+                    authored.push(basis);
+                }
+            }
+            WorkflowResult::Ok(authored)
+        })
+        .await
+}
@@ -110,9 +110,16 @@ async fn integrate_query() {
     // dump_tmp(&env.env());
     let test_network = test_network(None, None).await;
     let holochain_p2p_cell = test_network.dna_network();
-    integrate_dht_ops_workflow(env.env().into(), qt, holochain_p2p_cell)
-        .await
-        .unwrap();
+    let authored_env = test_authored_env();
+    integrate_dht_ops_workflow(
+        env.env().into(),
+        authored_env.env().into(),
+        qt,
+        holochain_p2p_cell,
+        SignalBroadcaster::noop(),
+    )
+    .await
+    .unwrap();
     let hashes = env
         .conn()
         .unwrap()
@@ -379,9 +379,16 @@ async fn call_workflow<'env>(env: DbWrite<DbKindDht>) {
     let (qt, _rx) = TriggerSender::new();
     let test_network = test_network(None, None).await;
     let holochain_p2p_cell = test_network.dna_network();
-    integrate_dht_ops_workflow(env.clone(), qt, holochain_p2p_cell)
-        .await
-        .unwrap();
+    let authored_env = test_authored_env();
+    integrate_dht_ops_workflow(
+        env.clone(),
+        authored_env.env().into(),
+        qt,
+        holochain_p2p_cell,
+        SignalBroadcaster::noop(),
+    )
+    .await
+    .unwrap();
 }
 
 // Need to clear the data from the previous test
@@ -1049,6 +1056,8 @@ mod slow_tests {
                 uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
                 properties: SerializedBytes::try_from(()).unwrap(),
                 zomes: vec![TestWasm::Create.into()].into(),
+                integrity: Default::default(),
+                lineage: None,
             },
             vec![TestWasm::Create.into()],
         )
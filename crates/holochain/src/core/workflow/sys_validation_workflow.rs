@@ -438,7 +438,14 @@ async fn validate_op_inner(
             Ok(())
         }
         DhtOp::RegisterAddLink(_, header) => {
-            register_add_link(header, workspace, network, incoming_dht_ops_sender).await?;
+            register_add_link(
+                header,
+                conductor_handle,
+                workspace,
+                network,
+                incoming_dht_ops_sender,
+            )
+            .await?;
             Ok(())
         }
         DhtOp::RegisterRemoveLink(_, header) => {
@@ -523,7 +530,14 @@ async fn sys_validate_element_inner(
                     .await?;
             }
             Header::CreateLink(header) => {
-                register_add_link(header, workspace, network, incoming_dht_ops_sender).await?;
+                register_add_link(
+                    header,
+                    conductor_handle,
+                    workspace,
+                    network,
+                    incoming_dht_ops_sender,
+                )
+                .await?;
             }
             Header::DeleteLink(header) => {
                 register_delete_link(header, workspace, network, incoming_dht_ops_sender).await?;
@@ -628,7 +642,8 @@ async fn store_entry(
     }
 
     check_entry_hash(entry_hash, entry).await?;
-    check_entry_size(entry)?;
+    let integrity = get_integrity_settings(workspace.dna_hash(), conductor_handle)?;
+    check_entry_size(entry, &integrity)?;
 
     // Additional checks if this is an Update
     if let NewEntryHeaderRef::Update(entry_update) = header {
@@ -746,6 +761,7 @@ async fn register_deleted_entry_header(
 
 async fn register_add_link(
     link_add: &CreateLink,
+    conductor_handle: &dyn ConductorHandleT,
     workspace: &SysValidationWorkspace,
     network: HolochainP2pDna,
     incoming_dht_ops_sender: Option<IncomingDhtOpSender>,
@@ -770,7 +786,8 @@ async fn register_add_link(
         .await?
         .ok_or_else(|| ValidationOutcome::DepMissingFromDht(target_entry_address.clone().into()))?;
 
-    check_tag_size(&link_add.tag)?;
+    let integrity = get_integrity_settings(workspace.dna_hash(), conductor_handle)?;
+    check_tag_size(&link_add.tag, &integrity)?;
     Ok(())
 }
 
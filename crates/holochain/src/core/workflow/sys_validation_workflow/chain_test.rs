@@ -27,6 +27,8 @@ async fn sys_validation_agent_activity_test() {
             uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            integrity: Default::default(),
+            lineage: None,
         },
         vec![TestWasm::Create.into()],
     )
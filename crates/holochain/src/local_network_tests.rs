@@ -524,6 +524,8 @@ async fn setup(
             uid,
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: zomes.clone().into_iter().map(Into::into).collect(),
+            integrity: Default::default(),
+            lineage: None,
         },
         zomes.into_iter().map(Into::into),
     )
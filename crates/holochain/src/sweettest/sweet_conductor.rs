@@ -44,6 +44,9 @@ pub fn standard_config() -> ConductorConfig {
     }];
     let admin_interface = AdminInterfaceConfig {
         driver: InterfaceDriver::Websocket { port: 0 },
+        require_auth: false,
+        session_duration_seconds: None,
+        tls: None,
     };
     ConductorConfig {
         network: Some(network),
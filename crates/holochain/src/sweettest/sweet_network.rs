@@ -1,4 +1,5 @@
 use kitsune_p2p::KitsuneP2pConfig;
+use kitsune_p2p_types::tx2::{tx2_mem_adapter, MemConfig, MemNetSim};
 
 /// Helper for constructing common kitsune networks
 pub struct SweetNetwork;
@@ -35,4 +36,19 @@ impl SweetNetwork {
         }];
         network
     }
+
+    /// In-memory network with fault injection.
+    /// All conductors configured with the same `sim` share one simulated
+    /// network, and `group` names the node group this conductor belongs to
+    /// when setting up links and partitions.
+    pub async fn mem_sim(sim: &MemNetSim, group: &str) -> KitsuneP2pConfig {
+        let mut conf = MemConfig::default();
+        conf.net_sim = Some(sim.node(group));
+        let mock_network = tx2_mem_adapter(conf).await.unwrap();
+        let mut network = KitsuneP2pConfig::default();
+        network.transport_pool = vec![kitsune_p2p::TransportConfig::Mock {
+            mock_network: mock_network.into(),
+        }];
+        network
+    }
 }
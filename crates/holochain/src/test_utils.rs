@@ -308,6 +308,9 @@ pub async fn setup_app_inner(
         .config(ConductorConfig {
             admin_interfaces: Some(vec![AdminInterfaceConfig {
                 driver: InterfaceDriver::Websocket { port: 0 },
+                require_auth: false,
+                session_duration_seconds: None,
+                tls: None,
             }]),
             network,
             ..Default::default()
@@ -171,6 +171,8 @@ async fn get_from_another_agent() {
             uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            integrity: Default::default(),
+            lineage: None,
         },
         vec![TestWasm::Create.into()],
     )
@@ -315,6 +317,8 @@ async fn get_links_from_another_agent() {
             uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            integrity: Default::default(),
+            lineage: None,
         },
         vec![TestWasm::Create.into()],
     )
@@ -42,6 +42,9 @@ async fn test_new_lair_conductor_integration() {
     let mut conductor_config = ConductorConfig::default();
     conductor_config.admin_interfaces = Some(vec![AdminInterfaceConfig {
         driver: InterfaceDriver::Websocket { port: ADMIN_PORT },
+        require_auth: false,
+        session_duration_seconds: None,
+        tls: None,
     }]);
     conductor_config.environment_path = tmp.path().to_owned().into();
     conductor_config.keystore = KeystoreConfig::LairServer {
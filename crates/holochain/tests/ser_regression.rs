@@ -53,6 +53,8 @@ async fn ser_regression_test() {
             uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::SerRegression.into()].into(),
+            integrity: Default::default(),
+            lineage: None,
         },
         vec![TestWasm::SerRegression.into()],
     )
@@ -131,6 +131,8 @@ async fn speed_test(n: Option<usize>) -> TestEnvs {
             uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Anchor.into()].into(),
+            integrity: Default::default(),
+            lineage: None,
         },
         vec![TestWasm::Anchor.into()],
     )
@@ -190,11 +192,16 @@ async fn speed_test(n: Option<usize>) -> TestEnvs {
 
     // Setup websocket handle and app interface
     let (mut client, _) = websocket_client(&handle).await.unwrap();
-    let request = AdminRequest::AttachAppInterface { port: None };
+    let request = AdminRequest::AttachAppInterface {
+        port: None,
+        require_auth: false,
+        path: None,
+        tls: None,
+    };
     let response = client.request(request);
     let response = response.await.unwrap();
     let app_port = match response {
-        AdminResponse::AppInterfaceAttached { port } => port,
+        AdminResponse::AppInterfaceAttached { port, .. } => port,
         _ => panic!("Attach app interface failed: {:?}", response),
     };
     let (mut app_interface, _) = websocket_client_by_port(app_port).await.unwrap();
@@ -314,6 +321,9 @@ pub async fn setup_app(
         .config(ConductorConfig {
             admin_interfaces: Some(vec![AdminInterfaceConfig {
                 driver: InterfaceDriver::Websocket { port: 0 },
+                require_auth: false,
+                session_duration_seconds: None,
+                tls: None,
             }]),
             ..Default::default()
         })
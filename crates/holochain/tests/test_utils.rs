@@ -114,11 +114,16 @@ pub async fn call_zome_fn<S>(
 }
 
 pub async fn attach_app_interface(client: &mut WebsocketSender, port: Option<u16>) -> u16 {
-    let request = AdminRequest::AttachAppInterface { port };
+    let request = AdminRequest::AttachAppInterface {
+        port,
+        require_auth: false,
+        path: None,
+        tls: None,
+    };
     let response = client.request(request);
     let response = check_timeout(response, 3000).await;
     match response {
-        AdminResponse::AppInterfaceAttached { port } => port,
+        AdminResponse::AppInterfaceAttached { port, .. } => port,
         _ => panic!("Attach app interface failed: {:?}", response),
     }
 }
@@ -250,12 +255,16 @@ pub fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
     ConductorConfig {
         admin_interfaces: Some(vec![AdminInterfaceConfig {
             driver: InterfaceDriver::Websocket { port },
+            require_auth: false,
+            session_duration_seconds: None,
+            tls: None,
         }]),
         environment_path: environment_path.into(),
         network: None,
         dpki: None,
         keystore: KeystoreConfig::DangerTestKeystoreLegacyDeprecated,
         db_sync_strategy: DbSyncStrategy::default(),
+        trusted_bundle_publishers: None,
     }
 }
 
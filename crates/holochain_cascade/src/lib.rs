@@ -530,6 +530,21 @@ where
         Ok(results)
     }
 
+    #[instrument(skip(self, options))]
+    /// The conflict set of an entry: every update whose
+    /// `original_entry_address` is this entry.
+    pub async fn get_conflicts(
+        &mut self,
+        entry_hash: EntryHash,
+        options: GetOptions,
+    ) -> CascadeResult<Vec<SignedHeaderHashed>> {
+        Ok(self
+            .get_entry_details(entry_hash, options)
+            .await?
+            .map(|details| details.updates)
+            .unwrap_or_default())
+    }
+
     #[instrument(skip(self, options))]
     pub async fn get_header_details(
         &mut self,
@@ -3,7 +3,10 @@ use holochain_types::prelude::*;
 use holochain_zome_types::cell::CellId;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 
-use crate::{FullStateDump, InstalledAppInfo};
+use crate::{
+    FullStateDump, InstalledAppInfo, InterfaceDriver, InterfaceTlsConfig, SessionScope,
+    SessionToken,
+};
 
 /// Represents the available conductor functions to call over an Admin interface
 /// and will result in a corresponding [`AdminResponse`] message being sent back over the
@@ -219,6 +222,8 @@ pub enum AdminRequest {
     /// Any active `App` will be callable via this interface.
     /// The successful [`AdminResponse::AppInterfaceAttached`] message will contain
     /// the port chosen by the conductor if `None` was passed.
+    /// If a `path` is given the interface is served over a unix domain socket
+    /// at that path instead and the port is ignored.
     ///
     /// Will be responded to with an [`AdminResponse::AppInterfaceAttached`]
     /// or an [`AdminResponse::Error`]
@@ -229,6 +234,16 @@ pub enum AdminRequest {
         /// Optional port, use None to let the
         /// OS choose a free port
         port: Option<u16>,
+        /// Require connections to authenticate with a session token
+        /// scoped to the apps they use
+        #[serde(default)]
+        require_auth: bool,
+        /// Optional path of a unix domain socket to serve the interface on
+        #[serde(default)]
+        path: Option<std::path::PathBuf>,
+        /// Optionally serve the interface over TLS (`wss`)
+        #[serde(default)]
+        tls: Option<InterfaceTlsConfig>,
     },
 
     /// List all the app interfaces currently attached with [`AttachAppInterface`].
@@ -346,6 +361,55 @@ pub enum AdminRequest {
         /// The elements to inserted into the source chain.
         elements: Vec<Element>,
     },
+
+    /// Authenticate this connection with a [`SessionToken`].
+    ///
+    /// Interfaces that require authentication answer every other request
+    /// with an [`ExternalApiWireError::Unauthenticated`] error until this has
+    /// succeeded. Sending it again replaces the connection's token.
+    ///
+    /// Will be responded to with an [`AdminResponse::Authenticated`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::Authenticated`]: enum.AdminResponse.html#variant.Authenticated
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    Authenticate {
+        /// The token to authenticate with
+        token: SessionToken,
+    },
+
+    /// Issue a new [`SessionToken`] with the given [`SessionScope`].
+    ///
+    /// The token can be used on any admin or app interface of this conductor
+    /// until it expires or is revoked. Only connections with the
+    /// [`SessionScope::Admin`] scope can issue tokens.
+    ///
+    /// Will be responded to with an [`AdminResponse::SessionTokenIssued`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::SessionTokenIssued`]: enum.AdminResponse.html#variant.SessionTokenIssued
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    IssueSessionToken {
+        /// What the token allows
+        scope: SessionScope,
+        /// How long the token is valid for. Defaults to the interface's
+        /// `session_duration_seconds`.
+        duration_seconds: Option<u32>,
+    },
+
+    /// Revoke a [`SessionToken`] so it can no longer be used.
+    ///
+    /// Connections that authenticated with the token lose access straight away.
+    ///
+    /// Will be responded to with an [`AdminResponse::SessionTokenRevoked`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::SessionTokenRevoked`]: enum.AdminResponse.html#variant.SessionTokenRevoked
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    RevokeSessionToken {
+        /// The token to revoke
+        token: SessionToken,
+    },
 }
 
 /// Represents the possible responses to an [`AdminRequest`]
@@ -463,16 +527,26 @@ pub enum AdminResponse {
     ///
     /// `AppInterfaceApi` successfully attached.
     /// Contains the port number that was selected (if not specified) by Holochain
-    /// for running this App interface
+    /// for running this App interface, or the path of its unix domain socket
     ///
     /// [`AdminRequest::AttachAppInterface`]: enum.AdminRequest.html#variant.AttachAppInterface
     AppInterfaceAttached {
-        /// Networking port of the new `AppInterfaceApi`
+        /// Networking port of the new `AppInterfaceApi`,
+        /// or 0 if it is served over a unix domain socket
         port: u16,
+        /// The path of the unix domain socket the new `AppInterfaceApi`
+        /// is served over, if it is
+        #[serde(default)]
+        path: Option<std::path::PathBuf>,
+        /// The digest of the interface's TLS certificate that clients pin,
+        /// if it is served over TLS
+        #[serde(default)]
+        tls_cert_digest: Option<String>,
     },
 
-    /// The list of attached app interfaces.
-    AppInterfacesListed(Vec<u16>),
+    /// The drivers of the attached app interfaces,
+    /// which have either a port or a unix domain socket path.
+    AppInterfacesListed(Vec<InterfaceDriver>),
 
     /// The succesful response to an [`AdminRequest::EnableApp`].
     ///
@@ -549,6 +623,28 @@ pub enum AdminResponse {
     AgentInfoRequested(Vec<AgentInfoSigned>),
 
     ElementsAdded,
+
+    /// The succesful response to an [`AdminRequest::Authenticate`].
+    ///
+    /// [`AdminRequest::Authenticate`]: enum.AdminRequest.html#variant.Authenticate
+    Authenticated,
+
+    /// The succesful response to an [`AdminRequest::IssueSessionToken`].
+    ///
+    /// [`AdminRequest::IssueSessionToken`]: enum.AdminRequest.html#variant.IssueSessionToken
+    SessionTokenIssued {
+        /// The new token
+        token: SessionToken,
+        /// When the token stops being valid
+        expires_at: Timestamp,
+    },
+
+    /// The succesful response to an [`AdminRequest::RevokeSessionToken`].
+    ///
+    /// Contains `false` if the token was unknown or had already expired.
+    ///
+    /// [`AdminRequest::RevokeSessionToken`]: enum.AdminRequest.html#variant.RevokeSessionToken
+    SessionTokenRevoked(bool),
 }
 
 /// Error type that goes over the websocket wire.
@@ -574,6 +670,10 @@ pub enum ExternalApiWireError {
     ZomeCallUnauthorized(String),
     /// A countersigning session has failed.
     CountersigningSessionError(String),
+    /// The connection has not authenticated with a valid session token.
+    Unauthenticated(String),
+    /// The scope of the connection's session token doesn't allow the request.
+    OutOfScope(String),
 }
 
 impl ExternalApiWireError {
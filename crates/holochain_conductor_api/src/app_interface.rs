@@ -1,4 +1,4 @@
-use crate::{signal_subscription::SignalSubscription, ExternalApiWireError};
+use crate::{signal_subscription::SignalSubscription, ExternalApiWireError, SessionToken};
 use holo_hash::AgentPubKey;
 use holochain_types::prelude::*;
 
@@ -39,11 +39,49 @@ pub enum AppRequest {
     /// DEPRECATED. Use `ZomeCall`.
     ZomeCallInvocation(Box<ZomeCall>),
 
-    /// Update signal subscriptions.
+    /// Update this connection's signal subscription for an app.
     ///
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`](enum.AppResponse.html#variant.Unimplemented)
+    /// The filters decide which of the app's Cells this connection receives
+    /// app signals from, and replace any earlier subscription for the app.
+    /// System signals are always sent.
+    ///
+    /// Will be responded to with an [`AppResponse::SignalSubscriptionUpdated`]
+    /// or an [`AppResponse::Error`]
+    ///
+    /// [`AppResponse::SignalSubscriptionUpdated`]: enum.AppResponse.html#variant.SignalSubscriptionUpdated
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
     SignalSubscription(SignalSubscription),
+
+    /// Get the publish and validation receipt status of a header
+    /// authored by one of this conductor's Cells.
+    ///
+    /// Will be responded to with an [`AppResponse::PublishStatus`]
+    /// or an [`AppResponse::Error`]
+    ///
+    /// [`AppResponse::PublishStatus`]: enum.AppResponse.html#variant.PublishStatus
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
+    PublishStatus {
+        /// The Cell that authored the header
+        cell_id: CellId,
+        /// The header to get the status of
+        header_hash: HeaderHash,
+    },
+
+    /// Authenticate this connection with a [`SessionToken`].
+    ///
+    /// Interfaces that require authentication answer every other request
+    /// with an [`ExternalApiWireError::Unauthenticated`] error until this has
+    /// succeeded. Sending it again replaces the connection's token.
+    ///
+    /// Will be responded to with an [`AppResponse::Authenticated`]
+    /// or an [`AppResponse::Error`]
+    ///
+    /// [`AppResponse::Authenticated`]: enum.AppResponse.html#variant.Authenticated
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
+    Authenticate {
+        /// The token to authenticate with
+        token: SessionToken,
+    },
 }
 
 /// Responses to requests received on an App interface
@@ -83,6 +121,23 @@ pub enum AppResponse {
 
     /// DEPRECATED. See `ZomeCall`.
     ZomeCallInvocation(Box<ExternIO>),
+
+    /// The successful response to an [`AppRequest::PublishStatus`].
+    ///
+    /// Option will be `None` if the Cell did not author the header.
+    ///
+    /// [`AppRequest::PublishStatus`]: enum.AppRequest.html#variant.PublishStatus
+    PublishStatus(Option<PublishStatus>),
+
+    /// The successful response to an [`AppRequest::Authenticate`].
+    ///
+    /// [`AppRequest::Authenticate`]: enum.AppRequest.html#variant.Authenticate
+    Authenticated,
+
+    /// The successful response to an [`AppRequest::SignalSubscription`].
+    ///
+    /// [`AppRequest::SignalSubscription`]: enum.AppRequest.html#variant.SignalSubscription
+    SignalSubscriptionUpdated,
 }
 
 /// The data provided across an App interface in order to make a zome call
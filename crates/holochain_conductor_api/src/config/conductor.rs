@@ -54,6 +54,13 @@ pub struct ConductorConfig {
     /// This is best left at it's default value unless you know what you
     /// are doing.
     pub db_sync_strategy: DbSyncStrategy,
+
+    /// The publishers whose hApp bundles `InstallAppBundle` trusts.
+    /// If set, only bundles signed by one of these publishers, with all of
+    /// their DNAs bundled or pinned to a hash, can be installed.
+    /// Keys are ed25519 public keys in url safe base64, as printed by
+    /// `hc app pack --signing-key`.
+    pub trusted_bundle_publishers: Option<Vec<mr_bundle::PublisherKey>>,
     //
     //
     // /// Which signals to emit
@@ -125,10 +132,39 @@ pub mod tests {
                 keystore: KeystoreConfig::DangerTestKeystoreLegacyDeprecated,
                 admin_interfaces: None,
                 db_sync_strategy: DbSyncStrategy::default(),
+                trusted_bundle_publishers: None,
             }
         );
     }
 
+    #[test]
+    fn test_config_unix_socket_admin_interface() {
+        let yaml = r#"---
+    environment_path: /path/to/env
+
+    keystore:
+      type: danger_test_keystore_legacy_deprecated
+
+    admin_interfaces:
+      - driver:
+          type: unix_socket
+          path: /run/holochain/admin.sock
+        require_auth: true
+    "#;
+        let result: ConductorConfig = config_from_yaml(yaml).unwrap();
+        assert_eq!(
+            result.admin_interfaces,
+            Some(vec![AdminInterfaceConfig {
+                driver: InterfaceDriver::UnixSocket {
+                    path: PathBuf::from("/run/holochain/admin.sock"),
+                },
+                require_auth: true,
+                session_duration_seconds: None,
+                tls: None,
+            }])
+        );
+    }
+
     #[test]
     fn test_config_complete_config() {
         observability::test_run().ok();
@@ -174,6 +210,9 @@ pub mod tests {
       network_type: quic_bootstrap
 
     db_sync_strategy: Fast
+
+    trusted_bundle_publishers:
+      - AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA
     "#;
         let result: ConductorConfigResult<ConductorConfig> = config_from_yaml(yaml);
         use holochain_p2p::kitsune_p2p::*;
@@ -187,6 +226,7 @@ pub mod tests {
             }),
             proxy_config: ProxyConfig::LocalProxyServer {
                 proxy_accept_config: Some(ProxyAcceptConfig::RejectAll),
+                proxy_quota_config: None,
             },
         });
         let mut tuning_params =
@@ -213,10 +253,18 @@ pub mod tests {
                     danger_passphrase_insecure_from_config: "test-passphrase".to_string(),
                 },
                 admin_interfaces: Some(vec![AdminInterfaceConfig {
-                    driver: InterfaceDriver::Websocket { port: 1234 }
+                    driver: InterfaceDriver::Websocket { port: 1234 },
+                    require_auth: true,
+                    session_duration_seconds: None,
+                    tls: None,
                 }]),
                 network: Some(network_config),
                 db_sync_strategy: DbSyncStrategy::Fast,
+                trusted_bundle_publishers: Some(vec![
+                    "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"
+                        .parse()
+                        .unwrap()
+                ]),
             }
         );
     }
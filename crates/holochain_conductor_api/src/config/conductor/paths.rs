@@ -12,6 +12,7 @@ const ORGANIZATION: &str = "holochain";
 const APPLICATION: &str = "holochain";
 const KEYS_DIRECTORY: &str = "keys";
 const DATABASES_DIRECTORY: &str = "databases";
+const RESOURCE_CACHE_DIRECTORY: &str = "resource_cache";
 const CONFIG_FILENAME: &str = "conductor-config.yml";
 
 /// Newtype for the database path. Has a Default.
@@ -35,6 +36,14 @@ impl Default for EnvironmentRootPath {
     }
 }
 
+impl EnvironmentRootPath {
+    /// The directory that resources fetched from urls pinned to a hash are
+    /// cached in, so installing them again works offline.
+    pub fn resource_cache(&self) -> PathBuf {
+        self.0.join(RESOURCE_CACHE_DIRECTORY)
+    }
+}
+
 /// Returns the project root builder for holochain directories.
 fn project_root() -> Option<directories::ProjectDirs> {
     directories::ProjectDirs::from(QUALIFIER, ORGANIZATION, APPLICATION)
@@ -1,15 +1,56 @@
 use serde::Deserialize;
 use serde::Serialize;
+use std::path::Path;
+use std::path::PathBuf;
 
 /// Information neeeded to spawn an Admin interface
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
 pub struct AdminInterfaceConfig {
     /// By what means will the interface be exposed?
-    /// Current only option is a local websocket running on a configurable port.
+    /// Either a local websocket running on a configurable port
+    /// or a unix domain socket at a configurable path.
     pub driver: InterfaceDriver,
-    // /// How long will this interface be accessible between authentications?
-    // /// TODO: implement once we have authentication
-    // _session_duration_seconds: Option<u32>,
+    /// Require every connection to authenticate with a session token before
+    /// it can make any other request.
+    /// The conductor writes a token that can do everything to the
+    /// `admin_token` file in its environment directory when it starts.
+    /// Defaults to `true`; set it to `false` to leave the interface open to
+    /// anyone who can reach it.
+    #[serde(default = "default_require_auth")]
+    pub require_auth: bool,
+    /// How long session tokens issued through this interface are valid for
+    /// when the request doesn't say.
+    /// Defaults to [`DEFAULT_SESSION_DURATION_SECONDS`](crate::DEFAULT_SESSION_DURATION_SECONDS).
+    #[serde(default)]
+    pub session_duration_seconds: Option<u32>,
+    /// Serve the interface over TLS (`wss`).
+    /// Only websocket interfaces can use TLS.
+    #[serde(default)]
+    pub tls: Option<InterfaceTlsConfig>,
+}
+
+/// The certificate a websocket interface serves TLS with.
+///
+/// Clients pin the digest of the certificate rather than checking it against
+/// certificate authorities, so a self-signed certificate is fine.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Default)]
+pub struct InterfaceTlsConfig {
+    /// A PEM file with the certificate chain.
+    /// If this and `key_path` aren't set a new self-signed certificate is
+    /// generated every time the interface starts.
+    #[serde(default)]
+    pub cert_path: Option<PathBuf>,
+    /// A PEM file with the PKCS#8 or RSA private key of the certificate.
+    #[serde(default)]
+    pub key_path: Option<PathBuf>,
+    /// Host names to put in a self-signed certificate.
+    /// Defaults to `localhost`.
+    #[serde(default)]
+    pub subject_alt_names: Vec<String>,
+}
+
+fn default_require_auth() -> bool {
+    true
 }
 
 /// Configuration for interfaces, specifying the means by which an interface
@@ -25,13 +66,32 @@ pub enum InterfaceDriver {
         /// The port on which to establish the WebsocketListener
         port: u16,
     },
+    /// The same websocket protocol served over a unix domain socket.
+    ///
+    /// The socket file is only readable and writable by the user running the
+    /// conductor, so nothing is exposed on a network port and file
+    /// permissions decide who can connect.
+    UnixSocket {
+        /// The path of the socket file.
+        /// A stale socket file left at this path is replaced.
+        path: PathBuf,
+    },
 }
 
 impl InterfaceDriver {
-    /// Get the port for this driver.
-    pub fn port(&self) -> u16 {
+    /// Get the port for this driver, if it listens on one.
+    pub fn port(&self) -> Option<u16> {
+        match self {
+            InterfaceDriver::Websocket { port } => Some(*port),
+            InterfaceDriver::UnixSocket { .. } => None,
+        }
+    }
+
+    /// Get the socket path for this driver, if it listens on one.
+    pub fn path(&self) -> Option<&Path> {
         match self {
-            InterfaceDriver::Websocket { port } => *port,
+            InterfaceDriver::Websocket { .. } => None,
+            InterfaceDriver::UnixSocket { path } => Some(path),
         }
     }
 }
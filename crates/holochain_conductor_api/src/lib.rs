@@ -3,10 +3,12 @@
 mod admin_interface;
 mod app_interface;
 pub mod config;
+mod session;
 pub mod signal_subscription;
 pub mod state_dump;
 
 pub use admin_interface::*;
 pub use app_interface::*;
 pub use config::*;
+pub use session::*;
 pub use state_dump::*;
@@ -0,0 +1,189 @@
+//! Session tokens for authenticating connections to admin and app interfaces.
+//!
+//! An interface with authentication turned on only answers a connection
+//! after it has sent an `Authenticate` request with a valid [`SessionToken`].
+//! Tokens are issued with [`AdminRequest::IssueSessionToken`] and carry a
+//! [`SessionScope`] that limits what the connection may do with them.
+//!
+//! [`AdminRequest::IssueSessionToken`]: crate::AdminRequest::IssueSessionToken
+
+use crate::AdminRequest;
+use holochain_types::prelude::*;
+use std::collections::BTreeSet;
+
+/// How long an issued session token is valid for when neither the request
+/// nor the interface config set a duration.
+pub const DEFAULT_SESSION_DURATION_SECONDS: u32 = 60 * 60;
+
+/// The file in the conductor's environment directory that the admin token is
+/// written to when an admin interface requires authentication.
+///
+/// This token has the [`SessionScope::Admin`] scope and is valid until the
+/// conductor shuts down. It is replaced every time the conductor starts
+/// and when it is revoked.
+pub const ADMIN_TOKEN_FILE: &str = "admin_token";
+
+/// A secret that authenticates a connection to an interface.
+#[derive(Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct SessionToken(String);
+
+impl SessionToken {
+    /// Wrap a token string, e.g. one read from the [`ADMIN_TOKEN_FILE`].
+    pub fn new(token: String) -> Self {
+        Self(token.trim().to_string())
+    }
+
+    /// The token as it is sent over the wire.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for SessionToken {
+    // Keep tokens out of the logs.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SessionToken(..)")
+    }
+}
+
+/// What a connection authenticated with a session token is allowed to do.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "data")]
+pub enum SessionScope {
+    /// Any request on any interface.
+    Admin,
+    /// Only the listed admin requests, by the `type` they are sent with,
+    /// e.g. `list_apps`.
+    ///
+    /// Requests that manage interfaces or session tokens always need the
+    /// [`SessionScope::Admin`] scope so they can't be used to widen a scope.
+    AdminRequests(BTreeSet<String>),
+    /// App interface requests for the listed apps.
+    Apps(BTreeSet<InstalledAppId>),
+}
+
+impl SessionScope {
+    /// Can a connection with this scope make this admin request?
+    pub fn allows_admin_request(&self, request: &AdminRequest) -> bool {
+        match self {
+            SessionScope::Admin => true,
+            SessionScope::AdminRequests(request_types) => {
+                !request.manages_access() && request_types.contains(request.request_type())
+            }
+            SessionScope::Apps(_) => false,
+        }
+    }
+
+    /// Can a connection with this scope make app requests for this app?
+    pub fn allows_app(&self, installed_app_id: &InstalledAppId) -> bool {
+        match self {
+            SessionScope::Admin => true,
+            SessionScope::AdminRequests(_) => false,
+            SessionScope::Apps(installed_app_ids) => installed_app_ids.contains(installed_app_id),
+        }
+    }
+}
+
+impl AdminRequest {
+    /// The `type` this request is sent with.
+    pub fn request_type(&self) -> &'static str {
+        use AdminRequest::*;
+        match self {
+            AddAdminInterfaces(_) => "add_admin_interfaces",
+            RegisterDna(_) => "register_dna",
+            CreateCloneCell(_) => "create_clone_cell",
+            InstallApp(_) => "install_app",
+            InstallAppBundle(_) => "install_app_bundle",
+            UninstallApp { .. } => "uninstall_app",
+            ListDnas => "list_dnas",
+            GenerateAgentPubKey => "generate_agent_pub_key",
+            ListCellIds => "list_cell_ids",
+            ListEnabledApps => "list_enabled_apps",
+            ListActiveApps => "list_active_apps",
+            ListApps { .. } => "list_apps",
+            EnableApp { .. } => "enable_app",
+            ActivateApp { .. } => "activate_app",
+            DisableApp { .. } => "disable_app",
+            DeactivateApp { .. } => "deactivate_app",
+            StartApp { .. } => "start_app",
+            AttachAppInterface { .. } => "attach_app_interface",
+            ListAppInterfaces => "list_app_interfaces",
+            DumpState { .. } => "dump_state",
+            DumpFullState { .. } => "dump_full_state",
+            DumpNetworkMetrics { .. } => "dump_network_metrics",
+            AddAgentInfo { .. } => "add_agent_info",
+            RequestAgentInfo { .. } => "request_agent_info",
+            AddElements { .. } => "add_elements",
+            Authenticate { .. } => "authenticate",
+            IssueSessionToken { .. } => "issue_session_token",
+            RevokeSessionToken { .. } => "revoke_session_token",
+        }
+    }
+
+    /// Does this request change who can reach the conductor?
+    fn manages_access(&self) -> bool {
+        matches!(
+            self,
+            AdminRequest::AddAdminInterfaces(_)
+                | AdminRequest::AttachAppInterface { .. }
+                | AdminRequest::IssueSessionToken { .. }
+                | AdminRequest::RevokeSessionToken { .. }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_type_matches_serialized_type() {
+        let requests = vec![
+            AdminRequest::ListApps {
+                status_filter: None,
+            },
+            AdminRequest::GenerateAgentPubKey,
+            AdminRequest::RevokeSessionToken {
+                token: SessionToken::new("abc".into()),
+            },
+        ];
+        #[derive(serde::Deserialize)]
+        struct Tagged {
+            #[serde(rename = "type")]
+            request_type: String,
+        }
+        for request in requests {
+            let request_type = request.request_type();
+            let serialized = SerializedBytes::try_from(request).unwrap();
+            let tagged: Tagged = holochain_serialized_bytes::decode(serialized.bytes()).unwrap();
+            assert_eq!(tagged.request_type, request_type);
+        }
+    }
+
+    #[test]
+    fn scopes_cannot_widen_themselves() {
+        let scope = SessionScope::AdminRequests(
+            vec!["list_apps".to_string(), "issue_session_token".to_string()]
+                .into_iter()
+                .collect(),
+        );
+        assert!(scope.allows_admin_request(&AdminRequest::ListApps {
+            status_filter: None
+        }));
+        assert!(!scope.allows_admin_request(&AdminRequest::ListDnas));
+        assert!(
+            !scope.allows_admin_request(&AdminRequest::IssueSessionToken {
+                scope: SessionScope::Admin,
+                duration_seconds: None,
+            })
+        );
+        assert!(!scope.allows_app(&"app".to_string()));
+
+        let scope = SessionScope::Apps(vec!["app".to_string()].into_iter().collect());
+        assert!(scope.allows_app(&"app".to_string()));
+        assert!(!scope.allows_app(&"other".to_string()));
+        assert!(!scope.allows_admin_request(&AdminRequest::ListDnas));
+        assert!(SessionScope::Admin.allows_admin_request(&AdminRequest::ListDnas));
+    }
+}
@@ -13,6 +13,26 @@ pub struct SignalSubscription {
     filters: SignalFilterSet,
 }
 
+impl SignalSubscription {
+    /// Constructor
+    pub fn new(installed_app_id: InstalledAppId, filters: SignalFilterSet) -> Self {
+        Self {
+            installed_app_id,
+            filters,
+        }
+    }
+
+    /// The app for which to manage subscription
+    pub fn installed_app_id(&self) -> &InstalledAppId {
+        &self.installed_app_id
+    }
+
+    /// Fine-grained per-cell filters
+    pub fn filters(&self) -> &SignalFilterSet {
+        &self.filters
+    }
+}
+
 /// Associate a SignalFilter with each Cell in an App.
 /// The filtering can be interpreted as inclusive or exclusive,
 /// depending on the use case.
@@ -46,6 +66,14 @@ impl SignalFilterSet {
     pub fn block_all() -> Self {
         SignalFilterSet::Include(HashMap::new())
     }
+
+    /// Whether signals from this Cell are let through.
+    pub fn allows(&self, cell_id: &CellId) -> bool {
+        match self {
+            SignalFilterSet::Include(filters) => filters.contains_key(cell_id),
+            SignalFilterSet::Exclude(filters) => !filters.contains_key(cell_id),
+        }
+    }
 }
 
 /// Specifies fine-grained filter controls for the signals
@@ -122,6 +122,27 @@ pub(crate) fn new_connection_pool(path: &Path, synchronous_level: DbSyncLevel) -
         .unwrap()
 }
 
+/// A pool of connections that can only read an existing database file.
+pub(crate) fn new_read_only_connection_pool(path: &Path) -> ConnectionPool {
+    use r2d2_sqlite::SqliteConnectionManager;
+    let manager = SqliteConnectionManager::file(path).with_flags(
+        OpenFlags::SQLITE_OPEN_READ_ONLY
+            | OpenFlags::SQLITE_OPEN_NO_MUTEX
+            | OpenFlags::SQLITE_OPEN_URI,
+    );
+    let customizer = Box::new(ConnCustomizer {
+        synchronous_level: DbSyncLevel::default(),
+    });
+    r2d2::Pool::builder()
+        .max_size(num_read_threads() as u32)
+        .min_idle(Some(0))
+        .idle_timeout(Some(Duration::from_secs(30)))
+        .thread_pool(R2D2_THREADPOOL.clone())
+        .connection_customizer(customizer)
+        .build(manager)
+        .unwrap()
+}
+
 #[derive(Debug)]
 struct ConnCustomizer {
     synchronous_level: DbSyncLevel,
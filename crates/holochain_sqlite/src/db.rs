@@ -1,7 +1,10 @@
 //! Functions dealing with obtaining and referencing singleton databases
 
 use crate::{
-    conn::{new_connection_pool, ConnectionPool, DbSyncLevel, PConn, DATABASE_HANDLES},
+    conn::{
+        new_connection_pool, new_read_only_connection_pool, ConnectionPool, DbSyncLevel, PConn,
+        DATABASE_HANDLES,
+    },
     prelude::*,
 };
 use derive_more::Into;
@@ -131,6 +134,33 @@ impl<Kind: DbKindT> PermittedConn for DbWrite<Kind> {
 }
 
 impl<Kind: DbKindT> DbRead<Kind> {
+    /// Open an existing database without creating, migrating or writing to it,
+    /// e.g. to inspect the databases of a conductor that isn't running.
+    ///
+    /// This isn't shared with the handles that [`DbWrite::open`] hands out.
+    pub fn open_read_only(path_prefix: &Path, kind: Kind) -> DatabaseResult<Self> {
+        let path = path_prefix.join(kind.filename());
+        if !path.is_file() {
+            return Err(DatabaseError::DatabaseMissing(path));
+        }
+        let connection_pool = new_read_only_connection_pool(&path);
+        // Fail now if the file isn't a database.
+        connection_pool
+            .get()?
+            .query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+                row.get::<_, i64>(0)
+            })?;
+        Ok(Self {
+            write_semaphore: Arc::new(Semaphore::new(0)),
+            read_semaphore: Arc::new(Semaphore::new(num_read_threads())),
+            max_readers: num_read_threads(),
+            num_readers: Arc::new(AtomicUsize::new(0)),
+            kind,
+            path,
+            connection_pool,
+        })
+    }
+
     pub fn conn(&self) -> DatabaseResult<PConn> {
         self.connection_pooled()
     }
@@ -705,3 +735,41 @@ where
         tokio::time::sleep(RETRY_INTERVAL).await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn read_only_db_reads_but_cant_write() {
+        let tmpdir = tempdir::TempDir::new("read_only_db").unwrap();
+        assert!(DbRead::open_read_only(tmpdir.path(), DbKindWasm).is_err());
+
+        let db = DbWrite::test(&tmpdir, DbKindWasm).unwrap();
+        db.async_commit(|txn| {
+            txn.execute(
+                "INSERT INTO Wasm (hash, blob) VALUES(?, ?)",
+                [vec![0], vec![1]],
+            )?;
+            DatabaseResult::Ok(())
+        })
+        .await
+        .unwrap();
+
+        let read_only = DbRead::open_read_only(tmpdir.path(), DbKindWasm).unwrap();
+        let count: usize = read_only
+            .async_reader(|txn| {
+                DatabaseResult::Ok(
+                    txn.query_row("SELECT COUNT(rowid) FROM Wasm", [], |row| row.get(0))?,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+        let write = read_only.conn().unwrap().execute(
+            "INSERT INTO Wasm (hash, blob) VALUES(?, ?)",
+            [vec![2], vec![3]],
+        );
+        assert!(write.is_err());
+    }
+}
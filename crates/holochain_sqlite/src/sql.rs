@@ -14,6 +14,7 @@ pub mod sql_cell {
         include_str!("sql/cell/update_dep_store_entry_basis.sql");
     pub const UPDATE_INTEGRATE_DEP_CREATE_LINK: &str =
         include_str!("sql/cell/update_dep_create_link.sql");
+    pub const INTEGRATED_BASES: &str = include_str!("sql/cell/integrated_bases.sql");
 
     pub const FETCH_OP_HASHES_P1: &str =
         include_str!("sql/cell/fetch_hashes/fetch_op_hashes_p1.sql");
@@ -0,0 +1,6 @@
+SELECT
+  DISTINCT basis_hash
+FROM
+  DhtOp
+WHERE
+  when_integrated = :when_integrated
\ No newline at end of file
@@ -79,6 +79,9 @@ CREATE TABLE IF NOT EXISTS Header (
 CREATE INDEX IF NOT EXISTS Header_type_idx ON Header ( type );
 CREATE INDEX IF NOT EXISTS Header_author ON Header ( author );
 CREATE INDEX IF NOT EXISTS Header_seq_idx ON Header ( seq );
+-- Paginated source chain queries.
+CREATE INDEX IF NOT EXISTS Header_author_seq_idx ON Header ( author, seq );
+CREATE INDEX IF NOT EXISTS Header_author_entry_type_idx ON Header ( author, entry_type, seq );
 
 
 -- NB: basis_hash, header_hash, and entry_hash, in general, will have
@@ -146,6 +149,7 @@ CREATE INDEX IF NOT EXISTS DhtOp_authored_timestamp_idx ON DhtOp ( authored_time
 CREATE INDEX IF NOT EXISTS DhtOp_storage_center_loc_idx ON DhtOp ( storage_center_loc );
 CREATE INDEX IF NOT EXISTS DhtOp_header_hash_idx ON DhtOp ( header_hash );
 CREATE INDEX IF NOT EXISTS DhtOp_basis_hash_idx ON DhtOp ( basis_hash );
+CREATE INDEX IF NOT EXISTS DhtOp_when_integrated_idx ON DhtOp ( when_integrated );
 
 CREATE TABLE IF NOT EXISTS ValidationReceipt (
     hash            BLOB           PRIMARY KEY ON CONFLICT IGNORE,
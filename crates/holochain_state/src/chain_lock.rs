@@ -38,6 +38,34 @@ pub fn is_chain_locked(
     }
 }
 
+/// True if the given lock is still in the database, whether or not it has expired.
+/// Locks are removed when the chain is unlocked.
+pub fn is_lock_held(
+    txn: &Transaction,
+    lock: &[u8],
+    author: &AgentPubKey,
+) -> StateMutationResult<bool> {
+    let mut lock = lock.to_vec();
+    lock.extend(author.get_raw_39());
+    Ok(txn
+        .query_row(
+            "
+            SELECT 1
+            FROM ChainLock
+            WHERE lock = :lock
+            AND author = :author
+            LIMIT 1
+            ",
+            named_params! {
+                ":lock": lock,
+                ":author": author,
+            },
+            |row| row.get::<_, u32>(0),
+        )
+        .optional()?
+        .is_some())
+}
+
 /// Check if a lock is expired.
 pub fn is_lock_expired(
     txn: &Transaction,
@@ -314,13 +314,7 @@ pub fn set_validation_stage(
     hash: DhtOpHash,
     status: ValidationLimboStatus,
 ) -> StateMutationResult<()> {
-    let stage = match status {
-        ValidationLimboStatus::Pending => None,
-        ValidationLimboStatus::AwaitingSysDeps(_) => Some(0),
-        ValidationLimboStatus::SysValidated => Some(1),
-        ValidationLimboStatus::AwaitingAppDeps(_) => Some(2),
-        ValidationLimboStatus::AwaitingIntegration => Some(3),
-    };
+    let stage = status.validation_stage();
     let now = holochain_zome_types::Timestamp::now();
     txn.execute(
         "
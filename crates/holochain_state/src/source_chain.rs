@@ -12,6 +12,8 @@ use holo_hash::HasHash;
 use holo_hash::HeaderHash;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::HolochainP2pDnaT;
+use holochain_sqlite::rusqlite::OptionalExtension;
+use holochain_sqlite::rusqlite::Row;
 use holochain_sqlite::rusqlite::Transaction;
 use holochain_types::dht_op::produce_op_lights_from_elements;
 use holochain_types::dht_op::produce_op_lights_from_iter;
@@ -24,7 +26,11 @@ use holochain_types::env::DbRead;
 use holochain_types::env::DbWrite;
 use holochain_zome_types::entry::EntryHashed;
 use holochain_zome_types::header;
+use holochain_zome_types::query::ChainQueryCursor;
+use holochain_zome_types::query::ChainQueryDirection;
 use holochain_zome_types::query::ChainQueryFilterRange;
+use holochain_zome_types::query::ChainQueryPage;
+use holochain_zome_types::query::ChainQueryPageInput;
 use holochain_zome_types::CapAccess;
 use holochain_zome_types::CapGrant;
 use holochain_zome_types::CapSecret;
@@ -744,27 +750,7 @@ where
                                     _ => None,
                                 },
                             },
-                            |row| {
-                                let header = from_blob::<SignedHeader>(row.get("header_blob")?)?;
-                                let SignedHeader(header, signature) = header;
-                                let private_entry = header
-                                    .entry_type()
-                                    .map_or(false, |e| *e.visibility() == EntryVisibility::Private);
-                                let hash: HeaderHash = row.get("header_hash")?;
-                                let header = HeaderHashed::with_pre_hashed(header, hash);
-                                let shh = SignedHeaderHashed::with_presigned(header, signature);
-                                let entry =
-                                    if query.include_entries && (!private_entry || !public_only) {
-                                        let entry: Option<Vec<u8>> = row.get("entry_blob")?;
-                                        match entry {
-                                            Some(entry) => Some(from_blob::<Entry>(entry)?),
-                                            None => None,
-                                        }
-                                    } else {
-                                        None
-                                    };
-                                StateQueryResult::Ok(Element::new(shh, entry))
-                            },
+                            |row| element_from_row(row, query.include_entries, public_only),
                         )?
                         .collect::<StateQueryResult<Vec<_>>>();
                     elements
@@ -789,6 +775,225 @@ where
         Ok(query.filter_elements(elements))
     }
 
+    /// Query a single page of Headers in the source chain.
+    /// Every filter is applied in the database so a page only ever holds up
+    /// to `limit` elements no matter how long the chain is.
+    pub async fn query_page(
+        &self,
+        input: ChainQueryPageInput,
+    ) -> SourceChainResult<ChainQueryPage> {
+        let ChainQueryPageInput {
+            filter,
+            cursor,
+            limit,
+            direction,
+        } = input;
+        if limit == 0 {
+            return Ok(ChainQueryPage {
+                elements: Vec::with_capacity(0),
+                next: None,
+            });
+        }
+        // Hash bounded ranges are turned into sequence ranges so they can
+        // be paged through. There are no forks on our own chain so this
+        // selects the same headers.
+        let seq_range = match &filter.sequence_range {
+            ChainQueryFilterRange::Unbounded => None,
+            ChainQueryFilterRange::HeaderSeqRange(start, end) => Some((*start, *end)),
+            ChainQueryFilterRange::HeaderHashRange(start, end) => {
+                match (self.header_seq(start).await?, self.header_seq(end).await?) {
+                    (Some(start), Some(end)) => Some((start, end)),
+                    _ => Some((1, 0)),
+                }
+            }
+            ChainQueryFilterRange::HeaderHashTerminated(end, prior_count) => {
+                match self.header_seq(end).await? {
+                    Some(end) => Some((end.saturating_sub(*prior_count), end)),
+                    None => Some((1, 0)),
+                }
+            }
+        };
+        let filter = match seq_range {
+            Some((start, end)) => {
+                filter.sequence_range(ChainQueryFilterRange::HeaderSeqRange(start, end))
+            }
+            None => filter,
+        };
+        let after_cursor = |seq: u32| match (cursor, direction) {
+            (None, _) => true,
+            (Some(cursor), ChainQueryDirection::Ascending) => seq > cursor.header_seq(),
+            (Some(cursor), ChainQueryDirection::Descending) => seq < cursor.header_seq(),
+        };
+
+        let author = self.author.clone();
+        let public_only = self.public_only;
+        let mut elements = self
+            .vault
+            .async_reader({
+                let filter = filter.clone();
+                move |txn| {
+                    let mut sql = "
+                    SELECT DISTINCT
+                    Header.hash AS header_hash, Header.blob AS header_blob, Header.seq AS seq
+                    "
+                    .to_string();
+                    if filter.include_entries {
+                        sql.push_str(", Entry.blob AS entry_blob");
+                    }
+                    sql.push_str(" FROM Header ");
+                    if filter.include_entries {
+                        sql.push_str("LEFT JOIN Entry On Header.entry_hash = Entry.hash");
+                    }
+                    sql.push_str(
+                        "
+                    JOIN DhtOp On DhtOp.header_hash = Header.hash
+                    WHERE
+                    Header.author = :author
+                    AND
+                    (:range_start IS NULL OR Header.seq BETWEEN :range_start AND :range_end)
+                    AND
+                    (:cursor IS NULL OR (:descending AND Header.seq < :cursor) OR (NOT :descending AND Header.seq > :cursor))
+                    AND
+                    (:entry_type IS NULL OR Header.entry_type = :entry_type)
+                    AND
+                    (:header_type IS NULL OR Header.type = :header_type)
+                    AND
+                    (:timestamp_start IS NULL OR DhtOp.authored_timestamp BETWEEN :timestamp_start AND :timestamp_end)
+                    ",
+                    );
+                    let entry_types = filter.entry_types.clone().unwrap_or_default();
+                    let entry_hashes: Vec<EntryHash> = filter
+                        .entry_hashes
+                        .clone()
+                        .map(|entry_hashes| entry_hashes.into_iter().collect())
+                        .unwrap_or_default();
+                    if filter.entry_types.is_some() {
+                        sql.push_str(&format!(
+                            "AND Header.entry_type IN ({})",
+                            in_list("entry_type", entry_types.len())
+                        ));
+                    }
+                    if filter.entry_hashes.is_some() {
+                        sql.push_str(&format!(
+                            "AND Header.entry_hash IN ({})",
+                            in_list("entry_hash", entry_hashes.len())
+                        ));
+                    }
+                    sql.push_str(match direction {
+                        ChainQueryDirection::Ascending => " ORDER BY Header.seq ASC",
+                        ChainQueryDirection::Descending => " ORDER BY Header.seq DESC",
+                    });
+                    // One extra to know if there is another page.
+                    sql.push_str(" LIMIT :limit");
+
+                    let range_start = seq_range.map(|(start, _)| start);
+                    let range_end = seq_range.map(|(_, end)| end);
+                    let cursor = cursor.map(|cursor| cursor.header_seq());
+                    let descending = direction == ChainQueryDirection::Descending;
+                    let timestamp_start = filter.timestamp_range.map(|(start, _)| start);
+                    let timestamp_end = filter.timestamp_range.map(|(_, end)| end);
+                    let page_limit = limit as i64 + 1;
+                    let entry_type_names: Vec<String> = (0..entry_types.len())
+                        .map(|i| format!(":entry_type{}", i))
+                        .collect();
+                    let entry_hash_names: Vec<String> = (0..entry_hashes.len())
+                        .map(|i| format!(":entry_hash{}", i))
+                        .collect();
+                    let mut params: Vec<(&str, &dyn holochain_sqlite::rusqlite::ToSql)> = vec![
+                        (":author", author.as_ref()),
+                        (":range_start", &range_start),
+                        (":range_end", &range_end),
+                        (":cursor", &cursor),
+                        (":descending", &descending),
+                        (":entry_type", &filter.entry_type),
+                        (":header_type", &filter.header_type),
+                        (":timestamp_start", &timestamp_start),
+                        (":timestamp_end", &timestamp_end),
+                        (":limit", &page_limit),
+                    ];
+                    for (name, entry_type) in entry_type_names.iter().zip(entry_types.iter()) {
+                        params.push((name.as_str(), entry_type));
+                    }
+                    for (name, entry_hash) in entry_hash_names.iter().zip(entry_hashes.iter()) {
+                        params.push((name.as_str(), entry_hash));
+                    }
+
+                    let mut stmt = txn.prepare(&sql)?;
+                    let elements = stmt
+                        .query_and_then(&params[..], |row| {
+                            element_from_row(row, filter.include_entries, public_only)
+                        })?
+                        .collect::<StateQueryResult<Vec<_>>>();
+                    elements
+                }
+            })
+            .await?;
+
+        // Everything in the scratch comes after everything in the database.
+        let scratch_elements = self.scratch.apply(|scratch| {
+            let mut scratch_elements: Vec<_> = scratch
+                .headers()
+                .filter(|shh| after_cursor(shh.header().header_seq()))
+                .filter_map(|shh| {
+                    let entry = match shh.header().entry_hash() {
+                        Some(eh) if filter.include_entries => scratch.get_entry(eh).ok()?,
+                        _ => None,
+                    };
+                    Some(Element::new(shh.clone(), entry))
+                })
+                .collect();
+            scratch_elements.sort_unstable_by_key(|e| e.header().header_seq());
+            filter.filter_elements(scratch_elements)
+        })?;
+        match direction {
+            ChainQueryDirection::Ascending => elements.extend(scratch_elements),
+            ChainQueryDirection::Descending => {
+                elements = scratch_elements.into_iter().rev().chain(elements).collect()
+            }
+        }
+
+        let next = if elements.len() > limit as usize {
+            elements.truncate(limit as usize);
+            elements
+                .last()
+                .map(|element| ChainQueryCursor::new(element.header().header_seq()))
+        } else {
+            None
+        };
+        Ok(ChainQueryPage { elements, next })
+    }
+
+    /// The sequence number of one of our headers, if we have it.
+    async fn header_seq(&self, header_hash: &HeaderHash) -> SourceChainResult<Option<u32>> {
+        let in_scratch = self.scratch.apply(|scratch| {
+            scratch
+                .headers()
+                .find(|shh| shh.header_address() == header_hash)
+                .map(|shh| shh.header().header_seq())
+        })?;
+        if in_scratch.is_some() {
+            return Ok(in_scratch);
+        }
+        let author = self.author.clone();
+        let header_hash = header_hash.clone();
+        Ok(self
+            .vault
+            .async_reader(move |txn| {
+                let seq = txn
+                    .query_row(
+                        "SELECT seq FROM Header WHERE hash = :hash AND author = :author",
+                        named_params! {
+                            ":hash": header_hash,
+                            ":author": author.as_ref(),
+                        },
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                StateQueryResult::Ok(seq)
+            })
+            .await?)
+    }
+
     pub async fn is_chain_locked(&self, lock: Vec<u8>) -> SourceChainResult<bool> {
         let author = self.author.clone();
         Ok(self
@@ -826,6 +1031,41 @@ where
     }
 }
 
+/// Build an element from a row selecting `header_hash`, `header_blob` and,
+/// if entries are included, `entry_blob`.
+fn element_from_row(
+    row: &Row,
+    include_entries: bool,
+    public_only: bool,
+) -> StateQueryResult<Element> {
+    let header = from_blob::<SignedHeader>(row.get("header_blob")?)?;
+    let SignedHeader(header, signature) = header;
+    let private_entry = header
+        .entry_type()
+        .map_or(false, |e| *e.visibility() == EntryVisibility::Private);
+    let hash: HeaderHash = row.get("header_hash")?;
+    let header = HeaderHashed::with_pre_hashed(header, hash);
+    let shh = SignedHeaderHashed::with_presigned(header, signature);
+    let entry = if include_entries && (!private_entry || !public_only) {
+        let entry: Option<Vec<u8>> = row.get("entry_blob")?;
+        match entry {
+            Some(entry) => Some(from_blob::<Entry>(entry)?),
+            None => None,
+        }
+    } else {
+        None
+    };
+    Ok(Element::new(shh, entry))
+}
+
+/// Named parameters for an `IN` list, e.g. `:entry_type0, :entry_type1`.
+fn in_list(name: &str, len: usize) -> String {
+    (0..len)
+        .map(|i| format!(":{}{}", name, i))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 pub fn lock_for_entry(entry: Option<&Entry>) -> SourceChainResult<Vec<u8>> {
     Ok(match entry {
         Some(Entry::CounterSign(session_data, _)) => holo_hash::encode::blake2b_256(
@@ -1442,6 +1682,117 @@ pub mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn query_page_walks_the_chain() -> SourceChainResult<()> {
+        let test_env = test_authored_env();
+        let dht_env = test_dht_env();
+        let keystore = test_keystore();
+        let env = test_env.env();
+        let alice = fixt!(AgentPubKey, Predictable, 0);
+        let zome = fixt!(Zome);
+
+        let mut mock = MockHolochainP2pDnaT::new();
+        mock.expect_authority_for_hash().returning(|_| Ok(false));
+
+        source_chain::genesis(
+            env.clone(),
+            dht_env.env(),
+            keystore.clone(),
+            fake_dna_hash(1),
+            alice.clone(),
+            None,
+        )
+        .await
+        .unwrap();
+        let chain = SourceChain::new(
+            env.clone().into(),
+            dht_env.env(),
+            keystore.clone(),
+            alice.clone(),
+        )
+        .await?;
+        let header_builder = builder::CloseChain {
+            new_dna_hash: fixt!(DnaHash),
+        };
+        for _ in 0..4 {
+            chain
+                .put(
+                    Some(zome.clone()),
+                    header_builder.clone(),
+                    None,
+                    ChainTopOrdering::default(),
+                )
+                .await?;
+        }
+        chain.flush(&mock).await?;
+
+        // Two more that are only in the scratch.
+        let chain = SourceChain::new(env.clone(), dht_env.env(), keystore, alice).await?;
+        for _ in 0..2 {
+            chain
+                .put(
+                    Some(zome.clone()),
+                    header_builder.clone(),
+                    None,
+                    ChainTopOrdering::default(),
+                )
+                .await?;
+        }
+
+        let seqs = |page: &ChainQueryPage| {
+            page.elements
+                .iter()
+                .map(|element| element.header().header_seq())
+                .collect::<Vec<_>>()
+        };
+
+        let mut input = ChainQueryPageInput::new(ChainQueryFilter::new(), 4);
+        let mut pages = Vec::new();
+        loop {
+            let page = chain.query_page(input.clone()).await?;
+            pages.push(seqs(&page));
+            match page.next {
+                Some(cursor) => input = input.cursor(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(pages, vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8]]);
+
+        let mut input = ChainQueryPageInput::new(ChainQueryFilter::new(), 3).descending();
+        let mut pages = Vec::new();
+        loop {
+            let page = chain.query_page(input.clone()).await?;
+            pages.push(seqs(&page));
+            match page.next {
+                Some(cursor) => input = input.cursor(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(pages, vec![vec![8, 7, 6], vec![5, 4, 3], vec![2, 1, 0]]);
+
+        let all = chain
+            .query_page(ChainQueryPageInput::new(ChainQueryFilter::new(), 10))
+            .await?;
+        let filter =
+            ChainQueryFilter::new().entry_types(vec![EntryType::AgentPubKey, EntryType::CapGrant]);
+        let page = chain
+            .query_page(ChainQueryPageInput::new(filter, 10))
+            .await?;
+        assert_eq!(seqs(&page), vec![2]);
+
+        let filter = ChainQueryFilter::new().timestamp_range(
+            all.elements[3].header().timestamp(),
+            all.elements[7].header().timestamp(),
+        );
+        let page = chain
+            .query_page(ChainQueryPageInput::new(filter, 2).descending())
+            .await?;
+        assert_eq!(seqs(&page), vec![7, 6]);
+        assert_eq!(page.next, Some(ChainQueryCursor::new(6)));
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_get_cap_grant() -> SourceChainResult<()> {
         let test_env = test_authored_env();
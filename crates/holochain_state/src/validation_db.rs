@@ -17,3 +17,26 @@ pub enum ValidationLimboStatus {
     /// Is awaiting to be integrated.
     AwaitingIntegration,
 }
+
+impl ValidationLimboStatus {
+    /// The `validation_stage` of ops awaiting sys validation dependencies.
+    pub const AWAITING_SYS_DEPS_STAGE: i64 = 0;
+    /// The `validation_stage` of ops that passed sys validation.
+    pub const SYS_VALIDATED_STAGE: i64 = 1;
+    /// The `validation_stage` of ops awaiting app validation dependencies.
+    pub const AWAITING_APP_DEPS_STAGE: i64 = 2;
+    /// The `validation_stage` of ops awaiting integration.
+    pub const AWAITING_INTEGRATION_STAGE: i64 = 3;
+
+    /// The `validation_stage` this status is stored as in the `DhtOp` table.
+    /// Ops pending validation have no stage.
+    pub fn validation_stage(&self) -> Option<i64> {
+        match self {
+            Self::Pending => None,
+            Self::AwaitingSysDeps(_) => Some(Self::AWAITING_SYS_DEPS_STAGE),
+            Self::SysValidated => Some(Self::SYS_VALIDATED_STAGE),
+            Self::AwaitingAppDeps(_) => Some(Self::AWAITING_APP_DEPS_STAGE),
+            Self::AwaitingIntegration => Some(Self::AWAITING_INTEGRATION_STAGE),
+        }
+    }
+}
@@ -3,6 +3,7 @@
 use futures::StreamExt;
 use holo_hash::AgentPubKey;
 use holo_hash::DhtOpHash;
+use holo_hash::HeaderHash;
 use holochain_keystore::AgentPubKeyExt;
 use holochain_keystore::MetaLairClient;
 use holochain_serialized_bytes::prelude::*;
@@ -10,7 +11,12 @@ use holochain_sqlite::prelude::*;
 use holochain_sqlite::rusqlite::named_params;
 use holochain_sqlite::rusqlite::OptionalExtension;
 use holochain_sqlite::rusqlite::Transaction;
+use holochain_types::dht_op::DhtOpType;
 use holochain_zome_types::signature::Signature;
+use holochain_zome_types::Header;
+use holochain_zome_types::OpPublishStatus;
+use holochain_zome_types::PublishStatus;
+use holochain_zome_types::SignedHeader;
 use holochain_zome_types::Timestamp;
 use holochain_zome_types::ValidationStatus;
 use mutations::StateMutationResult;
@@ -149,6 +155,85 @@ pub fn add_if_unique(
     mutations::insert_validation_receipt(txn, receipt)
 }
 
+/// Get the publish status of the ops authored for a header.
+/// The ops are read from the authored database and their receipts
+/// are counted from the DHT database, where receipts are stored.
+/// The number of receipts each op needs is given by `required_receipts`.
+/// Returns `None` if this header was not authored here.
+pub async fn get_publish_status(
+    authored: &DbRead<DbKindAuthored>,
+    dht: &DbRead<DbKindDht>,
+    header_hash: HeaderHash,
+    required_receipts: impl FnOnce(&Header) -> usize,
+) -> StateQueryResult<Option<PublishStatus>> {
+    let hash = header_hash.clone();
+    let authored_ops = authored
+        .async_reader(move |txn| {
+            let header: Option<Vec<u8>> = txn
+                .query_row(
+                    "SELECT blob FROM Header WHERE hash = :hash",
+                    named_params! {
+                        ":hash": hash,
+                    },
+                    |row| row.get("blob"),
+                )
+                .optional()?;
+            let header = match header {
+                Some(header) => from_blob::<SignedHeader>(header)?,
+                None => return Ok(None),
+            };
+            let mut stmt = txn.prepare(
+                "
+                SELECT
+                DhtOp.hash, DhtOp.type, DhtOp.last_publish_time
+                FROM DhtOp
+                JOIN Header ON Header.hash = DhtOp.header_hash
+                WHERE
+                DhtOp.header_hash = :hash
+                AND
+                (DhtOp.type != :store_entry OR Header.private_entry = 0)
+                ",
+            )?;
+            let ops = stmt
+                .query_map(
+                    named_params! {
+                        ":hash": hash,
+                        ":store_entry": DhtOpType::StoreEntry,
+                    },
+                    |row| {
+                        let op_hash: DhtOpHash = row.get("hash")?;
+                        let op_type: DhtOpType = row.get("type")?;
+                        let last_publish_time: Option<i64> = row.get("last_publish_time")?;
+                        Ok((op_hash, op_type, last_publish_time.is_some()))
+                    },
+                )?
+                .collect::<Result<Vec<_>, _>>()?;
+            StateQueryResult::Ok(Some((header, ops)))
+        })
+        .await?;
+    let (header, ops) = match authored_ops {
+        Some(authored_ops) => authored_ops,
+        None => return Ok(None),
+    };
+    let required_receipts = required_receipts(&header.0);
+    let ops = dht
+        .async_reader(move |txn| {
+            ops.into_iter()
+                .map(|(op_hash, op_type, published)| {
+                    Ok(OpPublishStatus {
+                        receipt_count: count_valid(&txn, &op_hash)?,
+                        op_hash,
+                        op_type: op_type.to_string(),
+                        published,
+                        required_receipts,
+                    })
+                })
+                .collect::<StateQueryResult<Vec<_>>>()
+        })
+        .await?;
+    Ok(Some(PublishStatus { header_hash, ops }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,4 +312,88 @@ mod tests {
         });
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_publish_status() {
+        observability::test_run().ok();
+
+        let authored = crate::test_utils::test_authored_env();
+        let dht = crate::test_utils::test_dht_env();
+        let keystore = crate::test_utils::test_keystore();
+
+        let op = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            fixt!(Signature),
+            fixt!(Header),
+        ));
+        let op_hash = op.as_hash().clone();
+        let header_hash = HeaderHash::with_data_sync(&op.as_content().header());
+        authored
+            .env()
+            .conn()
+            .unwrap()
+            .with_commit_sync(|txn| {
+                mutations::insert_op(txn, op.clone())?;
+                mutations::set_last_publish_time(
+                    txn,
+                    op_hash.clone(),
+                    std::time::Duration::from_secs(1),
+                )
+            })
+            .unwrap();
+
+        let vr1 = fake_vr(&op_hash, &keystore).await;
+        let vr2 = fake_vr(&op_hash, &keystore).await;
+        dht.env()
+            .conn()
+            .unwrap()
+            .with_commit_sync(|txn| {
+                mutations::insert_op(txn, op)?;
+                add_if_unique(txn, vr1)?;
+                add_if_unique(txn, vr2)
+            })
+            .unwrap();
+
+        let status = get_publish_status(
+            &authored.env().into(),
+            &dht.env().into(),
+            header_hash.clone(),
+            |_| 2,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(header_hash, status.header_hash);
+        assert_eq!(
+            vec![OpPublishStatus {
+                op_hash: op_hash.clone(),
+                op_type: DhtOpType::RegisterAgentActivity.to_string(),
+                published: true,
+                receipt_count: 2,
+                required_receipts: 2,
+            }],
+            status.ops
+        );
+        assert!(status.is_saturated());
+
+        let status = get_publish_status(
+            &authored.env().into(),
+            &dht.env().into(),
+            header_hash,
+            |_| 3,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(!status.is_saturated());
+
+        let missing = get_publish_status(
+            &authored.env().into(),
+            &dht.env().into(),
+            fixt!(HeaderHash),
+            |_| 3,
+        )
+        .await
+        .unwrap();
+        assert!(missing.is_none());
+    }
 }
@@ -120,6 +120,13 @@ pub struct InstallAppBundlePayload {
     /// The app can still use existing Cells, i.e. this does not require that
     /// all Cells have DNAs with the same overridden DNA.
     pub uid: Option<Uid>,
+
+    /// Cells which other installed apps already run for this agent, keyed by
+    /// the AppRoleId of a role with `use_existing` or `create_if_not_exists`
+    /// provisioning. Listing a cell here consents to this app sharing it.
+    /// Roles which aren't listed are never bound to an existing Cell.
+    #[serde(default)]
+    pub existing_cells: HashMap<AppRoleId, CellId>,
 }
 
 /// The possible locations of an AppBundle
@@ -535,6 +542,7 @@ impl InstalledAppCommon {
                     is_provisioned: true,
                     clones: HashSet::new(),
                     clone_limit: 0,
+                    is_shared: false,
                 };
                 (role_id, role)
             })
@@ -764,6 +772,10 @@ pub struct AppRoleAssignment {
     /// Cells which were cloned at runtime. The length cannot grow beyond
     /// `clone_limit`
     clones: HashSet<CellId>,
+    /// Records that the installer consented to binding this role to a Cell
+    /// which another app already ran, rather than creating one for this app.
+    #[serde(default)]
+    is_shared: bool,
 }
 
 impl AppRoleAssignment {
@@ -777,6 +789,14 @@ impl AppRoleAssignment {
         }
     }
 
+    /// Constructor for a role bound to a Cell which another app already runs.
+    pub fn new_shared(base_cell_id: CellId, clone_limit: u32) -> Self {
+        Self {
+            is_shared: true,
+            ..Self::new(base_cell_id, true, clone_limit)
+        }
+    }
+
     /// Accessor
     pub fn cell_id(&self) -> &CellId {
         &self.base_cell_id
@@ -792,6 +812,11 @@ impl AppRoleAssignment {
         self.base_cell_id.agent_pubkey()
     }
 
+    /// Whether this role shares a Cell which was created for another app
+    pub fn is_shared(&self) -> bool {
+        self.is_shared
+    }
+
     /// Accessor
     pub fn provisioned_cell(&self) -> Option<&CellId> {
         if self.is_provisioned {
@@ -2,7 +2,10 @@ use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use self::error::AppBundleResult;
 
-use super::{dna_gamut::DnaGamut, AppManifest, AppManifestValidated};
+use super::{
+    dna_gamut::{CellResolution, DnaGamut, DnaResolution},
+    AppManifest, AppManifestValidated,
+};
 use crate::prelude::*;
 
 #[allow(missing_docs)]
@@ -47,17 +50,34 @@ impl AppBundle {
 
     /// Given a DnaGamut, decide which of the available DNAs or Cells should be
     /// used for each cell in this app.
+    ///
+    /// A role is only bound to an existing Cell if that Cell is given for the
+    /// role in `existing_cells`, belongs to `agent`, and matches the role's
+    /// version spec.
+    ///
+    /// A role which creates a Cell uses the bundled DNA if it matches the
+    /// role's version spec, or else the best matching registered DNA.
     pub async fn resolve_cells(
         self,
         agent: AgentPubKey,
-        _gamut: DnaGamut,
+        gamut: DnaGamut,
         membrane_proofs: HashMap<AppRoleId, MembraneProof>,
+        existing_cells: HashMap<AppRoleId, CellId>,
     ) -> AppBundleResult<AppRoleResolution> {
         let AppManifestValidated { name: _, roles } = self.manifest().clone().validate()?;
         let bundle = Arc::new(self);
-        let tasks = roles.into_iter().map(|(role_id, role)| async {
+        let gamut = Arc::new(gamut);
+        let tasks = roles.into_iter().map(|(role_id, role)| {
             let bundle = bundle.clone();
-            Ok((role_id, bundle.resolve_cell(role).await?))
+            let gamut = gamut.clone();
+            let agent = agent.clone();
+            let existing = existing_cells.get(&role_id).cloned();
+            async move {
+                let op = bundle
+                    .resolve_cell(&role_id, role, &gamut, &agent, existing)
+                    .await?;
+                Ok((role_id, op))
+            }
         });
         let resolution = futures::future::join_all(tasks)
             .await
@@ -79,8 +99,16 @@ impl AppBundle {
                                 resolution.dnas_to_register.push((dna, proof));
                                 resolution.role_assignments.push((role_id, role));
                             }
-                            CellProvisioningOp::Existing(cell_id, clone_limit) => {
+                            CellProvisioningOp::CreateFromRegistered(dna_hash, clone_limit) => {
+                                let cell_id =
+                                    CellId::new(dna_hash.clone(), resolution.agent.clone());
                                 let role = AppRoleAssignment::new(cell_id, true, clone_limit);
+                                let proof = membrane_proofs.get(&role_id).cloned();
+                                resolution.registered_dnas.push((dna_hash, proof));
+                                resolution.role_assignments.push((role_id, role));
+                            }
+                            CellProvisioningOp::Existing(cell_id, clone_limit) => {
+                                let role = AppRoleAssignment::new_shared(cell_id, clone_limit);
                                 resolution.role_assignments.push((role_id, role));
                             }
                             CellProvisioningOp::Noop(cell_id, clone_limit) => {
@@ -89,12 +117,13 @@ impl AppBundle {
                                     AppRoleAssignment::new(cell_id, false, clone_limit),
                                 ));
                             }
-                            other => {
-                                tracing::error!(
-                                    "Encountered unexpected CellProvisioningOp: {:?}",
-                                    other
-                                );
-                                unimplemented!()
+                            CellProvisioningOp::NoMatch => {
+                                return Err(AppBundleError::CellResolutionFailure(role_id));
+                            }
+                            CellProvisioningOp::Conflict(conflict) => {
+                                return Err(AppBundleError::CellResolutionConflict(
+                                    role_id, conflict,
+                                ));
                             }
                         }
                         Ok(resolution)
@@ -110,7 +139,11 @@ impl AppBundle {
 
     async fn resolve_cell(
         &self,
+        role_id: &AppRoleId,
         role: AppRoleManifestValidated,
+        gamut: &DnaGamut,
+        agent: &AgentPubKey,
+        existing: Option<CellId>,
     ) -> AppBundleResult<CellProvisioningOp> {
         Ok(match role {
             AppRoleManifestValidated::Create {
@@ -121,8 +154,15 @@ impl AppBundle {
                 uid,
                 deferred: _,
             } => {
-                self.resolve_cell_create(&location, version.as_ref(), clone_limit, uid, properties)
-                    .await?
+                self.resolve_cell_create(
+                    &location,
+                    version.as_ref(),
+                    clone_limit,
+                    uid,
+                    properties,
+                    gamut,
+                )
+                .await?
             }
 
             AppRoleManifestValidated::CreateClone { .. } => {
@@ -132,7 +172,13 @@ impl AppBundle {
                 version,
                 clone_limit,
                 deferred: _,
-            } => self.resolve_cell_existing(&version, clone_limit),
+            } => {
+                match resolve_cell_existing(role_id, &version, clone_limit, gamut, agent, existing)?
+                {
+                    CellProvisioningOp::NoMatch => unshared_cell(role_id, version, gamut, agent)?,
+                    op => op,
+                }
+            }
             AppRoleManifestValidated::CreateIfNotExists {
                 location,
                 version,
@@ -140,28 +186,33 @@ impl AppBundle {
                 properties,
                 uid,
                 deferred: _,
-            } => match self.resolve_cell_existing(&version, clone_limit) {
-                op @ CellProvisioningOp::Existing(_, _) => op,
-                CellProvisioningOp::NoMatch => {
-                    self.resolve_cell_create(
-                        &location,
-                        Some(&version),
-                        clone_limit,
-                        uid,
-                        properties,
-                    )
-                    .await?
-                }
-                CellProvisioningOp::Conflict(_) => {
-                    unimplemented!("conflicts are not handled, or even possible yet")
-                }
-                CellProvisioningOp::Create(_, _) => {
-                    unreachable!("resolve_cell_existing will never return a Create op")
-                }
-                CellProvisioningOp::Noop(_, _) => {
-                    unreachable!("resolve_cell_existing will never return a Noop")
+            } => {
+                match resolve_cell_existing(role_id, &version, clone_limit, gamut, agent, existing)?
+                {
+                    op @ CellProvisioningOp::Existing(_, _) => op,
+                    CellProvisioningOp::NoMatch => {
+                        self.resolve_cell_create(
+                            &location,
+                            Some(&version),
+                            clone_limit,
+                            uid,
+                            properties,
+                            gamut,
+                        )
+                        .await?
+                    }
+                    CellProvisioningOp::Conflict(_) => {
+                        unreachable!("resolve_cell_existing will never return a Conflict")
+                    }
+                    CellProvisioningOp::Create(_, _)
+                    | CellProvisioningOp::CreateFromRegistered(_, _) => {
+                        unreachable!("resolve_cell_existing will never return a Create op")
+                    }
+                    CellProvisioningOp::Noop(_, _) => {
+                        unreachable!("resolve_cell_existing will never return a Noop")
+                    }
                 }
-            },
+            }
             AppRoleManifestValidated::Disabled {
                 version: _,
                 clone_limit: _,
@@ -179,24 +230,81 @@ impl AppBundle {
         clone_limit: u32,
         uid: Option<Uid>,
         properties: Option<YamlProperties>,
+        gamut: &DnaGamut,
     ) -> AppBundleResult<CellProvisioningOp> {
         let bytes = self.resolve(location).await?;
-        let dna_bundle: DnaBundle = mr_bundle::Bundle::decode(&bytes)?.into();
+        // Zomes the DNA fetches from pinned urls share the app's cache
+        let dna_bundle: DnaBundle = mr_bundle::Bundle::decode(&bytes)?
+            .with_resource_cache(self.resource_cache().cloned())
+            .into();
         let (dna_file, original_dna_hash) = dna_bundle.into_dna_file(uid, properties).await?;
         if let Some(spec) = version {
-            if !spec.matches(original_dna_hash) {
-                return Ok(CellProvisioningOp::NoMatch);
+            if !spec.matches(original_dna_hash, dna_file.dna_def().lineage.as_ref()) {
+                // The bundled DNA doesn't satisfy the spec, but a registered one may
+                return Ok(match gamut.resolve_dna(spec.clone()) {
+                    DnaResolution::Match(dna_hash, _) => {
+                        CellProvisioningOp::CreateFromRegistered(dna_hash.into(), clone_limit)
+                    }
+                    DnaResolution::NoMatch => CellProvisioningOp::NoMatch,
+                    DnaResolution::Conflict(dna_hashes) => {
+                        CellProvisioningOp::Conflict(CellProvisioningConflict::Dnas(dna_hashes))
+                    }
+                });
             }
         }
         Ok(CellProvisioningOp::Create(dna_file, clone_limit))
     }
+}
+
+/// A `use_existing` role was given no existing Cell to share. Report the
+/// agent's Cells which match the role's version spec, so that the installer
+/// can choose one to share.
+fn unshared_cell(
+    role_id: &AppRoleId,
+    version: DnaVersionSpec,
+    gamut: &DnaGamut,
+    agent: &AgentPubKey,
+) -> AppBundleResult<CellProvisioningOp> {
+    match gamut.resolve_cell(version, agent) {
+        CellResolution::NoMatch => Ok(CellProvisioningOp::NoMatch),
+        CellResolution::Match(cell_id, _) => Err(AppBundleError::ExistingCellNotShared(
+            role_id.clone(),
+            cell_id,
+        )),
+        CellResolution::Conflict(cell_ids) => Ok(CellProvisioningOp::Conflict(
+            CellProvisioningConflict::Cells(cell_ids),
+        )),
+    }
+}
 
-    fn resolve_cell_existing(
-        &self,
-        _version: &DnaVersionSpec,
-        _clone_limit: u32,
-    ) -> CellProvisioningOp {
-        unimplemented!("Reusing existing cells is not yet implemented")
+/// Bind a role to the existing Cell the installer consented to share, if any.
+/// Returns NoMatch if there is no such Cell, so that `create_if_not_exists`
+/// can fall back to creating one.
+fn resolve_cell_existing(
+    role_id: &AppRoleId,
+    version: &DnaVersionSpec,
+    clone_limit: u32,
+    gamut: &DnaGamut,
+    agent: &AgentPubKey,
+    existing: Option<CellId>,
+) -> AppBundleResult<CellProvisioningOp> {
+    match existing {
+        None => Ok(CellProvisioningOp::NoMatch),
+        Some(cell_id) => {
+            if cell_id.agent_pubkey() != agent {
+                Err(AppBundleError::ExistingCellAgentMismatch(
+                    role_id.clone(),
+                    cell_id,
+                ))
+            } else if !gamut.cell_matches(version, &cell_id) {
+                Err(AppBundleError::ExistingCellMismatch(
+                    role_id.clone(),
+                    cell_id,
+                ))
+            } else {
+                Ok(CellProvisioningOp::Existing(cell_id, clone_limit))
+            }
+        }
     }
 }
 
@@ -216,6 +324,8 @@ pub fn we_must_remember_to_rework_cell_panic_handling_after_implementing_use_exi
 pub struct AppRoleResolution {
     pub agent: AgentPubKey,
     pub dnas_to_register: Vec<(DnaFile, Option<MembraneProof>)>,
+    /// DNAs which are already registered, and which new cells are created for
+    pub registered_dnas: Vec<(DnaHash, Option<MembraneProof>)>,
     pub role_assignments: Vec<(AppRoleId, AppRoleAssignment)>,
 }
 
@@ -225,6 +335,7 @@ impl AppRoleResolution {
         Self {
             agent,
             dnas_to_register: Default::default(),
+            registered_dnas: Default::default(),
             role_assignments: Default::default(),
         }
     }
@@ -235,9 +346,15 @@ impl AppRoleResolution {
     pub fn cells_to_create(&self) -> Vec<(CellId, Option<MembraneProof>)> {
         self.dnas_to_register
             .iter()
-            .map(|(dna, proof)| {
+            .map(|(dna, proof)| (dna.dna_hash(), proof))
+            .chain(
+                self.registered_dnas
+                    .iter()
+                    .map(|(dna_hash, proof)| (dna_hash, proof)),
+            )
+            .map(|(dna_hash, proof)| {
                 (
-                    CellId::new(dna.dna_hash().clone(), self.agent.clone()),
+                    CellId::new(dna_hash.clone(), self.agent.clone()),
                     proof.clone(),
                 )
             })
@@ -251,6 +368,8 @@ impl AppRoleResolution {
 pub enum CellProvisioningOp {
     /// Create a new Cell
     Create(DnaFile, u32),
+    /// Create a new Cell of a DNA which is already registered
+    CreateFromRegistered(DnaHash, u32),
     /// Use an existing Cell
     Existing(CellId, u32),
     /// No provisioning needed, but there might be a clone_limit, and so we need
@@ -262,6 +381,11 @@ pub enum CellProvisioningOp {
     Conflict(CellProvisioningConflict),
 }
 
-/// Uninhabitable placeholder
-#[derive(Debug)]
-pub enum CellProvisioningConflict {}
+/// The candidates which match a role's version spec equally well
+#[derive(Debug, PartialEq, Eq)]
+pub enum CellProvisioningConflict {
+    /// Several registered DNAs
+    Dnas(Vec<DnaHashB64>),
+    /// Several of the agent's existing Cells
+    Cells(Vec<CellId>),
+}
@@ -1,7 +1,7 @@
 use holochain_util::ffs;
 use mr_bundle::error::MrBundleError;
 
-use crate::prelude::{AppManifestError, AppRoleId, DnaError};
+use crate::prelude::{AppManifestError, AppRoleId, CellId, CellProvisioningConflict, DnaError};
 
 /// Errors occurring while installing an AppBundle
 #[derive(thiserror::Error, Debug)]
@@ -9,6 +9,18 @@ pub enum AppBundleError {
     #[error("Could not resolve the app role '{0}'")]
     CellResolutionFailure(AppRoleId),
 
+    #[error("Could not resolve the app role '{0}', because several candidates match its version spec equally well: {1:?}")]
+    CellResolutionConflict(AppRoleId, CellProvisioningConflict),
+
+    #[error("The app role '{0}' can use the existing cell {1:?}, but only if it is given in `existing_cells`")]
+    ExistingCellNotShared(AppRoleId, CellId),
+
+    #[error("The existing cell {1:?} given for the app role '{0}' belongs to another agent")]
+    ExistingCellAgentMismatch(AppRoleId, CellId),
+
+    #[error("The existing cell {1:?} given for the app role '{0}' is not installed, or its DNA does not match the role's version spec")]
+    ExistingCellMismatch(AppRoleId, CellId),
+
     #[error(transparent)]
     AppManifestError(#[from] AppManifestError),
 
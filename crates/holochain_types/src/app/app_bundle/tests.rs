@@ -54,7 +54,12 @@ async fn provisioning_1_create() {
     let cell_id = CellId::new(dna.dna_hash().to_owned(), agent.clone());
 
     let resolution = bundle
-        .resolve_cells(agent.clone(), DnaGamut::placeholder(), Default::default())
+        .resolve_cells(
+            agent.clone(),
+            DnaGamut::placeholder(),
+            Default::default(),
+            Default::default(),
+        )
         .await
         .unwrap();
 
@@ -65,7 +70,282 @@ async fn provisioning_1_create() {
     let expected = AppRoleResolution {
         agent,
         dnas_to_register: vec![(dna, None)],
+        registered_dnas: vec![],
         role_assignments: vec![("role_id".into(), role)],
     };
     assert_eq!(resolution, expected);
 }
+
+/// Test that a DNA can be selected by a version range of its lineage
+#[tokio::test]
+async fn provisioning_create_by_version_range() {
+    let agent = fixt!(AgentPubKey);
+    let dna_wasm = DnaWasmHashed::from_content(DnaWasm::new_invalid()).await;
+    let mut dna_def = DnaDef::unique_from_zomes(vec![Zome::new(
+        "hi".into(),
+        ZomeDef::Wasm(WasmZome::new(dna_wasm.as_hash().clone())),
+    )]);
+    dna_def.lineage = Some(DnaLineage {
+        id: "chat".into(),
+        version: "1.2.0".into(),
+    });
+    let dna = DnaFile::new(dna_def, vec![dna_wasm.into_content()])
+        .await
+        .unwrap();
+    let path = PathBuf::from(format!("{}", dna.dna_hash()));
+
+    let (mut manifest, _) =
+        app_manifest_fixture(Some(DnaLocation::Bundled(path.clone())), vec![]).await;
+    match &mut manifest {
+        AppManifest::V1(manifest) => {
+            manifest.roles[0].dna.version = Some(DnaVersionFlexible::Multiple(
+                DnaVersionRange {
+                    lineage: "chat".into(),
+                    range: semver::VersionReq::parse(">=1.1, <2").unwrap(),
+                }
+                .into(),
+            ))
+        }
+    }
+    let resources = vec![(path, DnaBundle::from_dna_file(dna).await.unwrap())];
+    let bundle = AppBundle::new(manifest, resources, PathBuf::from("."))
+        .await
+        .unwrap();
+
+    let resolution = bundle
+        .resolve_cells(
+            agent,
+            DnaGamut::new(std::iter::empty(), std::iter::empty()),
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resolution.dnas_to_register.len(), 1);
+    assert_eq!(
+        resolution.dnas_to_register[0].0.dna_def().lineage,
+        Some(DnaLineage {
+            id: "chat".into(),
+            version: "1.2.0".into(),
+        })
+    );
+}
+
+/// Test that a role is only bound to an existing Cell with the installer's consent
+#[tokio::test]
+async fn provisioning_use_existing() {
+    let agent = fixt!(AgentPubKey);
+    let dna_def = fixt!(DnaDef);
+    let dna_hash = DnaHash::with_data_sync(&dna_def);
+    let cell_id = CellId::new(dna_hash.clone(), agent.clone());
+    let other_cell_id = CellId::new(dna_hash.clone(), fixt!(AgentPubKey));
+    let gamut = || {
+        DnaGamut::new(
+            vec![(dna_hash.clone(), None)],
+            vec![cell_id.clone(), other_cell_id.clone()],
+        )
+    };
+    let bundle = |provisioning| {
+        let dna_def = dna_def.clone();
+        async move {
+            // The DNA is never fetched unless the role falls back to creating a cell
+            let location = Some(DnaLocation::Bundled("missing.dna".into()));
+            let (mut manifest, _) =
+                app_manifest_fixture(location, vec![fixt!(DnaDef), dna_def]).await;
+            match &mut manifest {
+                AppManifest::V1(manifest) => manifest.roles[0].provisioning = Some(provisioning),
+            }
+            AppBundle::new(manifest, vec![], PathBuf::from("."))
+                .await
+                .unwrap()
+        }
+    };
+    let consent = |cell_id: &CellId| {
+        maplit::hashmap! {
+            "role_id".to_string() => cell_id.clone(),
+        }
+    };
+
+    // - With consent, the role shares the existing cell
+    let resolution = bundle(CellProvisioning::UseExisting { deferred: false })
+        .await
+        .resolve_cells(
+            agent.clone(),
+            gamut(),
+            Default::default(),
+            consent(&cell_id),
+        )
+        .await
+        .unwrap();
+    assert!(resolution.dnas_to_register.is_empty());
+    assert_eq!(
+        resolution.role_assignments,
+        vec![(
+            "role_id".to_string(),
+            AppRoleAssignment::new_shared(cell_id.clone(), 50)
+        )]
+    );
+    assert!(resolution.role_assignments[0].1.is_shared());
+    assert!(resolution.cells_to_create().is_empty());
+
+    // - Without consent, the matching cell is reported but not used
+    matches::assert_matches!(
+        bundle(CellProvisioning::UseExisting { deferred: false })
+            .await
+            .resolve_cells(
+                agent.clone(),
+                gamut(),
+                Default::default(),
+                Default::default()
+            )
+            .await,
+        Err(AppBundleError::ExistingCellNotShared(_, c)) if c == cell_id
+    );
+
+    // - And if there is no matching cell, there is nothing to use
+    matches::assert_matches!(
+        bundle(CellProvisioning::UseExisting { deferred: false })
+            .await
+            .resolve_cells(
+                agent.clone(),
+                DnaGamut::new(vec![(dna_hash.clone(), None)], vec![other_cell_id.clone()]),
+                Default::default(),
+                Default::default()
+            )
+            .await,
+        Err(AppBundleError::CellResolutionFailure(_))
+    );
+
+    // - Another agent's cell can't be shared
+    matches::assert_matches!(
+        bundle(CellProvisioning::UseExisting { deferred: false })
+            .await
+            .resolve_cells(
+                agent.clone(),
+                gamut(),
+                Default::default(),
+                consent(&other_cell_id)
+            )
+            .await,
+        Err(AppBundleError::ExistingCellAgentMismatch(_, _))
+    );
+
+    // - Nor can a cell which doesn't match the version spec
+    let unknown = CellId::new(fixt!(DnaHash), agent.clone());
+    matches::assert_matches!(
+        bundle(CellProvisioning::CreateIfNotExists { deferred: false })
+            .await
+            .resolve_cells(
+                agent.clone(),
+                gamut(),
+                Default::default(),
+                consent(&unknown)
+            )
+            .await,
+        Err(AppBundleError::ExistingCellMismatch(_, _))
+    );
+}
+
+/// Test that roles fall back to the best registered DNA, and that ambiguous
+/// version ranges are reported as conflicts
+#[tokio::test]
+async fn provisioning_from_registered_dnas_by_version_range() {
+    let agent = fixt!(AgentPubKey);
+    let lineage = |version: &str| {
+        Some(DnaLineage {
+            id: "chat".into(),
+            version: version.into(),
+        })
+    };
+    let dna_wasm = DnaWasmHashed::from_content(DnaWasm::new_invalid()).await;
+    let mut dna_def = DnaDef::unique_from_zomes(vec![Zome::new(
+        "hi".into(),
+        ZomeDef::Wasm(WasmZome::new(dna_wasm.as_hash().clone())),
+    )]);
+    dna_def.lineage = lineage("1.2.0");
+    let dna = DnaFile::new(dna_def, vec![dna_wasm.into_content()])
+        .await
+        .unwrap();
+    let path = PathBuf::from(format!("{}", dna.dna_hash()));
+    let bundle = |provisioning| {
+        let path = path.clone();
+        let dna = dna.clone();
+        async move {
+            let (mut manifest, _) =
+                app_manifest_fixture(Some(DnaLocation::Bundled(path.clone())), vec![]).await;
+            match &mut manifest {
+                AppManifest::V1(manifest) => {
+                    manifest.roles[0].provisioning = Some(provisioning);
+                    manifest.roles[0].dna.version = Some(DnaVersionFlexible::Multiple(
+                        DnaVersionRange {
+                            lineage: "chat".into(),
+                            range: semver::VersionReq::parse("^2").unwrap(),
+                        }
+                        .into(),
+                    ))
+                }
+            }
+            let resources = vec![(path, DnaBundle::from_dna_file(dna).await.unwrap())];
+            AppBundle::new(manifest, resources, PathBuf::from("."))
+                .await
+                .unwrap()
+        }
+    };
+    let (v2_0, v2_1, other_v2_1) = (fixt!(DnaHash), fixt!(DnaHash), fixt!(DnaHash));
+
+    // - The bundled DNA is too old, so the best registered DNA is used
+    let gamut = DnaGamut::new(
+        vec![
+            (v2_0.clone(), lineage("2.0.0")),
+            (v2_1.clone(), lineage("2.1.0")),
+        ],
+        vec![],
+    );
+    let resolution = bundle(CellProvisioning::Create { deferred: false })
+        .await
+        .resolve_cells(agent.clone(), gamut, Default::default(), Default::default())
+        .await
+        .unwrap();
+    assert!(resolution.dnas_to_register.is_empty());
+    assert_eq!(resolution.registered_dnas, vec![(v2_1.clone(), None)]);
+    assert_eq!(
+        resolution.cells_to_create(),
+        vec![(CellId::new(v2_1.clone(), agent.clone()), None)]
+    );
+
+    // - Two registered DNAs declare the best version
+    let gamut = || {
+        DnaGamut::new(
+            vec![
+                (v2_1.clone(), lineage("2.1.0")),
+                (other_v2_1.clone(), lineage("2.1.0")),
+            ],
+            vec![
+                CellId::new(v2_1.clone(), agent.clone()),
+                CellId::new(other_v2_1.clone(), agent.clone()),
+            ],
+        )
+    };
+    matches::assert_matches!(
+        bundle(CellProvisioning::CreateIfNotExists { deferred: false })
+            .await
+            .resolve_cells(agent.clone(), gamut(), Default::default(), Default::default())
+            .await,
+        Err(AppBundleError::CellResolutionConflict(
+            _,
+            CellProvisioningConflict::Dnas(hashes)
+        )) if hashes.len() == 2
+    );
+
+    // - And the agent has cells of both, so there is no telling which to share
+    matches::assert_matches!(
+        bundle(CellProvisioning::UseExisting { deferred: false })
+            .await
+            .resolve_cells(agent.clone(), gamut(), Default::default(), Default::default())
+            .await,
+        Err(AppBundleError::CellResolutionConflict(
+            _,
+            CellProvisioningConflict::Cells(cells)
+        )) if cells.len() == 2
+    );
+}
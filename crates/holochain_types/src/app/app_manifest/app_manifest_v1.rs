@@ -9,7 +9,7 @@ use super::{
 };
 use crate::prelude::{AppRoleId, YamlProperties};
 use holo_hash::{DnaHash, DnaHashB64};
-use holochain_zome_types::Uid;
+use holochain_zome_types::{DnaLineage, Uid};
 use std::collections::HashMap;
 
 /// Version 1 of the App manifest schema
@@ -123,7 +123,7 @@ pub enum DnaVersionFlexible {
 impl From<DnaVersionFlexible> for DnaVersionSpec {
     fn from(v: DnaVersionFlexible) -> Self {
         match v {
-            DnaVersionFlexible::Singleton(h) => DnaVersionSpec(vec![h]),
+            DnaVersionFlexible::Singleton(h) => DnaVersionSpec::Hashes(vec![h]),
             DnaVersionFlexible::Multiple(v) => v,
         }
     }
@@ -134,28 +134,79 @@ pub type DnaLocation = mr_bundle::Location;
 
 /// Defines a criterion for a DNA version to match against.
 ///
-/// Currently we're using the most simple possible version spec: A list of
-/// valid DnaHashes. The order of the list is from latest version to earliest.
-/// In subsequent manifest versions, this will become more expressive.
+/// Either a list of valid DnaHashes, ordered from latest version to earliest,
+/// or a [semantic version](https://semver.org) range of a DNA lineage, which
+/// matches any DNA whose manifest declares that lineage and a version within
+/// the range.
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, derive_more::From)]
+#[serde(untagged)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
-pub struct DnaVersionSpec(Vec<DnaHashB64>);
+pub enum DnaVersionSpec {
+    /// Any of these exact DNAs
+    Hashes(Vec<DnaHashB64>),
+    /// Any version of a lineage within a range
+    Range(DnaVersionRange),
+}
+
+/// A range of versions of a DNA lineage, e.g.
+///
+/// ```yaml
+/// version:
+///   lineage: chat
+///   range: ">=1.2, <2"
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DnaVersionRange {
+    /// The id of the lineage, as declared in the DNA manifest
+    pub lineage: String,
+    /// The requirement the DNA's version must satisfy
+    pub range: semver::VersionReq,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for DnaVersionRange {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let major: u16 = u.arbitrary()?;
+        Ok(Self {
+            lineage: u.arbitrary()?,
+            range: semver::VersionReq::parse(&format!("^{}", major))
+                .map_err(|_| arbitrary::Error::IncorrectFormat)?,
+        })
+    }
+}
 
 // NB: the following is likely to remain in the API for DnaVersionSpec
 impl DnaVersionSpec {
-    /// Check if a DNA satisfies this version spec
-    pub fn matches(&self, hash: DnaHash) -> bool {
-        self.0.contains(&hash.into())
+    /// Check if a DNA satisfies this version spec, given its hash and the
+    /// lineage it declares, if any
+    pub fn matches(&self, hash: DnaHash, lineage: Option<&DnaLineage>) -> bool {
+        match self {
+            Self::Hashes(hashes) => hashes.contains(&hash.into()),
+            Self::Range(range) => lineage.map_or(false, |lineage| range.matches(lineage)),
+        }
+    }
+}
+
+impl DnaVersionRange {
+    /// Check if a lineage is this one, at a version within the range
+    pub fn matches(&self, lineage: &DnaLineage) -> bool {
+        lineage.id == self.lineage
+            && semver::Version::parse(&lineage.version)
+                .map_or(false, |version| self.range.matches(&version))
     }
 }
 
 // NB: the following is likely to be removed from the API for DnaVersionSpec
 // after our versioning becomes more sophisticated
 impl DnaVersionSpec {
-    /// Return the list of hashes covered by a version (obviously temporary,
-    /// while we don't have real versioning)
+    /// Return the list of hashes covered by a version.
+    /// A version range doesn't list any hashes.
     pub fn dna_hashes(&self) -> Vec<&DnaHashB64> {
-        self.0.iter().collect()
+        match self {
+            Self::Hashes(hashes) => hashes.iter().collect(),
+            Self::Range(_) => vec![],
+        }
     }
 }
 
@@ -174,6 +225,8 @@ pub enum CellProvisioning {
     /// Require that a Cell is already installed which matches the DNA version
     /// spec, and which has an Agent that's associated with this App's agent
     /// via DPKI. If no such Cell exists, *app installation fails*.
+    /// The installer consents to sharing a Cell by giving it for this role in
+    /// the `existing_cells` of the install request.
     UseExisting { deferred: bool },
     /// Try `UseExisting`, and if that fails, fallback to `Create`
     CreateIfNotExists { deferred: bool },
@@ -416,4 +469,29 @@ roles:
         assert_ne!(manifest.roles[2].dna.uid.as_ref(), Some(&uid));
         assert_ne!(manifest.roles[3].dna.uid.as_ref(), Some(&uid));
     }
+
+    #[test]
+    fn version_range_from_yaml() {
+        let spec: DnaVersionFlexible = serde_yaml::from_str(
+            r#"
+lineage: chat
+range: ">=1.2, <2"
+"#,
+        )
+        .unwrap();
+        let spec = DnaVersionSpec::from(spec);
+        let hash = fixt!(DnaHash);
+        let lineage = |id: &str, version: &str| DnaLineage {
+            id: id.into(),
+            version: version.into(),
+        };
+
+        assert!(spec.matches(hash.clone(), Some(&lineage("chat", "1.2.0"))));
+        assert!(spec.matches(hash.clone(), Some(&lineage("chat", "1.9.3"))));
+        assert!(!spec.matches(hash.clone(), Some(&lineage("chat", "1.1.0"))));
+        assert!(!spec.matches(hash.clone(), Some(&lineage("chat", "2.0.0"))));
+        assert!(!spec.matches(hash.clone(), Some(&lineage("mail", "1.2.0"))));
+        assert!(!spec.matches(hash, None));
+        assert!(spec.dna_hashes().is_empty());
+    }
 }
@@ -1,47 +1,66 @@
 //! A DNA gamut is a representation of all DNAs available in a given context.
 
-use super::DnaVersionSpec;
+use super::{DnaVersionRange, DnaVersionSpec};
 use crate::prelude::*;
-use std::collections::{hash_map, HashMap, HashSet};
+use std::collections::{HashMap, HashSet};
 
 /// Representation of all DNAs and Cells available in a given context.
 /// When given a DnaVersionSpec, a particular DNA can be selected from this
 /// gamut.
 ///
-/// Moreover, each DNA hash has associated with it a list of Agents. Each agent
-/// represents a Cell which exists on the conductor, using that DNA and agent
-/// pair. A DNA with no agents listed is simply registered but does not exist
-/// in any Cell.
-///
-/// NB: since our DnaVersionSpec is currently very simplistic, so is the gamut.
-/// As our versioning becomes more expressive, so will this type. For instance,
-/// if we introduce semver, the gamut will include versions of DNAs as well.
+/// Each DNA hash has associated with it the version the DNA declares, if any,
+/// and a list of Agents. Each agent represents a Cell which exists on the
+/// conductor, using that DNA and agent pair. A DNA with no agents listed is
+/// simply registered but does not exist in any Cell.
 ///
 /// This type basically exists as an abstract adapter between the conductor's
 /// DNA store and the app installation process. Without needing to know exactly
 /// what we will need from the DNA store, we can define what questions we will
 /// need to ask of it through this type.
-pub struct DnaGamut(HashMap<DnaHash, HashSet<AgentPubKey>>);
+pub struct DnaGamut(HashMap<DnaHash, GamutEntry>);
+
+#[derive(Default)]
+struct GamutEntry {
+    version: Option<DnaVersion>,
+    agents: HashSet<AgentPubKey>,
+}
 
-/// We don't have any notion of DNA versioning other than the hash, but this is
-/// a placeholder to indicate the need for it in the future and to start using
-/// it in public interfaces.
-pub struct DnaVersion;
+/// The semantic version of a DNA within its lineage.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DnaVersion {
+    /// The id of the lineage
+    pub lineage: String,
+    /// The version within the lineage
+    pub version: semver::Version,
+}
+
+impl DnaVersion {
+    /// Parse the version a DNA declares.
+    /// Returns None if the version isn't a semantic version.
+    pub fn from_lineage(lineage: &DnaLineage) -> Option<Self> {
+        Some(Self {
+            lineage: lineage.id.clone(),
+            version: semver::Version::parse(&lineage.version).ok()?,
+        })
+    }
+}
 
 impl DnaGamut {
-    /// Constructor. Restructure a list of CellIds into the proper format.
-    pub fn new<I: IntoIterator<Item = CellId>>(cells: I) -> Self {
-        let mut map: HashMap<DnaHash, HashSet<AgentPubKey>> = HashMap::new();
+    /// Constructor. Takes the registered DNAs along with the lineage each
+    /// declares, and the CellIds which exist.
+    pub fn new<D, C>(dnas: D, cells: C) -> Self
+    where
+        D: IntoIterator<Item = (DnaHash, Option<DnaLineage>)>,
+        C: IntoIterator<Item = CellId>,
+    {
+        let mut map: HashMap<DnaHash, GamutEntry> = HashMap::new();
+        for (dna, lineage) in dnas {
+            map.entry(dna).or_default().version =
+                lineage.as_ref().and_then(DnaVersion::from_lineage);
+        }
         for cell in cells {
             let (dna, agent) = cell.into_dna_and_agent();
-            match map.entry(dna) {
-                hash_map::Entry::Occupied(mut e) => {
-                    e.get_mut().insert(agent);
-                }
-                hash_map::Entry::Vacant(e) => {
-                    e.insert(vec![agent].into_iter().collect());
-                }
-            }
+            map.entry(dna).or_default().agents.insert(agent);
         }
         Self(map)
     }
@@ -49,60 +68,254 @@ impl DnaGamut {
     #[deprecated = "Stop using the placeholder"]
     #[allow(missing_docs)]
     pub fn placeholder() -> Self {
-        Self::new(std::iter::empty())
+        Self::new(std::iter::empty(), std::iter::empty())
     }
 
-    /// Given a version spec, return the best-matching DNA in the gamut
+    /// Given a version spec, return the best-matching DNA in the gamut.
+    ///
+    /// For a list of hashes, the first one in the gamut is the best.
+    /// For a version range, the highest version in the range is the best,
+    /// and if several DNAs declare that version, it's a conflict.
     pub fn resolve_dna(&self, spec: DnaVersionSpec) -> DnaResolution {
-        for hash in spec.dna_hashes() {
-            if self.0.contains_key(hash.as_ref()) {
-                return DnaResolution::Match(hash.clone(), DnaVersion);
+        let mut best = self.best_matches(&spec, |_| true);
+        match best.len() {
+            0 => DnaResolution::NoMatch,
+            1 => {
+                let (hash, entry) = best.remove(0);
+                DnaResolution::Match(hash.clone().into(), entry.version.clone())
             }
+            _ => DnaResolution::Conflict(
+                best.into_iter()
+                    .map(|(hash, _)| hash.clone().into())
+                    .collect(),
+            ),
         }
-        DnaResolution::NoMatch
     }
 
-    /// Given a version spec, return the best-matching CellId
-    // TODO: use DPKI to filter Cells which belong to Agents that are not
+    /// Given a version spec, return the best-matching Cell of the given agent
+    // TODO: use DPKI to also match Cells which belong to Agents that are
     //       associated with the provided agent
-    pub fn resolve_cell(&self, spec: DnaVersionSpec, _agent: &AgentPubKey) -> CellResolution {
-        for hash in spec.dna_hashes() {
-            if let Some(agent) = self
-                .0
-                .get(hash.as_ref())
-                // TODO: this is where an agent check could go, but for now we
-                //       just return the first one available
-                .map(|agents| agents.iter().next())
-                .unwrap_or(None)
-            {
-                return CellResolution::Match(
-                    CellId::new(hash.clone().into(), agent.clone()),
-                    DnaVersion,
-                );
+    pub fn resolve_cell(&self, spec: DnaVersionSpec, agent: &AgentPubKey) -> CellResolution {
+        let mut best = self.best_matches(&spec, |entry| entry.agents.contains(agent));
+        let cell_id = |hash: &DnaHash| CellId::new(hash.clone(), agent.clone());
+        match best.len() {
+            0 => CellResolution::NoMatch,
+            1 => {
+                let (hash, entry) = best.remove(0);
+                CellResolution::Match(cell_id(hash), entry.version.clone())
+            }
+            _ => {
+                CellResolution::Conflict(best.into_iter().map(|(hash, _)| cell_id(hash)).collect())
+            }
+        }
+    }
+
+    /// Check whether a Cell exists in the gamut and its DNA satisfies the spec
+    pub fn cell_matches(&self, spec: &DnaVersionSpec, cell_id: &CellId) -> bool {
+        self.0
+            .get(cell_id.dna_hash())
+            .filter(|entry| entry.agents.contains(cell_id.agent_pubkey()))
+            .map_or(false, |entry| match spec {
+                DnaVersionSpec::Hashes(hashes) => {
+                    hashes.contains(&cell_id.dna_hash().clone().into())
+                }
+                DnaVersionSpec::Range(range) => entry
+                    .version
+                    .as_ref()
+                    .map_or(false, |version| in_range(version, range)),
+            })
+    }
+
+    /// The DNAs which match the spec equally well, among those accepted
+    /// by the filter.
+    fn best_matches<F>(&self, spec: &DnaVersionSpec, filter: F) -> Vec<(&DnaHash, &GamutEntry)>
+    where
+        F: Fn(&GamutEntry) -> bool,
+    {
+        match spec {
+            DnaVersionSpec::Hashes(hashes) => hashes
+                .iter()
+                .find_map(|hash| {
+                    self.0
+                        .get_key_value(hash.as_ref())
+                        .filter(|(_, entry)| filter(entry))
+                })
+                .into_iter()
+                .collect(),
+            DnaVersionSpec::Range(range) => {
+                let mut matches: Vec<_> = self
+                    .0
+                    .iter()
+                    .filter(|(_, entry)| filter(entry))
+                    .filter_map(|(hash, entry)| {
+                        let version = entry.version.as_ref()?;
+                        if in_range(version, range) {
+                            Some((&version.version, (hash, entry)))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect();
+                let top = match matches.iter().map(|(version, _)| *version).max() {
+                    Some(top) => top.clone(),
+                    None => return vec![],
+                };
+                matches.retain(|(version, _)| **version == top);
+                let mut best: Vec<_> = matches.into_iter().map(|(_, m)| m).collect();
+                // Report conflicts in a stable order
+                best.sort_by(|(a, _), (b, _)| a.cmp(b));
+                best
             }
         }
-        CellResolution::NoMatch
     }
 }
 
+fn in_range(version: &DnaVersion, range: &DnaVersionRange) -> bool {
+    version.lineage == range.lineage && range.range.matches(&version.version)
+}
+
 /// Possible results of DNA resolution
+#[derive(Debug, PartialEq, Eq)]
 pub enum DnaResolution {
     /// A match was found within the gamut
-    Match(DnaHashB64, DnaVersion),
+    Match(DnaHashB64, Option<DnaVersion>),
     /// No match was found
     NoMatch,
-    /// Multiple matches were found, or other scenario that requires user
-    /// intervention for resolution (TODO, placeholder)
-    Conflict,
+    /// Several DNAs match equally well, and the user must choose between them
+    Conflict(Vec<DnaHashB64>),
 }
 
 /// Possible results of Cell resolution
+#[derive(Debug, PartialEq, Eq)]
 pub enum CellResolution {
     /// A match was found within the gamut
-    Match(CellId, DnaVersion),
+    Match(CellId, Option<DnaVersion>),
     /// No match was found
     NoMatch,
-    /// Multiple matches were found, or other scenario that requires user
-    /// intervention for resolution (TODO, placeholder)
-    Conflict,
+    /// Cells of several DNAs match equally well, and the user must choose
+    /// between them
+    Conflict(Vec<CellId>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+
+    fn lineage(version: &str) -> Option<DnaLineage> {
+        Some(DnaLineage {
+            id: "chat".into(),
+            version: version.into(),
+        })
+    }
+
+    fn range(range: &str) -> DnaVersionSpec {
+        DnaVersionRange {
+            lineage: "chat".into(),
+            range: semver::VersionReq::parse(range).unwrap(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn resolve_dna_by_version_range() {
+        let (v1_0, v1_4, v2_0, other) = (
+            fixt!(DnaHash),
+            fixt!(DnaHash),
+            fixt!(DnaHash),
+            fixt!(DnaHash),
+        );
+        let gamut = DnaGamut::new(
+            vec![
+                (v1_0.clone(), lineage("1.0.0")),
+                (v1_4.clone(), lineage("1.4.0")),
+                (v2_0.clone(), lineage("2.0.0")),
+                (other, None),
+            ],
+            vec![],
+        );
+
+        // The highest version within the range wins
+        assert_eq!(
+            gamut.resolve_dna(range("^1")),
+            DnaResolution::Match(
+                v1_4.clone().into(),
+                DnaVersion::from_lineage(&lineage("1.4.0").unwrap())
+            )
+        );
+        assert_eq!(
+            gamut.resolve_dna(range(">=1")),
+            DnaResolution::Match(
+                v2_0.clone().into(),
+                DnaVersion::from_lineage(&lineage("2.0.0").unwrap())
+            )
+        );
+        assert_eq!(gamut.resolve_dna(range("^3")), DnaResolution::NoMatch);
+
+        // Hash lists still work, and take the first registered hash
+        assert_eq!(
+            gamut.resolve_dna(vec![fixt!(DnaHash).into(), v1_0.clone().into()].into()),
+            DnaResolution::Match(
+                v1_0.into(),
+                DnaVersion::from_lineage(&lineage("1.0.0").unwrap())
+            )
+        );
+    }
+
+    #[test]
+    fn resolve_conflicts() {
+        let (a, b) = (fixt!(DnaHash), fixt!(DnaHash));
+        let agent = fixt!(AgentPubKey);
+        let gamut = DnaGamut::new(
+            vec![(a.clone(), lineage("1.4.0")), (b.clone(), lineage("1.4.0"))],
+            vec![CellId::new(a.clone(), agent.clone())],
+        );
+
+        match gamut.resolve_dna(range("^1")) {
+            DnaResolution::Conflict(hashes) => {
+                assert_eq!(hashes.len(), 2);
+                assert!(hashes.contains(&a.clone().into()));
+                assert!(hashes.contains(&b.into()));
+            }
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+
+        // Only one of the DNAs has a cell, so there is no conflict between cells
+        assert_eq!(
+            gamut.resolve_cell(range("^1"), &agent),
+            CellResolution::Match(
+                CellId::new(a, agent.clone()),
+                DnaVersion::from_lineage(&lineage("1.4.0").unwrap())
+            )
+        );
+    }
+
+    #[test]
+    fn cells_match_only_their_own_agent_and_spec() {
+        let (dna, other_dna) = (fixt!(DnaHash), fixt!(DnaHash));
+        let (agent, other_agent) = (fixt!(AgentPubKey), fixt!(AgentPubKey));
+        let cell = CellId::new(dna.clone(), agent.clone());
+        let gamut = DnaGamut::new(
+            vec![(dna.clone(), lineage("1.0.0")), (other_dna.clone(), None)],
+            vec![
+                cell.clone(),
+                CellId::new(other_dna.clone(), other_agent.clone()),
+            ],
+        );
+
+        assert!(gamut.cell_matches(&range("^1"), &cell));
+        assert!(gamut.cell_matches(&vec![dna.clone().into()].into(), &cell));
+        assert!(!gamut.cell_matches(&range("^2"), &cell));
+        assert!(!gamut.cell_matches(&range("^1"), &CellId::new(dna.clone(), other_agent.clone())));
+
+        // Another agent's cell is never resolved
+        assert_eq!(
+            gamut.resolve_cell(vec![other_dna.into()].into(), &agent),
+            CellResolution::NoMatch
+        );
+        assert_eq!(
+            gamut.resolve_cell(range("^1"), &other_agent),
+            CellResolution::NoMatch
+        );
+    }
 }
@@ -121,6 +121,21 @@ impl DnaBundle {
     ) -> DnaResult<(DnaDefHashed, DnaHash)> {
         match self.manifest() {
             DnaManifest::V1(manifest) => {
+                let integrity = manifest.integrity.unwrap_or_default();
+                if !integrity.is_within_bounds() {
+                    return Err(DnaError::Invalid(format!(
+                        "integrity limits are out of bounds: max_entry_size must be between {} and {} bytes, max_tag_size must be between {} and {} bytes",
+                        MIN_ENTRY_SIZE_LIMIT, ENTRY_SIZE_LIMIT, MIN_TAG_SIZE_LIMIT, MAX_TAG_SIZE_LIMIT,
+                    )));
+                }
+                if let Some(lineage) = &manifest.lineage {
+                    semver::Version::parse(&lineage.version).map_err(|e| {
+                        DnaError::Invalid(format!(
+                            "the version {:?} of DNA lineage {:?} is not a semantic version: {}",
+                            lineage.version, lineage.id, e
+                        ))
+                    })?;
+                }
                 let mut dna_def = DnaDef {
                     name: manifest.name.clone(),
                     uid: manifest.uid.clone().unwrap_or_default(),
@@ -128,6 +143,8 @@ impl DnaBundle {
                         manifest.properties.clone().unwrap_or_default(),
                     )?,
                     zomes,
+                    integrity,
+                    lineage: manifest.lineage.clone(),
                 };
 
                 if uid.is_none() && properties.is_none() {
@@ -197,6 +214,8 @@ impl DnaBundle {
                 ))
             })?),
             zomes,
+            integrity: Some(dna_def.integrity).filter(|integrity| !integrity.is_default()),
+            lineage: dna_def.lineage,
         }
         .into())
     }
@@ -233,6 +252,8 @@ mod tests {
                     location: mr_bundle::Location::Bundled(path2.clone()),
                 },
             ],
+            integrity: None,
+            lineage: None,
         };
         let resources = vec![(path1, wasm1), (path2, wasm2)];
 
@@ -273,4 +294,93 @@ mod tests {
             SerializedBytes::try_from(properties).unwrap()
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn integrity_settings_change_the_dna_hash() {
+        let manifest = |integrity| DnaManifestCurrent {
+            name: "name".into(),
+            uid: None,
+            properties: None,
+            zomes: vec![],
+            integrity,
+            lineage: None,
+        };
+        let dna_file = |manifest: DnaManifestCurrent| async move {
+            let bundle: DnaBundle = mr_bundle::Bundle::new_unchecked(manifest.into(), vec![])
+                .unwrap()
+                .into();
+            bundle
+                .into_dna_file(None, None)
+                .await
+                .map(|(dna_file, _)| dna_file)
+        };
+
+        let plain = dna_file(manifest(None)).await.unwrap();
+        let defaults = dna_file(manifest(Some(IntegritySettings::default())))
+            .await
+            .unwrap();
+        // Unset limits don't change the hash of existing DNAs.
+        assert_eq!(plain.dna_hash(), defaults.dna_hash());
+        assert!(plain.dna_def().integrity.is_default());
+
+        let small = IntegritySettings {
+            max_entry_size: Some(4096),
+            max_tag_size: None,
+        };
+        let limited = dna_file(manifest(Some(small))).await.unwrap();
+        assert_ne!(plain.dna_hash(), limited.dna_hash());
+        assert_eq!(limited.dna_def().integrity.entry_size_limit(), 4096);
+        assert_eq!(
+            limited.dna_def().integrity.tag_size_limit(),
+            DEFAULT_TAG_SIZE_LIMIT
+        );
+
+        let too_big = IntegritySettings {
+            max_entry_size: None,
+            max_tag_size: Some(MAX_TAG_SIZE_LIMIT as u32 + 1),
+        };
+        matches::assert_matches!(
+            dna_file(manifest(Some(too_big))).await,
+            Err(DnaError::Invalid(_))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn lineage_is_part_of_the_dna_hash() {
+        let manifest = |lineage| DnaManifestCurrent {
+            name: "name".into(),
+            uid: None,
+            properties: None,
+            zomes: vec![],
+            integrity: None,
+            lineage,
+        };
+        let dna_file = |manifest: DnaManifestCurrent| async move {
+            let bundle: DnaBundle = mr_bundle::Bundle::new_unchecked(manifest.into(), vec![])
+                .unwrap()
+                .into();
+            bundle
+                .into_dna_file(None, None)
+                .await
+                .map(|(dna_file, _)| dna_file)
+        };
+        let lineage = |version: &str| {
+            Some(DnaLineage {
+                id: "chat".into(),
+                version: version.into(),
+            })
+        };
+
+        let plain = dna_file(manifest(None)).await.unwrap();
+        let v1 = dna_file(manifest(lineage("1.0.0"))).await.unwrap();
+        let v1_1 = dna_file(manifest(lineage("1.1.0"))).await.unwrap();
+        assert_ne!(plain.dna_hash(), v1.dna_hash());
+        assert_ne!(v1.dna_hash(), v1_1.dna_hash());
+        assert_eq!(v1.dna_def().lineage, lineage("1.0.0"));
+
+        matches::assert_matches!(
+            dna_file(manifest(lineage("one"))).await,
+            Err(DnaError::Invalid(_))
+        );
+    }
 }
@@ -42,7 +42,7 @@ impl DnaManifest {
         properties: Option<YamlProperties>,
         zomes: Vec<ZomeManifest>,
     ) -> Self {
-        DnaManifestCurrent::new(name, uid, properties, zomes).into()
+        DnaManifestCurrent::new(name, uid, properties, zomes, None, None).into()
     }
 
     /// Getter for properties
@@ -65,4 +65,18 @@ impl DnaManifest {
             DnaManifest::V1(manifest) => manifest.name.clone(),
         }
     }
+
+    /// Getter for integrity settings
+    pub fn integrity(&self) -> IntegritySettings {
+        match self {
+            DnaManifest::V1(manifest) => manifest.integrity.unwrap_or_default(),
+        }
+    }
+
+    /// Getter for the lineage and version
+    pub fn lineage(&self) -> Option<DnaLineage> {
+        match self {
+            DnaManifest::V1(manifest) => manifest.lineage.clone(),
+        }
+    }
 }
@@ -29,6 +29,19 @@ pub struct DnaManifestV1 {
     /// An array of zomes associated with your DNA.
     /// The order is significant: it determines initialization order.
     pub(crate) zomes: Vec<ZomeManifest>,
+
+    /// Limits on entry and link tag sizes that every agent in the network
+    /// enforces. These are part of the DNA hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub(crate) integrity: Option<IntegritySettings>,
+
+    /// The lineage this DNA is a version of, and its semantic version.
+    /// Apps can depend on a range of versions of a lineage rather than
+    /// on specific DNA hashes. This is part of the DNA hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub(crate) lineage: Option<DnaLineage>,
 }
 
 /// Manifest for an individual Zome
@@ -3,6 +3,8 @@
 //! - App-defined signals are produced via the `emit_signal` host function.
 //! - System-defined signals are produced in various places in the system
 
+use crate::app::AppStatus;
+use crate::app::InstalledAppId;
 use crate::impl_from;
 use holochain_serialized_bytes::prelude::*;
 use holochain_zome_types::prelude::*;
@@ -18,17 +20,58 @@ pub enum Signal {
 }
 
 /// A Signal which originates from within the Holochain system, as opposed to
-/// from within a Cell
+/// from within a Cell.
 ///
-/// TODO, decide what these will be. For instance, maybe there is a
-/// DataAvailable signal for doing async network requests
+/// System signals are sent to every app interface, so clients can react to
+/// changes instead of polling zome functions.
+/// Connections whose session is scoped to some apps only get the signals
+/// about those apps and the DNAs of their cells.
 #[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq, Eq)]
 pub enum SystemSignal {
-    /// Since we have no real system signals, we use a test signal for testing
-    /// TODO: replace instances of this with something real
+    /// A signal used only in tests.
     Test(String),
     /// A countersigning session has successfully completed.
     SuccessfulCountersigning(holo_hash::EntryHash),
+    /// A countersigning session failed. If the session entry was invalid,
+    /// the chain is unlocked and the entry was not committed. If it could not
+    /// be published to the entry authorities, the chain stays locked until
+    /// the session times out.
+    FailedCountersigning(holo_hash::EntryHash),
+    /// A countersigning session reached its end time without all the
+    /// signatures being gathered. The session entry will not be committed.
+    CountersigningTimedOut(holo_hash::EntryHash),
+    /// Every published op for an authored header has received
+    /// its required number of validation receipts.
+    HeaderSaturated(holo_hash::HeaderHash),
+    /// New ops have been integrated into the DHT database for a DNA.
+    DataIntegrated {
+        /// The DNA whose DHT database the ops were integrated into.
+        dna_hash: holo_hash::DnaHash,
+        /// The distinct bases of the newly integrated ops, limited to the
+        /// bases that data authored on this conductor lives at.
+        bases: Vec<holo_hash::AnyDhtHash>,
+    },
+    /// The status of an installed app has changed.
+    AppStatusChanged {
+        /// The app whose status changed.
+        installed_app_id: InstalledAppId,
+        /// The new status.
+        status: AppStatus,
+    },
+    /// A peer has joined the network space of a DNA.
+    PeerJoined {
+        /// The DNA of the space.
+        dna_hash: holo_hash::DnaHash,
+        /// The peer that joined.
+        agent: holo_hash::AgentPubKey,
+    },
+    /// A peer has left the network space of a DNA.
+    PeerLeft {
+        /// The DNA of the space.
+        dna_hash: holo_hash::DnaHash,
+        /// The peer that left.
+        agent: holo_hash::AgentPubKey,
+    },
 }
 
 /// Create a test signal
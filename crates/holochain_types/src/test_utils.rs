@@ -38,6 +38,8 @@ pub fn fake_dna_zomes_named(uid: &str, name: &str, zomes: Vec<(ZomeName, DnaWasm
             .unwrap(),
         uid: uid.to_string(),
         zomes: Vec::new(),
+        integrity: Default::default(),
+        lineage: None,
     };
     tokio_helper::block_forever_on(async move {
         let mut wasm_code = Vec::new();
@@ -12,6 +12,13 @@
 //! [`WebsocketReceiver`]
 //! ).
 //!
+//! Both can also use a unix domain socket instead of a tcp port
+//! by passing a url made with [`unix_socket_url`].
+//!
+//! Listeners serve TLS when the config has a [`TlsIdentity`] and the scheme
+//! is [`TLS_SCHEME`]. Clients connecting over TLS must pin the
+//! [`CertDigest`] of the listener's certificate.
+//!
 //! If you want to be able to shutdown the stream use [`WebsocketListener::bind_with_handle`]
 //! which will give you a tuple ([`ListenerHandle`], [`ListenerStream`]).
 //! You can use [`ListenerHandle::close`] to close immediately or
@@ -84,7 +91,12 @@ use holochain_serialized_bytes::prelude::*;
 use stream_cancel::Valve;
 use tracing::instrument;
 use url2::Url2;
+use util::addr_to_url;
+use util::connect_unix;
 use util::url_to_addr;
+use util::url_to_unix_path;
+use util::RawSocket;
+use util::UNIX_HANDSHAKE_URL;
 use websocket::Websocket;
 
 mod websocket_config;
@@ -106,21 +118,32 @@ pub use websocket_receiver::*;
 mod websocket;
 
 mod util;
+pub use util::unix_socket_url;
+pub use util::UNIX_SCHEME;
+
+mod tls;
+pub use tls::CertDigest;
+pub use tls::TlsIdentity;
+pub use tls::TLS_SCHEME;
 
 #[instrument(skip(config))]
 /// Create a new external websocket connection.
+///
+/// Connects over a unix domain socket if the url has the [`UNIX_SCHEME`],
+/// see [`unix_socket_url`].
 pub async fn connect(
     url: Url2,
     config: Arc<WebsocketConfig>,
 ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
-    let addr = url_to_addr(&url, config.scheme).await?;
-    let socket = tokio::net::TcpStream::connect(addr).await?;
-    // TODO: find equivalent of this in new tokio
-    // socket.set_keepalive(Some(std::time::Duration::from_secs(
-    //     config.tcp_keepalive_s as u64,
-    // )))?;
+    let (socket, remote_addr, handshake_url) = match url_to_unix_path(&url) {
+        Some(path) => (connect_unix(&path).await?, url.clone(), UNIX_HANDSHAKE_URL),
+        None => {
+            let (socket, remote_addr) = connect_tcp(&url, &config).await?;
+            (socket, remote_addr, url.as_str())
+        }
+    };
     let (socket, _) = tokio_tungstenite::client_async_with_config(
-        url.as_str(),
+        handshake_url,
         socket,
         Some(config.to_tungstenite()),
     )
@@ -132,7 +155,34 @@ pub async fn connect(
     // ends when creating a client
     let (exit, valve) = Valve::new();
     exit.disable();
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, remote_addr, valve)
+}
+
+/// Connect over tcp, and tls if the scheme is [`TLS_SCHEME`].
+async fn connect_tcp(
+    url: &Url2,
+    config: &WebsocketConfig,
+) -> WebsocketResult<(Box<dyn RawSocket>, Url2)> {
+    let addr = url_to_addr(url, config.scheme).await?;
+    let socket = tokio::net::TcpStream::connect(addr).await?;
+    // TODO: find equivalent of this in new tokio
+    // socket.set_keepalive(Some(std::time::Duration::from_secs(
+    //     config.tcp_keepalive_s as u64,
+    // )))?;
+    let remote_addr = addr_to_url(socket.peer_addr()?, config.scheme);
+    if config.scheme != TLS_SCHEME {
+        return Ok((Box::new(socket), remote_addr));
+    }
+    let pin = config.tls_pin.ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "connecting over wss needs the listener's certificate digest to be pinned",
+        )
+    })?;
+    let socket = tls::connector(pin)
+        .connect(tls::server_name(), socket)
+        .await?;
+    Ok((Box::new(socket), remote_addr))
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
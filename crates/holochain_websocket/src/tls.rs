@@ -0,0 +1,203 @@
+//! TLS for websockets served and connected to over `wss`.
+//!
+//! Listeners serve TLS with a [`TlsIdentity`] loaded from PEM files or
+//! generated as a self-signed certificate. Clients don't check certificates
+//! against certificate authorities. Instead they pin the [`CertDigest`] of the
+//! certificate they expect the listener to have.
+
+use std::io::BufReader;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::path::Path;
+use std::sync::Arc;
+use tokio_rustls::rustls;
+
+/// The url scheme for websockets over TLS.
+pub const TLS_SCHEME: &str = "wss";
+
+/// A certificate and private key that a listener serves `wss` with.
+#[derive(Clone)]
+pub struct TlsIdentity {
+    cert_chain: Vec<rustls::Certificate>,
+    private_key: rustls::PrivateKey,
+}
+
+impl TlsIdentity {
+    /// Load a PEM encoded certificate chain and a PEM encoded
+    /// PKCS#8 or RSA private key.
+    pub fn from_pem_files(cert_path: &Path, key_path: &Path) -> Result<Self> {
+        let cert_file = std::fs::File::open(cert_path)?;
+        let cert_chain = rustls::internal::pemfile::certs(&mut BufReader::new(cert_file))
+            .ok()
+            .filter(|certs| !certs.is_empty())
+            .ok_or_else(|| {
+                invalid_input(format!(
+                    "'{}' does not contain a PEM certificate",
+                    cert_path.display()
+                ))
+            })?;
+        let key_pem = std::fs::read(key_path)?;
+        let private_key = rustls::internal::pemfile::pkcs8_private_keys(&mut &key_pem[..])
+            .ok()
+            .filter(|keys| !keys.is_empty())
+            .or_else(|| rustls::internal::pemfile::rsa_private_keys(&mut &key_pem[..]).ok())
+            .and_then(|keys| keys.into_iter().next())
+            .ok_or_else(|| {
+                invalid_input(format!(
+                    "'{}' does not contain a PEM PKCS#8 or RSA private key",
+                    key_path.display()
+                ))
+            })?;
+        let identity = Self {
+            cert_chain,
+            private_key,
+        };
+        // Catch a key that doesn't match the certificate now
+        // rather than on the first connection.
+        identity.acceptor()?;
+        Ok(identity)
+    }
+
+    /// Generate a new self-signed certificate for these host names.
+    pub fn self_signed(subject_alt_names: Vec<String>) -> Result<Self> {
+        let cert = rcgen::generate_simple_self_signed(subject_alt_names)
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
+        let cert_der = cert
+            .serialize_der()
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
+        Ok(Self {
+            cert_chain: vec![rustls::Certificate(cert_der)],
+            private_key: rustls::PrivateKey(cert.serialize_private_key_der()),
+        })
+    }
+
+    /// The digest clients pin to connect to a listener with this identity.
+    pub fn cert_digest(&self) -> CertDigest {
+        CertDigest::of(self.cert_der())
+    }
+
+    /// The DER encoded certificate,
+    /// e.g. to add to the certificates a browser trusts.
+    pub fn cert_der(&self) -> &[u8] {
+        &self.cert_chain[0].0
+    }
+
+    pub(crate) fn acceptor(&self) -> Result<tokio_rustls::TlsAcceptor> {
+        let mut config = rustls::ServerConfig::new(rustls::NoClientAuth::new());
+        config
+            .set_single_cert(self.cert_chain.clone(), self.private_key.clone())
+            .map_err(|e| invalid_input(e.to_string()))?;
+        Ok(Arc::new(config).into())
+    }
+}
+
+impl std::fmt::Debug for TlsIdentity {
+    // Keep the private key out of the logs.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsIdentity")
+            .field("cert_digest", &self.cert_digest())
+            .finish()
+    }
+}
+
+/// The blake2b-256 digest of a DER encoded certificate.
+/// Displayed and parsed as url safe base64.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CertDigest([u8; 32]);
+
+impl CertDigest {
+    /// The digest of this DER encoded certificate.
+    pub fn of(cert_der: &[u8]) -> Self {
+        let hash = blake2b_simd::Params::new().hash_length(32).hash(cert_der);
+        let mut digest = [0; 32];
+        digest.copy_from_slice(hash.as_bytes());
+        Self(digest)
+    }
+}
+
+impl std::fmt::Display for CertDigest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&base64::encode_config(self.0, base64::URL_SAFE_NO_PAD))
+    }
+}
+
+impl std::fmt::Debug for CertDigest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CertDigest({})", self)
+    }
+}
+
+impl std::str::FromStr for CertDigest {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bytes = base64::decode_config(s.trim(), base64::URL_SAFE_NO_PAD)
+            .map_err(|e| invalid_input(e.to_string()))?;
+        if bytes.len() != 32 {
+            return Err(invalid_input(format!(
+                "a certificate digest is 32 bytes, got {}",
+                bytes.len()
+            )));
+        }
+        let mut digest = [0; 32];
+        digest.copy_from_slice(&bytes);
+        Ok(Self(digest))
+    }
+}
+
+/// internal helper to make a connector that only accepts the pinned certificate
+pub(crate) fn connector(pin: CertDigest) -> tokio_rustls::TlsConnector {
+    let mut config = rustls::ClientConfig::new();
+    config
+        .dangerous()
+        .set_certificate_verifier(Arc::new(PinnedCertVerifier(pin)));
+    Arc::new(config).into()
+}
+
+/// The name sent to the listener when connecting.
+/// It isn't checked because the certificate is pinned,
+/// and ip addresses can't be used as names.
+pub(crate) fn server_name() -> tokio_rustls::webpki::DNSNameRef<'static> {
+    tokio_rustls::webpki::DNSNameRef::try_from_ascii_str("localhost")
+        .expect("localhost is a valid dns name")
+}
+
+struct PinnedCertVerifier(CertDigest);
+
+impl rustls::ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        _roots: &rustls::RootCertStore,
+        presented_certs: &[rustls::Certificate],
+        _dns_name: tokio_rustls::webpki::DNSNameRef,
+        _ocsp_response: &[u8],
+    ) -> std::result::Result<rustls::ServerCertVerified, rustls::TLSError> {
+        match presented_certs.first() {
+            Some(cert) if CertDigest::of(&cert.0) == self.0 => {
+                Ok(rustls::ServerCertVerified::assertion())
+            }
+            _ => Err(rustls::TLSError::General(
+                "the certificate does not match the pinned digest".into(),
+            )),
+        }
+    }
+}
+
+fn invalid_input(msg: String) -> Error {
+    Error::new(ErrorKind::InvalidInput, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cert_digest_round_trips() {
+        let identity = TlsIdentity::self_signed(vec!["localhost".into()]).unwrap();
+        let digest = identity.cert_digest();
+        assert_eq!(digest, digest.to_string().parse().unwrap());
+        assert!("not a digest".parse::<CertDigest>().is_err());
+        assert!(identity.acceptor().is_ok());
+    }
+}
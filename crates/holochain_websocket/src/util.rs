@@ -6,7 +6,27 @@ use url2::{url2, Url2};
 
 use std::io::{Error, ErrorKind, Result};
 
-pub(crate) type ToFromSocket = tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>;
+/// A connected stream that the websocket protocol runs over,
+/// either a tcp or a unix domain socket.
+pub(crate) trait RawSocket:
+    tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static
+{
+}
+
+impl<T> RawSocket for T where
+    T: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static
+{
+}
+
+pub(crate) type ToFromSocket = tokio_tungstenite::WebSocketStream<Box<dyn RawSocket>>;
+
+/// The url scheme for websockets over a unix domain socket,
+/// e.g. `unix:///run/holochain/admin.sock`.
+pub const UNIX_SCHEME: &str = "unix";
+
+/// The url used for the websocket handshake over a unix domain socket,
+/// which has no host or port of its own.
+pub(crate) const UNIX_HANDSHAKE_URL: &str = "ws://localhost/";
 
 /// Amount of time to spend waiting for channels to empty before forcing them to close.
 pub(crate) const CLOSE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
@@ -16,6 +36,37 @@ pub(crate) fn addr_to_url(a: SocketAddr, scheme: &str) -> Url2 {
     url2!("{}://{}", scheme, a)
 }
 
+/// The url of a websocket served over the unix domain socket at this path.
+/// Relative paths are resolved against the current directory.
+pub fn unix_socket_url(path: &std::path::Path) -> Result<Url2> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+    let url = url2!("{}://{}", UNIX_SCHEME, path.display());
+    // Keep it simple and only allow paths that don't need escaping in a url.
+    if url_to_unix_path(&url).as_ref() != Some(&path) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "socket path '{}' can't be used in a url, choose a path without special characters",
+                path.display()
+            ),
+        ));
+    }
+    Ok(url)
+}
+
+/// internal helper to get the socket path out of a unix socket url
+pub(crate) fn url_to_unix_path(url: &Url2) -> Option<std::path::PathBuf> {
+    if url.scheme() == UNIX_SCHEME && url.host_str().map_or(true, str::is_empty) {
+        Some(url.path().into())
+    } else {
+        None
+    }
+}
+
 /// internal helper convert urls to socket addrs for binding / connection
 pub(crate) async fn url_to_addr(url: &Url2, scheme: &str) -> Result<SocketAddr> {
     if url.scheme() != scheme || url.host_str().is_none() || url.port().is_none() {
@@ -52,3 +103,21 @@ pub(crate) async fn url_to_addr(url: &Url2, scheme: &str) -> Result<SocketAddr>
         format!("could not parse '{}', as 'host:port'", rendered),
     ))
 }
+
+/// internal helper to connect to a unix domain socket
+#[cfg(unix)]
+pub(crate) async fn connect_unix(path: &std::path::Path) -> Result<Box<dyn RawSocket>> {
+    Ok(Box::new(tokio::net::UnixStream::connect(path).await?))
+}
+
+/// internal helper to connect to a unix domain socket
+#[cfg(not(unix))]
+pub(crate) async fn connect_unix(path: &std::path::Path) -> Result<Box<dyn RawSocket>> {
+    Err(Error::new(
+        ErrorKind::Other,
+        format!(
+            "can't connect to '{}', unix domain sockets are not supported on this platform",
+            path.display()
+        ),
+    ))
+}
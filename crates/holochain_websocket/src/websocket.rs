@@ -16,7 +16,6 @@ use ghost_actor::*;
 use tungstenite::protocol::frame::coding::CloseCode;
 use tungstenite::protocol::CloseFrame;
 
-use crate::util::addr_to_url;
 use crate::util::ToFromSocket;
 use crate::util::CLOSE_TIMEOUT;
 use crate::CancelResponse;
@@ -124,13 +123,10 @@ impl Websocket {
     pub fn create_ends(
         config: Arc<WebsocketConfig>,
         socket: ToFromSocket,
+        remote_addr: url2::Url2,
         listener_shutdown: Valve,
     ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
-        let remote_addr = url2::url2!(
-            "{}#{}",
-            addr_to_url(socket.get_ref().peer_addr()?, config.scheme),
-            nanoid::nanoid!(),
-        );
+        let remote_addr = url2::url2!("{}#{}", remote_addr, nanoid::nanoid!());
 
         // Channel to the websocket from the application
         let (tx_to_websocket, rx_to_websocket) = tokio::sync::mpsc::channel(config.max_send_queue);
@@ -1,5 +1,8 @@
 //! defines a builder-style config struct for setting up websockets
 
+use crate::CertDigest;
+use crate::TlsIdentity;
+
 /// A builder-style config struct for setting up websockets.
 #[derive(Debug)]
 pub struct WebsocketConfig {
@@ -26,6 +29,14 @@ pub struct WebsocketConfig {
 
     /// Maximum number of pending new incoming connections. [default = 255]
     pub max_pending_connections: usize,
+
+    /// The certificate and key listeners serve TLS with.
+    /// Required when the scheme is "wss". [default = None]
+    pub tls: Option<TlsIdentity>,
+
+    /// The digest of the certificate a "wss" listener must present
+    /// for a client to connect to it. [default = None]
+    pub tls_pin: Option<CertDigest>,
 }
 
 impl Default for WebsocketConfig {
@@ -38,6 +49,8 @@ impl Default for WebsocketConfig {
             max_message_size: 64 << 20,
             max_frame_size: 16 << 20,
             max_pending_connections: 255,
+            tls: None,
+            tls_pin: None,
         }
     }
 }
@@ -78,6 +91,22 @@ impl WebsocketConfig {
         self.max_frame_size = max;
         self
     }
+
+    /// Builder-style setter.
+    /// Also sets the scheme to "wss".
+    pub fn tls(mut self, identity: TlsIdentity) -> Self {
+        self.scheme = crate::TLS_SCHEME;
+        self.tls = Some(identity);
+        self
+    }
+
+    /// Builder-style setter.
+    /// Also sets the scheme to "wss".
+    pub fn tls_pin(mut self, pin: CertDigest) -> Self {
+        self.scheme = crate::TLS_SCHEME;
+        self.tls_pin = Some(pin);
+        self
+    }
 }
 
 /// internal helper to convert our configs into tungstenite configs
@@ -13,12 +13,15 @@ use url2::Url2;
 
 use crate::util::addr_to_url;
 use crate::util::url_to_addr;
+use crate::util::url_to_unix_path;
+use crate::util::RawSocket;
 use crate::websocket::Websocket;
 use crate::WebsocketConfig;
 use crate::WebsocketError;
 use crate::WebsocketReceiver;
 use crate::WebsocketResult;
 use crate::WebsocketSender;
+use crate::TLS_SCHEME;
 
 /// Listens for connecting clients.
 ///
@@ -187,11 +190,29 @@ impl futures::stream::Stream for WebsocketListener {
 async fn websocket_bind(
     addr: Url2,
     config: Arc<WebsocketConfig>,
-) -> WebsocketResult<(
-    ListenerHandle,
-    impl futures::stream::Stream<Item = ListenerItem>,
-)> {
+) -> WebsocketResult<(ListenerHandle, ListenerStream)> {
+    if let Some(path) = url_to_unix_path(&addr) {
+        return unix_bind(addr, path, config).await;
+    }
     let addr = url_to_addr(&addr, config.scheme).await?;
+    let acceptor = match (&config.tls, config.scheme == TLS_SCHEME) {
+        (Some(identity), true) => Some(identity.acceptor()?),
+        (None, false) => None,
+        (None, true) => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "listening on wss needs a tls identity in the config",
+            )
+            .into())
+        }
+        (Some(_), false) => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("tls can't be served with the '{}' scheme", config.scheme),
+            )
+            .into())
+        }
+    };
     let socket = match &addr {
         SocketAddr::V4(_) => net2::TcpBuilder::new_v4()?,
         SocketAddr::V6(_) => net2::TcpBuilder::new_v6()?,
@@ -212,12 +233,24 @@ async fn websocket_bind(
         .map_ok({
             let config = config.clone();
             let valve = valve.clone();
-            move |socket_result| connect(config.clone(), socket_result, valve.clone())
+            move |socket: tokio::net::TcpStream| {
+                let config = config.clone();
+                let valve = valve.clone();
+                let acceptor = acceptor.clone();
+                async move {
+                    let remote_addr = addr_to_url(socket.peer_addr()?, config.scheme);
+                    let socket: Box<dyn RawSocket> = match acceptor {
+                        Some(acceptor) => Box::new(acceptor.accept(socket).await?),
+                        None => Box::new(socket),
+                    };
+                    connect(config, socket, remote_addr, valve).await
+                }
+            }
         })
         .try_buffer_unordered(config.max_pending_connections);
     tracing::debug!(sever_listening_on = ?local_addr);
 
-    let stream = valve.wrap(buffered_listener);
+    let stream = valve.wrap(buffered_listener).boxed();
 
     let listener_handle = ListenerHandle {
         shutdown,
@@ -227,10 +260,99 @@ async fn websocket_bind(
     Ok((listener_handle, stream))
 }
 
+/// Listen on a unix domain socket.
+///
+/// The socket file is made readable and writable only by the user running the
+/// listener, so file permissions decide who can connect. Put the socket in a
+/// directory only that user can enter to close the window between binding and
+/// setting the permissions.
+#[cfg(unix)]
+async fn unix_bind(
+    local_addr: Url2,
+    path: std::path::PathBuf,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(ListenerHandle, ListenerStream)> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::fs::PermissionsExt;
+
+    if config.tls.is_some() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "tls is not served on unix domain sockets",
+        )
+        .into());
+    }
+
+    // A socket file left behind by a listener that wasn't shut down cleanly
+    // stops us binding, but one that is still in use must be left alone.
+    if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+        if metadata.file_type().is_socket() {
+            if tokio::net::UnixStream::connect(&path).await.is_ok() {
+                return Err(Error::new(
+                    ErrorKind::AddrInUse,
+                    format!("'{}' is already being listened on", path.display()),
+                )
+                .into());
+            }
+            std::fs::remove_file(&path)?;
+        }
+    }
+    let listener = tokio::net::UnixListener::bind(&path)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    let listener_stream = tokio_stream::wrappers::UnixListenerStream::new(listener);
+
+    // Setup proper shutdown
+    let (shutdown, valve) = Valve::new();
+
+    let buffered_listener = listener_stream
+        .map_err(WebsocketError::from)
+        .map_ok({
+            let config = config.clone();
+            let valve = valve.clone();
+            let remote_addr = local_addr.clone();
+            move |socket| {
+                connect(
+                    config.clone(),
+                    Box::new(socket),
+                    remote_addr.clone(),
+                    valve.clone(),
+                )
+            }
+        })
+        .try_buffer_unordered(config.max_pending_connections);
+    tracing::debug!(sever_listening_on = ?local_addr);
+
+    let stream = valve.wrap(buffered_listener).boxed();
+
+    let listener_handle = ListenerHandle {
+        shutdown,
+        config,
+        local_addr,
+    };
+    Ok((listener_handle, stream))
+}
+
+#[cfg(not(unix))]
+async fn unix_bind(
+    _local_addr: Url2,
+    path: std::path::PathBuf,
+    _config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(ListenerHandle, ListenerStream)> {
+    Err(Error::new(
+        ErrorKind::Other,
+        format!(
+            "can't listen on '{}', unix domain sockets are not supported on this platform",
+            path.display()
+        ),
+    )
+    .into())
+}
+
 #[instrument(skip(config, socket, valve))]
 async fn connect(
     config: Arc<WebsocketConfig>,
-    socket: tokio::net::TcpStream,
+    socket: Box<dyn RawSocket>,
+    remote_addr: Url2,
     valve: Valve,
 ) -> WebsocketResult<Pair> {
     // TODO: find alternative to set the keepalive
@@ -239,7 +361,7 @@ async fn connect(
     // )))?;
     tracing::debug!(
         message = "accepted incoming raw socket",
-        remote_addr = %socket.peer_addr()?,
+        remote_addr = %remote_addr,
     );
     let socket = tokio_tungstenite::accept_async_with_config(
         socket,
@@ -253,5 +375,5 @@ async fn connect(
     .await
     .map_err(|e| Error::new(ErrorKind::Other, e))?;
 
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, remote_addr, valve)
 }
@@ -5,6 +5,7 @@ use holochain_serialized_bytes::prelude::*;
 use holochain_websocket::connect;
 use holochain_websocket::ListenerHandle;
 use holochain_websocket::ListenerItem;
+use holochain_websocket::TlsIdentity;
 use holochain_websocket::WebsocketConfig;
 use holochain_websocket::WebsocketError;
 use holochain_websocket::WebsocketListener;
@@ -452,3 +453,112 @@ async fn cancel_response() {
     c_jh.await.unwrap();
     s_jh.await.unwrap();
 }
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_request_over_unix_socket() {
+    use std::os::unix::fs::PermissionsExt;
+    observability::test_run().ok();
+    let path = std::env::temp_dir().join(format!("hc-ws-{}.sock", nanoid::nanoid!(8)));
+    let addr = holochain_websocket::unix_socket_url(&path).unwrap();
+    let (handle, mut listener) =
+        WebsocketListener::bind_with_handle(addr, Arc::new(WebsocketConfig::default()))
+            .await
+            .unwrap();
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    let jh = tokio::task::spawn(async move {
+        let (_, mut receiver) = listener.next().await.unwrap().unwrap();
+        let (msg, resp) = receiver.next().await.unwrap();
+        let msg: TestString = msg.try_into().unwrap();
+        assert_eq!(msg.0, "Hey from client");
+        resp.respond(TestString("Bye from server".into()).try_into().unwrap())
+            .await
+            .unwrap();
+    });
+
+    let binding = handle.local_addr().clone();
+    let (mut sender, _receiver) = connect(binding, Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    let msg: TestString = sender
+        .request(TestString("Hey from client".into()))
+        .await
+        .unwrap();
+    assert_eq!(msg.0, "Bye from server");
+
+    jh.await.unwrap();
+
+    // - A second listener can't take over a socket that is in use
+    assert!(WebsocketListener::bind(
+        handle.local_addr().clone(),
+        Arc::new(WebsocketConfig::default())
+    )
+    .await
+    .is_err());
+
+    handle.close();
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_request_over_tls_with_pinned_cert() {
+    observability::test_run().ok();
+    let identity = TlsIdentity::self_signed(vec!["localhost".into()]).unwrap();
+    let pin = identity.cert_digest();
+    let (handle, mut listener) = WebsocketListener::bind_with_handle(
+        url2!("wss://127.0.0.1:0"),
+        Arc::new(WebsocketConfig::default().tls(identity)),
+    )
+    .await
+    .unwrap();
+    let binding = handle.local_addr().clone();
+    assert_eq!(binding.scheme(), "wss");
+
+    // - A client pinning another certificate doesn't connect
+    let other_pin = TlsIdentity::self_signed(vec!["localhost".into()])
+        .unwrap()
+        .cert_digest();
+    assert!(connect(
+        binding.clone(),
+        Arc::new(WebsocketConfig::default().tls_pin(other_pin))
+    )
+    .await
+    .is_err());
+    // - Nor does one without a pin
+    assert!(connect(
+        binding.clone(),
+        Arc::new(WebsocketConfig::default().scheme("wss"))
+    )
+    .await
+    .is_err());
+
+    let jh = tokio::task::spawn(async move {
+        // Skip the failed handshakes from the clients above.
+        let (_, mut receiver) = loop {
+            if let Ok(pair) = listener.next().await.unwrap() {
+                break pair;
+            }
+        };
+        let (msg, resp) = receiver.next().await.unwrap();
+        let msg: TestString = msg.try_into().unwrap();
+        assert_eq!(msg.0, "Hey from client");
+        resp.respond(TestString("Bye from server".into()).try_into().unwrap())
+            .await
+            .unwrap();
+    });
+
+    let (mut sender, _receiver) =
+        connect(binding, Arc::new(WebsocketConfig::default().tls_pin(pin)))
+            .await
+            .unwrap();
+    let msg: TestString = sender
+        .request(TestString("Hey from client".into()))
+        .await
+        .unwrap();
+    assert_eq!(msg.0, "Bye from server");
+
+    jh.await.unwrap();
+    handle.close();
+}
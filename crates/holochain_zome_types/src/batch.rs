@@ -0,0 +1,56 @@
+//! Types for applying several source chain writes in a single host call.
+
+use crate::entry::CreateInput;
+use crate::entry::DeleteInput;
+use crate::entry::UpdateInput;
+use crate::link::CreateLinkInput;
+use crate::link::DeleteLinkInput;
+use holochain_serialized_bytes::prelude::*;
+
+/// A single write applied as part of a `commit_batch` call.
+///
+/// Each op is applied to the scratch in order so later ops can reference the
+/// headers and entries written by earlier ops in the same batch.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum BatchOp {
+    /// Create an entry.
+    Create(CreateInput),
+    /// Update an entry.
+    Update(UpdateInput),
+    /// Delete an entry.
+    Delete(DeleteInput),
+    /// Create a link.
+    CreateLink(CreateLinkInput),
+    /// Delete a link.
+    DeleteLink(DeleteLinkInput),
+}
+
+impl From<CreateInput> for BatchOp {
+    fn from(create_input: CreateInput) -> Self {
+        Self::Create(create_input)
+    }
+}
+
+impl From<UpdateInput> for BatchOp {
+    fn from(update_input: UpdateInput) -> Self {
+        Self::Update(update_input)
+    }
+}
+
+impl From<DeleteInput> for BatchOp {
+    fn from(delete_input: DeleteInput) -> Self {
+        Self::Delete(delete_input)
+    }
+}
+
+impl From<CreateLinkInput> for BatchOp {
+    fn from(create_link_input: CreateLinkInput) -> Self {
+        Self::CreateLink(create_link_input)
+    }
+}
+
+impl From<DeleteLinkInput> for BatchOp {
+    fn from(delete_link_input: DeleteLinkInput) -> Self {
+        Self::DeleteLink(delete_link_input)
+    }
+}
@@ -0,0 +1,164 @@
+//! Types for storing payloads larger than an entry as a set of content
+//! addressed chunk entries.
+
+use crate::element::Element;
+use crate::entry::AppEntryBytes;
+use crate::entry::CreateInput;
+use crate::entry::Entry;
+use crate::entry::GetOptions;
+use crate::entry_def::EntryDefId;
+use crate::header::ChainTopOrdering;
+use holo_hash::EntryHash;
+use holochain_serialized_bytes::prelude::*;
+use holochain_wasmer_common::WasmError;
+
+/// The entry def id that [ `Chunk` ] entries are committed with.
+pub const CHUNK_ENTRY_DEF_ID: &str = "hdk.chunk";
+
+/// The entry def id that [ `ChunkSet` ] entries are committed with.
+pub const CHUNK_SET_ENTRY_DEF_ID: &str = "hdk.chunk_set";
+
+/// One piece of a chunked payload, committed as its own entry.
+///
+/// Chunks are content addressed so identical chunks of different payloads
+/// are the same entry on the DHT.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct Chunk(#[serde(with = "serde_bytes")] Vec<u8>);
+
+impl Chunk {
+    /// Constructor.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// The bytes of this piece of the payload.
+    pub fn bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Consume into the bytes of this piece of the payload.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+/// The manifest of a chunked payload.
+///
+/// Lists the hash of every chunk in order and the total size of the payload.
+/// The hash of this entry is all that is needed to fetch the payload again.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct ChunkSet {
+    size: u64,
+    chunks: Vec<EntryHash>,
+}
+
+impl ChunkSet {
+    /// Constructor.
+    pub fn new(size: u64, chunks: Vec<EntryHash>) -> Self {
+        Self { size, chunks }
+    }
+
+    /// The total size of the payload in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// The hashes of the chunks in payload order.
+    pub fn chunks(&self) -> &[EntryHash] {
+        &self.chunks
+    }
+}
+
+/// Input to the `get_chunks` host fn.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, SerializedBytes)]
+pub struct GetChunksInput {
+    /// The hash of the [ `ChunkSet` ] entry.
+    pub chunk_set_hash: EntryHash,
+    /// The chunks to get by their position in the [ `ChunkSet` ].
+    /// A range past the end of the set is cut short.
+    pub range: std::ops::Range<u32>,
+    /// Options for getting the manifest and the chunks.
+    pub get_options: GetOptions,
+}
+
+impl GetChunksInput {
+    /// Constructor.
+    pub fn new(
+        chunk_set_hash: EntryHash,
+        range: std::ops::Range<u32>,
+        get_options: GetOptions,
+    ) -> Self {
+        Self {
+            chunk_set_hash,
+            range,
+            get_options,
+        }
+    }
+}
+
+/// The conversions an app entry type gets from the HDK's `app_entry!` macro,
+/// which can't be used from this crate.
+macro_rules! chunk_entry {
+    ( $t:ident, $entry_def_id:expr ) => {
+        impl TryFrom<&Entry> for $t {
+            type Error = WasmError;
+            fn try_from(entry: &Entry) -> Result<Self, Self::Error> {
+                match entry {
+                    Entry::App(bytes) => {
+                        Ok(Self::try_from(SerializedBytes::from(bytes.to_owned()))?)
+                    }
+                    _ => Err(WasmError::Guest(format!(
+                        "{:?} is not an Entry::App so can't be a {}",
+                        entry,
+                        stringify!($t)
+                    ))),
+                }
+            }
+        }
+
+        impl TryFrom<&Element> for $t {
+            type Error = WasmError;
+            fn try_from(element: &Element) -> Result<Self, Self::Error> {
+                match element.entry().as_option() {
+                    Some(entry) => Self::try_from(entry),
+                    None => Err(WasmError::Guest(format!(
+                        "Element {} has no entry so can't be a {}",
+                        element.header_address(),
+                        stringify!($t)
+                    ))),
+                }
+            }
+        }
+
+        impl TryFrom<&$t> for Entry {
+            type Error = WasmError;
+            fn try_from(t: &$t) -> Result<Self, Self::Error> {
+                Ok(Self::App(
+                    AppEntryBytes::try_from(SerializedBytes::try_from(t)?)
+                        .map_err(|entry_error| WasmError::Guest(entry_error.to_string()))?,
+                ))
+            }
+        }
+
+        impl TryFrom<$t> for Entry {
+            type Error = WasmError;
+            fn try_from(t: $t) -> Result<Self, Self::Error> {
+                Self::try_from(&t)
+            }
+        }
+
+        impl TryFrom<$t> for CreateInput {
+            type Error = WasmError;
+            fn try_from(t: $t) -> Result<Self, Self::Error> {
+                Ok(Self::new(
+                    EntryDefId::App($entry_def_id.into()),
+                    Entry::try_from(&t)?,
+                    ChainTopOrdering::default(),
+                ))
+            }
+        }
+    };
+}
+
+chunk_entry!(Chunk, CHUNK_ENTRY_DEF_ID);
+chunk_entry!(ChunkSet, CHUNK_SET_ENTRY_DEF_ID);
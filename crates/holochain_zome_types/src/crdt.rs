@@ -1,2 +1,210 @@
-#[derive(Default, Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
-pub struct CrdtType;
+//! Conflict resolution for concurrent updates to an entry.
+
+use crate::element::Element;
+use crate::element::SignedHeaderHashed;
+use holo_hash::AgentPubKey;
+
+/// How concurrent `Update`s to the same entry are resolved.
+///
+/// When an entry has updates, a `get` of the entry hash from a zome call with
+/// [`GetStrategy::Latest`](crate::entry::GetStrategy::Latest) follows the
+/// winning update picked by this strategy until it reaches an entry with
+/// no updates, and returns that element.
+///
+/// Only the winning element is returned. The conflict set the winner was
+/// picked from is every update of an entry on the way, which `get_details`
+/// of that entry hash already returns as its updates, so it isn't repeated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum CrdtType {
+    /// Updates are not resolved.
+    /// `get` returns the requested entry and the updates are left
+    /// for the app to inspect with `get_details`.
+    Unresolved,
+    /// The update with the latest header timestamp wins.
+    /// Ties are broken by the header hash.
+    LastWriterWins,
+    /// Only updates by the author of the updated element count,
+    /// and the latest of those wins.
+    SingleAuthor,
+    /// The zome defining the entry picks the winner in a `resolve_conflict`
+    /// extern, which takes a [`ResolveConflictInput`] and returns an
+    /// `Option<HeaderHash>` of the winning update.
+    /// A `get` made within `resolve_conflict` leaves conflicts unresolved.
+    ResolveCallback,
+}
+
+impl Default for CrdtType {
+    fn default() -> Self {
+        Self::Unresolved
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for CrdtType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // CrdtType used to be a unit struct, so entry defs serialized
+        // before strategies existed hold a unit value.
+        #[derive(serde::Deserialize)]
+        enum Strategy {
+            Unresolved,
+            LastWriterWins,
+            SingleAuthor,
+            ResolveCallback,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Compat {
+            Legacy(()),
+            Strategy(Strategy),
+        }
+
+        Ok(match Compat::deserialize(deserializer)? {
+            Compat::Legacy(()) | Compat::Strategy(Strategy::Unresolved) => Self::Unresolved,
+            Compat::Strategy(Strategy::LastWriterWins) => Self::LastWriterWins,
+            Compat::Strategy(Strategy::SingleAuthor) => Self::SingleAuthor,
+            Compat::Strategy(Strategy::ResolveCallback) => Self::ResolveCallback,
+        })
+    }
+}
+
+impl CrdtType {
+    /// Pick the winning update for the strategies that holochain resolves
+    /// itself. Returns `None` if there is no eligible update, or if the
+    /// strategy is [`CrdtType::Unresolved`] or [`CrdtType::ResolveCallback`].
+    pub fn pick_winner<'a>(
+        &self,
+        original_author: &AgentPubKey,
+        updates: &'a [SignedHeaderHashed],
+    ) -> Option<&'a SignedHeaderHashed> {
+        let latest = |a: &&SignedHeaderHashed, b: &&SignedHeaderHashed| {
+            a.header()
+                .timestamp()
+                .cmp(&b.header().timestamp())
+                .then_with(|| a.as_hash().cmp(b.as_hash()))
+        };
+        match self {
+            Self::Unresolved | Self::ResolveCallback => None,
+            Self::LastWriterWins => updates.iter().max_by(latest),
+            Self::SingleAuthor => updates
+                .iter()
+                .filter(|u| u.header().author() == original_author)
+                .max_by(latest),
+        }
+    }
+}
+
+/// The input to a zome's `resolve_conflict` extern
+/// for entries with [`CrdtType::ResolveCallback`].
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ResolveConflictInput {
+    /// The element that was updated concurrently.
+    pub original: Element,
+    /// The conflict set: every update to the original entry.
+    pub conflicts: Vec<SignedHeaderHashed>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use holochain_serialized_bytes::prelude::*;
+
+    #[derive(serde::Serialize, serde::Deserialize, SerializedBytes, Debug)]
+    struct Legacy;
+
+    #[derive(serde::Serialize, serde::Deserialize, SerializedBytes, Debug)]
+    struct Wrap(CrdtType);
+
+    #[test]
+    fn crdt_type_round_trip_and_legacy() {
+        for crdt_type in [
+            CrdtType::Unresolved,
+            CrdtType::LastWriterWins,
+            CrdtType::SingleAuthor,
+            CrdtType::ResolveCallback,
+        ] {
+            let sb = SerializedBytes::try_from(Wrap(crdt_type)).unwrap();
+            assert_eq!(crdt_type, Wrap::try_from(sb).unwrap().0);
+        }
+
+        let sb = SerializedBytes::try_from(Legacy).unwrap();
+        let crdt_type: CrdtType = holochain_serialized_bytes::decode(sb.bytes()).unwrap();
+        assert_eq!(CrdtType::Unresolved, crdt_type);
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "fixturators")]
+mod pick_winner_test {
+    use super::*;
+    use crate::fixt::*;
+    use crate::timestamp::Timestamp;
+    use crate::HeaderHashed;
+    use ::fixt::prelude::*;
+
+    fn update(author: &AgentPubKey, micros: i64) -> SignedHeaderHashed {
+        let mut update = fixt!(Update);
+        update.author = author.clone();
+        update.timestamp = Timestamp::from_micros(micros);
+        SignedHeaderHashed::with_presigned(
+            HeaderHashed::from_content_sync(update.into()),
+            fixt!(Signature),
+        )
+    }
+
+    #[test]
+    fn last_writer_wins_picks_the_latest_update() {
+        let (alice, bob) = (fixt!(AgentPubKey), fixt!(AgentPubKey));
+        let updates = vec![update(&alice, 10), update(&bob, 30), update(&alice, 20)];
+        assert_eq!(
+            CrdtType::LastWriterWins.pick_winner(&alice, &updates),
+            Some(&updates[1])
+        );
+    }
+
+    #[test]
+    fn last_writer_wins_breaks_ties_by_header_hash() {
+        let (alice, bob) = (fixt!(AgentPubKey), fixt!(AgentPubKey));
+        let updates = vec![update(&alice, 10), update(&bob, 10)];
+        let highest_hash = updates.iter().max_by_key(|u| u.as_hash()).unwrap();
+        assert_eq!(
+            CrdtType::LastWriterWins.pick_winner(&alice, &updates),
+            Some(highest_hash)
+        );
+        // The order the updates arrive in doesn't matter.
+        let reversed: Vec<_> = updates.iter().rev().cloned().collect();
+        assert_eq!(
+            CrdtType::LastWriterWins.pick_winner(&alice, &reversed),
+            Some(highest_hash)
+        );
+    }
+
+    #[test]
+    fn single_author_ignores_other_authors() {
+        let (alice, bob) = (fixt!(AgentPubKey), fixt!(AgentPubKey));
+        let updates = vec![update(&alice, 10), update(&bob, 30), update(&alice, 20)];
+        assert_eq!(
+            CrdtType::SingleAuthor.pick_winner(&alice, &updates),
+            Some(&updates[2])
+        );
+        assert_eq!(
+            CrdtType::SingleAuthor.pick_winner(&fixt!(AgentPubKey), &updates),
+            None
+        );
+    }
+
+    #[test]
+    fn unresolved_and_callback_pick_nothing() {
+        let alice = fixt!(AgentPubKey);
+        let updates = vec![update(&alice, 10)];
+        assert_eq!(CrdtType::Unresolved.pick_winner(&alice, &updates), None);
+        assert_eq!(
+            CrdtType::ResolveCallback.pick_winner(&alice, &updates),
+            None
+        );
+        assert_eq!(CrdtType::LastWriterWins.pick_winner(&alice, &[]), None);
+    }
+}
@@ -44,6 +44,95 @@ pub struct DnaDef {
 
     /// A vector of zomes associated with your DNA.
     pub zomes: Zomes,
+
+    /// Limits that every agent enforces on this DNA's data during sys validation.
+    /// Left out of the serialized form when unset so existing DNA hashes don't change.
+    #[serde(default, skip_serializing_if = "IntegritySettings::is_default")]
+    #[cfg_attr(feature = "full-dna-def", builder(default))]
+    pub integrity: IntegritySettings,
+
+    /// The lineage this DNA belongs to and its semantic version within it,
+    /// which apps can select DNAs by.
+    /// Left out of the serialized form when unset so existing DNA hashes don't change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "full-dna-def", builder(default))]
+    pub lineage: Option<DnaLineage>,
+}
+
+/// Declares that a DNA is one version of a lineage of DNAs,
+/// e.g. the successive releases of one app's DNA.
+///
+/// The version is part of the DNA hash, so every release has a distinct hash.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct DnaLineage {
+    /// Identifies the lineage. DNAs with the same id are versions of each other.
+    pub id: String,
+
+    /// The [semantic version](https://semver.org) of this DNA within the lineage.
+    pub version: String,
+}
+
+/// The smallest entry size limit a DNA can set, in bytes.
+pub const MIN_ENTRY_SIZE_LIMIT: usize = 256;
+
+/// The link tag size limit used when a DNA doesn't set one, in bytes.
+pub const DEFAULT_TAG_SIZE_LIMIT: usize = 1000;
+
+/// The smallest link tag size limit a DNA can set, in bytes.
+pub const MIN_TAG_SIZE_LIMIT: usize = 32;
+
+/// The largest link tag size limit a DNA can set, in bytes.
+/// Tags are used as database keys so they can't grow much.
+pub const MAX_TAG_SIZE_LIMIT: usize = 4000;
+
+/// Network wide limits on the data in a DNA.
+///
+/// These are part of the DnaDef so they change the DnaHash, which means every
+/// agent on the network agrees on them and sys validation can reject anything
+/// that breaks them. Unset limits fall back to the conductor defaults.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct IntegritySettings {
+    /// App entries must be smaller than this, in bytes.
+    /// Must be between [`MIN_ENTRY_SIZE_LIMIT`] and [`ENTRY_SIZE_LIMIT`].
+    #[serde(default)]
+    pub max_entry_size: Option<u32>,
+
+    /// Link tags must be smaller than this, in bytes.
+    /// Must be between [`MIN_TAG_SIZE_LIMIT`] and [`MAX_TAG_SIZE_LIMIT`].
+    #[serde(default)]
+    pub max_tag_size: Option<u32>,
+}
+
+impl IntegritySettings {
+    /// True if no limits are set.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The entry size limit to enforce, clamped to the hard bounds.
+    pub fn entry_size_limit(&self) -> usize {
+        self.max_entry_size
+            .map(|size| (size as usize).clamp(MIN_ENTRY_SIZE_LIMIT, ENTRY_SIZE_LIMIT))
+            .unwrap_or(ENTRY_SIZE_LIMIT)
+    }
+
+    /// The link tag size limit to enforce, clamped to the hard bounds.
+    pub fn tag_size_limit(&self) -> usize {
+        self.max_tag_size
+            .map(|size| (size as usize).clamp(MIN_TAG_SIZE_LIMIT, MAX_TAG_SIZE_LIMIT))
+            .unwrap_or(DEFAULT_TAG_SIZE_LIMIT)
+    }
+
+    /// True if every set limit is within its hard bounds.
+    pub fn is_within_bounds(&self) -> bool {
+        self.max_entry_size
+            .map_or(true, |size| size as usize == self.entry_size_limit())
+            && self
+                .max_tag_size
+                .map_or(true, |size| size as usize == self.tag_size_limit())
+    }
 }
 
 #[cfg(feature = "test_utils")]
@@ -73,7 +73,7 @@ pub struct EntryDef {
     pub id: EntryDefId,
     /// Public or Private
     pub visibility: EntryVisibility,
-    /// TBD -- Special types of conflict resolution support from Holochain (e.g. Single-Author, )
+    /// How concurrent updates to entries of this type are resolved
     pub crdt_type: CrdtType,
     /// how many validations to receive before considered "network saturated" (MAX value of 50?)
     pub required_validations: RequiredValidations,
@@ -120,6 +120,11 @@ impl EntryDefs {
             .position(|entry_def| entry_def.id == entry_def_id)
             .map(|u_size| EntryDefIndex(u_size as u8))
     }
+
+    /// Get the entry def at this position, if there is one
+    pub fn get(&self, index: usize) -> Option<&EntryDef> {
+        self.0.get(index)
+    }
 }
 
 impl std::ops::Index<usize> for EntryDefs {
@@ -451,9 +451,7 @@ fixturator!(
 
 fixturator!(
     CrdtType;
-    curve Empty CrdtType;
-    curve Unpredictable CrdtType;
-    curve Predictable CrdtType;
+    unit variants [ Unresolved LastWriterWins SingleAuthor ResolveCallback ] empty Unresolved;
 );
 
 fixturator!(
@@ -696,6 +694,8 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Empty, get_fixt_index!())
             .next()
             .unwrap(),
+        integrity: Default::default(),
+        lineage: None,
     };
 
     curve Unpredictable DnaDef {
@@ -711,6 +711,8 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Unpredictable, get_fixt_index!())
             .next()
             .unwrap(),
+        integrity: Default::default(),
+        lineage: None,
     };
 
     curve Predictable DnaDef {
@@ -726,5 +728,7 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Predictable, get_fixt_index!())
             .next()
             .unwrap(),
+        integrity: Default::default(),
+        lineage: None,
     };
 );
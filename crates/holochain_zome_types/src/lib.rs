@@ -11,11 +11,13 @@
 
 #[allow(missing_docs)]
 pub mod agent_activity;
+pub mod batch;
 pub mod bytes;
 #[allow(missing_docs)]
 pub mod call;
 pub mod capability;
 pub mod cell;
+pub mod chunk;
 pub mod countersigning;
 #[allow(missing_docs)]
 pub mod crdt;
@@ -38,6 +40,7 @@ pub mod metadata;
 #[allow(missing_docs)]
 pub mod migrate_agent;
 pub mod prelude;
+pub mod publish;
 pub mod query;
 pub mod request;
 /// Schedule functions to run outside a direct zome call.
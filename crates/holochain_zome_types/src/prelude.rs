@@ -1,10 +1,12 @@
 //! Common types
 
 pub use crate::agent_activity::*;
+pub use crate::batch::*;
 pub use crate::bytes::*;
 pub use crate::call::*;
 pub use crate::capability::*;
 pub use crate::cell::*;
+pub use crate::chunk::*;
 pub use crate::countersigning::*;
 pub use crate::crdt::*;
 pub use crate::dna_def::*;
@@ -21,6 +23,7 @@ pub use crate::judged::*;
 pub use crate::link::*;
 pub use crate::metadata::*;
 pub use crate::migrate_agent::*;
+pub use crate::publish::*;
 pub use crate::query::ChainQueryFilter as QueryFilter;
 pub use crate::query::*;
 pub use crate::request::*;
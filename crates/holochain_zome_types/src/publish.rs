@@ -0,0 +1,44 @@
+//! Types for reporting how far a header's ops have been published.
+
+use holo_hash::DhtOpHash;
+use holo_hash::HeaderHash;
+use holochain_serialized_bytes::prelude::*;
+
+/// The publish status of a single op produced by an authored header.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct OpPublishStatus {
+    /// The hash of the op.
+    pub op_hash: DhtOpHash,
+    /// The type of the op, e.g. `StoreElement`.
+    pub op_type: String,
+    /// Whether the op has been published to the network at least once.
+    pub published: bool,
+    /// The number of validation receipts received for this op.
+    pub receipt_count: usize,
+    /// The number of receipts needed before the op stops being republished.
+    pub required_receipts: usize,
+}
+
+impl OpPublishStatus {
+    /// Has this op received all the receipts it needs?
+    pub fn is_saturated(&self) -> bool {
+        self.receipt_count >= self.required_receipts
+    }
+}
+
+/// The publish status of every public op produced by an authored header.
+/// Private entries are never published, so their `StoreEntry` op is not listed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct PublishStatus {
+    /// The header these ops were produced from.
+    pub header_hash: HeaderHash,
+    /// The status of each op.
+    pub ops: Vec<OpPublishStatus>,
+}
+
+impl PublishStatus {
+    /// Have all the ops for this header received all the receipts they need?
+    pub fn is_saturated(&self) -> bool {
+        !self.ops.is_empty() && self.ops.iter().all(OpPublishStatus::is_saturated)
+    }
+}
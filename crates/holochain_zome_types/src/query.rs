@@ -5,6 +5,7 @@ use std::collections::HashSet;
 
 use crate::header::EntryType;
 use crate::header::HeaderType;
+use crate::timestamp::Timestamp;
 use crate::warrant::Warrant;
 use crate::Element;
 use crate::HeaderHashed;
@@ -69,16 +70,112 @@ pub struct ChainQueryFilter {
     // NB: if this filter is set, you can't verify the results, so don't
     //     use this in validation
     pub entry_type: Option<EntryType>,
+    /// Filter by several EntryTypes, keeping elements that match any of them.
+    // NB: if this filter is set, you can't verify the results, so don't
+    //     use this in validation
+    #[serde(default)]
+    pub entry_types: Option<Vec<EntryType>>,
     /// Filter by a list of `EntryHash`.
     pub entry_hashes: Option<HashSet<EntryHash>>,
     /// Filter by HeaderType
     // NB: if this filter is set, you can't verify the results, so don't
     //     use this in validation
     pub header_type: Option<HeaderType>,
+    /// Filter by the timestamp of the header.
+    /// Inclusive start and end.
+    // NB: if this filter is set, you can't verify the results, so don't
+    //     use this in validation
+    #[serde(default)]
+    pub timestamp_range: Option<(Timestamp, Timestamp)>,
     /// Include the entries in the elements
     pub include_entries: bool,
 }
 
+/// The order a paginated chain query walks the source chain in.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChainQueryDirection {
+    /// Oldest first, starting from genesis.
+    Ascending,
+    /// Newest first, starting from the chain head.
+    Descending,
+}
+
+impl Default for ChainQueryDirection {
+    fn default() -> Self {
+        Self::Ascending
+    }
+}
+
+/// Where the next page of a paginated chain query starts.
+/// Pass the cursor from one page back in the input of the next call as is.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChainQueryCursor(u32);
+
+impl ChainQueryCursor {
+    /// A cursor that continues after the element with this header sequence.
+    pub fn new(header_seq: u32) -> Self {
+        Self(header_seq)
+    }
+
+    /// The header sequence of the last element on the previous page.
+    pub fn header_seq(&self) -> u32 {
+        self.0
+    }
+}
+
+/// Input to a paginated chain query.
+#[derive(serde::Serialize, serde::Deserialize, SerializedBytes, PartialEq, Clone, Debug)]
+pub struct ChainQueryPageInput {
+    /// The query to run. Fork disambiguation is not applied to hash bounded
+    /// ranges because a page may not contain the whole range.
+    pub filter: ChainQueryFilter,
+    /// Continue from a previous page. `None` starts from the end of the
+    /// chain the direction walks from.
+    pub cursor: Option<ChainQueryCursor>,
+    /// The most elements to return in a single page.
+    pub limit: u32,
+    /// The order to walk the chain in.
+    pub direction: ChainQueryDirection,
+}
+
+impl ChainQueryPageInput {
+    /// The first page of up to `limit` elements, oldest first.
+    pub fn new(filter: ChainQueryFilter, limit: u32) -> Self {
+        Self {
+            filter,
+            cursor: None,
+            limit,
+            direction: ChainQueryDirection::default(),
+        }
+    }
+
+    /// Continue from the cursor of a previous page.
+    pub fn cursor(mut self, cursor: ChainQueryCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Walk the chain in this direction.
+    pub fn direction(mut self, direction: ChainQueryDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Walk the chain newest first.
+    pub fn descending(self) -> Self {
+        self.direction(ChainQueryDirection::Descending)
+    }
+}
+
+/// A single page of a paginated chain query.
+#[derive(serde::Serialize, serde::Deserialize, SerializedBytes, PartialEq, Clone, Debug)]
+pub struct ChainQueryPage {
+    /// The elements in this page, in the order of the query's direction.
+    pub elements: Vec<Element>,
+    /// The cursor for the next page, or `None` if this is the last page.
+    pub next: Option<ChainQueryCursor>,
+}
+
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
 /// An agents chain elements returned from a agent_activity_query
 pub struct AgentActivity {
@@ -188,6 +285,12 @@ impl ChainQueryFilter {
         self
     }
 
+    /// Filter on several entry types, keeping elements of any of them.
+    pub fn entry_types(mut self, entry_types: Vec<EntryType>) -> Self {
+        self.entry_types = Some(entry_types);
+        self
+    }
+
     /// Filter on entry hashes.
     pub fn entry_hashes(mut self, entry_hashes: HashSet<EntryHash>) -> Self {
         self.entry_hashes = Some(entry_hashes);
@@ -200,6 +303,12 @@ impl ChainQueryFilter {
         self
     }
 
+    /// Filter on header timestamps between start and end inclusive.
+    pub fn timestamp_range(mut self, start: Timestamp, end: Timestamp) -> Self {
+        self.timestamp_range = Some((start, end));
+        self
+    }
+
     /// Include the entries in the ElementsVec that is returned.
     pub fn include_entries(mut self, include_entries: bool) -> Self {
         self.include_entries = include_entries;
@@ -277,6 +386,22 @@ impl ChainQueryFilter {
                         .as_ref()
                         .map(|entry_type| header.entry_type() == Some(entry_type))
                         .unwrap_or(true)
+                    && self
+                        .entry_types
+                        .as_ref()
+                        .map(|entry_types| {
+                            header
+                                .entry_type()
+                                .map_or(false, |entry_type| entry_types.contains(entry_type))
+                        })
+                        .unwrap_or(true)
+                    && self
+                        .timestamp_range
+                        .as_ref()
+                        .map(|(start, end)| {
+                            *start <= header.timestamp() && header.timestamp() <= *end
+                        })
+                        .unwrap_or(true)
                     && self
                         .entry_hashes
                         .as_ref()
@@ -403,6 +528,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn filter_by_several_entry_types() {
+        let headers = fixtures();
+
+        let query = ChainQueryFilter::new().entry_types(vec![
+            headers[0].entry_type().unwrap().to_owned(),
+            headers[1].entry_type().unwrap().to_owned(),
+        ]);
+
+        assert_eq!(
+            map_query(&query, &headers),
+            [true, true, false, true, true, true, false].to_vec()
+        );
+    }
+
     #[test]
     fn filter_by_entry_hash() {
         let headers = fixtures();
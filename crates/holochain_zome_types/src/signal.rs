@@ -29,3 +29,31 @@ pub struct RemoteSignal {
     /// The signal to send.
     pub signal: crate::ExternIO,
 }
+
+/// What happened to a remote signal sent to one agent with `remote_signal_acked`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub enum RemoteSignalDelivery {
+    /// The agent's `recv_remote_signal` handled the signal.
+    Delivered,
+    /// The agent was reached but didn't take the signal,
+    /// e.g. there is no cap grant or the handler returned an error.
+    Rejected(String),
+    /// The agent couldn't be reached before the network timeout.
+    Unreachable(String),
+}
+
+/// The delivery outcome of an acknowledged remote signal for one agent.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct RemoteSignalAck {
+    /// The agent the signal was sent to.
+    pub agent: AgentPubKey,
+    /// What happened to the signal.
+    pub delivery: RemoteSignalDelivery,
+}
+
+impl RemoteSignalAck {
+    /// True if the agent handled the signal.
+    pub fn is_delivered(&self) -> bool {
+        matches!(self.delivery, RemoteSignalDelivery::Delivered)
+    }
+}
@@ -107,6 +107,10 @@ wasm_io_types! {
     // Returns HeaderHash of the newly created element.
     fn create (zt::entry::CreateInput) -> holo_hash::HeaderHash;
 
+    // Apply several writes to the scratch and validate them together.
+    // Returns the HeaderHash of every write in order.
+    fn commit_batch (Vec<zt::batch::BatchOp>) -> Vec<holo_hash::HeaderHash>;
+
     // Create a link between two entries.
     fn create_link (zt::link::CreateLinkInput) -> holo_hash::HeaderHash;
 
@@ -138,6 +142,9 @@ wasm_io_types! {
     // Attempt to get a live entry from the cascade.
     fn get (Vec<zt::entry::GetInput>) -> Vec<Option<zt::element::Element>>;
 
+    // Get a range of the chunks of a chunked payload from the cascade.
+    fn get_chunks (zt::chunk::GetChunksInput) -> Option<Vec<zt::chunk::Chunk>>;
+
     // Hash an entry on the host.
     fn hash_entry (zt::entry::Entry) -> holo_hash::EntryHash;
 
@@ -150,15 +157,24 @@ wasm_io_types! {
     // Retrieve a header from the DHT or short circuit.
     fn must_get_header (zt::entry::MustGetHeaderInput) -> zt::SignedHeaderHashed;
 
+    // Get the publish and validation receipt status of an authored header.
+    fn publish_status (holo_hash::HeaderHash) -> Option<zt::publish::PublishStatus>;
+
     // Query the source chain for data.
     fn query (zt::query::ChainQueryFilter) -> Vec<crate::Element>;
 
+    // Query a page of the source chain, continuing from a cursor.
+    fn query_page (zt::query::ChainQueryPageInput) -> zt::query::ChainQueryPage;
+
     // the length of random bytes to create
     fn random_bytes (u32) -> zt::bytes::Bytes;
 
     // Remotely signal many agents without waiting for responses
     fn remote_signal (zt::signal::RemoteSignal) -> ();
 
+    // Send a remote signal to many agents and wait for each to acknowledge it.
+    fn remote_signal_acked (zt::signal::RemoteSignal) -> Vec<zt::signal::RemoteSignalAck>;
+
     // // @todo
     // fn send (()) -> ();
 
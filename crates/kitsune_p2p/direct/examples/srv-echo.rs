@@ -38,6 +38,7 @@ async fn main() {
                 respond_cb(Ok(resp)).await.unwrap();
             }
             KdSrvEvt::WebsocketConnected { .. } => (),
+            KdSrvEvt::WebsocketDisconnected { .. } => (),
             KdSrvEvt::WebsocketMessage { con, data } => {
                 let data = serde_json::json!({
                     "event": "KdSrv.incoming_websocket",
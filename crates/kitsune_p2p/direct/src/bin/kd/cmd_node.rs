@@ -2,7 +2,14 @@ use super::*;
 use futures::stream::StreamExt;
 
 pub(crate) async fn run(opt: KdOptNode) -> KdResult<()> {
-    let persist = new_persist_mem();
+    let persist = match &opt.persist_path {
+        Some(path) => {
+            // signing keypairs are kept in a key file next to the database
+            let keystore = new_persist_mem_with_key_file(path.with_extension("keys")).await?;
+            new_persist_sqlite(path, keystore).await?
+        }
+        None => new_persist_mem(),
+    };
 
     let conf = KitsuneDirectV1Config {
         tuning_params: Default::default(),
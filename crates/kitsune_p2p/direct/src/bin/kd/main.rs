@@ -14,6 +14,13 @@ struct KdOptNode {
 
     /// You must specify a proxy address to connect to
     proxy_url: String,
+
+    /// Persist agents and entries to a sqlite database at this path.
+    /// Signing keypairs are written to a key file next to it, with the
+    /// extension "keys". If not specified, all data is kept in memory
+    /// and lost when the node shuts down.
+    #[structopt(long)]
+    persist_path: Option<std::path::PathBuf>,
 }
 
 #[derive(Debug, StructOpt)]
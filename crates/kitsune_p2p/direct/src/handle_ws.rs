@@ -357,6 +357,19 @@ impl AsKdHnd for Hnd {
         }
         .boxed()
     }
+
+    fn app_validator_register(&self, root: KdHash) -> BoxFuture<'static, KdResult<()>> {
+        let msg_id = new_msg_id();
+        let api = KdApi::AppValidatorRegisterReq { msg_id, root };
+        let api = self.request(api);
+        async move {
+            match api.await {
+                Ok(KdApi::AppValidatorRegisterRes { .. }) => Ok(()),
+                oth => Err(format!("unexpected: {:?}", oth).into()),
+            }
+        }
+        .boxed()
+    }
 }
 
 async fn handle_ws_recv(
@@ -411,6 +424,45 @@ async fn handle_ws_recv(
                     }
                     return;
                 }
+                if let KdApi::EntryValidateReq {
+                    msg_id,
+                    root,
+                    entry_signed,
+                } = api
+                {
+                    let ws_write = match hnd.inner.share_mut(|i, _| Ok(i.ws_write.clone())) {
+                        Ok(ws_write) => ws_write,
+                        Err(_) => return,
+                    };
+                    let respond: EntryValidateRespondCb = Box::new(move |res| {
+                        async move {
+                            let (valid, reason) = match res {
+                                Ok(_) => (true, None),
+                                Err(err) => (false, Some(err.to_string())),
+                            };
+                            let api = KdApi::EntryValidateRes {
+                                msg_id,
+                                valid,
+                                reason,
+                            };
+                            let api = tungstenite::Message::Text(api.to_string());
+                            let mut ws_write = ws_write;
+                            ws_write.send(Ok(api)).await.map_err(KdError::other)
+                        }
+                        .boxed()
+                    });
+                    if let Err(err) = lhnd
+                        .emit(KdHndEvt::EntryValidate {
+                            root,
+                            entry: entry_signed,
+                            respond,
+                        })
+                        .await
+                    {
+                        tracing::error!(?err, "error emitting entry validate request");
+                    }
+                    return;
+                }
                 if api.is_res() {
                     if let Ok(Some(snd)) = hnd
                         .inner
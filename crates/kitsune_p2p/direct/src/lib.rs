@@ -15,6 +15,11 @@ pub mod types;
 mod persist_mem;
 pub use persist_mem::*;
 
+mod persist_sqlite;
+pub use persist_sqlite::*;
+
+mod ui_file;
+
 mod srv;
 pub use srv::*;
 
@@ -37,9 +42,10 @@ pub mod dependencies {
 pub mod prelude {
     pub use crate::handle_ws::*;
     pub use crate::persist_mem::*;
+    pub use crate::persist_sqlite::*;
     pub use crate::srv::*;
     pub use crate::types::direct::{KitsuneDirect, KitsuneDirectDriver};
-    pub use crate::types::handle::{KdHnd, KdHndEvt, KdHndEvtStream};
+    pub use crate::types::handle::{EntryValidateRespondCb, KdHnd, KdHndEvt, KdHndEvtStream};
     pub use crate::types::kdagent::{KdAgentInfo, KdAgentInfoExt};
     pub use crate::types::kdentry::{KdEntryContent, KdEntrySigned, KdEntrySignedExt};
     pub use crate::types::kdhash::{KdHash, KdHashExt};
@@ -9,14 +9,77 @@ use kitsune_p2p_types::tls::*;
 use kitsune_p2p_types::tx2::tx2_utils::*;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 /// construct a new in-memory persistence module for kitsune direct
 pub fn new_persist_mem() -> KdPersist {
-    KdPersist(PersistMem::new())
+    KdPersist(PersistMem::new(None))
+}
+
+/// construct a new in-memory persistence module for kitsune direct,
+/// which also writes the signing keypairs it generates to the file at
+/// `path`, and loads the keypairs already in that file, so that they
+/// survive a restart. The secret keys are not encrypted, so the file
+/// is only readable by its owner.
+pub async fn new_persist_mem_with_key_file<P: AsRef<Path>>(path: P) -> KdResult<KdPersist> {
+    let path = path.as_ref().to_path_buf();
+    let load_path = path.clone();
+    let keys = tokio::task::spawn_blocking(move || read_key_file(&load_path))
+        .await
+        .map_err(KdError::other)??;
+    let persist = PersistMem::new(Some(path));
+    persist
+        .0
+        .share_mut(move |i, _| {
+            i.priv_keys.extend(keys);
+            Ok(())
+        })
+        .map_err(KdError::other)?;
+    Ok(KdPersist(persist))
 }
 
 // -- private -- //
 
+/// each record in a key file is a 39 byte KdHash pub key
+/// followed by the 64 byte secret key
+const KEY_RECORD_LEN: usize = 39 + 64;
+
+fn read_key_file(path: &Path) -> KdResult<Vec<(KdHash, sodoken::BufReadSized<64>)>> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(KdError::other(e)),
+    };
+    if bytes.len() % KEY_RECORD_LEN != 0 {
+        return Err(format!("corrupt key file: {}", path.display()).into());
+    }
+    bytes
+        .chunks(KEY_RECORD_LEN)
+        .map(|record| {
+            let pub_key = KdHash::from_bytes(*arrayref::array_ref![record, 0, 39]);
+            let sk = <sodoken::BufWriteSized<64>>::new_mem_locked().map_err(KdError::other)?;
+            sk.write_lock().copy_from_slice(&record[39..]);
+            Ok((pub_key, sk.to_read_sized()))
+        })
+        .collect()
+}
+
+fn append_key_file(path: &Path, pub_key: &KdHash, sk: &sodoken::BufReadSized<64>) -> KdResult<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path).map_err(KdError::other)?;
+
+    let mut record = Vec::with_capacity(KEY_RECORD_LEN);
+    record.extend_from_slice(pub_key.as_ref());
+    record.extend_from_slice(&*sk.read_lock());
+    file.write_all(&record).map_err(KdError::other)?;
+    file.sync_all().map_err(KdError::other)
+}
+
 struct AgentStoreInner {
     pub_key_to_info_map: HashMap<KdHash, KdAgentInfo>,
 }
@@ -205,10 +268,10 @@ struct PersistMemInner {
     ui_cache: Arc<UiStore>,
 }
 
-struct PersistMem(Share<PersistMemInner>, Uniq);
+struct PersistMem(Share<PersistMemInner>, Uniq, Option<PathBuf>);
 
 impl PersistMem {
-    pub fn new() -> Arc<Self> {
+    pub fn new(key_file: Option<PathBuf>) -> Arc<Self> {
         Arc::new(Self(
             Share::new(PersistMemInner {
                 tls: None,
@@ -218,6 +281,7 @@ impl PersistMem {
                 ui_cache: UiStore::new(),
             }),
             Uniq::default(),
+            key_file,
         ))
     }
 }
@@ -264,6 +328,7 @@ impl AsKdPersist for PersistMem {
 
     fn generate_signing_keypair(&self) -> BoxFuture<'static, KdResult<KdHash>> {
         let inner = self.0.clone();
+        let key_file = self.2.clone();
         async move {
             let pk = sodoken::BufWriteSized::new_no_lock();
             let sk = sodoken::BufWriteSized::new_mem_locked().map_err(KdError::other)?;
@@ -278,10 +343,19 @@ impl AsKdPersist for PersistMem {
                 .await
                 .map_err(KdError::other)?;
 
+            let sk = sk.to_read_sized();
+            if let Some(key_file) = key_file {
+                let pk_hash = pk_hash.clone();
+                let sk = sk.clone();
+                tokio::task::spawn_blocking(move || append_key_file(&key_file, &pk_hash, &sk))
+                    .await
+                    .map_err(KdError::other)??;
+            }
+
             let pk_hash_clone = pk_hash.clone();
             inner
                 .share_mut(move |i, _| {
-                    i.priv_keys.insert(pk_hash_clone, sk.to_read_sized());
+                    i.priv_keys.insert(pk_hash_clone, sk);
                     Ok(())
                 })
                 .map_err(KdError::other)?;
@@ -477,45 +551,12 @@ impl AsKdPersist for PersistMem {
 
     fn get_ui_file(&self, path: &str) -> BoxFuture<'static, KdResult<(String, Vec<u8>)>> {
         if path == "/favicon.svg" {
-            return async move {
-                Ok((
-                    "image/svg+xml".to_string(),
-                    br#"<?xml version="1.0" encoding="UTF-8"?>
-<svg version="1.1" xmlns="http://www.w3.org/2000/svg" width="256" height="256">
-    <path d="M 24 16 L 24 240 L 48 240 L 48 152 L 104 240 L 192 240 L 240 128 L 192 16 L 104 16 L 48 104 L 48 16 L 24 16 z M 128 32 L 128 224 L 64 128 L 128 32 z M 152 32 L 176 32 L 216 128 L 176 224 L 152 224 L 152 32 z " />
-</svg>"#.to_vec(),
-                ))
-            }.boxed();
-        } else if path.is_empty() || path == "/" || path == "/index.html" {
+            return async move { Ok(ui_file::favicon()) }.boxed();
+        } else if ui_file::is_index_path(path) {
             let roots = self
                 .0
                 .share_mut(|i, _| Ok(i.entries.keys().cloned().collect::<Vec<_>>()));
-            return async move {
-                let roots = roots
-                    .map_err(KdError::other)?
-                    .into_iter()
-                    .map(|h| format!(r#"<li><a href="/{}/index.html">{}</a></li>"#, h, h))
-                    .collect::<Vec<_>>();
-                let content = format!(
-                    r#"<!DOCTYPE html>
-<html>
-  <head>
-    <meta charset="UTF-8" />
-    <link rel="icon" type="image/svg+xml" href="favicon.svg" />
-  </head>
-  <body>
-    <h1>App Index:</h1>
-    <ul>
-      {}
-    </ul>
-  </body>
-</html>"#,
-                    roots.join("\n")
-                )
-                .into_bytes();
-                Ok(("text/html".to_string(), content))
-            }
-            .boxed();
+            return async move { Ok(ui_file::app_index(roots.map_err(KdError::other)?)) }.boxed();
         }
 
         let ui_cache = self.0.share_mut(|i, _| Ok(i.ui_cache.clone()));
@@ -0,0 +1,604 @@
+//! sqlite persistence module for kitsune direct
+
+use crate::types::persist::*;
+use crate::*;
+use futures::future::{BoxFuture, FutureExt};
+use kitsune_p2p::dht_arc::{DhtArcSet, DhtLocation};
+use kitsune_p2p::event::TimeWindow;
+use kitsune_p2p_types::tls::*;
+use kitsune_p2p_types::tx2::tx2_utils::*;
+use rusqlite::{named_params, Connection, OptionalExtension};
+use std::path::Path;
+
+/// construct a new sqlite persistence module for kitsune direct,
+/// creating the database file at `path` if it does not already exist.
+/// Signing keypairs are delegated to `keystore`, see
+/// [new_persist_mem_with_key_file] to keep them across restarts.
+/// The database holds the private key of the node's tls certificate,
+/// so the file is only readable by its owner.
+pub async fn new_persist_sqlite<P: AsRef<Path>>(
+    path: P,
+    keystore: KdPersist,
+) -> KdResult<KdPersist> {
+    let path = path.as_ref().to_path_buf();
+    let con = tokio::task::spawn_blocking(move || {
+        let mut con = Connection::open(&path).map_err(KdError::other)?;
+        // sqlite creates the -wal and -shm files with the same permissions
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+                .map_err(KdError::other)?;
+        }
+        initialize_connection(&mut con).map_err(KdError::other)?;
+        initialize_database(&mut con)?;
+        KdResult::Ok(con)
+    })
+    .await
+    .map_err(KdError::other)??;
+    Ok(KdPersist(PersistSqlite::new(con, keystore)))
+}
+
+// -- private -- //
+
+mod sql {
+    pub(crate) const SCHEMA: &str = include_str!("sql/schema.sql");
+    pub(crate) const SELECT_TLS: &str = include_str!("sql/select_tls.sql");
+    pub(crate) const INSERT_TLS: &str = include_str!("sql/insert_tls.sql");
+    pub(crate) const INSERT_AGENT_INFO: &str = include_str!("sql/insert_agent_info.sql");
+    pub(crate) const SELECT_AGENT_INFO: &str = include_str!("sql/select_agent_info.sql");
+    pub(crate) const SELECT_AGENT_INFO_BY_ROOT: &str =
+        include_str!("sql/select_agent_info_by_root.sql");
+    pub(crate) const INSERT_ENTRY: &str = include_str!("sql/insert_entry.sql");
+    pub(crate) const SELECT_ENTRY: &str = include_str!("sql/select_entry.sql");
+    pub(crate) const SELECT_ENTRIES: &str = include_str!("sql/select_entries.sql");
+    pub(crate) const SELECT_ROOTS: &str = include_str!("sql/select_roots.sql");
+    pub(crate) const INSERT_UI_FILE: &str = include_str!("sql/insert_ui_file.sql");
+    pub(crate) const SELECT_UI_FILE: &str = include_str!("sql/select_ui_file.sql");
+}
+
+/// The schema version, stored as the database `user_version`.
+/// As in holochain_sqlite, a `user_version` of 0 means uninitialized.
+const SCHEMA_VERSION: u16 = 1;
+
+fn initialize_connection(con: &mut Connection) -> rusqlite::Result<()> {
+    // https://sqlite.org/pragma.html#pragma_trusted_schema
+    con.pragma_update(None, "trusted_schema", &false)?;
+    con.pragma_update(None, "foreign_keys", &"ON".to_string())?;
+    con.pragma_update(None, "journal_mode", &"WAL".to_string())?;
+    con.pragma_update(None, "synchronous", &"1".to_string())?;
+    Ok(())
+}
+
+fn initialize_database(con: &mut Connection) -> KdResult<()> {
+    let user_version: u16 = con
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(KdError::other)?;
+    match user_version {
+        0 => {
+            let tx = con.transaction().map_err(KdError::other)?;
+            tx.execute_batch(sql::SCHEMA).map_err(KdError::other)?;
+            tx.pragma_update(None, "user_version", &SCHEMA_VERSION)
+                .map_err(KdError::other)?;
+            tx.commit().map_err(KdError::other)?;
+            tracing::info!("kdirect database initialized");
+            Ok(())
+        }
+        SCHEMA_VERSION => Ok(()),
+        _ => Err(format!("unsupported kdirect database version: {}", user_version).into()),
+    }
+}
+
+fn tls_from_row(row: &rusqlite::Row) -> rusqlite::Result<TlsConfig> {
+    Ok(TlsConfig {
+        cert: row.get::<_, Vec<u8>>(0)?.into(),
+        cert_priv_key: row.get::<_, Vec<u8>>(1)?.into(),
+        cert_digest: row.get::<_, Vec<u8>>(2)?.into(),
+    })
+}
+
+fn kd_hash_to_sql(h: &KdHash) -> &[u8] {
+    h.as_ref()
+}
+
+fn kd_hash_from_sql(b: Vec<u8>) -> KdResult<KdHash> {
+    if b.len() != 39 {
+        return Err(format!("invalid stored hash length: {}", b.len()).into());
+    }
+    Ok(KdHash::from_bytes(*arrayref::array_ref![b, 0, 39]))
+}
+
+/// run a blocking database operation on the tokio blocking thread pool
+fn db_exec<R, F>(inner: Share<Connection>, f: F) -> BoxFuture<'static, KdResult<R>>
+where
+    R: 'static + Send,
+    F: 'static + Send + FnOnce(&mut Connection) -> KdResult<R>,
+{
+    async move {
+        tokio::task::spawn_blocking(move || inner.share_mut(move |con, _| Ok(f(con))))
+            .await
+            .map_err(KdError::other)?
+            .map_err(KdError::other)?
+    }
+    .boxed()
+}
+
+struct PersistSqlite(Share<Connection>, KdPersist, Uniq);
+
+impl PersistSqlite {
+    pub fn new(con: Connection, keystore: KdPersist) -> Arc<Self> {
+        Arc::new(Self(Share::new(con), keystore, Uniq::default()))
+    }
+
+    fn exec<R, F>(&self, f: F) -> BoxFuture<'static, KdResult<R>>
+    where
+        R: 'static + Send,
+        F: 'static + Send + FnOnce(&mut Connection) -> KdResult<R>,
+    {
+        db_exec(self.0.clone(), f)
+    }
+
+    fn get_agent_info_list(&self, root: KdHash) -> BoxFuture<'static, KdResult<Vec<KdAgentInfo>>> {
+        self.exec(move |con| {
+            let mut stmt = con
+                .prepare_cached(sql::SELECT_AGENT_INFO_BY_ROOT)
+                .map_err(KdError::other)?;
+            let rows = stmt
+                .query_map(named_params! { ":root": kd_hash_to_sql(&root) }, |row| {
+                    row.get::<_, String>(0)
+                })
+                .map_err(KdError::other)?;
+            let mut out = Vec::new();
+            for encoded in rows {
+                out.push(KdAgentInfo::from_str(&encoded.map_err(KdError::other)?)?);
+            }
+            Ok(out)
+        })
+    }
+}
+
+impl AsKdPersist for PersistSqlite {
+    fn uniq(&self) -> Uniq {
+        self.2
+    }
+
+    fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
+
+    fn close(&self) -> BoxFuture<'static, ()> {
+        self.0.close();
+        async move {}.boxed()
+    }
+
+    fn singleton_tls_config(&self) -> BoxFuture<'static, KdResult<TlsConfig>> {
+        let load = self.exec(|con| {
+            con.query_row(sql::SELECT_TLS, [], tls_from_row)
+                .optional()
+                .map_err(KdError::other)
+        });
+        let inner = self.0.clone();
+        async move {
+            if let Some(tls) = load.await? {
+                return Ok(tls);
+            }
+            let tls = TlsConfig::new_ephemeral().await.map_err(KdError::other)?;
+            db_exec(inner, move |con| {
+                con.execute(
+                    sql::INSERT_TLS,
+                    named_params! {
+                        ":cert": tls.cert.to_vec(),
+                        ":priv_key": tls.cert_priv_key.to_vec(),
+                        ":digest": tls.cert_digest.to_vec(),
+                    },
+                )
+                .map_err(KdError::other)?;
+                con.query_row(sql::SELECT_TLS, [], tls_from_row)
+                    .map_err(KdError::other)
+            })
+            .await
+        }
+        .boxed()
+    }
+
+    fn generate_signing_keypair(&self) -> BoxFuture<'static, KdResult<KdHash>> {
+        AsKdPersist::generate_signing_keypair(&*self.1 .0)
+    }
+
+    fn sign(&self, pub_key: KdHash, data: &[u8]) -> BoxFuture<'static, KdResult<Arc<[u8; 64]>>> {
+        AsKdPersist::sign(&*self.1 .0, pub_key, data)
+    }
+
+    fn store_agent_info(&self, agent_info: KdAgentInfo) -> BoxFuture<'static, KdResult<()>> {
+        self.exec(move |con| {
+            con.execute(
+                sql::INSERT_AGENT_INFO,
+                named_params! {
+                    ":root": kd_hash_to_sql(agent_info.root()),
+                    ":agent": kd_hash_to_sql(agent_info.agent()),
+                    ":encoded": agent_info.to_string(),
+                    ":signed_at_ms": agent_info.signed_at_ms(),
+                },
+            )
+            .map_err(KdError::other)?;
+            Ok(())
+        })
+    }
+
+    fn get_agent_info(
+        &self,
+        root: KdHash,
+        agent: KdHash,
+    ) -> BoxFuture<'static, KdResult<KdAgentInfo>> {
+        self.exec(move |con| {
+            let encoded = con
+                .query_row(
+                    sql::SELECT_AGENT_INFO,
+                    named_params! {
+                        ":root": kd_hash_to_sql(&root),
+                        ":agent": kd_hash_to_sql(&agent),
+                    },
+                    |row| row.get::<_, String>(0),
+                )
+                .optional()
+                .map_err(KdError::other)?;
+            match encoded {
+                Some(encoded) => KdAgentInfo::from_str(&encoded),
+                None => Err("agent not found".into()),
+            }
+        })
+    }
+
+    fn query_agent_info(&self, root: KdHash) -> BoxFuture<'static, KdResult<Vec<KdAgentInfo>>> {
+        self.get_agent_info_list(root)
+    }
+
+    fn query_agent_info_near_basis(
+        &self,
+        root: KdHash,
+        basis_loc: u32,
+        limit: u32,
+    ) -> BoxFuture<'static, KdResult<Vec<KdAgentInfo>>> {
+        let list = self.get_agent_info_list(root);
+        async move {
+            let mut with_dist = list
+                .await?
+                .into_iter()
+                .map(|info| (info.basis_distance_to_storage(basis_loc), info))
+                .collect::<Vec<_>>();
+            with_dist.sort_by(|a, b| a.0.cmp(&b.0));
+            Ok(with_dist
+                .into_iter()
+                .map(|(_, info)| info)
+                .take(limit as usize)
+                .collect())
+        }
+        .boxed()
+    }
+
+    fn query_peer_density(
+        &self,
+        root: KdHash,
+        dht_arc: kitsune_p2p_types::dht_arc::DhtArc,
+    ) -> BoxFuture<'static, KdResult<kitsune_p2p_types::dht_arc::PeerDensity>> {
+        let list = self.get_agent_info_list(root);
+        async move {
+            let list = list.await?;
+            if list.is_empty() {
+                return Err("root not found".into());
+            }
+            let arcs = list
+                .into_iter()
+                .filter_map(|v| {
+                    if dht_arc.contains(v.agent().as_loc()) {
+                        Some(*v.storage_arc())
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            // contains is already checked in the iterator
+            let bucket = kitsune_p2p::dht_arc::DhtArcBucket::new_unchecked(dht_arc, arcs);
+
+            Ok(bucket.density())
+        }
+        .boxed()
+    }
+
+    fn store_entry(
+        &self,
+        root: KdHash,
+        agent: KdHash,
+        entry: KdEntrySigned,
+    ) -> BoxFuture<'static, KdResult<()>> {
+        use kitsune_p2p_direct_api::kd_sys_kind::*;
+
+        // mirror the in-memory ui cache, but persisted
+        let ui_file = if entry.kind() == "s.file" {
+            match KdSysKind::from_kind(entry.kind(), entry.raw_data().clone()) {
+                Ok(KdSysKind::File(file)) => {
+                    let path = format!("/{}/{}", root, file.name);
+                    Some((path, file.mime))
+                }
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        self.exec(move |con| {
+            let tx = con.transaction().map_err(KdError::other)?;
+            tx.execute(
+                sql::INSERT_ENTRY,
+                named_params! {
+                    ":root": kd_hash_to_sql(&root),
+                    ":agent": kd_hash_to_sql(&agent),
+                    ":hash": kd_hash_to_sql(entry.hash()),
+                    ":wire": entry.as_wire_data_ref(),
+                    ":loc": entry.hash().as_loc(),
+                },
+            )
+            .map_err(KdError::other)?;
+            if let Some((path, mime)) = ui_file {
+                tx.execute(
+                    sql::INSERT_UI_FILE,
+                    named_params! {
+                        ":path": path,
+                        ":mime": mime,
+                        ":data": entry.as_binary_ref(),
+                    },
+                )
+                .map_err(KdError::other)?;
+            }
+            tx.commit().map_err(KdError::other)?;
+            Ok(())
+        })
+    }
+
+    fn get_entry(
+        &self,
+        root: KdHash,
+        agent: KdHash,
+        hash: KdHash,
+    ) -> BoxFuture<'static, KdResult<KdEntrySigned>> {
+        self.exec(move |con| {
+            let wire = con
+                .query_row(
+                    sql::SELECT_ENTRY,
+                    named_params! {
+                        ":root": kd_hash_to_sql(&root),
+                        ":agent": kd_hash_to_sql(&agent),
+                        ":hash": kd_hash_to_sql(&hash),
+                    },
+                    |row| row.get::<_, Vec<u8>>(0),
+                )
+                .optional()
+                .map_err(KdError::other)?;
+            match wire {
+                Some(wire) => KdEntrySigned::from_wire_unchecked(wire.into_boxed_slice()),
+                None => Err("hash not found".into()),
+            }
+        })
+    }
+
+    fn query_entries(
+        &self,
+        root: KdHash,
+        agent: KdHash,
+        _window: TimeWindow,
+        dht_arc: DhtArcSet,
+    ) -> BoxFuture<'static, KdResult<Vec<KdEntrySigned>>> {
+        // TODO - entries do not yet carry a timestamp to filter the window by
+
+        self.exec(move |con| {
+            let mut stmt = con
+                .prepare_cached(sql::SELECT_ENTRIES)
+                .map_err(KdError::other)?;
+            let rows = stmt
+                .query_map(
+                    named_params! {
+                        ":root": kd_hash_to_sql(&root),
+                        ":agent": kd_hash_to_sql(&agent),
+                    },
+                    |row| Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, u32>(1)?)),
+                )
+                .map_err(KdError::other)?;
+            let mut out = Vec::new();
+            for row in rows {
+                let (wire, loc) = row.map_err(KdError::other)?;
+                if !dht_arc.contains(DhtLocation::new(loc)) {
+                    continue;
+                }
+                out.push(KdEntrySigned::from_wire_unchecked(wire.into_boxed_slice())?);
+            }
+            Ok(out)
+        })
+    }
+
+    fn get_ui_file(&self, path: &str) -> BoxFuture<'static, KdResult<(String, Vec<u8>)>> {
+        if path == "/favicon.svg" {
+            return async move { Ok(ui_file::favicon()) }.boxed();
+        } else if ui_file::is_index_path(path) {
+            let roots = self.exec(|con| {
+                let mut stmt = con
+                    .prepare_cached(sql::SELECT_ROOTS)
+                    .map_err(KdError::other)?;
+                let rows = stmt
+                    .query_map([], |row| row.get::<_, Vec<u8>>(0))
+                    .map_err(KdError::other)?;
+                let mut out = Vec::new();
+                for root in rows {
+                    out.push(kd_hash_from_sql(root.map_err(KdError::other)?)?);
+                }
+                Ok(out)
+            });
+            return async move { Ok(ui_file::app_index(roots.await?)) }.boxed();
+        }
+
+        let path = path.to_string();
+        self.exec(move |con| {
+            let ui_file = con
+                .query_row(
+                    sql::SELECT_UI_FILE,
+                    named_params! { ":path": path },
+                    |row| Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?)),
+                )
+                .optional()
+                .map_err(KdError::other)?;
+            match ui_file {
+                Some(ui_file) => Ok(ui_file),
+                None => Err(format!("404: {}", path).into()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::kdagent::KdAgentInfoInner;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_persist_sqlite_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kd.sqlite3");
+
+        let key_path = dir.path().join("kd.keys");
+
+        let keystore = new_persist_mem_with_key_file(&key_path).await.unwrap();
+        let persist = new_persist_sqlite(&path, keystore).await.unwrap();
+        let tls = persist.singleton_tls_config().await.unwrap();
+        let agent = persist.generate_signing_keypair().await.unwrap();
+        let root = persist.generate_signing_keypair().await.unwrap();
+
+        let content = KdEntryContent {
+            kind: "u.foo".to_string(),
+            parent: root.clone(),
+            author: agent.clone(),
+            verify: "".to_string(),
+            data: serde_json::json!({ "hello": "world" }),
+        };
+        let entry = KdEntrySigned::from_content(&persist, content)
+            .await
+            .unwrap();
+        persist
+            .store_entry(root.clone(), agent.clone(), entry.clone())
+            .await
+            .unwrap();
+        persist.close().await;
+        drop(persist);
+
+        // rebuild everything from what was written to disk
+        let keystore = new_persist_mem_with_key_file(&key_path).await.unwrap();
+        let persist = new_persist_sqlite(&path, keystore).await.unwrap();
+        assert_eq!(
+            tls.cert_digest,
+            persist.singleton_tls_config().await.unwrap().cert_digest
+        );
+
+        // the keypair generated before the restart can still sign
+        let sig = persist.sign(agent.clone(), b"test").await.unwrap();
+        assert!(
+            agent
+                .verify_signature(sodoken::BufRead::new_no_lock(b"test"), sig)
+                .await
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            for path in &[&path, &key_path] {
+                let mode = std::fs::metadata(path).unwrap().permissions().mode();
+                assert_eq!(0o600, mode & 0o777);
+            }
+        }
+
+        let got = persist
+            .get_entry(root.clone(), agent.clone(), entry.hash().clone())
+            .await
+            .unwrap();
+        assert_eq!(entry, got);
+
+        let all = persist
+            .query_entries(
+                root,
+                agent,
+                kitsune_p2p::event::full_time_window(),
+                DhtArcSet::Full,
+            )
+            .await
+            .unwrap();
+        assert_eq!(vec![entry], all);
+    }
+    fn agent_info(root: &KdHash, agent: KdHash, storage_arc: DhtArc) -> KdAgentInfo {
+        KdAgentInfo(Arc::new(KdAgentInfoInner {
+            root: root.clone(),
+            agent,
+            storage_arc,
+            url_list: vec![],
+            signed_at_ms: 1,
+            expires_at_ms: i64::MAX,
+            signature: vec![0; 64].into_boxed_slice().into(),
+            encoded_info: vec![].into_boxed_slice().into(),
+        }))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_persist_sqlite_agent_queries_match_mem() {
+        let dir = tempfile::tempdir().unwrap();
+        let mem = new_persist_mem();
+        let persist = new_persist_sqlite(dir.path().join("kd.sqlite3"), mem.clone())
+            .await
+            .unwrap();
+
+        let root = mem.generate_signing_keypair().await.unwrap();
+        let near_zero = mem.generate_signing_keypair().await.unwrap();
+        let near_half = mem.generate_signing_keypair().await.unwrap();
+        for info in vec![
+            agent_info(&root, near_zero.clone(), DhtArc::new(0, 100)),
+            agent_info(&root, near_half.clone(), DhtArc::new(u32::MAX / 2, 100)),
+        ] {
+            mem.store_agent_info(info.clone()).await.unwrap();
+            persist.store_agent_info(info).await.unwrap();
+        }
+
+        let near = persist
+            .query_agent_info_near_basis(root.clone(), 10, 1)
+            .await
+            .unwrap();
+        assert_eq!(vec![near_zero.clone()], agents(&near));
+
+        let near = persist
+            .query_agent_info_near_basis(root.clone(), u32::MAX / 2 + 10, 2)
+            .await
+            .unwrap();
+        assert_eq!(vec![near_half, near_zero], agents(&near));
+        assert_eq!(
+            near,
+            mem.query_agent_info_near_basis(root.clone(), u32::MAX / 2 + 10, 2)
+                .await
+                .unwrap()
+        );
+
+        let arc = DhtArc::full(0);
+        assert_eq!(
+            format!(
+                "{:?}",
+                mem.query_peer_density(root.clone(), arc).await.unwrap()
+            ),
+            format!(
+                "{:?}",
+                persist.query_peer_density(root.clone(), arc).await.unwrap()
+            ),
+        );
+
+        // an unknown root is an error, as it is for the in-memory store
+        let unknown = mem.generate_signing_keypair().await.unwrap();
+        assert!(persist.query_peer_density(unknown, arc).await.is_err());
+    }
+
+    fn agents(list: &[KdAgentInfo]) -> Vec<KdHash> {
+        list.iter().map(|info| info.agent().clone()).collect()
+    }
+}
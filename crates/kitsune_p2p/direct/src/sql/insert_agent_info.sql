@@ -0,0 +1,22 @@
+-- because UPSERT isn't guaranteed to exist on our sqlite version
+-- we need to fashion our own with an INSERT SELECT statement
+INSERT INTO
+  agent_info
+SELECT
+  :root AS root,
+  :agent AS agent,
+  :encoded AS encoded,
+  :signed_at_ms AS signed_at_ms
+WHERE
+  (
+    -- count the rows that should supercede the one we're trying to insert
+    SELECT
+      count(rowid)
+    FROM
+      agent_info
+    WHERE
+      root = :root
+      AND agent = :agent
+      AND signed_at_ms >= :signed_at_ms
+  ) = 0 -- if there are none, proceed with the insert
+;
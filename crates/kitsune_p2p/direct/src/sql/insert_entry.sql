@@ -0,0 +1,4 @@
+INSERT INTO
+  entry (root, agent, hash, wire, loc)
+VALUES
+  (:root, :agent, :hash, :wire, :loc)
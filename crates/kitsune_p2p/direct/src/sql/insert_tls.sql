@@ -0,0 +1,5 @@
+-- if another task raced us to store a cert, keep theirs
+INSERT
+  OR IGNORE INTO tls (id, cert, priv_key, digest)
+VALUES
+  (0, :cert, :priv_key, :digest)
@@ -0,0 +1,4 @@
+INSERT INTO
+  ui_file (path, mime, data)
+VALUES
+  (:path, :mime, :data)
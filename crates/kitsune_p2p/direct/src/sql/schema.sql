@@ -0,0 +1,47 @@
+-- no-sql-format --
+
+-- singleton tls certificate for this node
+CREATE TABLE IF NOT EXISTS tls (
+  -- there is only ever one row in this table
+  id                      INTEGER   PRIMARY KEY CHECK (id = 0),
+
+  cert                    BLOB      NOT NULL,
+  priv_key                BLOB      NOT NULL,
+  digest                  BLOB      NOT NULL
+);
+
+-- agent info, keyed by app root / agent
+CREATE TABLE IF NOT EXISTS agent_info (
+  root                    BLOB      NOT NULL,
+  agent                   BLOB      NOT NULL,
+
+  -- json encoded KdAgentInfo
+  encoded                 TEXT      NOT NULL,
+
+  -- Additional queryable fields extracted from encoding
+  signed_at_ms            INTEGER   NOT NULL,
+
+  PRIMARY KEY (root, agent) ON CONFLICT REPLACE
+);
+
+-- signed entries, keyed by app root / agent / entry hash
+CREATE TABLE IF NOT EXISTS entry (
+  root                    BLOB      NOT NULL,
+  agent                   BLOB      NOT NULL,
+  hash                    BLOB      NOT NULL,
+
+  -- KdEntrySigned wire encoding
+  wire                    BLOB      NOT NULL,
+
+  -- Additional queryable fields extracted from encoding
+  loc                     INTEGER   NOT NULL,
+
+  PRIMARY KEY (root, agent, hash) ON CONFLICT REPLACE
+);
+
+-- ui files extracted from "s.file" entries
+CREATE TABLE IF NOT EXISTS ui_file (
+  path                    TEXT      PRIMARY KEY ON CONFLICT REPLACE,
+  mime                    TEXT      NOT NULL,
+  data                    BLOB      NOT NULL
+);
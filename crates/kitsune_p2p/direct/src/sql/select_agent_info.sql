@@ -0,0 +1,7 @@
+SELECT
+  encoded
+FROM
+  agent_info
+WHERE
+  root = :root
+  AND agent = :agent
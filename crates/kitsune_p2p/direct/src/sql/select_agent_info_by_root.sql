@@ -0,0 +1,6 @@
+SELECT
+  encoded
+FROM
+  agent_info
+WHERE
+  root = :root
@@ -0,0 +1,8 @@
+SELECT
+  wire,
+  loc
+FROM
+  entry
+WHERE
+  root = :root
+  AND agent = :agent
@@ -0,0 +1,8 @@
+SELECT
+  wire
+FROM
+  entry
+WHERE
+  root = :root
+  AND agent = :agent
+  AND hash = :hash
@@ -0,0 +1,4 @@
+SELECT
+  DISTINCT root
+FROM
+  entry
@@ -0,0 +1,8 @@
+SELECT
+  cert,
+  priv_key,
+  digest
+FROM
+  tls
+WHERE
+  id = 0
@@ -0,0 +1,7 @@
+SELECT
+  mime,
+  data
+FROM
+  ui_file
+WHERE
+  path = :path
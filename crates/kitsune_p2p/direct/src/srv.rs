@@ -177,6 +177,7 @@ impl Srv {
                 .map_err(KdError::other)?;
 
             let lhnd2 = lhnd.clone();
+            let inner2 = inner.clone();
             tokio::task::spawn(async move {
                 while let Some(msg) = ws_read.next().await {
                     let api = match msg {
@@ -215,6 +216,14 @@ impl Srv {
                         break;
                     }
                 }
+
+                let _ = inner2.share_mut(|i, _| {
+                    i.websockets.remove(&uniq);
+                    Ok(())
+                });
+                let _ = lhnd2
+                    .emit(KdSrvEvt::WebsocketDisconnected { con: uniq })
+                    .await;
             });
 
             lhnd.emit(KdSrvEvt::WebsocketConnected { con: uniq })
@@ -92,6 +92,22 @@ async fn test_direct_sanity() {
     let evt = m_r.recv().await.unwrap();
     println!("GOT HND EVT: {:#?}", evt);
 
+    // only accept app entries
+    hnd.app_validator_register(root.clone()).await.unwrap();
+
+    tokio::task::spawn(async move {
+        while let Some(evt) = m_r.recv().await {
+            if let KdHndEvt::EntryValidate { entry, respond, .. } = evt {
+                let res = if entry.kind() == "s.app" {
+                    Ok(())
+                } else {
+                    Err("only app entries allowed".into())
+                };
+                respond(res).await.unwrap();
+            }
+        }
+    });
+
     let app_entry = hnd
         .entry_author(
             root.clone(),
@@ -120,8 +136,170 @@ async fn test_direct_sanity() {
 
     assert_eq!(app_entry, e);
 
+    let res = hnd
+        .entry_author(
+            root.clone(),
+            root.clone(),
+            KdEntryContent {
+                kind: "u.bad".to_string(),
+                parent: app_entry.hash().clone(),
+                author: root.clone(),
+                verify: "".to_string(),
+                data: serde_json::json!({}),
+            },
+            vec![].into_boxed_slice().into(),
+        )
+        .await;
+    assert!(res.is_err());
+
     bootstrap_close(0, "").await;
     proxy_close(0, "").await;
     hnd.close(0, "").await;
     kd.close(0, "").await;
 }
+
+/// a kitsune direct node, along with the close callbacks
+/// for it and the bootstrap / proxy it uses
+async fn setup_node() -> (KitsuneDirect, Vec<CloseCb>) {
+    let (bootstrap, driver, bootstrap_close) =
+        new_quick_bootstrap_v1(Default::default()).await.unwrap();
+    tokio::task::spawn(driver);
+
+    let (proxy, driver, proxy_close) = new_quick_proxy_v1(Default::default()).await.unwrap();
+    tokio::task::spawn(driver);
+
+    let conf = KitsuneDirectV1Config {
+        tuning_params: Default::default(),
+        persist: new_persist_mem(),
+        bootstrap,
+        proxy,
+        ui_port: 0,
+    };
+
+    let (kd, driver) = new_kitsune_direct_v1(conf).await.unwrap();
+    tokio::task::spawn(driver);
+
+    (kd, vec![bootstrap_close, proxy_close])
+}
+
+async fn connect(kd: &KitsuneDirect) -> (KdHnd, KdHndEvtStream) {
+    let pass = sodoken::BufWrite::new_mem_locked(4).unwrap();
+    pass.write_lock().copy_from_slice(&[1, 2, 3, 4]);
+    new_handle_ws(kd.get_ui_addr().unwrap(), pass.to_read())
+        .await
+        .unwrap()
+}
+
+/// answer validation requests, only accepting entries of kind `accept`
+fn spawn_validator(mut evt: KdHndEvtStream, accept: &'static str) {
+    tokio::task::spawn(async move {
+        while let Some(evt) = evt.next().await {
+            if let KdHndEvt::EntryValidate { entry, respond, .. } = evt {
+                let res = if entry.kind() == accept {
+                    Ok(())
+                } else {
+                    Err(format!("only {} entries allowed", accept).into())
+                };
+                let _ = respond(res).await;
+            }
+        }
+    });
+}
+
+fn entry_content(root: &KdHash, kind: &str, n: u32) -> KdEntryContent {
+    KdEntryContent {
+        kind: kind.to_string(),
+        parent: root.clone(),
+        author: root.clone(),
+        verify: "".to_string(),
+        data: serde_json::json!({ "n": n }),
+    }
+}
+
+async fn stored_kinds(kd: &KitsuneDirect, root: &KdHash) -> Vec<String> {
+    kd.get_persist()
+        .query_entries(
+            root.clone(),
+            root.clone(),
+            kitsune_p2p::event::full_time_window(),
+            kitsune_p2p::dht_arc::DhtArcSet::Full,
+        )
+        .await
+        .unwrap()
+        .iter()
+        .map(|entry| entry.kind().to_string())
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_direct_app_validator_is_replaced() {
+    let (kd, closers) = setup_node().await;
+    let (hnd, _hnd_evt) = connect(&kd).await;
+    let root = hnd.keypair_get_or_create_tagged("test_root").await.unwrap();
+
+    let (first, first_evt) = connect(&kd).await;
+    first.app_validator_register(root.clone()).await.unwrap();
+    spawn_validator(first_evt, "u.first");
+
+    // registering another validator for the root replaces the first
+    let (second, second_evt) = connect(&kd).await;
+    second.app_validator_register(root.clone()).await.unwrap();
+    spawn_validator(second_evt, "u.second");
+
+    let author = |kind, n| {
+        hnd.entry_author(
+            root.clone(),
+            root.clone(),
+            entry_content(&root, kind, n),
+            vec![].into_boxed_slice().into(),
+        )
+    };
+    assert!(author("u.first", 1).await.is_err());
+    author("u.second", 2).await.unwrap();
+    assert_eq!(vec!["u.second".to_string()], stored_kinds(&kd, &root).await);
+
+    for close in closers {
+        close(0, "").await;
+    }
+    first.close(0, "").await;
+    second.close(0, "").await;
+    hnd.close(0, "").await;
+    kd.close(0, "").await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_direct_app_validator_disconnect_refuses_entries() {
+    let (kd, closers) = setup_node().await;
+    let (hnd, _hnd_evt) = connect(&kd).await;
+    let root = hnd.keypair_get_or_create_tagged("test_root").await.unwrap();
+
+    let (validator, validator_evt) = connect(&kd).await;
+    validator
+        .app_validator_register(root.clone())
+        .await
+        .unwrap();
+    spawn_validator(validator_evt, "u.good");
+
+    let author = |kind, n| {
+        hnd.entry_author(
+            root.clone(),
+            root.clone(),
+            entry_content(&root, kind, n),
+            vec![].into_boxed_slice().into(),
+        )
+    };
+    author("u.good", 1).await.unwrap();
+    assert!(author("u.bad", 2).await.is_err());
+
+    // without its validator, the root doesn't accept entries unvalidated
+    validator.close(0, "").await;
+    assert!(author("u.bad", 3).await.is_err());
+    assert!(author("u.good", 4).await.is_err());
+    assert_eq!(vec!["u.good".to_string()], stored_kinds(&kd, &root).await);
+
+    for close in closers {
+        close(0, "").await;
+    }
+    hnd.close(0, "").await;
+    kd.close(0, "").await;
+}
@@ -9,8 +9,12 @@ use std::future::Future;
 pub type HelloRespondCb =
     Box<dyn FnOnce(KdResult<KdEntryBinary>) -> BoxFuture<'static, KdResult<()>> + 'static + Send>;
 
+/// Respond to an incoming entry validation request.
+/// `Ok(())` accepts the entry, `Err(reason)` rejects it.
+pub type EntryValidateRespondCb =
+    Box<dyn FnOnce(KdResult<()>) -> BoxFuture<'static, KdResult<()>> + 'static + Send>;
+
 /// Events emitted from a KdHnd instance
-#[derive(Debug)]
 pub enum KdHndEvt {
     /// An incoming message from a remote node
     Message {
@@ -26,6 +30,43 @@ pub enum KdHndEvt {
         /// the binary data associated with this message
         binary: KdEntryBinary,
     },
+
+    /// An entry needs validating before it is stored / gossiped.
+    /// Only emitted for app roots we have registered as validator for.
+    EntryValidate {
+        /// the root app hash
+        root: KdHash,
+
+        /// the signed entry to validate
+        entry: KdEntrySigned,
+
+        /// validation verdict callback
+        respond: EntryValidateRespondCb,
+    },
+}
+
+impl std::fmt::Debug for KdHndEvt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Message {
+                root,
+                to_agent,
+                content,
+                binary,
+            } => f
+                .debug_struct("Message")
+                .field("root", root)
+                .field("to_agent", to_agent)
+                .field("content", content)
+                .field("binary", binary)
+                .finish(),
+            Self::EntryValidate { root, entry, .. } => f
+                .debug_struct("EntryValidate")
+                .field("root", root)
+                .field("entry", entry)
+                .finish(),
+        }
+    }
 }
 
 /// Stream of KdHndEvt instances
@@ -106,6 +147,13 @@ pub trait AsKdHnd: 'static + Send + Sync {
         parent: KdHash,
         kind: Option<String>,
     ) -> BoxFuture<'static, KdResult<Vec<KdEntrySigned>>>;
+
+    /// Register this handle as the entry validator for an app root hash,
+    /// replacing any previously registered validator for the root.
+    /// Entries under this root will be emitted as `KdHndEvt::EntryValidate`
+    /// and will not be stored or gossiped unless accepted, including
+    /// while the validator is disconnected.
+    fn app_validator_register(&self, root: KdHash) -> BoxFuture<'static, KdResult<()>>;
 }
 
 /// Struct representing a kitsune direct api implementation
@@ -240,4 +288,12 @@ impl KdHnd {
     ) -> impl Future<Output = KdResult<Vec<KdEntrySigned>>> + 'static + Send {
         AsKdHnd::entry_get_children(&*self.0, root, parent, kind)
     }
+
+    /// Register this handle as the entry validator for an app root hash.
+    pub fn app_validator_register(
+        &self,
+        root: KdHash,
+    ) -> impl Future<Output = KdResult<()>> + 'static + Send {
+        AsKdHnd::app_validator_register(&*self.0, root)
+    }
 }
@@ -66,6 +66,12 @@ pub enum KdSrvEvt {
         /// incoming structured message
         data: KdApi,
     },
+
+    /// A websocket connection has closed
+    WebsocketDisconnected {
+        /// Connection Ref
+        con: Uniq,
+    },
 }
 
 /// Stream of KdSrvEvt instances
@@ -0,0 +1,46 @@
+//! ui file responses shared by the kitsune direct persistence modules
+
+use crate::*;
+
+/// Is this the path of the app index page?
+pub(crate) fn is_index_path(path: &str) -> bool {
+    path.is_empty() || path == "/" || path == "/index.html"
+}
+
+/// The kitsune direct favicon
+pub(crate) fn favicon() -> (String, Vec<u8>) {
+    (
+        "image/svg+xml".to_string(),
+        br#"<?xml version="1.0" encoding="UTF-8"?>
+<svg version="1.1" xmlns="http://www.w3.org/2000/svg" width="256" height="256">
+    <path d="M 24 16 L 24 240 L 48 240 L 48 152 L 104 240 L 192 240 L 240 128 L 192 16 L 104 16 L 48 104 L 48 16 L 24 16 z M 128 32 L 128 224 L 64 128 L 128 32 z M 152 32 L 176 32 L 216 128 L 176 224 L 152 224 L 152 32 z " />
+</svg>"#
+            .to_vec(),
+    )
+}
+
+/// The app index page, linking to the index of every app root
+pub(crate) fn app_index(roots: impl IntoIterator<Item = KdHash>) -> (String, Vec<u8>) {
+    let roots = roots
+        .into_iter()
+        .map(|h| format!(r#"<li><a href="/{}/index.html">{}</a></li>"#, h, h))
+        .collect::<Vec<_>>();
+    let content = format!(
+        r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="UTF-8" />
+    <link rel="icon" type="image/svg+xml" href="favicon.svg" />
+  </head>
+  <body>
+    <h1>App Index:</h1>
+    <ul>
+      {}
+    </ul>
+  </body>
+</html>"#,
+        roots.join("\n")
+    )
+    .into_bytes();
+    ("text/html".to_string(), content)
+}
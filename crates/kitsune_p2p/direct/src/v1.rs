@@ -14,8 +14,9 @@ use kitsune_p2p::*;
 use kitsune_p2p_types::config::KitsuneP2pTuningParams;
 use kitsune_p2p_types::dependencies::ghost_actor;
 use kitsune_p2p_types::tx2::tx2_utils::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Config for v1 impl of KitsuneDirect
 pub struct KitsuneDirectV1Config {
@@ -159,7 +160,7 @@ pub fn new_kitsune_direct_v1(
         let mut logic_chan = <LogicChan<()>>::new(tuning_params.concurrent_limit_per_thread);
 
         let (srv, srv_evt) = new_srv(Default::default(), ui_port).await?;
-        let kdirect = Kd1::new(srv.clone(), persist, p2p);
+        let kdirect = Kd1::new(tuning_params.clone(), srv.clone(), persist, p2p);
 
         let cc = logic_chan.handle().clone();
 
@@ -185,35 +186,150 @@ pub fn new_kitsune_direct_v1(
 
 // -- private -- //
 
+/// How many rejected (root, entry hash) pairs we remember,
+/// so we don't keep asking the validator about the same bad entries.
+const REJECT_CACHE_MAX: usize = 4096;
+
+static VALIDATE_MSG_ID: AtomicU64 = AtomicU64::new(1);
+
 struct Kd1Inner {
     srv: KdSrv,
     p2p: ghost_actor::GhostSender<actor::KitsuneP2p>,
     auth_set: HashSet<Uniq>,
+    // None once the root's validator has disconnected,
+    // its entries are refused until another validator registers
+    validators: HashMap<KdHash, Option<Uniq>>,
+    // keyed by msg_id, along with the validator con that must respond
+    pending_validations: HashMap<String, (Uniq, tokio::sync::oneshot::Sender<Result<(), String>>)>,
+    reject_cache: HashSet<(KdHash, KdHash)>,
+    reject_order: VecDeque<(KdHash, KdHash)>,
+}
+
+impl Kd1Inner {
+    fn reject(&mut self, key: (KdHash, KdHash)) {
+        if !self.reject_cache.insert(key.clone()) {
+            return;
+        }
+        self.reject_order.push_back(key);
+        while self.reject_order.len() > REJECT_CACHE_MAX {
+            if let Some(old) = self.reject_order.pop_front() {
+                self.reject_cache.remove(&old);
+            }
+        }
+    }
 }
 
 #[derive(Clone)]
 struct Kd1 {
     uniq: Uniq,
+    tuning_params: KitsuneP2pTuningParams,
     persist: KdPersist,
     inner: Share<Kd1Inner>,
 }
 
 impl Kd1 {
     pub fn new(
+        tuning_params: KitsuneP2pTuningParams,
         srv: KdSrv,
         persist: KdPersist,
         p2p: ghost_actor::GhostSender<actor::KitsuneP2p>,
     ) -> Arc<Self> {
         Arc::new(Self {
             uniq: Uniq::default(),
+            tuning_params,
             persist,
             inner: Share::new(Kd1Inner {
                 srv,
                 p2p,
                 auth_set: HashSet::new(),
+                validators: HashMap::new(),
+                pending_validations: HashMap::new(),
+                reject_cache: HashSet::new(),
+                reject_order: VecDeque::new(),
             }),
         })
     }
+
+    /// Check an entry with the validator registered for its app root
+    /// before we store / gossip it. If no validator was ever registered
+    /// for the root, all entries are accepted. If the root's validator
+    /// has disconnected, all entries are refused until another registers,
+    /// gossip will offer them to us again later.
+    async fn validate_entry(&self, root: &KdHash, entry: &KdEntrySigned) -> KdResult<()> {
+        let key = (root.clone(), entry.hash().clone());
+
+        let (rejected, validator) = self
+            .inner
+            .share_mut(|i, _| {
+                Ok((
+                    i.reject_cache.contains(&key),
+                    i.validators.get(root).map(|con| (*con, i.srv.clone())),
+                ))
+            })
+            .map_err(KdError::other)?;
+
+        if rejected {
+            return Err(format!("entry previously rejected: {}", entry.hash()).into());
+        }
+
+        let (con, srv) = match validator {
+            None => return Ok(()),
+            Some((None, _)) => {
+                return Err(format!("validator disconnected for root: {}", root).into())
+            }
+            Some((Some(con), srv)) => (con, srv),
+        };
+
+        let msg_id = format!(
+            "validate-{}",
+            VALIDATE_MSG_ID.fetch_add(1, Ordering::Relaxed)
+        );
+        let (s, r) = tokio::sync::oneshot::channel();
+        self.inner
+            .share_mut(|i, _| {
+                i.pending_validations.insert(msg_id.clone(), (con, s));
+                Ok(())
+            })
+            .map_err(KdError::other)?;
+
+        let timeout = std::time::Duration::from_millis(
+            self.tuning_params.default_rpc_single_timeout_ms as u64,
+        );
+        let res: KdResult<Result<(), String>> = async {
+            srv.websocket_send(
+                con,
+                KdApi::EntryValidateReq {
+                    msg_id: msg_id.clone(),
+                    root: root.clone(),
+                    entry_signed: entry.clone(),
+                },
+            )
+            .await?;
+            tokio::time::timeout(timeout, r)
+                .await
+                .map_err(KdError::other)?
+                .map_err(KdError::other)
+        }
+        .await;
+
+        // in case we errored / timed out before the validator responded
+        let _ = self.inner.share_mut(|i, _| {
+            i.pending_validations.remove(&msg_id);
+            Ok(())
+        });
+
+        // validator unavailable is not a verdict, don't cache it
+        match res? {
+            Ok(()) => Ok(()),
+            Err(reason) => {
+                let _ = self.inner.share_mut(move |i, _| {
+                    i.reject(key);
+                    Ok(())
+                });
+                Err(format!("entry rejected by validator: {}", reason).into())
+            }
+        }
+    }
 }
 
 impl AsKitsuneDirect for Kd1 {
@@ -326,6 +442,20 @@ async fn handle_srv_events(
                             tracing::error!(?err, "ws send error");
                         }
                     }
+                    KdSrvEvt::WebsocketDisconnected { con } => {
+                        // a disconnected validator can no longer answer,
+                        // dropping its pending senders fails those checks
+                        let _ = kdirect.inner.share_mut(|i, _| {
+                            i.auth_set.remove(&con);
+                            for v in i.validators.values_mut() {
+                                if *v == Some(con) {
+                                    *v = None;
+                                }
+                            }
+                            i.pending_validations.retain(|_, (v, _)| *v != con);
+                            Ok(())
+                        });
+                    }
                     KdSrvEvt::WebsocketMessage { con, data } => {
                         let msg_id = data.msg_id().to_string();
                         if let KdApi::HelloRes { .. } = data {
@@ -513,6 +643,9 @@ async fn handle_srv_events(
                                     }
                                     let entry_signed = KdEntrySigned::from_content_with_binary(&kdirect.persist, content, &binary).await?;
 
+                                    // make sure the app accepts this entry
+                                    kdirect.validate_entry(&root, &entry_signed).await?;
+
                                     // first, put this in our store
                                     // so it can begin gossiping
                                     kdirect.persist.store_entry(root.clone(), author, entry_signed.clone()).await.map_err(KdError::other)?;
@@ -565,6 +698,50 @@ async fn handle_srv_events(
                                     })
                                 }.boxed()).await;
                             }
+                            KdApi::AppValidatorRegisterReq {
+                                msg_id,
+                                root,
+                                ..
+                            } => {
+                                exec(msg_id.clone(), async {
+                                    // replaces any previous validator for the root
+                                    kdirect.inner.share_mut(|i, _| {
+                                        i.validators.insert(root, Some(con));
+                                        Ok(())
+                                    }).map_err(KdError::other)?;
+                                    Ok(KdApi::AppValidatorRegisterRes {
+                                        msg_id,
+                                    })
+                                }.boxed()).await;
+                            }
+                            KdApi::EntryValidateRes {
+                                msg_id,
+                                valid,
+                                reason,
+                                ..
+                            } => {
+                                let verdict = if valid {
+                                    Ok(())
+                                } else {
+                                    Err(reason.unwrap_or_else(|| "invalid".to_string()))
+                                };
+                                // only the validator we asked may answer
+                                match kdirect.inner.share_mut(|i, _| {
+                                    match i.pending_validations.get(&msg_id) {
+                                        Some((v, _)) if *v == con => {
+                                            Ok(i.pending_validations.remove(&msg_id))
+                                        }
+                                        _ => Ok(None),
+                                    }
+                                }) {
+                                    Ok(Some((_, snd))) => {
+                                        let _ = snd.send(verdict);
+                                    }
+                                    _ => {
+                                        tracing::warn!(%msg_id, "ignoring unexpected validation response");
+                                    }
+                                }
+                            }
                             KdApi::EntryGetChildrenReq {
                                 //msg_id,
                                 //root,
@@ -588,7 +765,9 @@ async fn handle_srv_events(
                             oth @ KdApi::MessageRecvEvt { .. } |
                             oth @ KdApi::EntryAuthorRes { .. } |
                             oth @ KdApi::EntryGetRes { .. } |
-                            oth @ KdApi::EntryGetChildrenRes { .. } => {
+                            oth @ KdApi::EntryGetChildrenRes { .. } |
+                            oth @ KdApi::AppValidatorRegisterRes { .. } |
+                            oth @ KdApi::EntryValidateReq { .. } => {
                                 let reason = format!("unexpected {}", oth);
                                 if let Err(err) = srv.websocket_send(con, KdApi::ErrorRes {
                                     msg_id,
@@ -679,6 +858,10 @@ async fn handle_events(
                             .map_err(KitsuneP2pError::other)?;
                         let root = KdHash::from_kitsune_space(&space);
                         let to_agent = KdHash::from_kitsune_agent(&to_agent);
+                        kdirect
+                            .validate_entry(&root, &entry)
+                            .await
+                            .map_err(KitsuneP2pError::other)?;
                         kdirect
                             .persist
                             .store_entry(root, to_agent, entry)
@@ -835,20 +1018,25 @@ async fn handle_gossip(
         .query_agent_info(root.clone())
         .await
         .map_err(KdError::other)?;
-    for info in agent_info_list {
-        let to_agent = info.agent();
-        for (op_hash, op_data) in ops.clone() {
-            let entry = KdEntrySigned::from_wire(op_data.into_boxed_slice())
-                .await
-                .map_err(KdError::other)?;
-            let op_hash = KdHash::from_kitsune_op_hash(&op_hash);
-            if &op_hash != entry.hash() {
-                return Err("data did not hash to given hash".into());
-            }
+    for (op_hash, op_data) in ops {
+        let entry = KdEntrySigned::from_wire(op_data.into_boxed_slice())
+            .await
+            .map_err(KdError::other)?;
+        let op_hash = KdHash::from_kitsune_op_hash(&op_hash);
+        if &op_hash != entry.hash() {
+            return Err("data did not hash to given hash".into());
+        }
+
+        // invalid entries are dropped, so we don't gossip them onward
+        if let Err(err) = kdirect.validate_entry(&root, &entry).await {
+            tracing::warn!(?err, "dropping gossiped entry");
+            continue;
+        }
 
+        for info in agent_info_list.iter() {
             kdirect
                 .persist
-                .store_entry(root.clone(), to_agent.clone(), entry)
+                .store_entry(root.clone(), info.agent().clone(), entry.clone())
                 .await?;
         }
     }
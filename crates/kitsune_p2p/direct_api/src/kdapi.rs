@@ -382,6 +382,62 @@ pub enum KdApi {
         #[serde(rename = "entrySignedList")]
         entry_signed_list: Vec<KdEntrySigned>,
     },
+
+    /// Register this connection as the entry validator for an app root hash.
+    /// Any previously registered validator for this root is replaced.
+    /// While the validator of a root is disconnected, entries under
+    /// that root are refused.
+    #[serde(rename = "appValidatorRegisterReq")]
+    AppValidatorRegisterReq {
+        /// message id
+        #[serde(rename = "msgId")]
+        msg_id: String,
+
+        /// root app hash
+        #[serde(rename = "root")]
+        root: KdHash,
+    },
+
+    /// Success registering an entry validator
+    #[serde(rename = "appValidatorRegisterRes")]
+    AppValidatorRegisterRes {
+        /// message id
+        #[serde(rename = "msgId")]
+        msg_id: String,
+    },
+
+    /// Sent from server to a registered validator,
+    /// requesting validation of an entry before it is stored / gossiped
+    #[serde(rename = "entryValidateReq")]
+    EntryValidateReq {
+        /// message id
+        #[serde(rename = "msgId")]
+        msg_id: String,
+
+        /// root app hash
+        #[serde(rename = "root")]
+        root: KdHash,
+
+        /// signed entry to validate
+        #[serde(rename = "entrySigned")]
+        entry_signed: KdEntrySigned,
+    },
+
+    /// The validation verdict for an entry
+    #[serde(rename = "entryValidateRes")]
+    EntryValidateRes {
+        /// message id
+        #[serde(rename = "msgId")]
+        msg_id: String,
+
+        /// true if the entry is valid
+        #[serde(rename = "valid")]
+        valid: bool,
+
+        /// if invalid, the reason the entry was rejected
+        #[serde(rename = "reason", default)]
+        reason: Option<String>,
+    },
 }
 
 impl std::fmt::Display for KdApi {
@@ -438,6 +494,10 @@ impl KdApi {
             Self::EntryGetRes { msg_id, .. } => msg_id,
             Self::EntryGetChildrenReq { msg_id, .. } => msg_id,
             Self::EntryGetChildrenRes { msg_id, .. } => msg_id,
+            Self::AppValidatorRegisterReq { msg_id, .. } => msg_id,
+            Self::AppValidatorRegisterRes { msg_id, .. } => msg_id,
+            Self::EntryValidateReq { msg_id, .. } => msg_id,
+            Self::EntryValidateRes { msg_id, .. } => msg_id,
         }
     }
 
@@ -458,6 +518,8 @@ impl KdApi {
             Self::EntryAuthorRes { .. } => true,
             Self::EntryGetRes { .. } => true,
             Self::EntryGetChildrenRes { .. } => true,
+            Self::AppValidatorRegisterRes { .. } => true,
+            Self::EntryValidateRes { .. } => true,
             _ => false,
         }
     }
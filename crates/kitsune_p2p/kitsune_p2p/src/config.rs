@@ -14,13 +14,24 @@ pub const BOOTSTRAP_SERVICE_DEV: &str = "https://bootstrap-dev.holohost.workers.
 
 pub(crate) enum KitsuneP2pTx2Backend {
     Mem,
-    Quic { bind_to: TxUrl },
-    Mock { mock_network: AdapterFactory },
+    Quic {
+        bind_to: TxUrl,
+    },
+    Tcp {
+        bind_to: TxUrl,
+        override_host: Option<String>,
+        override_port: Option<u16>,
+    },
+    Mock {
+        mock_network: AdapterFactory,
+    },
 }
 
 pub(crate) struct KitsuneP2pTx2Config {
     pub backend: KitsuneP2pTx2Backend,
     pub use_proxy: Option<TxUrl>,
+    pub proxy_accept_config: Option<ProxyAcceptConfig>,
+    pub proxy_quota_config: ProxyQuotaConfig,
 }
 
 /// Configure the kitsune actor
@@ -59,6 +70,13 @@ fn cnv_bind_to(bind_to: &Option<url2::Url2>) -> TxUrl {
     }
 }
 
+fn cnv_tcp_bind_to(bind_to: &Option<url2::Url2>) -> TxUrl {
+    match bind_to {
+        Some(bind_to) => bind_to.clone().into(),
+        None => "kitsune-tcp://0.0.0.0:0".into(),
+    }
+}
+
 impl KitsuneP2pConfig {
     /// tx2 is currently designed to use exactly one proxy wrapped transport
     /// so, convert a bunch of the options from the previous transport
@@ -75,19 +93,64 @@ impl KitsuneP2pConfig {
                         let bind_to = cnv_bind_to(bind_to);
                         KitsuneP2pTx2Backend::Quic { bind_to }
                     }
-                    _ => return Err("kitsune tx2 backend must be mem or quic".into()),
+                    TransportConfig::Tcp {
+                        bind_to,
+                        override_host,
+                        override_port,
+                    } => {
+                        let bind_to = cnv_tcp_bind_to(bind_to);
+                        KitsuneP2pTx2Backend::Tcp {
+                            bind_to,
+                            override_host: override_host.clone(),
+                            override_port: *override_port,
+                        }
+                    }
+                    _ => return Err("kitsune tx2 backend must be mem, quic or tcp".into()),
                 };
-                let use_proxy = match proxy_config {
-                    ProxyConfig::RemoteProxyClient { proxy_url } => Some(proxy_url.clone().into()),
-                    ProxyConfig::LocalProxyServer { .. } => None,
+                let (use_proxy, proxy_accept_config, proxy_quota_config) = match proxy_config {
+                    ProxyConfig::RemoteProxyClient { proxy_url } => {
+                        (Some(proxy_url.clone().into()), None, None)
+                    }
+                    ProxyConfig::LocalProxyServer {
+                        proxy_accept_config,
+                        proxy_quota_config,
+                    } => (
+                        None,
+                        proxy_accept_config.clone(),
+                        proxy_quota_config.clone(),
+                    ),
                 };
-                Ok(KitsuneP2pTx2Config { backend, use_proxy })
+                Ok(KitsuneP2pTx2Config {
+                    backend,
+                    use_proxy,
+                    proxy_accept_config,
+                    proxy_quota_config: proxy_quota_config.unwrap_or_default(),
+                })
             }
             Some(TransportConfig::Quic { bind_to, .. }) => {
                 let bind_to = cnv_bind_to(bind_to);
                 Ok(KitsuneP2pTx2Config {
                     backend: KitsuneP2pTx2Backend::Quic { bind_to },
                     use_proxy: None,
+                    proxy_accept_config: None,
+                    proxy_quota_config: ProxyQuotaConfig::default(),
+                })
+            }
+            Some(TransportConfig::Tcp {
+                bind_to,
+                override_host,
+                override_port,
+            }) => {
+                let bind_to = cnv_tcp_bind_to(bind_to);
+                Ok(KitsuneP2pTx2Config {
+                    backend: KitsuneP2pTx2Backend::Tcp {
+                        bind_to,
+                        override_host: override_host.clone(),
+                        override_port: *override_port,
+                    },
+                    use_proxy: None,
+                    proxy_accept_config: None,
+                    proxy_quota_config: ProxyQuotaConfig::default(),
                 })
             }
             Some(TransportConfig::Mock { mock_network }) => Ok(KitsuneP2pTx2Config {
@@ -95,10 +158,14 @@ impl KitsuneP2pConfig {
                     mock_network: mock_network.0.clone(),
                 },
                 use_proxy: None,
+                proxy_accept_config: None,
+                proxy_quota_config: ProxyQuotaConfig::default(),
             }),
             None | Some(TransportConfig::Mem {}) => Ok(KitsuneP2pTx2Config {
                 backend: KitsuneP2pTx2Backend::Mem,
                 use_proxy: None,
+                proxy_accept_config: None,
+                proxy_quota_config: ProxyQuotaConfig::default(),
             }),
         }
     }
@@ -128,6 +195,24 @@ pub enum TransportConfig {
         /// Default: None = use NIC port.
         override_port: Option<u16>,
     },
+    /// A transport that uses TLS over TCP. This is a fallback for
+    /// networks that block UDP, and therefore QUIC.
+    Tcp {
+        /// To which network interface / port should we bind?
+        /// Default: "kitsune-tcp://0.0.0.0:0".
+        bind_to: Option<Url2>,
+
+        /// If you have port-forwarding set up,
+        /// or wish to apply a vanity domain name,
+        /// you may need to override the local NIC ip.
+        /// Default: None = use NIC ip.
+        override_host: Option<String>,
+
+        /// If you have port-forwarding set up,
+        /// you may need to override the local NIC port.
+        /// Default: None = use NIC port.
+        override_port: Option<u16>,
+    },
     /// A transport that tls tunnels through a sub-transport (ALPN kitsune-proxy/0)
     Proxy {
         /// The 'Proxy' transport is a wrapper around a sub-transport
@@ -187,6 +272,11 @@ pub enum ProxyConfig {
         /// Accept proxy request options
         /// Default: None = reject all proxy requests
         proxy_accept_config: Option<ProxyAcceptConfig>,
+
+        /// Limits applied to each client we proxy for
+        /// Default: None = no limits
+        #[serde(default)]
+        proxy_quota_config: Option<ProxyQuotaConfig>,
     },
 }
 
@@ -199,6 +289,32 @@ pub enum ProxyAcceptConfig {
 
     /// We will reject all requests to proxy for remotes
     RejectAll,
+
+    /// We will only proxy for remotes whose tls certificate digest
+    /// is listed here (url-safe base64, no padding)
+    AcceptCertDigests {
+        /// The allowed tls certificate digests
+        cert_digests: Vec<String>,
+    },
+
+    /// We will only proxy for remotes that have published an agent info
+    /// in one of the spaces we have joined
+    AcceptSpaceMembers,
+}
+
+/// Limits a proxy server applies to each client it is proxying for
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct ProxyQuotaConfig {
+    /// Maximum bytes per second forwarded to any single client
+    /// Default: None = unlimited
+    #[serde(default)]
+    pub max_bytes_per_second: Option<u32>,
+
+    /// Maximum number of distinct remotes that may reach any single
+    /// client through us at one time
+    /// Default: None = unlimited
+    #[serde(default)]
+    pub max_connections: Option<u32>,
 }
 
 /// Method for connecting to other peers and broadcasting our AgentInfo
@@ -13,6 +13,7 @@ use futures::stream::StreamExt;
 use kitsune_p2p_proxy::tx2::*;
 use kitsune_p2p_proxy::ProxyUrl;
 use kitsune_p2p_transport_quic::tx2::*;
+use kitsune_p2p_transport_tcp::tx2::*;
 use kitsune_p2p_types::agent_info::AgentInfoSigned;
 use kitsune_p2p_types::async_lazy::AsyncLazy;
 use kitsune_p2p_types::tx2::tx2_api::*;
@@ -22,7 +23,7 @@ use kitsune_p2p_types::tx2::tx2_utils::TxUrl;
 use kitsune_p2p_types::tx2::*;
 use kitsune_p2p_types::*;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 /// The bootstrap service is much more thoroughly documented in the default service implementation.
@@ -90,11 +91,45 @@ pub(crate) struct KitsuneP2pActor {
             ghost_actor::GhostSender<space::SpaceInternal>,
         )>,
     >,
+    joined_spaces: Arc<parking_lot::Mutex<HashSet<KSpace>>>,
     config: Arc<KitsuneP2pConfig>,
     bandwidth_throttles: BandwidthThrottles,
     parallel_notify_permit: Arc<tokio::sync::Semaphore>,
 }
 
+/// Only accept proxy clients whose cert digest appears in the url list
+/// of an agent info in one of our joined spaces.
+fn accept_space_members_cb(
+    evt_sender: futures::channel::mpsc::Sender<KitsuneP2pEvent>,
+    joined_spaces: Arc<parking_lot::Mutex<HashSet<KSpace>>>,
+) -> kitsune_p2p_proxy::AcceptProxyCallback {
+    kitsune_p2p_proxy::AcceptProxyCallback(Arc::new(move |digest| {
+        let evt_sender = evt_sender.clone();
+        let spaces = joined_spaces.lock().iter().cloned().collect::<Vec<_>>();
+        let digest = Tx2Cert::from(digest);
+        async move {
+            for space in spaces {
+                let list = match evt_sender.query_agents(QueryAgentsEvt::new(space)).await {
+                    Ok(list) => list,
+                    Err(_) => continue,
+                };
+                for info in list {
+                    for url in info.url_list.iter() {
+                        if let Ok(purl) = ProxyUrl::from_full(url.as_str()) {
+                            if Tx2Cert::from(purl.digest()) == digest {
+                                return true;
+                            }
+                        }
+                    }
+                }
+            }
+            false
+        }
+        .boxed()
+        .into()
+    }))
+}
+
 impl KitsuneP2pActor {
     pub async fn new(
         config: KitsuneP2pConfig,
@@ -133,6 +168,23 @@ impl KitsuneP2pActor {
                     bind_to,
                 )
             }
+            KitsuneP2pTx2Backend::Tcp {
+                bind_to,
+                override_host,
+                override_port,
+            } => {
+                let mut conf = TcpConfig::default();
+                conf.tls = Some(tls_config.clone());
+                conf.tuning_params = Some(config.tuning_params.clone());
+                conf.override_host = override_host;
+                conf.override_port = override_port;
+                (
+                    tx2_tcp_adapter(conf)
+                        .await
+                        .map_err(KitsuneP2pError::other)?,
+                    bind_to,
+                )
+            }
             KitsuneP2pTx2Backend::Mock { mock_network } => {
                 is_mock = true;
                 (mock_network, "none:".into())
@@ -145,6 +197,8 @@ impl KitsuneP2pActor {
         // convert to frontend
         let f = tx2_pool_promote(f, config.tuning_params.clone());
 
+        let joined_spaces = Arc::new(parking_lot::Mutex::new(HashSet::new()));
+
         // wrap in proxy
         let f = if !is_mock {
             let mut conf = kitsune_p2p_proxy::tx2::ProxyConfig::default();
@@ -153,6 +207,36 @@ impl KitsuneP2pActor {
                 let proxy_url = ProxyUrl::from(use_proxy.as_str());
                 conf.client_of_remote_proxy = Some(proxy_url);
             }
+            match &tx2_conf.proxy_accept_config {
+                None | Some(ProxyAcceptConfig::RejectAll) => (),
+                Some(ProxyAcceptConfig::AcceptAll) => {
+                    conf.allow_proxy_fwd = true;
+                }
+                Some(ProxyAcceptConfig::AcceptCertDigests { cert_digests }) => {
+                    let mut digests: Vec<CertDigest> = Vec::new();
+                    for digest in cert_digests {
+                        let digest = base64::decode_config(digest, base64::URL_SAFE_NO_PAD)
+                            .map_err(KitsuneP2pError::other)?;
+                        digests.push(digest.into());
+                    }
+                    conf.allow_proxy_fwd = true;
+                    conf.access.accept_proxy_cb = Some(
+                        kitsune_p2p_proxy::AcceptProxyCallback::accept_cert_digests(digests),
+                    );
+                }
+                Some(ProxyAcceptConfig::AcceptSpaceMembers) => {
+                    conf.allow_proxy_fwd = true;
+                    conf.access.accept_proxy_cb = Some(accept_space_members_cb(
+                        evt_sender.clone(),
+                        joined_spaces.clone(),
+                    ));
+                }
+            }
+            conf.access.client_bytes_per_second = tx2_conf.proxy_quota_config.max_bytes_per_second;
+            conf.access.client_max_connections = tx2_conf
+                .proxy_quota_config
+                .max_connections
+                .map(|c| c as usize);
             let f = tx2_proxy(f, conf)?;
             f
         } else {
@@ -427,6 +511,7 @@ impl KitsuneP2pActor {
             evt_sender,
             ep_hnd,
             spaces: HashMap::new(),
+            joined_spaces,
             config: Arc::new(config),
             bandwidth_throttles,
             parallel_notify_permit,
@@ -688,6 +773,7 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         let config = Arc::clone(&self.config);
         let bandwidth_throttles = self.bandwidth_throttles.clone();
         let parallel_notify_permit = self.parallel_notify_permit.clone();
+        self.joined_spaces.lock().insert(space.clone());
         let space_sender = match self.spaces.entry(space.clone()) {
             Entry::Occupied(entry) => entry.into_mut(),
             Entry::Vacant(entry) => entry.insert(AsyncLazy::new(async move {
@@ -133,6 +133,29 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_transport_binding_tcp() -> Result<(), KitsuneP2pError> {
+        observability::test_run().ok();
+
+        let (harness, _evt) = spawn_test_harness_tcp().await?;
+
+        harness.add_space().await?;
+        let (_, p2p) = harness.add_direct_agent("DIRECT".into()).await?;
+
+        // The proxy wraps the tcp backend just as it does quic.
+        let bindings = p2p.list_transport_bindings().await?;
+        assert_eq!(1, bindings.len());
+        let binding = &bindings[0];
+        assert_eq!("kitsune-proxy", binding.scheme());
+        assert_eq!(
+            "kitsune-tcp",
+            binding.path_segments().unwrap().next().unwrap()
+        );
+
+        harness.ghost_actor_shutdown().await?;
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_request_workflow() -> Result<(), KitsuneP2pError> {
         observability::test_run().ok();
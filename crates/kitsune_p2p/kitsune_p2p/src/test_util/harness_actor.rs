@@ -81,6 +81,22 @@ pub async fn spawn_test_harness_quic() -> Result<
     .await
 }
 
+/// construct a test suite around a tcp transport
+pub async fn spawn_test_harness_tcp() -> Result<
+    (
+        ghost_actor::GhostSender<HarnessControlApi>,
+        HarnessEventChannel,
+    ),
+    KitsuneP2pError,
+> {
+    spawn_test_harness(TransportConfig::Tcp {
+        bind_to: Some(url2::url2!("kitsune-tcp://0.0.0.0:0")),
+        override_host: None,
+        override_port: None,
+    })
+    .await
+}
+
 /// construct a test suite around a sub transport config concept
 pub async fn spawn_test_harness(
     sub_config: TransportConfig,
@@ -230,6 +246,7 @@ impl HarnessControlApiHandler for HarnessActor {
                 sub_transport: Box::new(self.sub_config.clone()),
                 proxy_config: ProxyConfig::LocalProxyServer {
                     proxy_accept_config: Some(ProxyAcceptConfig::AcceptAll),
+                    proxy_quota_config: None,
                 },
             });
 
@@ -258,6 +275,7 @@ impl HarnessControlApiHandler for HarnessActor {
                 sub_transport: Box::new(self.sub_config.clone()),
                 proxy_config: ProxyConfig::LocalProxyServer {
                     proxy_accept_config: Some(ProxyAcceptConfig::RejectAll),
+                    proxy_quota_config: None,
                 },
             });
 
@@ -293,6 +311,7 @@ impl HarnessControlApiHandler for HarnessActor {
                 sub_transport: Box::new(self.sub_config.clone()),
                 proxy_config: ProxyConfig::LocalProxyServer {
                     proxy_accept_config: Some(ProxyAcceptConfig::RejectAll),
+                    proxy_quota_config: None,
                 },
             });
 
@@ -1,6 +1,7 @@
 use futures::stream::StreamExt;
 use kitsune_p2p_proxy::tx2::*;
 use kitsune_p2p_transport_quic::tx2::*;
+use kitsune_p2p_transport_tcp::tx2::*;
 use kitsune_p2p_types::config::KitsuneP2pTuningParams;
 use kitsune_p2p_types::dependencies::{ghost_actor::dependencies::tracing, serde_json};
 use kitsune_p2p_types::metrics::*;
@@ -24,8 +25,24 @@ pub struct Opt {
     pub danger_use_unenc_cert: Option<std::path::PathBuf>,
 
     /// To which network interface / port should we bind?
+    /// Use a "kitsune-tcp://" scheme to serve over tcp instead of quic.
     #[structopt(short = "b", long, default_value = "kitsune-quic://0.0.0.0:0")]
     pub bind_to: String,
+
+    /// Only forward messages to clients with these tls cert digests
+    /// (url-safe base64, no padding). May be specified multiple times.
+    /// If not specified, all clients are accepted.
+    #[structopt(long)]
+    pub allow_cert: Vec<String>,
+
+    /// Limit the bytes per second forwarded on behalf of any single client.
+    #[structopt(long)]
+    pub client_bytes_per_second: Option<u32>,
+
+    /// Limit the number of distinct peers that may reach any single
+    /// client through this proxy at one time.
+    #[structopt(long)]
+    pub client_max_connections: Option<usize>,
 }
 
 #[tokio::main(flavor = "multi_thread")]
@@ -105,15 +122,34 @@ async fn inner() -> KitsuneResult<()> {
         TlsConfig::new_ephemeral().await?
     };
 
-    let mut conf = QuicConfig::default();
-    conf.tls = Some(tls_conf.clone());
-    conf.tuning_params = Some(tuning_params.clone());
-
-    let f = QuicBackendAdapt::new(conf).await?;
+    let f = if opt.bind_to.starts_with("kitsune-tcp:") {
+        let mut conf = TcpConfig::default();
+        conf.tls = Some(tls_conf.clone());
+        conf.tuning_params = Some(tuning_params.clone());
+        TcpBackendAdapt::new(conf).await?
+    } else {
+        let mut conf = QuicConfig::default();
+        conf.tls = Some(tls_conf.clone());
+        conf.tuning_params = Some(tuning_params.clone());
+        QuicBackendAdapt::new(conf).await?
+    };
     let f = tx2_pool_promote(f, tuning_params.clone());
     let mut conf = ProxyConfig::default();
     conf.tuning_params = Some(tuning_params.clone());
     conf.allow_proxy_fwd = true;
+    if !opt.allow_cert.is_empty() {
+        let mut digests: Vec<CertDigest> = Vec::new();
+        for cert in opt.allow_cert.iter() {
+            let digest = base64::decode_config(cert, base64::URL_SAFE_NO_PAD)
+                .map_err(KitsuneError::other)?;
+            digests.push(digest.into());
+        }
+        conf.access.accept_proxy_cb = Some(
+            kitsune_p2p_proxy::AcceptProxyCallback::accept_cert_digests(digests),
+        );
+    }
+    conf.access.client_bytes_per_second = opt.client_bytes_per_second;
+    conf.access.client_max_connections = opt.client_max_connections;
     let f = tx2_proxy(f, conf)?;
 
     let ep = f
@@ -20,6 +20,19 @@ impl AcceptProxyCallback {
     pub fn accept_all() -> Self {
         Self(Arc::new(|_| async { true }.boxed().into()))
     }
+
+    /// Callback that only accepts proxy requests from the listed cert digests.
+    pub fn accept_cert_digests(digests: impl IntoIterator<Item = CertDigest>) -> Self {
+        let allow: std::collections::HashSet<kitsune_p2p_types::Tx2Cert> = digests
+            .into_iter()
+            .map(kitsune_p2p_types::Tx2Cert::from)
+            .collect();
+        let allow = Arc::new(allow);
+        Self(Arc::new(move |digest| {
+            let accept = allow.contains(&kitsune_p2p_types::Tx2Cert::from(digest));
+            async move { accept }.boxed().into()
+        }))
+    }
 }
 
 /// Configuration for proxy binding.
@@ -13,7 +13,8 @@ use kitsune_p2p_types::tx2::tx2_pool::*;
 use kitsune_p2p_types::tx2::tx2_utils::*;
 use kitsune_p2p_types::tx2::*;
 use kitsune_p2p_types::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroU32;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::sync::Notify;
 
@@ -30,6 +31,11 @@ pub struct ProxyConfig {
     /// Default: false.
     pub allow_proxy_fwd: bool,
 
+    /// If `allow_proxy_fwd` is enabled, these restrictions
+    /// are applied to the clients we forward messages for.
+    /// Default: no restrictions.
+    pub access: ProxyAccessConfig,
+
     /// If Some(addr), we will try to keep an open connection to addr.
     /// The node at addr should forward messages intended for us,
     /// and we will modify our local_addr() function to make that
@@ -42,6 +48,7 @@ impl Default for ProxyConfig {
         Self {
             tuning_params: None,
             allow_proxy_fwd: false,
+            access: ProxyAccessConfig::default(),
             client_of_remote_proxy: None,
         }
     }
@@ -49,19 +56,54 @@ impl Default for ProxyConfig {
 
 impl ProxyConfig {
     /// into inner contents with default application
-    pub fn split(self) -> KitsuneResult<(KitsuneP2pTuningParams, bool, Option<ProxyUrl>)> {
+    pub fn split(
+        self,
+    ) -> KitsuneResult<(
+        KitsuneP2pTuningParams,
+        bool,
+        ProxyAccessConfig,
+        Option<ProxyUrl>,
+    )> {
         let ProxyConfig {
             tuning_params,
             allow_proxy_fwd,
+            access,
             client_of_remote_proxy,
         } = self;
 
         let tuning_params = tuning_params.unwrap_or_else(KitsuneP2pTuningParams::default);
 
-        Ok((tuning_params, allow_proxy_fwd, client_of_remote_proxy))
+        Ok((
+            tuning_params,
+            allow_proxy_fwd,
+            access,
+            client_of_remote_proxy,
+        ))
     }
 }
 
+/// Restrictions a proxy server applies to the clients it forwards for.
+/// A "client" is a node connected directly to us, that others
+/// reach by sending messages through us.
+#[non_exhaustive]
+#[derive(Clone, Default)]
+pub struct ProxyAccessConfig {
+    /// Only forward messages to clients accepted by this callback.
+    /// The result is cached for as long as the client stays connected.
+    /// Default: None = accept all clients.
+    pub accept_proxy_cb: Option<AcceptProxyCallback>,
+
+    /// Limit the bytes per second forwarded on behalf of any single client.
+    /// Messages larger than one second's worth of quota are always rejected.
+    /// Default: None = unlimited.
+    pub client_bytes_per_second: Option<u32>,
+
+    /// Limit the number of distinct remote peers any single client
+    /// may be reached by through us at one time.
+    /// Default: None = unlimited.
+    pub client_max_connections: Option<usize>,
+}
+
 /// Wrap a tx2 transport pool adapter with proxy logic.
 pub fn tx2_proxy(sub_fact: EpFactory, config: ProxyConfig) -> KitsuneResult<EpFactory> {
     ProxyEpFactory::new(sub_fact, config)
@@ -207,6 +249,34 @@ impl Backoff {
     }
 }
 
+type ClientLimiter = governor::RateLimiter<
+    Tx2Cert,
+    governor::state::keyed::DefaultKeyedStateStore<Tx2Cert>,
+    governor::clock::DefaultClock,
+>;
+
+fn client_limiter(access: &ProxyAccessConfig) -> Option<ClientLimiter> {
+    access
+        .client_bytes_per_second
+        .and_then(NonZeroU32::new)
+        .map(|bps| ClientLimiter::keyed(governor::Quota::per_second(bps)))
+}
+
+/// Access control / quota tracking for an individual proxy client.
+#[derive(Default)]
+struct ProxyClientState {
+    /// Has the accept_proxy_cb accepted this client?
+    /// Only acceptance is cached. A rejected client is checked again
+    /// on its next message, e.g. once its agent info has arrived.
+    accepted: bool,
+
+    /// Remote peers currently being forwarded to this client.
+    peers: HashSet<Tx2Cert>,
+
+    /// Total bytes we have forwarded on behalf of this client.
+    fwd_bytes: u64,
+}
+
 struct ProxyEpInner {
     // map peer certs to connection handles
     // so on proxy requests we know who to send to
@@ -221,6 +291,13 @@ struct ProxyEpInner {
     // these are both INCOMING and OUTGOING
     direct_to_final_peer_con_map: HashMap<Uniq, HashMap<Tx2Cert, ConHnd>>,
 
+    // access control / quota tracking for clients we forward for
+    // keyed by the same certs as `digest_to_sub_con_map`
+    clients: HashMap<Tx2Cert, ProxyClientState>,
+
+    // per-client bandwidth limit, if configured
+    limiter: Option<ClientLimiter>,
+
     backoff: Backoff,
 }
 
@@ -287,9 +364,11 @@ impl ProxyEpHnd {
         sub_ep_hnd: EpHnd,
         logic_hnd: LogicChanHandle<EpEvent>,
         backoff: Backoff,
+        access: &ProxyAccessConfig,
         client_of_remote_proxy: Option<ProxyUrl>,
     ) -> KitsuneResult<Arc<ProxyEpHnd>> {
         let local_cert = sub_ep_hnd.local_cert();
+        let limiter = client_limiter(access);
         Ok(Arc::new(ProxyEpHnd {
             sub_ep_hnd,
             local_cert,
@@ -297,6 +376,8 @@ impl ProxyEpHnd {
             inner: Share::new(ProxyEpInner {
                 digest_to_sub_con_map: HashMap::new(),
                 direct_to_final_peer_con_map: HashMap::new(),
+                clients: HashMap::new(),
+                limiter,
                 backoff,
             }),
             client_of_remote_proxy,
@@ -311,7 +392,17 @@ impl AsEpHnd for ProxyEpHnd {
             let proxy_list = i
                 .digest_to_sub_con_map
                 .keys()
-                .map(|k| format!("{:?}", k))
+                .map(|k| match i.clients.get(k) {
+                    None => serde_json::json!({
+                        "cert": format!("{:?}", k),
+                    }),
+                    Some(c) => serde_json::json!({
+                        "cert": format!("{:?}", k),
+                        "accepted": c.accepted,
+                        "peer_count": c.peers.len(),
+                        "fwd_bytes": c.fwd_bytes,
+                    }),
+                })
                 .collect::<Vec<_>>();
             Ok(serde_json::json!({
                 "type": "tx2_proxy",
@@ -420,6 +511,7 @@ async fn close_ep(hnd: &Arc<ProxyEpHnd>, logic_hnd: &LogicChanHandle<EpEvent>) {
 async fn incoming_evt_logic(
     tuning_params: KitsuneP2pTuningParams,
     allow_proxy_fwd: bool,
+    access: ProxyAccessConfig,
     sub_ep: Ep,
     hnd: Arc<ProxyEpHnd>,
     logic_hnd: LogicChanHandle<EpEvent>,
@@ -428,6 +520,7 @@ async fn incoming_evt_logic(
     let local_cert = sub_ep.handle().local_cert();
     let local_cert = &local_cert;
     let tuning_params = &tuning_params;
+    let access = &access;
     let client_of_remote_proxy = &client_of_remote_proxy;
 
     // Benchmarks showed a slight slowdown when using semaphore count tasks
@@ -437,6 +530,7 @@ async fn incoming_evt_logic(
             incoming_evt_handle(
                 tuning_params,
                 allow_proxy_fwd,
+                access,
                 evt,
                 local_cert.clone(),
                 &hnd,
@@ -500,6 +594,7 @@ async fn ensure_proxy_register(
 async fn incoming_evt_handle(
     tuning_params: &KitsuneP2pTuningParams,
     allow_proxy_fwd: bool,
+    access: &ProxyAccessConfig,
     evt: EpEvent,
     local_cert: Tx2Cert,
     hnd: &Arc<ProxyEpHnd>,
@@ -590,9 +685,20 @@ async fn incoming_evt_handle(
                             tracing::error!("received fwd request on, but proxy fwd is disallowed");
                             Err("proxy fwd disallowed".into())
                         } else {
-                            hnd.inner.share_mut(|i, _| {
+                            match hnd.inner.share_mut(|i, _| {
                                 Ok(i.digest_to_sub_con_map.get(&dest_cert).cloned())
-                            })
+                            }) {
+                                Ok(Some(d_sub_con)) => check_fwd_access(
+                                    access,
+                                    &hnd.inner,
+                                    &src_cert,
+                                    &dest_cert,
+                                    data.len(),
+                                )
+                                .await
+                                .map(|_| Some(d_sub_con)),
+                                r => r,
+                            }
                         };
                         if let Err(e) = match dest {
                             Ok(Some(d_sub_con)) => {
@@ -702,6 +808,59 @@ async fn incoming_evt_handle(
     }
 }
 
+/// Determine if we are willing to forward `len` bytes from `src_cert`
+/// to our proxy client `dest_cert`, recording the usage if so.
+async fn check_fwd_access(
+    access: &ProxyAccessConfig,
+    inner: &Share<ProxyEpInner>,
+    src_cert: &Tx2Cert,
+    dest_cert: &Tx2Cert,
+    len: usize,
+) -> KitsuneResult<()> {
+    if let Some(accept_proxy_cb) = &access.accept_proxy_cb {
+        let cached =
+            inner.share_mut(|i, _| Ok(i.clients.get(dest_cert).map_or(false, |c| c.accepted)))?;
+        if !cached {
+            if !accept_proxy_cb(dest_cert.as_digest().clone()).await {
+                return Err(format!("proxy client not accepted: {:?}", dest_cert).into());
+            }
+            inner.share_mut(|i, _| {
+                i.clients.entry(dest_cert.clone()).or_default().accepted = true;
+                Ok(())
+            })?;
+        }
+    }
+
+    inner.share_mut(|i, _| {
+        let ProxyEpInner {
+            clients, limiter, ..
+        } = i;
+        let client = clients.entry(dest_cert.clone()).or_default();
+
+        if !client.peers.contains(src_cert) {
+            if let Some(max) = access.client_max_connections {
+                if client.peers.len() >= max {
+                    return Err(
+                        format!("proxy client connection limit reached: {:?}", dest_cert).into(),
+                    );
+                }
+            }
+        }
+
+        if let (Some(limiter), Some(n)) = (limiter, NonZeroU32::new(len as u32)) {
+            if limiter.check_key_n(dest_cert, n).is_err() {
+                return Err(
+                    format!("proxy client bandwidth limit reached: {:?}", dest_cert).into(),
+                );
+            }
+        }
+
+        client.peers.insert(src_cert.clone());
+        client.fwd_bytes += len as u64;
+        Ok(())
+    })
+}
+
 async fn write_to_sub_con(
     tuning_params: &KitsuneP2pTuningParams,
     inner: &Share<ProxyEpInner>,
@@ -765,6 +924,17 @@ async fn close_connection_inner(
         // if this is an INCOMING connection, remove it from our proxy list
         if let Tx2ConDir::Incoming = peer_dir {
             i.digest_to_sub_con_map.remove(&peer_cert);
+            i.clients.remove(&peer_cert);
+
+            // drop limiter state for clients that have since gone idle
+            if let Some(limiter) = &i.limiter {
+                limiter.retain_recent();
+            }
+        }
+
+        // this peer no longer counts against any client connection limits
+        for client in i.clients.values_mut() {
+            client.peers.remove(&peer_cert);
         }
 
         // remove all out cons associated with this exact connection
@@ -818,6 +988,7 @@ impl ProxyEp {
         sub_ep: Ep,
         tuning_params: KitsuneP2pTuningParams,
         allow_proxy_fwd: bool,
+        access: ProxyAccessConfig,
         client_of_remote_proxy: Option<ProxyUrl>,
     ) -> KitsuneResult<Ep> {
         // this isn't something that needs to be configurable,
@@ -835,12 +1006,14 @@ impl ProxyEp {
             sub_ep.handle().clone(),
             logic_hnd.clone(),
             backoff.clone(),
+            &access,
             client_of_remote_proxy.clone(),
         )?;
 
         let logic = incoming_evt_logic(
             tuning_params.clone(),
             allow_proxy_fwd,
+            access,
             sub_ep,
             hnd.clone(),
             logic_hnd,
@@ -894,16 +1067,18 @@ impl AsEp for ProxyEp {
 struct ProxyEpFactory {
     tuning_params: KitsuneP2pTuningParams,
     allow_proxy_fwd: bool,
+    access: ProxyAccessConfig,
     client_of_remote_proxy: Option<ProxyUrl>,
     sub_fact: EpFactory,
 }
 
 impl ProxyEpFactory {
     pub fn new(sub_fact: EpFactory, config: ProxyConfig) -> KitsuneResult<EpFactory> {
-        let (tuning_params, allow_proxy_fwd, client_of_remote_proxy) = config.split()?;
+        let (tuning_params, allow_proxy_fwd, access, client_of_remote_proxy) = config.split()?;
         let fact: EpFactory = Arc::new(ProxyEpFactory {
             tuning_params,
             allow_proxy_fwd,
+            access,
             client_of_remote_proxy,
             sub_fact,
         });
@@ -920,6 +1095,7 @@ impl AsEpFactory for ProxyEpFactory {
         let tuning_params = self.tuning_params.clone();
         let fut = self.sub_fact.bind(bind_spec, timeout);
         let allow_proxy_fwd = self.allow_proxy_fwd;
+        let access = self.access.clone();
         let client_of_remote_proxy = self.client_of_remote_proxy.clone();
         async move {
             let sub_ep = fut.await?;
@@ -927,6 +1103,7 @@ impl AsEpFactory for ProxyEpFactory {
                 sub_ep,
                 tuning_params,
                 allow_proxy_fwd,
+                access,
                 client_of_remote_proxy,
             )
             .await
@@ -941,8 +1118,16 @@ mod tests {
     use kitsune_p2p_types::tx2::tx2_pool_promote::*;
 
     async fn build_node(
+        s_done: Option<tokio::sync::oneshot::Sender<()>>,
+        expect_err: bool,
+    ) -> (tokio::task::JoinHandle<KitsuneResult<()>>, TxUrl, EpHnd) {
+        build_node_with_access(s_done, expect_err, ProxyAccessConfig::default()).await
+    }
+
+    async fn build_node_with_access(
         mut s_done: Option<tokio::sync::oneshot::Sender<()>>,
         expect_err: bool,
+        access: ProxyAccessConfig,
     ) -> (tokio::task::JoinHandle<KitsuneResult<()>>, TxUrl, EpHnd) {
         let t = KitsuneTimeout::from_millis(5000);
 
@@ -951,6 +1136,7 @@ mod tests {
 
         let mut conf = ProxyConfig::default();
         conf.allow_proxy_fwd = true;
+        conf.access = access;
         let f = tx2_proxy(f, conf).unwrap();
 
         let mut ep = f.bind("none:".into(), t).await.unwrap();
@@ -998,6 +1184,119 @@ mod tests {
         (join, addr, ephnd)
     }
 
+    fn test_inner(access: &ProxyAccessConfig) -> Share<ProxyEpInner> {
+        Share::new(ProxyEpInner {
+            digest_to_sub_con_map: HashMap::new(),
+            direct_to_final_peer_con_map: HashMap::new(),
+            clients: HashMap::new(),
+            limiter: client_limiter(access),
+            backoff: Backoff::new(10, 10),
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tx2_proxy_fwd_quota() {
+        let client: Tx2Cert = vec![0x01; 32].into();
+        let src: Tx2Cert = vec![0x02; 32].into();
+        let other_src: Tx2Cert = vec![0x03; 32].into();
+
+        let mut access = ProxyAccessConfig::default();
+        access.client_bytes_per_second = Some(10);
+        access.client_max_connections = Some(1);
+        let inner = test_inner(&access);
+
+        // larger than a full second of quota is always rejected
+        assert!(check_fwd_access(&access, &inner, &src, &client, 11)
+            .await
+            .is_err());
+
+        assert!(check_fwd_access(&access, &inner, &src, &client, 8)
+            .await
+            .is_ok());
+
+        // the quota is exhausted until it replenishes
+        assert!(check_fwd_access(&access, &inner, &src, &client, 8)
+            .await
+            .is_err());
+
+        // a second remote peer is over the connection limit
+        assert!(check_fwd_access(&access, &inner, &other_src, &client, 1)
+            .await
+            .is_err());
+
+        let fwd_bytes = inner
+            .share_ref(|i| Ok(i.clients.get(&client).unwrap().fwd_bytes))
+            .unwrap();
+        assert_eq!(8, fwd_bytes);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tx2_proxy_fwd_allowlist() {
+        let allowed: Tx2Cert = vec![0x01; 32].into();
+        let denied: Tx2Cert = vec![0x02; 32].into();
+        let src: Tx2Cert = vec![0x03; 32].into();
+
+        let mut access = ProxyAccessConfig::default();
+        let allow_list = vec![allowed.as_digest().clone()];
+        access.accept_proxy_cb = Some(AcceptProxyCallback::accept_cert_digests(allow_list));
+        let inner = test_inner(&access);
+
+        assert!(check_fwd_access(&access, &inner, &src, &allowed, 5)
+            .await
+            .is_ok());
+        assert!(check_fwd_access(&access, &inner, &src, &denied, 5)
+            .await
+            .is_err());
+
+        // only acceptance is cached for as long as the clients are connected
+        let accepted = inner
+            .share_ref(|i| {
+                Ok((
+                    i.clients.get(&allowed).map(|c| c.accepted),
+                    i.clients.get(&denied).map(|c| c.accepted),
+                ))
+            })
+            .unwrap();
+        assert_eq!((Some(true), None), accepted);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tx2_proxy_fwd_rejected_client_is_rechecked() {
+        use futures::future::FutureExt;
+        use std::sync::atomic::AtomicBool;
+
+        let client: Tx2Cert = vec![0x01; 32].into();
+        let src: Tx2Cert = vec![0x02; 32].into();
+
+        // e.g. the client's agent info hasn't arrived yet
+        let accept = Arc::new(AtomicBool::new(false));
+        let mut access = ProxyAccessConfig::default();
+        access.accept_proxy_cb = Some(AcceptProxyCallback(Arc::new({
+            let accept = accept.clone();
+            move |_| {
+                let accept = accept.load(Ordering::SeqCst);
+                async move { accept }.boxed().into()
+            }
+        })));
+        let inner = test_inner(&access);
+
+        assert!(check_fwd_access(&access, &inner, &src, &client, 5)
+            .await
+            .is_err());
+
+        // the same client is accepted once the callback accepts it
+        accept.store(true, Ordering::SeqCst);
+        assert!(check_fwd_access(&access, &inner, &src, &client, 5)
+            .await
+            .is_ok());
+
+        // and stays accepted without asking again
+        accept.store(false, Ordering::SeqCst);
+        assert!(check_fwd_access(&access, &inner, &src, &client, 5)
+            .await
+            .is_ok());
+    }
+
     fn proxify_addr(purl: &TxUrl, nurl: &TxUrl) -> TxUrl {
         let digest = ProxyUrl::from(nurl.as_str());
         let digest = digest.digest();
@@ -1041,6 +1340,52 @@ mod tests {
         futures::future::try_join_all(all_tasks).await.unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tx2_proxy_client_rejected() {
+        observability::test_run().ok();
+        let t = KitsuneTimeout::from_millis(5000);
+        let mut all_tasks = Vec::new();
+
+        let mut access = ProxyAccessConfig::default();
+        access.accept_proxy_cb = Some(AcceptProxyCallback::reject_all());
+        let (p_join, p_addr, p_ep) = build_node_with_access(None, false, access).await;
+        all_tasks.push(p_join);
+
+        let (t_join, t_addr, t_ep) = build_node(None, true).await;
+        all_tasks.push(t_join);
+
+        // establish proxy connection
+        let _ = t_ep.get_connection(p_addr.clone(), t).await.unwrap();
+
+        let t_addr_proxy = proxify_addr(&p_addr, &t_addr);
+
+        let (s_done, r_done) = tokio::sync::oneshot::channel();
+        let (n_join, _n_addr, n_ep) = build_node(Some(s_done), true).await;
+
+        let mut data = PoolBuf::new();
+        data.extend_from_slice(b"hello");
+        n_ep.write(t_addr_proxy, 0.into(), data, t).await.unwrap();
+        r_done.await.unwrap();
+        n_ep.close(0, "").await;
+        n_join.await.unwrap().unwrap();
+
+        let debug = p_ep.debug();
+        let t_cert = format!("{:?}", t_ep.local_cert());
+        let t_entry = debug["proxy_list"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|e| e["cert"].as_str() == Some(&t_cert))
+            .cloned()
+            .unwrap();
+        assert_eq!(Some(false), t_entry["accepted"].as_bool(), "{}", debug);
+
+        t_ep.close(0, "").await;
+        p_ep.close(0, "").await;
+
+        futures::future::try_join_all(all_tasks).await.unwrap();
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_tx2_proxy() {
         observability::test_run().ok();
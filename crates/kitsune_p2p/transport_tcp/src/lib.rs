@@ -0,0 +1,50 @@
+#![deny(missing_docs)]
+//! TCP fallback transport module for kitsune-p2p
+//!
+//! For networks that block UDP (and therefore QUIC), this backend
+//! tunnels tx2 connections through a single TLS-over-TCP stream per
+//! connection, multiplexing the tx2 channels within it.
+
+/// Re-exported dependencies.
+pub mod dependencies {
+    pub use ::kitsune_p2p_types;
+    pub use ::tokio_rustls;
+}
+
+use kitsune_p2p_types::dependencies::url2::*;
+use kitsune_p2p_types::*;
+use std::net::SocketAddr;
+
+const SCHEME: &str = "kitsune-tcp";
+
+/// internal helper convert urls to socket addrs for binding / connection
+pub(crate) async fn url_to_addr(url: &Url2, scheme: &str) -> KitsuneResult<SocketAddr> {
+    if url.scheme() != scheme || url.host_str().is_none() || url.port().is_none() {
+        return Err(format!(
+            "invalid input. got: '{}', expected: '{}://host:port'",
+            url, scheme
+        )
+        .into());
+    }
+
+    let rendered = format!("{}:{}", url.host_str().unwrap(), url.port().unwrap());
+
+    if let Ok(iter) = tokio::net::lookup_host(rendered.clone()).await {
+        let mut fallback = None;
+        for addr in iter {
+            if addr.is_ipv4() {
+                return Ok(addr);
+            }
+            if fallback.is_none() {
+                fallback = Some(addr);
+            }
+        }
+        if let Some(addr) = fallback {
+            return Ok(addr);
+        }
+    }
+
+    Err(format!("could not parse '{}', as 'host:port'", rendered).into())
+}
+
+pub mod tx2;
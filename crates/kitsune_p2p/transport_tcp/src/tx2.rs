@@ -0,0 +1,913 @@
+#![allow(clippy::new_ret_no_self)]
+//! kitsune tx2 tcp transport backend
+//!
+//! Each tx2 connection is a single TLS stream over TCP.
+//! Since TCP (unlike QUIC) has no native sub-streams, tx2 channels
+//! are multiplexed within the TLS stream. Every frame on the wire is:
+//!
+//! - `4 bytes` - little endian u32 total frame size (including header)
+//! - `8 bytes` - little endian u64 channel id (scoped to the sender)
+//! - `8 bytes` - little endian u64 tx2 MsgId
+//! - `[rest]`  - frame payload
+
+use futures::future::{BoxFuture, FutureExt};
+use futures::stream::{BoxStream, StreamExt};
+use kitsune_p2p_types::config::*;
+use kitsune_p2p_types::dependencies::{ghost_actor::dependencies::tracing, serde_json};
+use kitsune_p2p_types::metrics::metric_task;
+use kitsune_p2p_types::tls::*;
+use kitsune_p2p_types::tx2::tx2_adapter::*;
+use kitsune_p2p_types::tx2::tx2_utils::*;
+use kitsune_p2p_types::tx2::*;
+use kitsune_p2p_types::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_rustls::rustls;
+use tokio_rustls::webpki;
+
+/// Configuration for TcpBackendAdapt
+#[non_exhaustive]
+pub struct TcpConfig {
+    /// Tls config
+    /// Default: None = ephemeral.
+    pub tls: Option<TlsConfig>,
+
+    /// Tuning Params
+    /// Default: None = default.
+    pub tuning_params: Option<KitsuneP2pTuningParams>,
+
+    /// If you have port-forwarding set up,
+    /// or wish to apply a vanity domain name,
+    /// you may need to override the local NIC ip.
+    /// Default: None = use NIC ip.
+    pub override_host: Option<String>,
+
+    /// If you have port-forwarding set up,
+    /// you may need to override the local NIC port.
+    /// Default: None = use NIC port.
+    pub override_port: Option<u16>,
+
+    /// Largest frame (including header) we will accept from a peer.
+    /// A peer sending a larger frame has its connection closed.
+    /// Default: None = the kitsune max message size plus frame header.
+    pub max_frame_size: Option<usize>,
+}
+
+impl Default for TcpConfig {
+    fn default() -> Self {
+        Self {
+            tls: None,
+            tuning_params: None,
+            override_host: None,
+            override_port: None,
+            max_frame_size: None,
+        }
+    }
+}
+
+impl TcpConfig {
+    /// into inner contents with default application
+    pub async fn split(self) -> KitsuneResult<(TlsConfig, KitsuneP2pTuningParams)> {
+        let TcpConfig {
+            tls, tuning_params, ..
+        } = self;
+
+        let tls = match tls {
+            None => TlsConfig::new_ephemeral().await?,
+            Some(tls) => tls,
+        };
+
+        let tuning_params = tuning_params.unwrap_or_else(KitsuneP2pTuningParams::default);
+
+        Ok((tls, tuning_params))
+    }
+}
+
+/// Tcp endpoint bind adapter for kitsune tx2
+pub async fn tx2_tcp_adapter(config: TcpConfig) -> KitsuneResult<AdapterFactory> {
+    TcpBackendAdapt::new(config).await
+}
+
+// -- private -- //
+
+/// Tls ALPN identifier for kitsune tcp handshaking
+const ALPN_KITSUNE_TCP_0: &[u8] = b"kitsune-tcp/0";
+
+/// Frame size bytes.
+const FRAME_SIZE_BYTES: usize = 4;
+
+/// Channel id bytes.
+const CHAN_ID_BYTES: usize = 8;
+
+/// MsgId bytes.
+const MSG_ID_BYTES: usize = 8;
+
+/// Total header bytes preceding each frame payload.
+const HEADER_BYTES: usize = FRAME_SIZE_BYTES + CHAN_ID_BYTES + MSG_ID_BYTES;
+
+/// Bound on buffered frames per channel / outgoing frames per connection.
+const CHAN_BOUND: usize = 32;
+
+/// The kitsune max message size (this matches the gossip send buffer).
+const MAX_MESSAGE_SIZE: usize = 16_000_000;
+
+/// Default largest frame we will accept from a peer.
+const DEFAULT_MAX_FRAME_SIZE: usize = MAX_MESSAGE_SIZE + HEADER_BYTES;
+
+type TlsStream = tokio_rustls::TlsStream<tokio::net::TcpStream>;
+type RR = (MsgId, PoolBuf);
+type WriteItem = (u64, MsgId, PoolBuf);
+
+pub(crate) fn blake2b_32(data: &[u8]) -> Vec<u8> {
+    blake2b_simd::Params::new()
+        .hash_length(32)
+        .to_state()
+        .update(data)
+        .finalize()
+        .as_bytes()
+        .to_vec()
+}
+
+/// Outgoing tx2 channel - frames are forwarded to the connection writer.
+struct TcpOutChan {
+    chan_id: u64,
+    write_send: TSender<WriteItem>,
+}
+
+impl AsFramedWriter for TcpOutChan {
+    fn write(
+        &mut self,
+        msg_id: MsgId,
+        data: PoolBuf,
+        timeout: KitsuneTimeout,
+    ) -> BoxFuture<'_, KitsuneResult<()>> {
+        let fut = self.write_send.send((self.chan_id, msg_id, data));
+        timeout
+            .mix(async move {
+                fut.await
+                    .map_err(|_| KitsuneError::from(KitsuneErrorKind::Closed))
+            })
+            .boxed()
+    }
+}
+
+/// Incoming tx2 channel - frames are delivered by the connection reader.
+struct TcpInChan(TReceiver<RR>);
+
+impl AsFramedReader for TcpInChan {
+    fn read(&mut self, timeout: KitsuneTimeout) -> BoxFuture<'_, KitsuneResult<RR>> {
+        timeout
+            .mix(async move {
+                self.0
+                    .next()
+                    .await
+                    .ok_or_else(|| KitsuneErrorKind::Closed.into())
+            })
+            .boxed()
+    }
+}
+
+struct TcpInChanRecvAdapt(BoxStream<'static, InChanFut>);
+
+impl TcpInChanRecvAdapt {
+    pub fn new(recv: TReceiver<InChan>) -> Self {
+        Self(
+            recv.map(|in_chan| async move { Ok(in_chan) }.boxed())
+                .boxed(),
+        )
+    }
+}
+
+impl futures::stream::Stream for TcpInChanRecvAdapt {
+    type Item = InChanFut;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let inner = &mut self.0;
+        tokio::pin!(inner);
+        futures::stream::Stream::poll_next(inner, cx)
+    }
+}
+
+impl InChanRecvAdapt for TcpInChanRecvAdapt {}
+
+/// Drain outgoing frames from all our channels onto the tls stream.
+async fn con_write_task(
+    mut write: tokio::io::WriteHalf<TlsStream>,
+    mut write_recv: TReceiver<WriteItem>,
+) -> KitsuneResult<()> {
+    while let Some((chan_id, msg_id, mut data)) = write_recv.next().await {
+        let total = data.len() + HEADER_BYTES;
+        if total > u32::MAX as usize {
+            return Err("frame too large".into());
+        }
+
+        data.reserve_front(HEADER_BYTES);
+        data.prepend_from_slice(&msg_id.inner().to_le_bytes()[..]);
+        data.prepend_from_slice(&chan_id.to_le_bytes()[..]);
+        data.prepend_from_slice(&(total as u32).to_le_bytes()[..]);
+
+        write.write_all(&data).await.map_err(KitsuneError::other)?;
+    }
+
+    let _ = write.shutdown().await;
+
+    Ok(())
+}
+
+/// Demultiplex incoming frames from the tls stream into tx2 channels.
+/// New channel ids result in a new InChan being emitted.
+async fn con_read_task(
+    mut read: tokio::io::ReadHalf<TlsStream>,
+    in_chan_send: TSender<InChan>,
+    max_frame_size: usize,
+) -> KitsuneResult<()> {
+    let mut chans: HashMap<u64, TSender<RR>> = HashMap::new();
+    // the remote assigns channel ids in increasing order, so an unknown
+    // id at or below this one belongs to a channel we already pruned
+    let mut max_chan_id = 0_u64;
+    let mut header = [0_u8; HEADER_BYTES];
+    let mut local_buf = [0_u8; 4096];
+
+    loop {
+        read.read_exact(&mut header)
+            .await
+            .map_err(KitsuneError::other)?;
+
+        let mut size = [0_u8; FRAME_SIZE_BYTES];
+        size.copy_from_slice(&header[..FRAME_SIZE_BYTES]);
+        let size = u32::from_le_bytes(size) as usize;
+
+        if size < HEADER_BYTES {
+            return Err(format!("invalid frame size: {}", size).into());
+        }
+
+        // check before reserving anything, the size is peer supplied
+        if size > max_frame_size {
+            return Err(format!(
+                "frame size {} exceeds max frame size {}",
+                size, max_frame_size
+            )
+            .into());
+        }
+
+        let mut chan_id = [0_u8; CHAN_ID_BYTES];
+        chan_id.copy_from_slice(&header[FRAME_SIZE_BYTES..FRAME_SIZE_BYTES + CHAN_ID_BYTES]);
+        let chan_id = u64::from_le_bytes(chan_id);
+
+        let mut msg_id = [0_u8; MSG_ID_BYTES];
+        msg_id.copy_from_slice(&header[FRAME_SIZE_BYTES + CHAN_ID_BYTES..]);
+        let msg_id = MsgId::from(u64::from_le_bytes(msg_id));
+
+        let want_size = size - HEADER_BYTES;
+        let mut data = PoolBuf::new();
+        data.reserve(want_size);
+        while data.len() < want_size {
+            let to_read = std::cmp::min(local_buf.len(), want_size - data.len());
+            let sub_read = read
+                .read(&mut local_buf[..to_read])
+                .await
+                .map_err(KitsuneError::other)?;
+            if sub_read == 0 {
+                return Err(KitsuneErrorKind::Closed.into());
+            }
+            data.extend_from_slice(&local_buf[..sub_read]);
+        }
+
+        if !chans.contains_key(&chan_id) {
+            if chan_id <= max_chan_id {
+                tracing::debug!(%chan_id, "dropping frame for closed channel (tcp)");
+                continue;
+            }
+            let (chan_send, chan_recv) = t_chan(CHAN_BOUND);
+            let in_chan: InChan = Box::new(TcpInChan(chan_recv));
+            if in_chan_send.send(in_chan).await.is_err() {
+                return Err(KitsuneErrorKind::Closed.into());
+            }
+            max_chan_id = chan_id;
+            chans.insert(chan_id, chan_send);
+        }
+
+        // never await a single channel here, a slow reader
+        // would stall every other channel on this connection
+        if let Some(Err(err)) = chans.get(&chan_id).map(|s| s.try_send((msg_id, data))) {
+            match err {
+                TrySendError::Full(_) => {
+                    tracing::warn!(%chan_id, "closing channel with slow reader (tcp)");
+                }
+                TrySendError::Closed(_) => {
+                    tracing::debug!(%chan_id, "local side dropped channel (tcp)");
+                }
+            }
+            chans.remove(&chan_id);
+        }
+    }
+}
+
+struct TcpConAdaptInner {
+    peer_cert: Tx2Cert,
+    write_send: TSender<WriteItem>,
+    next_chan_id: u64,
+    con_active: Active,
+}
+
+struct TcpConAdapt(Share<TcpConAdaptInner>, Uniq, Tx2Cert, Tx2ConDir, TxUrl);
+
+impl ConAdapt for TcpConAdapt {
+    fn uniq(&self) -> Uniq {
+        self.1
+    }
+
+    fn dir(&self) -> Tx2ConDir {
+        self.3
+    }
+
+    fn peer_addr(&self) -> KitsuneResult<TxUrl> {
+        Ok(self.4.clone())
+    }
+
+    fn peer_cert(&self) -> Tx2Cert {
+        self.2.clone()
+    }
+
+    fn out_chan(&self, _timeout: KitsuneTimeout) -> OutChanFut {
+        let maybe_out = self.0.share_mut(|i, _| {
+            if !i.con_active.is_active() {
+                return Err(KitsuneErrorKind::Closed.into());
+            }
+            let chan_id = i.next_chan_id;
+            i.next_chan_id += 1;
+            Ok(TcpOutChan {
+                chan_id,
+                write_send: i.write_send.clone(),
+            })
+        });
+        async move {
+            let out: OutChan = Box::new(maybe_out?);
+            Ok(out)
+        }
+        .boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.0
+            .share_ref(|i| Ok(!i.con_active.is_active()))
+            .unwrap_or(true)
+    }
+
+    fn close(&self, code: u32, reason: &str) -> BoxFuture<'static, ()> {
+        let _ = self.0.share_mut(|i, c| {
+            tracing::info!(
+                peer_cert=?i.peer_cert,
+                %code,
+                %reason,
+                "close connection (tcp)",
+            );
+            *c = true;
+            i.con_active.kill();
+            i.write_send.close_channel();
+            Ok(())
+        });
+        async move {}.boxed()
+    }
+}
+
+/// Given an established tls stream, spawn the reader / writer tasks
+/// and construct the tx2 connection types.
+fn establish(
+    stream: TlsStream,
+    peer_addr: TxUrl,
+    local_cert: Tx2Cert,
+    dir: Tx2ConDir,
+    ep_active: &Active,
+    max_frame_size: usize,
+) -> KitsuneResult<Con> {
+    let peer_cert: Tx2Cert = {
+        use rustls::Session;
+        let (_, session) = stream.get_ref();
+        match session.get_peer_certificates() {
+            None => return Err("invalid peer certificate".into()),
+            Some(chain) => match chain.first() {
+                None => return Err("invalid peer certificate".into()),
+                Some(cert) => blake2b_32(&cert.0).into(),
+            },
+        }
+    };
+
+    let con_active = Active::new().mix(ep_active);
+
+    let (read, write) = tokio::io::split(stream);
+    let (write_send, write_recv) = t_chan(CHAN_BOUND);
+    let (in_chan_send, in_chan_recv) = t_chan(CHAN_BOUND);
+
+    {
+        let write_send = write_send.clone();
+        let in_chan_send = in_chan_send.clone();
+        con_active.register_kill_cb(move || {
+            write_send.close_channel();
+            in_chan_send.close_channel();
+        });
+    }
+
+    {
+        let con_active = con_active.clone();
+        metric_task(async move {
+            let res = con_active.fut(con_write_task(write, write_recv)).await;
+            con_active.kill();
+            res
+        });
+    }
+
+    {
+        let con_active = con_active.clone();
+        let peer_cert = peer_cert.clone();
+        metric_task(async move {
+            let res = con_active
+                .fut(con_read_task(read, in_chan_send, max_frame_size))
+                .await;
+            tracing::debug!(?peer_cert, ?res, "connection read loop ended (tcp)");
+            con_active.kill();
+            KitsuneResult::Ok(())
+        });
+    }
+
+    match dir {
+        Tx2ConDir::Outgoing => {
+            tracing::info!(?local_cert, ?peer_cert, %peer_addr, "established outgoing connection (tcp)");
+        }
+        Tx2ConDir::Incoming => {
+            tracing::info!(?local_cert, ?peer_cert, %peer_addr, "established incoming connection (tcp)");
+        }
+    }
+
+    let con: Arc<dyn ConAdapt> = Arc::new(TcpConAdapt(
+        Share::new(TcpConAdaptInner {
+            peer_cert: peer_cert.clone(),
+            write_send,
+            next_chan_id: 1,
+            con_active,
+        }),
+        Uniq::default(),
+        peer_cert,
+        dir,
+        peer_addr,
+    ));
+    let chan_recv: Box<dyn InChanRecvAdapt> = Box::new(TcpInChanRecvAdapt::new(in_chan_recv));
+
+    Ok((con, chan_recv))
+}
+
+fn addr_to_url(addr: std::net::SocketAddr) -> TxUrl {
+    use kitsune_p2p_types::dependencies::url2;
+    url2::url2!("{}://{}", crate::SCHEME, addr).into()
+}
+
+struct TcpConRecvAdapt(BoxStream<'static, ConFut>);
+
+impl TcpConRecvAdapt {
+    pub fn new(
+        listener: tokio::net::TcpListener,
+        acceptor: tokio_rustls::TlsAcceptor,
+        local_cert: Tx2Cert,
+        ep_active: Active,
+        ep: Arc<dyn EndpointAdapt>,
+        max_frame_size: usize,
+    ) -> Self {
+        struct OnDrop(Arc<dyn EndpointAdapt>);
+
+        impl Drop for OnDrop {
+            fn drop(&mut self) {
+                let f = self.0.close(500, "listener closed");
+                tokio::task::spawn(async move {
+                    let _ = f.await;
+                });
+            }
+        }
+
+        let on_drop = OnDrop(ep);
+
+        Self(
+            futures::stream::unfold(
+                (listener, acceptor, local_cert, ep_active, on_drop),
+                move |(listener, acceptor, local_cert, ep_active, on_drop)| async move {
+                    let (socket, addr) = match ep_active
+                        .fut(async { listener.accept().await.map_err(KitsuneError::other) })
+                        .await
+                    {
+                        Err(_) => return None,
+                        Ok(r) => r,
+                    };
+                    let con_fut = {
+                        let acceptor = acceptor.clone();
+                        let local_cert = local_cert.clone();
+                        let ep_active = ep_active.clone();
+                        async move {
+                            let _ = socket.set_nodelay(true);
+                            let stream =
+                                acceptor.accept(socket).await.map_err(KitsuneError::other)?;
+                            establish(
+                                stream.into(),
+                                addr_to_url(addr),
+                                local_cert,
+                                Tx2ConDir::Incoming,
+                                &ep_active,
+                                max_frame_size,
+                            )
+                        }
+                        .boxed()
+                    };
+                    Some((
+                        con_fut,
+                        (listener, acceptor, local_cert, ep_active, on_drop),
+                    ))
+                },
+            )
+            .boxed(),
+        )
+    }
+}
+
+impl futures::stream::Stream for TcpConRecvAdapt {
+    type Item = ConFut;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let inner = &mut self.0;
+        tokio::pin!(inner);
+        futures::stream::Stream::poll_next(inner, cx)
+    }
+}
+
+impl ConRecvAdapt for TcpConRecvAdapt {}
+
+struct TcpEndpointAdaptInner {
+    local_addr: std::net::SocketAddr,
+    local_cert: Tx2Cert,
+    connector: tokio_rustls::TlsConnector,
+    ep_active: Active,
+    override_host: Option<String>,
+    override_port: Option<u16>,
+    max_frame_size: usize,
+}
+
+struct TcpEndpointAdapt(Share<TcpEndpointAdaptInner>, Uniq, Tx2Cert);
+
+impl TcpEndpointAdapt {
+    pub fn new(
+        local_addr: std::net::SocketAddr,
+        local_cert: Tx2Cert,
+        connector: tokio_rustls::TlsConnector,
+        ep_active: Active,
+        override_host: Option<String>,
+        override_port: Option<u16>,
+        max_frame_size: usize,
+    ) -> Self {
+        Self(
+            Share::new(TcpEndpointAdaptInner {
+                local_addr,
+                local_cert: local_cert.clone(),
+                connector,
+                ep_active,
+                override_host,
+                override_port,
+                max_frame_size,
+            }),
+            Uniq::default(),
+            local_cert,
+        )
+    }
+}
+
+impl EndpointAdapt for TcpEndpointAdapt {
+    fn debug(&self) -> serde_json::Value {
+        match self.local_addr() {
+            Ok(addr) => serde_json::json!({
+                "type": "tx2_tcp",
+                "state": "open",
+                "addr": addr,
+            }),
+            Err(_) => serde_json::json!({
+                "type": "tx2_tcp",
+                "state": "closed",
+            }),
+        }
+    }
+
+    fn uniq(&self) -> Uniq {
+        self.1
+    }
+
+    fn local_addr(&self) -> KitsuneResult<TxUrl> {
+        let (addr, override_host, override_port) = self
+            .0
+            .share_ref(|i| Ok((i.local_addr, i.override_host.clone(), i.override_port)))?;
+
+        let mut url: kitsune_p2p_types::dependencies::url2::Url2 = addr_to_url(addr).into();
+
+        if let Some(override_port) = override_port {
+            url.set_port(Some(override_port)).unwrap();
+        }
+
+        // same override / naive NIC selection as the quic backend
+        if let Some(override_host) = &override_host {
+            url.set_host(Some(override_host))
+                .map_err(KitsuneError::other)?;
+        } else if let Some(host) = url.host_str() {
+            if host == "0.0.0.0" {
+                for iface in if_addrs::get_if_addrs().map_err(KitsuneError::other)? {
+                    let addr = iface.addr.ip();
+                    if let std::net::IpAddr::V4(addr) = addr {
+                        if addr != std::net::Ipv4Addr::from([127, 0, 0, 1]) {
+                            url.set_host(Some(&iface.addr.ip().to_string())).unwrap();
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(url.into())
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.2.clone()
+    }
+
+    fn connect(&self, url: TxUrl, timeout: KitsuneTimeout) -> ConFut {
+        let maybe_ep = self.0.share_ref(|i| {
+            Ok((
+                i.connector.clone(),
+                i.local_cert.clone(),
+                i.ep_active.clone(),
+                i.max_frame_size,
+            ))
+        });
+        timeout
+            .mix(async move {
+                let (connector, local_cert, ep_active, max_frame_size) = maybe_ep?;
+                let addr = crate::url_to_addr(url.as_url2(), crate::SCHEME).await?;
+                let con = async {
+                    let socket = tokio::net::TcpStream::connect(addr)
+                        .await
+                        .map_err(KitsuneError::other)?;
+                    let _ = socket.set_nodelay(true);
+                    // cert verification is done on the digest, not the name
+                    let domain = webpki::DNSNameRef::try_from_ascii_str("stub.stub").unwrap();
+                    let stream = connector
+                        .connect(domain, socket)
+                        .await
+                        .map_err(KitsuneError::other)?;
+                    establish(
+                        stream.into(),
+                        url,
+                        local_cert,
+                        Tx2ConDir::Outgoing,
+                        &ep_active,
+                        max_frame_size,
+                    )
+                };
+                match con.await {
+                    Ok(con) => Ok(con),
+                    Err(err) => {
+                        tracing::warn!(?err, "failed to establish outgoing connection (tcp)");
+                        Err(err)
+                    }
+                }
+            })
+            .boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
+
+    fn close(&self, _code: u32, _reason: &str) -> BoxFuture<'static, ()> {
+        let _ = self.0.share_mut(|i, c| {
+            tracing::warn!(
+                local_cert=?i.local_cert,
+                "CLOSING ENDPOINT"
+            );
+            *c = true;
+            i.ep_active.kill();
+            Ok(())
+        });
+        async move {}.boxed()
+    }
+}
+
+/// Tcp endpoint backend bind adapter for kitsune tx2
+pub struct TcpBackendAdapt {
+    local_cert: Tx2Cert,
+    tls_srv: Arc<rustls::ServerConfig>,
+    tls_cli: Arc<rustls::ClientConfig>,
+    override_host: Option<String>,
+    override_port: Option<u16>,
+    max_frame_size: usize,
+}
+
+impl TcpBackendAdapt {
+    /// Construct a new tcp tx2 backend bind adapter
+    pub async fn new(config: TcpConfig) -> KitsuneResult<AdapterFactory> {
+        let override_host = config.override_host.clone();
+        let override_port = config.override_port;
+        let max_frame_size = config.max_frame_size.unwrap_or(DEFAULT_MAX_FRAME_SIZE);
+
+        let (tls, tuning_params) = config.split().await?;
+
+        let local_cert = tls.cert_digest.clone().into();
+
+        let (tls_srv, tls_cli) = gen_tls_configs(ALPN_KITSUNE_TCP_0, &tls, tuning_params)?;
+
+        let out: AdapterFactory = Arc::new(Self {
+            local_cert,
+            tls_srv,
+            tls_cli,
+            override_host,
+            override_port,
+            max_frame_size,
+        });
+
+        Ok(out)
+    }
+}
+
+impl BindAdapt for TcpBackendAdapt {
+    fn bind(&self, url: TxUrl, timeout: KitsuneTimeout) -> EndpointFut {
+        let local_cert = self.local_cert.clone();
+        let acceptor = tokio_rustls::TlsAcceptor::from(self.tls_srv.clone());
+        let connector = tokio_rustls::TlsConnector::from(self.tls_cli.clone());
+        let override_host = self.override_host.clone();
+        let override_port = self.override_port;
+        let max_frame_size = self.max_frame_size;
+        timeout
+            .mix(async move {
+                let addr = crate::url_to_addr(url.as_url2(), crate::SCHEME).await?;
+
+                let listener = tokio::net::TcpListener::bind(addr)
+                    .await
+                    .map_err(KitsuneError::other)?;
+                let local_addr = listener.local_addr().map_err(KitsuneError::other)?;
+
+                let ep_active = Active::new();
+
+                let ep: Arc<dyn EndpointAdapt> = Arc::new(TcpEndpointAdapt::new(
+                    local_addr,
+                    local_cert.clone(),
+                    connector,
+                    ep_active.clone(),
+                    override_host,
+                    override_port,
+                    max_frame_size,
+                ));
+                let con_recv: Box<dyn ConRecvAdapt> = Box::new(TcpConRecvAdapt::new(
+                    listener,
+                    acceptor,
+                    local_cert.clone(),
+                    ep_active,
+                    ep.clone(),
+                    max_frame_size,
+                ));
+
+                let url = ep.local_addr()?;
+
+                tracing::info!(?local_cert, %url, "bound local endpoint (tcp)");
+
+                Ok((ep, con_recv))
+            })
+            .boxed()
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.local_cert.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tcp_tx2() {
+        kitsune_p2p_types::dependencies::observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let (s_done, r_done) = tokio::sync::oneshot::channel();
+
+        let config = TcpConfig::default();
+        let factory = TcpBackendAdapt::new(config).await.unwrap();
+        let (ep1, _con_recv1) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let config = TcpConfig::default();
+        let factory = TcpBackendAdapt::new(config).await.unwrap();
+        let (ep2, mut con_recv2) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let addr2 = ep2.local_addr().unwrap();
+
+        let ep1_cert = ep1.local_cert();
+
+        let rt = metric_task(async move {
+            if let Some(mc) = con_recv2.next().await {
+                let (con, mut recv) = mc.await.unwrap();
+                assert_eq!(ep1_cert, con.peer_cert());
+                let mut got = Vec::new();
+                // two separate channels should arrive as two in chans
+                for _ in 0..2 {
+                    if let Some(mc) = recv.next().await {
+                        let mut c = mc.await.unwrap();
+                        let t = KitsuneTimeout::from_millis(5000);
+                        let (_, data) = c.read(t).await.unwrap();
+                        got.push(data.to_vec());
+                    }
+                }
+                got.sort();
+                assert_eq!(vec![b"hello".to_vec(), b"world".to_vec()], got);
+                s_done.send(()).unwrap();
+            }
+            KitsuneResult::Ok(())
+        });
+
+        let (c, _recv) = ep1.connect(addr2, t).await.unwrap();
+        assert_eq!(ep2.local_cert(), c.peer_cert());
+
+        for msg in [&b"hello"[..], &b"world"[..]].iter() {
+            let mut chan = c.out_chan(t).await.unwrap();
+            let mut data = PoolBuf::new();
+            data.extend_from_slice(msg);
+            chan.write(0.into(), data, t).await.unwrap();
+        }
+
+        r_done.await.unwrap();
+
+        ep1.close(0, "").await;
+        ep2.close(0, "").await;
+
+        rt.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tcp_tx2_max_frame_size() {
+        kitsune_p2p_types::dependencies::observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let factory = TcpBackendAdapt::new(TcpConfig::default()).await.unwrap();
+        let (ep1, _con_recv1) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let mut config = TcpConfig::default();
+        config.max_frame_size = Some(HEADER_BYTES + 4);
+        let factory = TcpBackendAdapt::new(config).await.unwrap();
+        let (ep2, mut con_recv2) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let (c, _recv) = ep1.connect(ep2.local_addr().unwrap(), t).await.unwrap();
+        let mut chan = c.out_chan(t).await.unwrap();
+        let mut data = PoolBuf::new();
+        data.extend_from_slice(b"hello");
+        chan.write(0.into(), data, t).await.unwrap();
+
+        // the oversized frame closes the connection without emitting a channel
+        let (con, mut recv) = con_recv2.next().await.unwrap().await.unwrap();
+        assert!(recv.next().await.is_none());
+        assert!(con.is_closed());
+
+        ep1.close(0, "").await;
+        ep2.close(0, "").await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tcp_tx2_override_addr() {
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let mut config = TcpConfig::default();
+        config.override_host = Some("example.com".to_string());
+        config.override_port = Some(4242);
+        let factory = TcpBackendAdapt::new(config).await.unwrap();
+        let (ep, _con_recv) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let url = ep.local_addr().unwrap();
+        assert_eq!(Some("example.com"), url.host_str());
+        assert_eq!(Some(4242), url.port());
+
+        ep.close(0, "").await;
+    }
+}
@@ -6,6 +6,9 @@ pub use framed::*;
 mod mem;
 pub use mem::*;
 
+mod mem_sim;
+pub use mem_sim::*;
+
 pub mod tx2_adapter;
 
 pub mod tx2_api;
@@ -26,6 +26,10 @@ pub struct MemConfig {
     /// Tuning Params
     /// Default: None = default.
     pub tuning_params: Option<KitsuneP2pTuningParams>,
+
+    /// Fault injection - the simulated network node this backend belongs to.
+    /// Default: None = perfect network.
+    pub net_sim: Option<MemNetSimNode>,
 }
 
 impl Default for MemConfig {
@@ -33,6 +37,7 @@ impl Default for MemConfig {
         Self {
             tls: None,
             tuning_params: None,
+            net_sim: None,
         }
     }
 }
@@ -40,7 +45,9 @@ impl Default for MemConfig {
 impl MemConfig {
     /// into inner contents with default application
     pub async fn split(self) -> KitsuneResult<(TlsConfig, KitsuneP2pTuningParams)> {
-        let MemConfig { tls, tuning_params } = self;
+        let MemConfig {
+            tls, tuning_params, ..
+        } = self;
 
         let tls = match tls {
             None => TlsConfig::new_ephemeral().await?,
@@ -67,7 +74,7 @@ type ChanRecv = TReceiver<InChan>;
 type ConSend = TSender<Con>;
 type ConRecv = TReceiver<Con>;
 
-type EndpointItem = (ConSend, Active, Tx2Cert);
+type EndpointItem = (ConSend, Active, Tx2Cert, Option<MemNetSimNode>);
 static MEM_ENDPOINTS: Lazy<Mutex<HashMap<u64, EndpointItem>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
@@ -117,6 +124,7 @@ struct MemConAdaptInner {
     chan_send: ChanSend,
     con_active: Active,
     mix_active: Active,
+    sim_link: Option<MemNetSimLink>,
 }
 
 struct MemConAdapt(MemConAdaptInner);
@@ -129,6 +137,7 @@ impl MemConAdapt {
         chan_send: ChanSend,
         con_active: Active,
         mix_active: Active,
+        sim_link: Option<MemNetSimLink>,
     ) -> Self {
         Self(MemConAdaptInner {
             uniq: Uniq::default(),
@@ -138,6 +147,7 @@ impl MemConAdapt {
             chan_send,
             con_active,
             mix_active,
+            sim_link,
         })
     }
 }
@@ -161,9 +171,13 @@ impl ConAdapt for MemConAdapt {
 
     fn out_chan(&self, _timeout: KitsuneTimeout) -> OutChanFut {
         let sender = self.0.chan_send.clone();
+        let sim_link = self.0.sim_link.clone();
         let (send, recv) = bound_async_mem_channel(4096, Some(&self.0.mix_active));
         async move {
-            let send: OutChan = Box::new(FramedWriter::new(send));
+            let mut send: OutChan = Box::new(FramedWriter::new(send));
+            if let Some(sim_link) = sim_link {
+                send = sim_link.wrap(send);
+            }
             let recv: InChan = Box::new(FramedReader::new(recv));
             if sender.send(recv).await.is_err() {
                 return Err("failed to create out channel".into());
@@ -228,6 +242,7 @@ struct MemEndpointAdaptInner {
     url: TxUrl,
     ep_active: Active,
     c_send: ConSend,
+    net_sim: Option<MemNetSimNode>,
 }
 
 impl Drop for MemEndpointAdaptInner {
@@ -239,7 +254,12 @@ impl Drop for MemEndpointAdaptInner {
 struct MemEndpointAdapt(Mutex<MemEndpointAdaptInner>, Uniq, Tx2Cert);
 
 impl MemEndpointAdapt {
-    pub fn new(c_send: ConSend, id: u64, local_cert: Tx2Cert) -> (Self, Active) {
+    pub fn new(
+        c_send: ConSend,
+        id: u64,
+        local_cert: Tx2Cert,
+        net_sim: Option<MemNetSimNode>,
+    ) -> (Self, Active) {
         let url = format!("kitsune-mem://{}", id);
         let ep_active = Active::new();
         (
@@ -250,6 +270,7 @@ impl MemEndpointAdapt {
                     url: url.into(),
                     ep_active: ep_active.clone(),
                     c_send,
+                    net_sim,
                 }),
                 Uniq::default(),
                 local_cert,
@@ -293,7 +314,7 @@ impl EndpointAdapt for MemEndpointAdapt {
     }
 
     fn connect(&self, url: TxUrl, timeout: KitsuneTimeout) -> ConFut {
-        let (this_url, local_cert, this_ep_active) = {
+        let (this_url, local_cert, this_ep_active, this_net_sim) = {
             let inner = self.0.lock();
             if !inner.ep_active.is_active() {
                 return async move { Err(KitsuneErrorKind::Closed.into()) }.boxed();
@@ -302,6 +323,7 @@ impl EndpointAdapt for MemEndpointAdapt {
                 inner.url.clone(),
                 inner.local_cert.clone(),
                 inner.ep_active.clone(),
+                inner.net_sim.clone(),
             )
         };
         async move {
@@ -323,11 +345,23 @@ impl EndpointAdapt for MemEndpointAdapt {
                 Ok(id) => id,
             };
 
-            let (c_send, oth_ep_active, remote_cert) = match MEM_ENDPOINTS.lock().get(&id) {
-                None => return Err(format!("remote not found: {}", url).into()),
-                Some((s, a, d)) => (s.clone(), a.clone(), d.clone()),
+            let (c_send, oth_ep_active, remote_cert, oth_net_sim) =
+                match MEM_ENDPOINTS.lock().get(&id) {
+                    None => return Err(format!("remote not found: {}", url).into()),
+                    Some((s, a, d, n)) => (s.clone(), a.clone(), d.clone(), n.clone()),
+                };
+
+            let sim_link = match (&this_net_sim, &oth_net_sim) {
+                (Some(this), Some(oth)) => this.link_to(oth),
+                _ => None,
             };
 
+            if let Some(sim_link) = &sim_link {
+                if sim_link.is_partitioned() {
+                    return Err(format!("remote unreachable (partitioned): {}", url).into());
+                }
+            }
+
             let con_active = Active::new();
             let mix_ep_active = this_ep_active.mix(&oth_ep_active);
             let mix_active = con_active.mix(&mix_ep_active);
@@ -342,6 +376,7 @@ impl EndpointAdapt for MemEndpointAdapt {
                 oth_send,
                 con_active.clone(),
                 mix_active.clone(),
+                sim_link.as_ref().map(|l| l.reverse()),
             );
             let oth_con: Arc<dyn ConAdapt> = Arc::new(oth_con);
 
@@ -352,6 +387,7 @@ impl EndpointAdapt for MemEndpointAdapt {
                 send,
                 con_active,
                 mix_active.clone(),
+                sim_link,
             );
             let con: Arc<dyn ConAdapt> = Arc::new(con);
 
@@ -397,13 +433,14 @@ impl EndpointAdapt for MemEndpointAdapt {
 }
 
 /// Memory-based test endpoint adapter for kitsune tx2.
-struct MemBackendAdapt(Tx2Cert);
+struct MemBackendAdapt(Tx2Cert, Option<MemNetSimNode>);
 
 impl MemBackendAdapt {
     /// Construct a new memory-based test endpoint adapter for kitsune tx2.
     pub async fn new(config: MemConfig) -> KitsuneResult<AdapterFactory> {
+        let net_sim = config.net_sim.clone();
         let (tls, _tuning_params) = config.split().await?;
-        let out: AdapterFactory = Arc::new(Self(tls.cert_digest.into()));
+        let out: AdapterFactory = Arc::new(Self(tls.cert_digest.into(), net_sim));
         Ok(out)
     }
 }
@@ -411,14 +448,16 @@ impl MemBackendAdapt {
 impl BindAdapt for MemBackendAdapt {
     fn bind(&self, _url: TxUrl, timeout: KitsuneTimeout) -> EndpointFut {
         let local_cert = self.0.clone();
+        let net_sim = self.1.clone();
         timeout
             .mix(async move {
                 let id = NEXT_MEM_ID.fetch_add(1, atomic::Ordering::Relaxed);
                 let (c_send, c_recv) = t_chan(32);
-                let (ep, ep_active) = MemEndpointAdapt::new(c_send.clone(), id, local_cert.clone());
+                let (ep, ep_active) =
+                    MemEndpointAdapt::new(c_send.clone(), id, local_cert.clone(), net_sim.clone());
                 MEM_ENDPOINTS
                     .lock()
-                    .insert(id, (c_send, ep_active.clone(), local_cert));
+                    .insert(id, (c_send, ep_active.clone(), local_cert, net_sim));
                 let ep: Arc<dyn EndpointAdapt> = Arc::new(ep);
                 let url = ep.local_addr()?;
                 tracing::info!(%url, "bound local endpoint (mem)");
@@ -0,0 +1,434 @@
+//! Fault injection for the tx2 mem backend.
+//!
+//! A [MemNetSim] is shared by all mem backends participating in a simulated
+//! network. Each backend is assigned to a named node group, and the
+//! simulator applies latency, jitter, loss, bandwidth caps and partitions
+//! to frames sent between groups. Random decisions are drawn from a seeded
+//! generator, so a given seed and message ordering reproduces the same run.
+
+use crate::tx2::tx2_adapter::*;
+use crate::tx2::tx2_utils::*;
+use crate::tx2::*;
+use crate::*;
+use futures::future::{BoxFuture, FutureExt};
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Characteristics applied to frames sent from one node group to another.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MemNetLink {
+    /// Fixed latency added to every frame.
+    pub latency: Duration,
+
+    /// Additional random latency, uniformly distributed in `0..=jitter`.
+    pub jitter: Duration,
+
+    /// Probability (`0.0..=1.0`) that a frame is silently dropped.
+    pub loss: f64,
+
+    /// Bandwidth cap in bytes per second.
+    /// Default: None = unlimited.
+    pub bytes_per_second: Option<u64>,
+}
+
+/// A single change to a [MemNetSim], for use in scripted scenarios.
+#[derive(Debug, Clone)]
+pub enum MemNetStep {
+    /// Set the link characteristics from one group to another.
+    SetLink {
+        /// sending group
+        from: String,
+        /// receiving group
+        to: String,
+        /// the new link characteristics
+        link: MemNetLink,
+    },
+
+    /// Cut all traffic between every group in `a` and every group in `b`.
+    Partition {
+        /// first side of the partition
+        a: Vec<String>,
+        /// second side of the partition
+        b: Vec<String>,
+    },
+
+    /// Restore traffic between every group in `a` and every group in `b`.
+    Heal {
+        /// first side of the partition
+        a: Vec<String>,
+        /// second side of the partition
+        b: Vec<String>,
+    },
+
+    /// Remove all partitions.
+    HealAll,
+}
+
+struct MemNetSimInner {
+    rng: u64,
+    default_link: MemNetLink,
+    links: HashMap<(String, String), MemNetLink>,
+    partitions: HashSet<(String, String)>,
+    next_free: HashMap<(String, String), Instant>,
+}
+
+impl MemNetSimInner {
+    /// splitmix64 - we only need a small, seedable, portable generator
+    fn next_u64(&mut self) -> u64 {
+        self.rng = self.rng.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    fn set_partitioned(&mut self, a: &[String], b: &[String], partitioned: bool) {
+        for a in a.iter() {
+            for b in b.iter() {
+                for key in [(a.clone(), b.clone()), (b.clone(), a.clone())] {
+                    if partitioned {
+                        self.partitions.insert(key);
+                    } else {
+                        self.partitions.remove(&key);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A simulated network shared by a set of mem backends.
+#[derive(Clone)]
+pub struct MemNetSim(Arc<Mutex<MemNetSimInner>>);
+
+impl MemNetSim {
+    /// Construct a new simulated network. A perfect network
+    /// until configured otherwise.
+    pub fn new(seed: u64) -> Self {
+        Self(Arc::new(Mutex::new(MemNetSimInner {
+            rng: seed,
+            default_link: MemNetLink::default(),
+            links: HashMap::new(),
+            partitions: HashSet::new(),
+            next_free: HashMap::new(),
+        })))
+    }
+
+    /// Get a handle for a node in the named group.
+    /// Set this as the `net_sim` of a [MemConfig].
+    pub fn node(&self, group: impl Into<String>) -> MemNetSimNode {
+        MemNetSimNode {
+            sim: self.clone(),
+            group: group.into(),
+        }
+    }
+
+    /// Set the link characteristics used between groups
+    /// that don't have a specific link set.
+    pub fn set_default_link(&self, link: MemNetLink) {
+        self.0.lock().default_link = link;
+    }
+
+    /// Set the link characteristics for frames sent `from` one group `to` another.
+    pub fn set_link(&self, from: &str, to: &str, link: MemNetLink) {
+        self.apply(MemNetStep::SetLink {
+            from: from.to_string(),
+            to: to.to_string(),
+            link,
+        });
+    }
+
+    /// Cut all traffic between every group in `a` and every group in `b`.
+    pub fn partition(&self, a: &[&str], b: &[&str]) {
+        self.apply(MemNetStep::Partition {
+            a: a.iter().map(|s| s.to_string()).collect(),
+            b: b.iter().map(|s| s.to_string()).collect(),
+        });
+    }
+
+    /// Restore traffic between every group in `a` and every group in `b`.
+    pub fn heal(&self, a: &[&str], b: &[&str]) {
+        self.apply(MemNetStep::Heal {
+            a: a.iter().map(|s| s.to_string()).collect(),
+            b: b.iter().map(|s| s.to_string()).collect(),
+        });
+    }
+
+    /// Remove all partitions.
+    pub fn heal_all(&self) {
+        self.apply(MemNetStep::HealAll);
+    }
+
+    /// Is traffic from one group to another currently cut?
+    pub fn is_partitioned(&self, from: &str, to: &str) -> bool {
+        self.0
+            .lock()
+            .partitions
+            .contains(&(from.to_string(), to.to_string()))
+    }
+
+    /// Apply a single step to this simulated network.
+    pub fn apply(&self, step: MemNetStep) {
+        let mut inner = self.0.lock();
+        match step {
+            MemNetStep::SetLink { from, to, link } => {
+                inner.links.insert((from, to), link);
+            }
+            MemNetStep::Partition { a, b } => inner.set_partitioned(&a, &b, true),
+            MemNetStep::Heal { a, b } => inner.set_partitioned(&a, &b, false),
+            MemNetStep::HealAll => inner.partitions.clear(),
+        }
+    }
+
+    /// Apply a script of steps in the background. Each delay is relative
+    /// to the previous step.
+    pub fn run_script(
+        &self,
+        script: impl IntoIterator<Item = (Duration, MemNetStep)>,
+    ) -> tokio::task::JoinHandle<()> {
+        let this = self.clone();
+        let script = script.into_iter().collect::<Vec<_>>();
+        tokio::task::spawn(async move {
+            for (delay, step) in script {
+                tokio::time::sleep(delay).await;
+                tracing::debug!(?step, "mem net sim step");
+                this.apply(step);
+            }
+        })
+    }
+
+    /// Decide the fate of a frame of `len` bytes.
+    /// None = dropped, otherwise the instant it should be delivered.
+    fn plan(&self, from: &str, to: &str, len: usize) -> Option<Instant> {
+        let mut inner = self.0.lock();
+        let key = (from.to_string(), to.to_string());
+
+        if inner.partitions.contains(&key) {
+            return None;
+        }
+
+        let link = inner.links.get(&key).copied().unwrap_or(inner.default_link);
+
+        if link.loss > 0.0 && inner.next_f64() < link.loss {
+            return None;
+        }
+
+        let mut at = Instant::now();
+
+        if let Some(bps) = link.bytes_per_second {
+            let start = match inner.next_free.get(&key) {
+                Some(next_free) if *next_free > at => *next_free,
+                _ => at,
+            };
+            at = start + Duration::from_secs_f64(len as f64 / bps.max(1) as f64);
+            inner.next_free.insert(key, at);
+        }
+
+        at += link.latency;
+
+        if link.jitter > Duration::ZERO {
+            at += link.jitter.mul_f64(inner.next_f64());
+        }
+
+        Some(at)
+    }
+}
+
+/// A node group membership within a [MemNetSim].
+#[derive(Clone)]
+pub struct MemNetSimNode {
+    sim: MemNetSim,
+    group: String,
+}
+
+impl MemNetSimNode {
+    /// The group this node belongs to.
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    /// The link from this node to a remote node, if they share a simulator.
+    pub(crate) fn link_to(&self, remote: &MemNetSimNode) -> Option<MemNetSimLink> {
+        if !Arc::ptr_eq(&self.sim.0, &remote.sim.0) {
+            return None;
+        }
+        Some(MemNetSimLink {
+            sim: self.sim.clone(),
+            from: self.group.clone(),
+            to: remote.group.clone(),
+        })
+    }
+}
+
+/// One direction of a simulated connection.
+#[derive(Clone)]
+pub(crate) struct MemNetSimLink {
+    sim: MemNetSim,
+    from: String,
+    to: String,
+}
+
+impl MemNetSimLink {
+    /// Is this link currently cut?
+    pub fn is_partitioned(&self) -> bool {
+        self.sim.is_partitioned(&self.from, &self.to)
+    }
+
+    /// The opposite direction of this link.
+    pub fn reverse(&self) -> Self {
+        Self {
+            sim: self.sim.clone(),
+            from: self.to.clone(),
+            to: self.from.clone(),
+        }
+    }
+
+    /// Apply this link's faults to all frames written to `sub`.
+    pub fn wrap(&self, mut sub: OutChan) -> OutChan {
+        let (send, mut recv) = tokio::sync::mpsc::unbounded_channel::<DelayedFrame>();
+
+        // frames are delivered in write order, each no sooner than planned
+        tokio::task::spawn(async move {
+            while let Some((at, msg_id, data, timeout)) = recv.recv().await {
+                tokio::time::sleep_until(at).await;
+                if sub.write(msg_id, data, timeout).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Box::new(MemNetSimWriter {
+            link: self.clone(),
+            send,
+        })
+    }
+}
+
+type DelayedFrame = (Instant, MsgId, PoolBuf, KitsuneTimeout);
+
+struct MemNetSimWriter {
+    link: MemNetSimLink,
+    send: tokio::sync::mpsc::UnboundedSender<DelayedFrame>,
+}
+
+impl AsFramedWriter for MemNetSimWriter {
+    fn write(
+        &mut self,
+        msg_id: MsgId,
+        data: PoolBuf,
+        timeout: KitsuneTimeout,
+    ) -> BoxFuture<'_, KitsuneResult<()>> {
+        let res = match self
+            .link
+            .sim
+            .plan(&self.link.from, &self.link.to, data.len())
+        {
+            // dropped frames look like a successful write to the sender
+            None => Ok(()),
+            // like a real network, the sender doesn't wait for delivery
+            Some(at) => self
+                .send
+                .send((at, msg_id, data, timeout))
+                .map_err(|_| KitsuneErrorKind::Closed.into()),
+        };
+        async move { res }.boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream::StreamExt;
+
+    #[test]
+    fn test_mem_sim_loss_is_deterministic() {
+        let run = |seed| {
+            let sim = MemNetSim::new(seed);
+            sim.set_default_link(MemNetLink {
+                loss: 0.5,
+                ..Default::default()
+            });
+            (0..64)
+                .map(|_| sim.plan("a", "b", 1).is_some())
+                .collect::<Vec<_>>()
+        };
+        let res = run(42);
+        assert_eq!(res, run(42));
+        assert!(res.iter().any(|d| *d));
+        assert!(res.iter().any(|d| !*d));
+    }
+
+    #[test]
+    fn test_mem_sim_partition() {
+        let sim = MemNetSim::new(0);
+        assert!(sim.plan("a", "c", 1).is_some());
+
+        sim.partition(&["a", "b"], &["c"]);
+        assert!(sim.plan("a", "c", 1).is_none());
+        assert!(sim.plan("c", "b", 1).is_none());
+        assert!(sim.plan("a", "b", 1).is_some());
+
+        sim.heal(&["a"], &["c"]);
+        assert!(sim.plan("a", "c", 1).is_some());
+        assert!(sim.plan("b", "c", 1).is_none());
+
+        sim.heal_all();
+        assert!(sim.plan("b", "c", 1).is_some());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_mem_sim_latency_and_partition() {
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let sim = MemNetSim::new(0);
+        sim.set_link(
+            "a",
+            "b",
+            MemNetLink {
+                latency: Duration::from_millis(100),
+                ..Default::default()
+            },
+        );
+
+        let mut conf = MemConfig::default();
+        conf.net_sim = Some(sim.node("a"));
+        let fa = tx2_mem_adapter(conf).await.unwrap();
+        let mut conf = MemConfig::default();
+        conf.net_sim = Some(sim.node("b"));
+        let fb = tx2_mem_adapter(conf).await.unwrap();
+
+        let (ep_a, _con_recv_a) = fa.bind("none:".into(), t).await.unwrap();
+        let (ep_b, mut con_recv_b) = fb.bind("none:".into(), t).await.unwrap();
+        let addr_b = ep_b.local_addr().unwrap();
+
+        sim.partition(&["a"], &["b"]);
+        assert!(ep_a.connect(addr_b.clone(), t).await.is_err());
+        sim.heal_all();
+
+        let (con_a, _chan_recv_a) = ep_a.connect(addr_b, t).await.unwrap();
+        let (_con_b, mut chan_recv_b) = con_recv_b.next().await.unwrap().await.unwrap();
+
+        let start = Instant::now();
+        let mut out_chan = con_a.out_chan(t).await.unwrap();
+        let mut buf = PoolBuf::new();
+        buf.extend_from_slice(b"hello");
+        out_chan.write(0.into(), buf, t).await.unwrap();
+
+        // the write returns before the simulated latency elapses
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        let mut in_chan = chan_recv_b.next().await.unwrap().await.unwrap();
+        let (_, buf) = in_chan.read(t).await.unwrap();
+        assert_eq!(b"hello", &buf[..]);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        ep_a.close(0, "").await;
+        ep_b.close(0, "").await;
+    }
+}
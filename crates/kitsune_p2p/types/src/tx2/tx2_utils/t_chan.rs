@@ -5,6 +5,8 @@ use std::future::Future;
 use futures::future::FutureExt;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 
+pub use tokio::sync::mpsc::error::TrySendError;
+
 /// tokio::sync::mpsc::Sender is cheaply clonable,
 /// futures::channel::mpsc::Sender can be closed from the sender side.
 /// We want both these things.
@@ -49,6 +51,16 @@ impl<T: 'static + Send> TSender<T> {
         async move { sender.send(t).await.map_err(|e| e.0) }.boxed()
     }
 
+    /// Send a type instance to this channel sender's receiver,
+    /// without waiting for capacity if the channel is full.
+    pub fn try_send(&self, t: T) -> Result<(), TrySendError<T>> {
+        let sender = match self.0.share_mut(|i, _| Ok(i.clone())) {
+            Err(_) => return Err(TrySendError::Closed(t)),
+            Ok(s) => s,
+        };
+        sender.try_send(t)
+    }
+
     /// Close this channel from the sender side.
     /// The receiver can accept all pending sends, and then will close.
     pub fn close_channel(&self) {
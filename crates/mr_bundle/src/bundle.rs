@@ -3,6 +3,7 @@ use crate::{
     location::Location,
     manifest::Manifest,
     resource::ResourceBytes,
+    signature::{BundleSignature, PublisherKey},
 };
 use holochain_util::ffs;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -34,6 +35,11 @@ where
     /// are always relative paths (relative to the root_dir).
     resources: ResourceMap,
 
+    /// The publisher's signature over the manifest and resources, if the
+    /// bundle has been signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature: Option<BundleSignature>,
+
     /// Since the Manifest may contain local paths referencing unbundled files,
     /// on the local filesystem, we must have an absolute path at runtime for
     /// normalizing those locations.
@@ -46,6 +52,10 @@ where
     //       struct into two versions for each case.
     #[serde(skip)]
     root_dir: Option<PathBuf>,
+
+    /// Where to cache resources fetched from pinned urls, if anywhere.
+    #[serde(skip)]
+    resource_cache: Option<PathBuf>,
 }
 
 impl<M> Bundle<M>
@@ -103,7 +113,9 @@ where
         Ok(Self {
             manifest,
             resources,
+            signature: None,
             root_dir,
+            resource_cache: None,
         })
     }
 
@@ -114,8 +126,65 @@ where
 
     /// Return a new Bundle with an updated manifest, subject to the same
     /// validation constraints as creating a new Bundle from scratch.
+    ///
+    /// Any signature is dropped, since it no longer matches the manifest.
     pub fn update_manifest(self, manifest: M) -> MrBundleResult<Self> {
-        Self::from_parts(manifest, self.resources, self.root_dir)
+        let resource_cache = self.resource_cache;
+        Ok(Self {
+            resource_cache,
+            ..Self::from_parts(manifest, self.resources, self.root_dir)?
+        })
+    }
+
+    /// Cache resources fetched from urls pinned to a hash in this directory,
+    /// so that later resolutions of the same resources work offline.
+    pub fn with_resource_cache(mut self, dir: Option<PathBuf>) -> Self {
+        self.resource_cache = dir;
+        self
+    }
+
+    /// Accessor for the directory resources fetched from pinned urls are
+    /// cached in
+    pub fn resource_cache(&self) -> Option<&PathBuf> {
+        self.resource_cache.as_ref()
+    }
+
+    /// Sign the manifest and bundled resources with an ed25519 key pair,
+    /// given as a PKCS#8 document, replacing any existing signature.
+    pub fn sign(mut self, pkcs8: &[u8]) -> MrBundleResult<Self> {
+        self.signature = Some(BundleSignature::sign(
+            pkcs8,
+            &self.manifest,
+            &self.resources,
+        )?);
+        Ok(self)
+    }
+
+    /// Accessor for the publisher's signature, if the bundle is signed
+    pub fn signature(&self) -> Option<&BundleSignature> {
+        self.signature.as_ref()
+    }
+
+    /// Check that the bundle is signed, and that the signature matches the
+    /// manifest and bundled resources, returning the publisher's key.
+    ///
+    /// Resources which the manifest refers to by path or url are not
+    /// covered by the signature.
+    pub fn verify_signature(&self) -> MrBundleResult<PublisherKey> {
+        let signature = self.signature.as_ref().ok_or(BundleError::Unsigned)?;
+        signature.verify(&self.manifest, &self.resources)?;
+        Ok(signature.publisher)
+    }
+
+    /// Check that the bundle has a valid signature from one of the
+    /// `trusted` publishers.
+    pub fn verify_publisher(&self, trusted: &[PublisherKey]) -> MrBundleResult<PublisherKey> {
+        let publisher = self.verify_signature()?;
+        if trusted.contains(&publisher) {
+            Ok(publisher)
+        } else {
+            Err(BundleError::UntrustedPublisher(publisher).into())
+        }
     }
 
     /// Load a Bundle into memory from a file
@@ -138,7 +207,9 @@ where
                     .ok_or_else(|| BundleError::BundledResourceMissing(path.clone()))?,
             ),
             Location::Path(path) => Cow::Owned(crate::location::resolve_local(path).await?),
-            Location::Url(url) => Cow::Owned(crate::location::resolve_remote(url).await?),
+            Location::Url(url) => Cow::Owned(
+                crate::location::resolve_remote(url, self.resource_cache.as_deref()).await?,
+            ),
         };
         Ok(bytes)
     }
@@ -243,4 +314,99 @@ mod tests {
             Err(MrBundleError::BundleError(BundleError::BundledPathNotInManifest(path))) if path == PathBuf::from("3.thing")
         );
     }
+
+    #[test]
+    fn bundle_signing() {
+        let manifest = TestManifest(vec![
+            Location::Bundled("1.thing".into()),
+            Location::Bundled("2.thing".into()),
+        ]);
+        let resources = vec![("1.thing".into(), vec![1]), ("2.thing".into(), vec![2])];
+        let bundle = Bundle::new_unchecked(manifest.clone(), resources.clone()).unwrap();
+        matches::assert_matches!(
+            bundle.verify_signature(),
+            Err(MrBundleError::BundleError(BundleError::Unsigned))
+        );
+
+        let key = crate::generate_signing_key().unwrap();
+        let bundle = bundle.sign(&key).unwrap();
+        let publisher = bundle.verify_signature().unwrap();
+        assert_eq!(
+            publisher.to_string().parse::<PublisherKey>().unwrap(),
+            publisher
+        );
+        assert!(bundle.verify_publisher(&[publisher]).is_ok());
+
+        // The signature survives encoding, whatever order the resources
+        // are decoded in.
+        let decoded: Bundle<TestManifest> = Bundle::decode(&bundle.encode().unwrap()).unwrap();
+        assert_eq!(decoded.verify_signature().unwrap(), publisher);
+
+        let other = crate::generate_signing_key().unwrap();
+        let other = Bundle::new_unchecked(manifest.clone(), resources)
+            .unwrap()
+            .sign(&other)
+            .unwrap()
+            .verify_signature()
+            .unwrap();
+        matches::assert_matches!(
+            bundle.verify_publisher(&[other]),
+            Err(MrBundleError::BundleError(BundleError::UntrustedPublisher(p))) if p == publisher
+        );
+
+        // Tampering with a resource invalidates the signature.
+        let mut tampered = bundle;
+        tampered.resources.insert("2.thing".into(), vec![3]);
+        matches::assert_matches!(
+            tampered.verify_signature(),
+            Err(MrBundleError::BundleError(BundleError::InvalidSignature(p))) if p == publisher
+        );
+    }
+
+    /// A manifest whose shape has changed since the fixture bundle was
+    /// signed: a `retired` field was dropped and a `description` added.
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct UpgradedManifest {
+        locations: Vec<Location>,
+        #[serde(default)]
+        description: Option<String>,
+    }
+
+    impl Manifest for UpgradedManifest {
+        fn locations(&self) -> Vec<Location> {
+            self.locations.clone()
+        }
+
+        #[cfg(feature = "packing")]
+        fn path() -> PathBuf {
+            unimplemented!()
+        }
+
+        #[cfg(feature = "packing")]
+        fn bundle_extension() -> &'static str {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn signature_survives_manifest_upgrade() {
+        let bundle: Bundle<UpgradedManifest> =
+            Bundle::decode(include_bytes!("../tests/data/signed_before_upgrade.bundle")).unwrap();
+        assert_eq!(
+            bundle.manifest().locations,
+            vec![Location::Bundled("1.thing".into())]
+        );
+        let publisher: PublisherKey = "A6EHv_POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJVMbg"
+            .parse()
+            .unwrap();
+        assert_eq!(bundle.verify_signature().unwrap(), publisher);
+
+        // The signed manifest must still match the bundle's manifest.
+        let mut tampered = bundle;
+        tampered.manifest.description = Some("tampered".into());
+        matches::assert_matches!(
+            tampered.verify_signature(),
+            Err(MrBundleError::BundleError(BundleError::InvalidSignature(p))) if p == publisher
+        );
+    }
 }
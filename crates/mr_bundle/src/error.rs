@@ -49,6 +49,31 @@ pub enum BundleError {
         "Cannot use relative paths for local locations. The following local path is relative: {0}"
     )]
     RelativeLocalPath(std::path::PathBuf),
+
+    #[error("The bundle is not signed")]
+    Unsigned,
+
+    #[error("The bundle's signature by publisher {0} does not match its contents")]
+    InvalidSignature(crate::PublisherKey),
+
+    #[error("The bundle is signed by publisher {0}, which is not trusted")]
+    UntrustedPublisher(crate::PublisherKey),
+
+    #[error("Invalid ed25519 signing key: {0}")]
+    InvalidSigningKey(String),
+
+    #[error("Invalid publisher key: {0}")]
+    InvalidPublisherKey(String),
+
+    #[error("Invalid SHA-256 resource hash, expected 64 hex digits: {0}")]
+    InvalidResourceHash(String),
+
+    #[error("The resource at {url} has hash {actual}, but the manifest pins it to {expected}")]
+    ResourceHashMismatch {
+        url: String,
+        expected: crate::ResourceHash,
+        actual: crate::ResourceHash,
+    },
 }
 pub type BundleResult<T> = Result<T, BundleError>;
 
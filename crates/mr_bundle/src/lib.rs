@@ -11,6 +11,10 @@
 //! Manifest as "Bundled", and pulling them into the Bundle that way.
 //! Unpacking is done by specifying a target directory and creating a new file
 //! for each resource at a relative path specified by the Manifest.
+//!
+//! A Bundle can be [signed](Bundle::sign) by its publisher with an ed25519 key,
+//! so that whoever receives it can [check](Bundle::verify_publisher) that it
+//! came from a publisher they trust.
 
 #![warn(missing_docs)]
 
@@ -20,6 +24,7 @@ pub mod error;
 mod location;
 mod manifest;
 mod resource;
+mod signature;
 pub(crate) mod util;
 
 #[cfg(feature = "packing")]
@@ -27,6 +32,7 @@ mod packing;
 
 pub use bundle::Bundle;
 pub use encoding::{decode, encode};
-pub use location::Location;
+pub use location::{Location, ResourceHash, UrlLocation};
 pub use manifest::Manifest;
 pub use resource::ResourceBytes;
+pub use signature::{generate_signing_key, BundleSignature, PublisherKey};
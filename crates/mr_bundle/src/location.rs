@@ -21,8 +21,8 @@ pub enum Location {
     /// Get file from local filesystem (not bundled)
     Path(PathBuf),
 
-    /// Get file from URL
-    Url(String),
+    /// Get file from URL, checking its hash if it is pinned
+    Url(UrlLocation),
 }
 
 impl Location {
@@ -48,20 +48,192 @@ pub(crate) async fn resolve_local(path: &Path) -> MrBundleResult<ResourceBytes>
     Ok(ffs::read(path).await?)
 }
 
-pub(crate) async fn resolve_remote(url: &str) -> MrBundleResult<ResourceBytes> {
-    Ok(reqwest::get(url)
+/// A url to fetch a resource from, optionally pinned to the hash of the
+/// resource's content.
+///
+/// In a manifest an unpinned url is just a string, as in `url: <url>`,
+/// and a pinned one is a map, as in `url: { url: <url>, sha256: <hash> }`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "UrlLocationSerial", into = "UrlLocationSerial")]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct UrlLocation {
+    /// The url to fetch the resource from
+    pub url: String,
+
+    /// The expected hash of the resource
+    pub sha256: Option<ResourceHash>,
+}
+
+impl UrlLocation {
+    /// A url whose content isn't checked
+    pub fn unpinned(url: String) -> Self {
+        Self { url, sha256: None }
+    }
+
+    /// A url whose content must have this hash
+    pub fn pinned(url: String, sha256: ResourceHash) -> Self {
+        Self {
+            url,
+            sha256: Some(sha256),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum UrlLocationSerial {
+    Unpinned(String),
+    Pinned { url: String, sha256: ResourceHash },
+}
+
+impl From<UrlLocationSerial> for UrlLocation {
+    fn from(serial: UrlLocationSerial) -> Self {
+        match serial {
+            UrlLocationSerial::Unpinned(url) => Self::unpinned(url),
+            UrlLocationSerial::Pinned { url, sha256 } => Self::pinned(url, sha256),
+        }
+    }
+}
+
+impl From<UrlLocation> for UrlLocationSerial {
+    fn from(location: UrlLocation) -> Self {
+        match location.sha256 {
+            None => Self::Unpinned(location.url),
+            Some(sha256) => Self::Pinned {
+                url: location.url,
+                sha256,
+            },
+        }
+    }
+}
+
+/// The SHA-256 hash of a resource's content.
+/// Displayed and parsed as hex, as printed by `sha256sum`.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ResourceHash([u8; 32]);
+
+impl ResourceHash {
+    /// The hash of this content.
+    pub fn of(bytes: &[u8]) -> Self {
+        let mut hash = [0; 32];
+        hash.copy_from_slice(ring::digest::digest(&ring::digest::SHA256, bytes).as_ref());
+        Self(hash)
+    }
+}
+
+impl std::fmt::Display for ResourceHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for byte in self.0.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for ResourceHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ResourceHash({})", self)
+    }
+}
+
+impl std::str::FromStr for ResourceHash {
+    type Err = BundleError;
+
+    fn from_str(s: &str) -> Result<Self, BundleError> {
+        let s = s.trim();
+        let invalid = || BundleError::InvalidResourceHash(s.to_string());
+        if s.len() != 64 || !s.is_ascii() {
+            return Err(invalid());
+        }
+        let mut hash = [0; 32];
+        for (byte, hex) in hash.iter_mut().zip(s.as_bytes().chunks(2)) {
+            let hex = std::str::from_utf8(hex).map_err(|_| invalid())?;
+            *byte = u8::from_str_radix(hex, 16).map_err(|_| invalid())?;
+        }
+        Ok(Self(hash))
+    }
+}
+
+impl std::convert::TryFrom<String> for ResourceHash {
+    type Error = BundleError;
+
+    fn try_from(s: String) -> Result<Self, BundleError> {
+        s.parse()
+    }
+}
+
+impl From<ResourceHash> for String {
+    fn from(hash: ResourceHash) -> Self {
+        hash.to_string()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ResourceHash {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self(u.arbitrary()?))
+    }
+}
+
+/// Fetch a resource from a url.
+///
+/// If the url is pinned, the content must match its hash. Given a
+/// `cache_dir`, the content of pinned urls is kept there, named by its hash,
+/// and later resolutions are served from the cache without the network.
+pub(crate) async fn resolve_remote(
+    location: &UrlLocation,
+    cache_dir: Option<&Path>,
+) -> MrBundleResult<ResourceBytes> {
+    let cache_path = match (&location.sha256, cache_dir) {
+        (Some(hash), Some(dir)) => Some(dir.join(hash.to_string())),
+        _ => None,
+    };
+    if let (Some(path), Some(expected)) = (&cache_path, location.sha256) {
+        // A cached file which doesn't match its name is corrupt,
+        // so is fetched again and overwritten.
+        if let Ok(bytes) = ffs::read(path).await {
+            if ResourceHash::of(&bytes) == expected {
+                return Ok(bytes);
+            }
+        }
+    }
+
+    let bytes: ResourceBytes = reqwest::get(&location.url)
         .await?
+        .error_for_status()?
         .bytes()
         .await?
         .into_iter()
-        .collect())
+        .collect();
+
+    if let Some(expected) = location.sha256 {
+        let actual = ResourceHash::of(&bytes);
+        if actual != expected {
+            return Err(BundleError::ResourceHashMismatch {
+                url: location.url.clone(),
+                expected,
+                actual,
+            }
+            .into());
+        }
+    }
+    if let (Some(path), Some(dir)) = (cache_path, cache_dir) {
+        ffs::create_dir_all(dir).await?;
+        ffs::write(&path, &bytes).await?;
+    }
+    Ok(bytes)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::error::MrBundleError;
     use serde::{Deserialize, Serialize};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
     #[derive(Serialize, Deserialize)]
     struct TunaSalad {
@@ -86,7 +258,7 @@ mod tests {
 
         let tuna = TunaSalad {
             celery: vec![Location::Bundled("b".into()), Location::Path("p".into())],
-            mayo: Location::Url("http://r.co".into()),
+            mayo: Location::Url(UrlLocation::unpinned("http://r.co".into())),
         };
         let val = serde_yaml::to_value(&tuna).unwrap();
         println!("yaml produced:\n{}", serde_yaml::to_string(&tuna).unwrap());
@@ -95,4 +267,96 @@ mod tests {
         assert_eq!(val["celery"][1]["path"], Value::from("p"));
         assert_eq!(val["url"], Value::from("http://r.co"));
     }
+
+    #[test]
+    fn pinned_url_yaml() {
+        let hash = ResourceHash::of(b"tuna");
+        let yaml = format!("url:\n  url: http://r.co\n  sha256: {}\n", hash);
+        let location: Location = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(
+            location,
+            Location::Url(UrlLocation::pinned("http://r.co".into(), hash))
+        );
+        let val = serde_yaml::to_value(&location).unwrap();
+        assert_eq!(val["url"]["sha256"], Value::from(hash.to_string()));
+
+        assert!("not hex".parse::<ResourceHash>().is_err());
+        assert_eq!(hash.to_string().parse::<ResourceHash>().unwrap(), hash);
+    }
+
+    /// Serve `body` over http on a local port, returning the url and a count
+    /// of the requests made.
+    async fn http_fixture(body: Vec<u8>) -> (String, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/resource", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let count = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                count.fetch_add(1, Ordering::SeqCst);
+                let mut head = Vec::new();
+                let mut buf = [0; 1024];
+                while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => head.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                socket.write_all(response.as_bytes()).await.ok();
+                socket.write_all(&body).await.ok();
+            }
+        });
+        (url, requests)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn pinned_url_is_cached() {
+        let cache = tempdir::TempDir::new("resource-cache").unwrap();
+        let (url, requests) = http_fixture(b"tuna".to_vec()).await;
+        let hash = ResourceHash::of(b"tuna");
+        let location = UrlLocation::pinned(url, hash);
+
+        let bytes = resolve_remote(&location, Some(cache.path())).await.unwrap();
+        assert_eq!(bytes, b"tuna");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert!(cache.path().join(hash.to_string()).is_file());
+
+        // The second resolution is served from the cache
+        let bytes = resolve_remote(&location, Some(cache.path())).await.unwrap();
+        assert_eq!(bytes, b"tuna");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // A corrupt cache entry is fetched again
+        std::fs::write(cache.path().join(hash.to_string()), b"salad").unwrap();
+        let bytes = resolve_remote(&location, Some(cache.path())).await.unwrap();
+        assert_eq!(bytes, b"tuna");
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        // Unpinned urls are always fetched
+        let unpinned = UrlLocation::unpinned(location.url.clone());
+        resolve_remote(&unpinned, Some(cache.path())).await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn pinned_url_mismatch() {
+        let cache = tempdir::TempDir::new("resource-cache").unwrap();
+        let (url, _) = http_fixture(b"salad".to_vec()).await;
+        let expected = ResourceHash::of(b"tuna");
+        let location = UrlLocation::pinned(url.clone(), expected);
+
+        let err = resolve_remote(&location, Some(cache.path()))
+            .await
+            .unwrap_err();
+        matches::assert_matches!(
+            err,
+            MrBundleError::BundleError(BundleError::ResourceHashMismatch { url: u, expected: e, actual })
+                if u == url && e == expected && actual == ResourceHash::of(b"salad")
+        );
+        assert!(!cache.path().join(expected.to_string()).exists());
+    }
 }
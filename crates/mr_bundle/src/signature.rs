@@ -0,0 +1,161 @@
+//! Signing bundles so that a receiver can check who published them.
+//!
+//! The signature covers the manifest and every bundled resource. Resources
+//! which the manifest only refers to by path or url are not covered.
+
+use crate::{
+    error::{BundleError, BundleResult, MrBundleResult},
+    manifest::Manifest,
+    resource::ResourceBytes,
+};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use std::{collections::HashMap, path::PathBuf};
+
+/// The ed25519 public key of a bundle publisher.
+/// Displayed and parsed as url safe base64.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PublisherKey([u8; 32]);
+
+impl std::fmt::Display for PublisherKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&base64::encode_config(self.0, base64::URL_SAFE_NO_PAD))
+    }
+}
+
+impl std::fmt::Debug for PublisherKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PublisherKey({})", self)
+    }
+}
+
+impl std::str::FromStr for PublisherKey {
+    type Err = BundleError;
+
+    fn from_str(s: &str) -> BundleResult<Self> {
+        let bytes = base64::decode_config(s.trim(), base64::URL_SAFE_NO_PAD)
+            .map_err(|e| BundleError::InvalidPublisherKey(e.to_string()))?;
+        if bytes.len() != 32 {
+            return Err(BundleError::InvalidPublisherKey(format!(
+                "an ed25519 public key is 32 bytes, got {}",
+                bytes.len()
+            )));
+        }
+        let mut key = [0; 32];
+        key.copy_from_slice(&bytes);
+        Ok(Self(key))
+    }
+}
+
+impl std::convert::TryFrom<String> for PublisherKey {
+    type Error = BundleError;
+
+    fn try_from(s: String) -> BundleResult<Self> {
+        s.parse()
+    }
+}
+
+impl From<PublisherKey> for String {
+    fn from(key: PublisherKey) -> Self {
+        key.to_string()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for PublisherKey {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self(u.arbitrary()?))
+    }
+}
+
+/// A publisher's signature over the contents of a [`Bundle`](crate::Bundle).
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct BundleSignature {
+    /// The key of the publisher who signed the bundle
+    pub publisher: PublisherKey,
+
+    /// The ed25519 signature of the bundle digest
+    #[serde(with = "serde_bytes")]
+    pub signature: Vec<u8>,
+
+    /// The manifest exactly as it was signed, encoded as MessagePack.
+    ///
+    /// The digest is taken over these bytes rather than over a re-encoding
+    /// of the decoded manifest, so that the signature stays valid when a
+    /// later version changes the shape of the manifest.
+    #[serde(with = "serde_bytes")]
+    pub manifest: Vec<u8>,
+}
+
+impl BundleSignature {
+    /// Sign a bundle's manifest and bundled resources with an ed25519 key
+    /// pair, given as a PKCS#8 document.
+    pub(crate) fn sign<M: Manifest>(
+        pkcs8: &[u8],
+        manifest: &M,
+        resources: &HashMap<PathBuf, ResourceBytes>,
+    ) -> MrBundleResult<Self> {
+        let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(pkcs8)
+            .map_err(|e| BundleError::InvalidSigningKey(e.to_string()))?;
+        let mut publisher = [0; 32];
+        publisher.copy_from_slice(key_pair.public_key().as_ref());
+        let manifest = rmp_serde::to_vec_named(manifest)?;
+        let digest = digest(&manifest, resources);
+        Ok(Self {
+            publisher: PublisherKey(publisher),
+            signature: key_pair.sign(&digest).as_ref().to_vec(),
+            manifest,
+        })
+    }
+
+    /// Check that this signature was made by its publisher over these
+    /// bundled resources, and over a manifest which decodes to `manifest`.
+    pub(crate) fn verify<M: Manifest>(
+        &self,
+        manifest: &M,
+        resources: &HashMap<PathBuf, ResourceBytes>,
+    ) -> MrBundleResult<()> {
+        let invalid = || BundleError::InvalidSignature(self.publisher);
+        let digest = digest(&self.manifest, resources);
+        ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, &self.publisher.0)
+            .verify(&digest, &self.signature)
+            .map_err(|_| invalid())?;
+        let signed: M = rmp_serde::from_read_ref(&self.manifest).map_err(|_| invalid())?;
+        if signed == *manifest {
+            Ok(())
+        } else {
+            Err(invalid().into())
+        }
+    }
+}
+
+/// Generate a new ed25519 key pair for signing bundles, as a PKCS#8 document.
+pub fn generate_signing_key() -> BundleResult<Vec<u8>> {
+    Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new())
+        .map(|pkcs8| pkcs8.as_ref().to_vec())
+        .map_err(|_| BundleError::InvalidSigningKey("could not generate a key pair".into()))
+}
+
+/// The blake2b-256 digest of the encoded manifest and the bundled resources.
+///
+/// Resources are hashed in path order, so the digest doesn't depend on the
+/// order of the resource map.
+fn digest(manifest: &[u8], resources: &HashMap<PathBuf, ResourceBytes>) -> [u8; 32] {
+    let mut state = blake2b_simd::Params::new().hash_length(32).to_state();
+    update_with_len(&mut state, manifest);
+    let mut resources: Vec<_> = resources.iter().collect();
+    resources.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (path, bytes) in resources {
+        update_with_len(&mut state, path.to_string_lossy().as_bytes());
+        update_with_len(&mut state, bytes);
+    }
+    let mut digest = [0; 32];
+    digest.copy_from_slice(state.finalize().as_bytes());
+    digest
+}
+
+fn update_with_len(state: &mut blake2b_simd::State, bytes: &[u8]) {
+    state.update(&(bytes.len() as u64).to_le_bytes());
+    state.update(bytes);
+}
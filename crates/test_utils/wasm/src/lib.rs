@@ -33,6 +33,7 @@ pub enum TestWasm {
     PostCommitVolley,
     Query,
     RandomBytes,
+    RemoteSignal,
     Schedule,
     XSalsa20Poly1305,
     SerRegression,
@@ -82,6 +83,7 @@ impl From<TestWasm> for ZomeName {
             TestWasm::PostCommitVolley => "post_commit_volley",
             TestWasm::Query => "query",
             TestWasm::RandomBytes => "random_bytes",
+            TestWasm::RemoteSignal => "remote_signal",
             TestWasm::Schedule => "schedule",
             TestWasm::XSalsa20Poly1305 => "x_salsa20_poly1305",
             TestWasm::SerRegression => "ser_regression",
@@ -171,6 +173,9 @@ impl From<TestWasm> for DnaWasm {
             TestWasm::RandomBytes => {
                 get_code("wasm32-unknown-unknown/release/test_wasm_random_bytes.wasm")
             }
+            TestWasm::RemoteSignal => {
+                get_code("wasm32-unknown-unknown/release/test_wasm_remote_signal.wasm")
+            }
             TestWasm::Schedule => {
                 get_code("wasm32-unknown-unknown/release/test_wasm_schedule.wasm")
             }
@@ -56,6 +56,24 @@ fn create_post(post: crate::Post) -> ExternResult<HeaderHash> {
     hdk::prelude::create_entry(&post)
 }
 
+#[hdk_extern]
+fn create_batch(posts: Vec<String>) -> ExternResult<Vec<HeaderHash>> {
+    let batch = posts
+        .into_iter()
+        .map(|post| Ok(CreateInput::try_from(Post(post))?.into()))
+        .collect::<ExternResult<Vec<BatchOp>>>()?;
+    commit_batch(batch)
+}
+
+/// Try to commit a batch then commit a single post whether or not the batch
+/// was valid. Returns true if the batch was rejected.
+#[hdk_extern]
+fn create_batch_or_post(posts: Vec<String>) -> ExternResult<bool> {
+    let rejected = create_batch(posts).is_err();
+    hdk::prelude::create_entry(&post())?;
+    Ok(rejected)
+}
+
 #[hdk_extern]
 fn get_entry(_: ()) -> ExternResult<Option<Element>> {
     get(hash_entry(&post())?, GetOptions::content())
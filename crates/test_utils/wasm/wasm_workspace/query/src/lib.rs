@@ -13,6 +13,11 @@ fn query(args: QueryFilter) -> ExternResult<Vec<Element>> {
     hdk::prelude::query(args)
 }
 
+#[hdk_extern]
+fn query_page(input: ChainQueryPageInput) -> ExternResult<ChainQueryPage> {
+    hdk::prelude::query_page(input)
+}
+
 #[hdk_extern]
 fn add_path(s: String) -> ExternResult<EntryHash> {
     path(&s)
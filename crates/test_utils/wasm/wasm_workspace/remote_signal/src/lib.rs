@@ -0,0 +1,30 @@
+use hdk::prelude::*;
+
+#[hdk_extern]
+fn signal_others(
+    (agents, message): (Vec<AgentPubKey>, String),
+) -> ExternResult<Vec<RemoteSignalAck>> {
+    remote_signal_acked(&message, agents)
+}
+
+#[hdk_remote_signal]
+fn receive_message(message: String) -> ExternResult<()> {
+    if message == "spam" {
+        return Err(WasmError::Guest("No spam please".into()));
+    }
+    emit_signal(&message)
+}
+
+#[hdk_extern]
+fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    let mut functions: GrantedFunctions = BTreeSet::new();
+    functions.insert((zome_info()?.name, "recv_remote_signal".into()));
+    create_cap_grant(CapGrantEntry {
+        tag: "".into(),
+        // empty access converts to unrestricted
+        access: ().into(),
+        functions,
+    })?;
+
+    Ok(InitCallbackResult::Pass)
+}